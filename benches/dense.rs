@@ -0,0 +1,44 @@
+//! How long the dense backend takes to tick a 1024x1024 soup on one thread, for a rule that adds its rows of states straight in (Life)
+//! and one that has to turn them into 0s and 1s first (Brian's Brain).
+//! 
+//! ```text
+//! cargo bench --bench dense
+//! ```
+
+//--> Imports <--
+
+use cellular_snapp::{presets, AutomataRules, Backend};
+use cellular_snapp::flat::{self, Automaton, Vec2};
+use cellular_snapp::random::Rng;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+//--> Functions <--
+
+/// How many ticks get timed for each rule, after a few to warm up.
+const TICKS: usize = 200;
+
+fn main() {
+	for (name, rules) in [("Life", presets::life()), ("Brian's Brain", presets::brians_brain())] {
+		println!("{:<14} {:>8.3} ms per tick", name, median_tick(rules).as_secs_f64() * 1000.0);
+	}
+}
+
+/// Ticks a random soup `TICKS` times, giving back how long the median tick took, which other things running at the same time can't throw off as much as the average.
+/// Every cell gets worked on whether it's alive or not, so it doesn't matter if the soup dies down.
+fn median_tick(rules: AutomataRules) -> Duration {
+	let bounds = Vec2::new(1024, 1024);
+	let seed = flat::random_seed(bounds, 0.3, &mut Rng::new(1));
+	let mut a = Automaton::with_backend(rules, bounds, seed, Backend::Dense).unwrap();
+	for _ in 0..10 { a.tick(); }
+
+	let mut ticks: Vec<Duration> = (0..TICKS).map(|_| {
+		let start = Instant::now();
+		a.tick();
+		black_box(&a);
+		start.elapsed()
+	}).collect();
+
+	ticks.sort_unstable();
+	ticks[TICKS / 2]
+}
@@ -0,0 +1,214 @@
+//! This module has turmites: little agents (like Langton's Ant) that walk around a flat automaton's grid, reading and writing cells as they go.
+//! 
+//! Every step, a turmite looks at the cell it's standing on and its own state, and its rules tell it what to write into the cell, which way to turn, and what state to go into.
+//! Then it moves forward one cell.
+//! 
+//! ```
+//! # use cellular_snapp::{presets, flat::{Automaton, Vec2}, agents::{Heading, Turmite, World}};
+//! let grid = Automaton::new(presets::life(), Vec2::new(80, 80), Vec::new()).unwrap();
+//! let mut world = World::new(grid, vec![Turmite::langtons_ant(Vec2::new(40, 40), Heading::North)]);
+//! 
+//! // the ant flips the cell it was on, turns right, and moves on
+//! world.tick_agents();
+//! assert_eq!(world.get_automaton().get_cell(&Vec2::new(40, 40)), Some(1));
+//! assert!(world.get_turmites()[0].position() == &Vec2::new(41, 40));
+//! assert_eq!(world.get_turmites()[0].heading(), Heading::East);
+//! ```
+//! 
+//! Calling `World::tick` instead ticks the automaton first and then moves every turmite, so the two can run together.
+
+//--> Imports <--
+
+use crate::flat::{Automaton, Vec2};
+use std::collections::HashMap;
+
+//--> Structs <--
+
+/// What a turmite does in each situation, keyed by its own state and the state of the cell it's on.
+#[derive(Clone, Default)]
+pub struct TurmiteRules {
+	table: HashMap<(u8, u8), (u8, Turn, u8)>
+}
+
+/// A single turmite.
+#[derive(Clone)]
+pub struct Turmite {
+	rules: TurmiteRules,
+	position: Vec2,
+	heading: Heading,
+	state: u8,
+	halted: bool
+}
+
+/// A flat automaton with a bunch of turmites walking around on it.
+pub struct World {
+	automaton: Automaton,
+	turmites: Vec<Turmite>
+}
+
+//--> Enums <--
+
+/// Which way a turmite is facing. North is towards y = 0.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Heading {
+	/// Facing up, towards y = 0.
+	North,
+	/// Facing right.
+	East,
+	/// Facing down.
+	South,
+	/// Facing left, towards x = 0.
+	West
+}
+
+/// Which way a turmite turns before it moves.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Turn {
+	/// Keep going straight.
+	None,
+	/// Turn 90 degrees clockwise.
+	Right,
+	/// Turn all the way around.
+	Back,
+	/// Turn 90 degrees counterclockwise.
+	Left
+}
+
+//--> Functions <--
+
+impl TurmiteRules {
+	/// Creates a new, empty set of rules. A turmite with no rule for its situation halts.
+	pub fn new() -> TurmiteRules {
+		TurmiteRules::default()
+	}
+
+	/// Adds a rule: a turmite in state `state` standing on a cell in state `cell` writes `write` into the cell, turns, and goes into state `next`.
+	pub fn with(mut self, state: u8, cell: u8, write: u8, turn: Turn, next: u8) -> TurmiteRules {
+		self.table.insert((state, cell), (write, turn, next));
+		self
+	}
+
+	/// Langton's Ant, which turns right on dead cells and left on live ones, flipping every cell it leaves.
+	pub fn langtons_ant() -> TurmiteRules {
+		TurmiteRules::new()
+			.with(0, 0, 1, Turn::Right, 0)
+			.with(0, 1, 0, Turn::Left, 0)
+	}
+}
+
+impl Turmite {
+	/// Creates a new turmite in state 0 at the given position.
+	pub fn new(rules: TurmiteRules, position: Vec2, heading: Heading) -> Turmite {
+		Turmite { rules, position, heading, state: 0, halted: false }
+	}
+
+	/// Creates a new Langton's Ant at the given position.
+	pub fn langtons_ant(position: Vec2, heading: Heading) -> Turmite {
+		Turmite::new(TurmiteRules::langtons_ant(), position, heading)
+	}
+
+	/// Moves the turmite one step over the given automaton's grid.
+	/// 
+	/// A turmite stops for good if it ends up off the grid or its rules don't cover what it's standing on.
+	/// If the step forward would take it off the grid, it stays where it is (but still turns).
+	pub fn step(&mut self, automaton: &mut Automaton) {
+		if self.halted { return; }
+
+		let rule = automaton.get_cell(&self.position).and_then(|cell| self.rules.table.get(&(self.state, cell)));
+
+		let (write, turn, next) = match rule {
+			Some(rule) => *rule,
+			None => {
+				self.halted = true;
+				return;
+			}
+		};
+
+		automaton.set_cell(self.position, write);
+		self.heading = self.heading.turn(turn);
+		self.state = next;
+
+		let (x, y) = (self.position.x(), self.position.y());
+		let bounds = automaton.get_bounds();
+
+		let forward = match self.heading {
+			Heading::North => y.checked_sub(1).map(|y| Vec2::new(x, y)),
+			Heading::East => Some(Vec2::new(x + 1, y)).filter(|v| v.x() < bounds.x()),
+			Heading::South => Some(Vec2::new(x, y + 1)).filter(|v| v.y() < bounds.y()),
+			Heading::West => x.checked_sub(1).map(|x| Vec2::new(x, y))
+		};
+
+		if let Some(v) = forward { self.position = v; }
+	}
+
+	/// Get where the turmite is.
+	pub fn position(&self) -> &Vec2 {
+		&self.position
+	}
+
+	/// Get which way the turmite is facing.
+	pub fn heading(&self) -> Heading {
+		self.heading
+	}
+
+	/// Get the turmite's current state.
+	pub fn state(&self) -> u8 {
+		self.state
+	}
+
+	/// Checks whether the turmite has stopped for good.
+	pub fn is_halted(&self) -> bool {
+		self.halted
+	}
+}
+
+impl Heading {
+	/// Get the heading you end up facing after the given turn.
+	pub fn turn(self, turn: Turn) -> Heading {
+		let headings = [Heading::North, Heading::East, Heading::South, Heading::West];
+		let i = headings.iter().position(|h| *h == self).unwrap();
+		let by = match turn { Turn::None => 0, Turn::Right => 1, Turn::Back => 2, Turn::Left => 3 };
+
+		headings[(i + by) % 4]
+	}
+}
+
+impl World {
+	/// Puts the given turmites onto the given automaton.
+	pub fn new(automaton: Automaton, turmites: Vec<Turmite>) -> World {
+		World { automaton, turmites }
+	}
+
+	/// Adds another turmite to the world.
+	pub fn add_turmite(&mut self, turmite: Turmite) {
+		self.turmites.push(turmite);
+	}
+
+	/// Advances the world by one time step (or tick). The automaton ticks first, then every turmite takes a step in the order they were added.
+	pub fn tick(&mut self) {
+		self.automaton.tick();
+		self.tick_agents();
+	}
+
+	/// Moves every turmite one step without ticking the automaton, which is what you want for pure turmites like Langton's Ant.
+	pub fn tick_agents(&mut self) {
+		for t in self.turmites.iter_mut() {
+			t.step(&mut self.automaton);
+		}
+	}
+
+	/// Get the automaton the turmites are walking on.
+	pub fn get_automaton(&self) -> &Automaton {
+		&self.automaton
+	}
+
+	/// Get the automaton the turmites are walking on, so you can change it.
+	pub fn get_automaton_mut(&mut self) -> &mut Automaton {
+		&mut self.automaton
+	}
+
+	/// Get every turmite in the world.
+	pub fn get_turmites(&self) -> &[Turmite] {
+		&self.turmites
+	}
+}
@@ -0,0 +1,181 @@
+//! This module has tools for working out what a pattern actually does once it's running.
+//! 
+//! `detect_period` ticks an automaton until it finds a generation it's already seen, maybe moved over a bit, and tells you what kind of object it's looking at.
+//! 
+//! ```
+//! # use cellular_snapp::{presets, analysis::{detect_period, Behavior}, flat::{Automaton, IVec2, Vec2}};
+//! let mut blinker = Automaton::new(presets::life(), Vec2::new(8, 8), vec![Vec2::new(2, 3), Vec2::new(3, 3), Vec2::new(4, 3)]).unwrap();
+//! assert_eq!(detect_period(&mut blinker, 10), Some(Behavior::Oscillator { period: 2 }));
+//! 
+//! let mut glider = Automaton::new(presets::life(), Vec2::new(32, 32), presets::glider().positions()).unwrap();
+//! assert_eq!(detect_period(&mut glider, 10), Some(Behavior::Spaceship { period: 4, displacement: IVec2::new(1, 1) }));
+//! ```
+//! 
+//! There are also a few statistics about what the grid looks like right now: the entropy of its states, the sizes of its clusters of live cells, and how much cells look like their neighbors.
+//! Each of them has a `_deep` version for 3D automata.
+//! 
+//! ```
+//! # use cellular_snapp::{presets, analysis::{autocorrelation, cluster_sizes, entropy}, flat::{Automaton, Vec2}};
+//! // two blinkers and a lone cell, out of 100 cells
+//! let cells = vec![Vec2::new(1, 1), Vec2::new(2, 1), Vec2::new(3, 1), Vec2::new(1, 6), Vec2::new(1, 7), Vec2::new(1, 8), Vec2::new(7, 7)];
+//! let grid = Automaton::new(presets::life(), Vec2::new(10, 10), cells).unwrap();
+//! 
+//! assert_eq!(cluster_sizes(&grid), vec![3, 3, 1]);
+//! assert!((entropy(&grid) - 0.3659).abs() < 0.001);
+//! // live cells sit next to each other more than they would at random
+//! assert!(autocorrelation(&grid) > 0.0);
+//! ```
+
+//--> Imports <--
+
+use crate::flat::{Automaton, IVec2, Rect2, Vec2};
+use crate::{Method, deep};
+use std::collections::HashMap;
+
+//--> Enums <--
+
+/// What a pattern turned out to be.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub enum Behavior {
+	/// The pattern never changes. An empty grid counts as a still life with nothing in it.
+	StillLife,
+	/// The pattern comes back to the same shape in the same spot every `period` ticks.
+	Oscillator { period: usize },
+	/// The pattern comes back to the same shape every `period` ticks, but moved over by `displacement`.
+	Spaceship { period: usize, displacement: IVec2 }
+}
+
+//--> Functions <--
+
+/// Ticks the automaton (up to `max_ticks` times) until it repeats a generation it's already been through, ignoring where in the grid the cells are,
+/// and works out whether it's a still life, an oscillator, or a spaceship. Returns `None` if nothing repeats in time.
+/// 
+/// Patterns don't have to start out periodic, so a soup that settles down into a blinker gets found as an oscillator too.
+/// Generations are compared by their normalized state hashes, and the automaton is left wherever the search stopped.
+pub fn detect_period(automaton: &mut Automaton, max_ticks: usize) -> Option<Behavior> {
+	let mut seen: HashMap<u64, (usize, IVec2)> = HashMap::new();
+
+	for tick in 0..=max_ticks {
+		if tick > 0 { automaton.tick(); }
+
+		let corner = corner(automaton);
+
+		if let Some((first, first_corner)) = seen.insert(automaton.normalized_state_hash(), (tick, corner.clone())) {
+			let (period, displacement) = (tick - first, corner - first_corner);
+
+			return Some(match (period, displacement == IVec2::default()) {
+				(1, true) => Behavior::StillLife,
+				(_, true) => Behavior::Oscillator { period },
+				(_, false) => Behavior::Spaceship { period, displacement }
+			});
+		}
+	}
+
+	None
+}
+
+/// Get the top-left corner of the box around every live and dying cell.
+fn corner(automaton: &Automaton) -> IVec2 {
+	let live = automaton.live_cells_in(&Rect2::new(Vec2::new(0, 0), *automaton.get_bounds()));
+	IVec2::new(live.keys().map(|v| v.x()).min().unwrap_or(0) as i64, live.keys().map(|v| v.y()).min().unwrap_or(0) as i64)
+}
+
+/// Works out the Shannon entropy of the cell states, in bits, counting dead cells as a state too.
+/// A grid that's all one state has an entropy of 0, and the most it can be is the log (base 2) of the amount of states.
+pub fn entropy(automaton: &Automaton) -> f64 {
+	let (_, states) = grid(automaton);
+	state_entropy(&states)
+}
+
+/// Works out the Shannon entropy of the cell states of a 3D automaton, just like `entropy`.
+pub fn entropy_deep(automaton: &deep::Automaton) -> f64 {
+	let (_, states) = grid_deep(automaton);
+	state_entropy(&states)
+}
+
+/// Finds every cluster of live and dying cells touching each other (including at the corners), and gives back how big each one is, from biggest to smallest.
+/// How many clusters there are is just the length of the list.
+pub fn cluster_sizes(automaton: &Automaton) -> Vec<usize> {
+	automaton.components(&Method::Moore).iter().map(Vec::len).collect()
+}
+
+/// Finds every cluster of live and dying cells in a 3D automaton, just like `cluster_sizes`.
+pub fn cluster_sizes_deep(automaton: &deep::Automaton) -> Vec<usize> {
+	automaton.components(&Method::Moore).iter().map(Vec::len).collect()
+}
+
+/// Works out the spatial autocorrelation (Moran's I) of which cells are live or dying, with every cell weighed against the cells right next to it.
+/// It's close to 1 when live cells clump together, close to 0 when they're scattered at random, and below 0 when they keep away from each other, like in stripes one cell wide.
+/// Grids that are all dead (or all alive) don't have anything to correlate, so they give 0.
+pub fn autocorrelation(automaton: &Automaton) -> f64 {
+	let (size, states) = grid(automaton);
+	morans_i(size, &states)
+}
+
+/// Works out the spatial autocorrelation of a 3D automaton, just like `autocorrelation`.
+pub fn autocorrelation_deep(automaton: &deep::Automaton) -> f64 {
+	let (size, states) = grid_deep(automaton);
+	morans_i(size, &states)
+}
+
+/// Works out the Shannon entropy, in bits, of how things are spread out between the given counts.
+pub(crate) fn shannon_entropy(counts: &[usize]) -> f64 {
+	let total = counts.iter().sum::<usize>() as f64;
+	if total == 0.0 { return 0.0; }
+
+	counts.iter().filter(|c| **c > 0).map(|c| { let p = *c as f64 / total; p * (1.0 / p).log2() }).sum()
+}
+
+/// Get the size of a flat automaton's grid as `[x, y, 1]`, and the state of every cell, a row at a time.
+fn grid(automaton: &Automaton) -> ([usize; 3], Vec<u8>) {
+	let bounds = automaton.get_bounds();
+	let mut states = vec![0; bounds.x() * bounds.y()];
+	for (v, s) in automaton.live_cells_in(&Rect2::new(Vec2::new(0, 0), *bounds)) { states[v.y() * bounds.x() + v.x()] = s; }
+	([bounds.x(), bounds.y(), 1], states)
+}
+
+/// Get the size of a deep automaton's grid, and the state of every cell, a row at a time and then a layer at a time.
+fn grid_deep(automaton: &deep::Automaton) -> ([usize; 3], Vec<u8>) {
+	let bounds = automaton.get_bounds();
+	let mut states = vec![0; bounds.x() * bounds.y() * bounds.z()];
+	for (v, s) in automaton.live_cells_in(&deep::Box3::new(deep::Vec3::new(0, 0, 0), *bounds)) { states[(v.z() * bounds.y() + v.y()) * bounds.x() + v.x()] = s; }
+	([bounds.x(), bounds.y(), bounds.z()], states)
+}
+
+/// Works out the Shannon entropy of a grid's states.
+fn state_entropy(states: &[u8]) -> f64 {
+	let mut counts = [0; 256];
+	for s in states { counts[*s as usize] += 1; }
+	shannon_entropy(&counts)
+}
+
+/// Lists the index of every cell touching the given one (including at the corners and edges), in a grid of the given size.
+fn touching([w, h, d]: [usize; 3], i: usize) -> impl Iterator<Item = usize> {
+	let (x, y, z) = (i % w, i / w % h, i / (w * h));
+
+	(0..27).filter(|n| *n != 13).filter_map(move |n| {
+		let (nx, ny, nz) = ((x + n % 3).checked_sub(1)?, (y + n / 3 % 3).checked_sub(1)?, (z + n / 9).checked_sub(1)?);
+		(nx < w && ny < h && nz < d).then_some((nz * h + ny) * w + nx)
+	})
+}
+
+/// Works out Moran's I for which cells of a grid are live or dying, with a weight of 1 between every pair of cells that touch.
+fn morans_i(size: [usize; 3], states: &[u8]) -> f64 {
+	let n = states.len() as f64;
+	let mean = states.iter().filter(|s| **s > 0).count() as f64 / n;
+	let deviation = |i: usize| (states[i] > 0) as u8 as f64 - mean;
+
+	let variance: f64 = (0..states.len()).map(|i| deviation(i).powi(2)).sum();
+	if variance == 0.0 { return 0.0; }
+
+	let (mut weights, mut covariance) = (0.0, 0.0);
+
+	for i in 0..states.len() {
+		for j in touching(size, i) {
+			weights += 1.0;
+			covariance += deviation(i) * deviation(j);
+		}
+	}
+
+	if weights == 0.0 { 0.0 } else { n / weights * covariance / variance }
+}
@@ -0,0 +1,190 @@
+//! This module runs lots of small flat automata with the same rules at once, for rule searches and soup searches that spend all their time on tiny grids.
+//! 
+//! A `Batch` keeps every instance's cells in one big array, one grid after another, instead of a separate `Automaton` for each one,
+//! so there's nothing to allocate per instance and ticking goes straight through memory. With more than one thread, each thread gets its own instances to tick
+//! instead of splitting up each grid, since tiny grids aren't worth splitting.
+//! 
+//! ```
+//! # use cellular_snapp::{presets, batch::Batch, random::Rng, flat::{self, Automaton, Vec2}};
+//! let bounds = Vec2::new(16, 16);
+//! let mut rng = Rng::new(1);
+//! let soups: Vec<Vec<Vec2>> = (0..100).map(|_| flat::random_seed(bounds, 0.4, &mut rng)).collect();
+//! 
+//! let mut batch = Batch::new(presets::life(), bounds, soups.len()).unwrap().threads(4);
+//! for (i, soup) in soups.iter().enumerate() { batch.set_instance(i, soup); }
+//! for _ in 0..50 { batch.tick(); }
+//! 
+//! // every instance ends up just where it would have on its own
+//! let mut alone = Automaton::new(presets::life(), bounds, soups[42].clone()).unwrap();
+//! for _ in 0..50 { alone.tick(); }
+//! assert_eq!(batch.to_automaton(42).unwrap().live_cells_sorted(), alone.live_cells_sorted());
+//! ```
+//! 
+//! Deterministic rules tick exactly the way an `Automaton` would. Random rolls are tied to each instance as well as each cell's position,
+//! so instances of stochastic rules don't all roll the same way, but they won't match an `Automaton` with the same seed either.
+//! Species aren't kept track of.
+
+//--> Imports <--
+
+use crate::{AutomataError, AutomataRules, in_slabs_mut};
+use crate::flat::{self, Automaton, IVec2, Vec2};
+use crate::random::Rng;
+
+//--> Structs <--
+
+/// A bunch of small flat automata with the same rules and bounds, all ticked together.
+#[derive(Clone)]
+pub struct Batch {
+	rules: AutomataRules,
+	bounds: Vec2,
+	count: usize,
+	cells: Vec<u8>,
+	spare: Vec<u8>,
+	offsets: Vec<IVec2>,
+	rng: Rng,
+	threads: usize,
+	generation: u64
+}
+
+//--> Functions <--
+
+impl Batch {
+	/// Creates a batch of `count` automata with the given rules and bounds, where every cell starts out dead.
+	/// Just like with `Automaton::new`, this fails if your rules ask for more neighbors than a cell could have.
+	pub fn new(rules: AutomataRules, bounds: Vec2, count: usize) -> Result<Batch, AutomataError> {
+		rules.validate(flat::max_neighbors(rules.method())?, 2)?;
+
+		let offsets = flat::neighbor_offsets(rules.method());
+		let cells = vec![0; bounds.x() * bounds.y() * count];
+
+		Ok(Batch { rules, bounds, count, cells, spare: Vec::new(), offsets, rng: Rng::new(0), threads: 1, generation: 0 })
+	}
+
+	/// Sets how many threads each tick gets split up between. Each thread ticks whole instances, so there's no point in having more threads than instances.
+	/// Random rolls are tied to each instance and cell, so the amount of threads never changes how a batch ticks.
+	pub fn threads(mut self, threads: usize) -> Batch {
+		self.threads = threads.max(1);
+		self
+	}
+
+	/// Seeds the random number generator used by stochastic rules. Batches start out seeded with 0.
+	pub fn seed_rng(&mut self, seed: u64) {
+		self.rng = Rng::new(seed);
+	}
+
+	/// Advances every instance by one tick.
+	pub fn tick(&mut self) {
+		let key = self.rng.next_u64();
+		let (width, height) = (self.bounds.x(), self.bounds.y());
+		let area = width * height;
+
+		// rules that only count neighbors get looked up in a table, instead of working everything out for every cell
+		let max = self.offsets.len() as u8;
+		let table = self.rules.count_table(max);
+		let counted = self.rules.counted_states();
+		let stochastic = self.rules.is_stochastic();
+		let (rules, offsets, cells) = (&self.rules, &self.offsets, &self.cells);
+
+		self.spare.resize(self.cells.len(), 0);
+		in_slabs_mut(&mut self.spare, area.max(1), self.threads, |instances, out| {
+			for (instance, out) in instances.zip(out.chunks_mut(area.max(1))) {
+				let grid = &cells[instance * area..(instance + 1) * area];
+
+				for (i, cell) in out.iter_mut().enumerate() {
+					let v = Vec2::new(i % width, i / width);
+					let neighbors = offsets.iter().map(|offset| v.checked_add_offset(offset).filter(|n| n.x() < width && n.y() < height).map_or(0, |n| grid[n.y() * width + n.x()]));
+
+					*cell = match &table {
+						Some(table) => table[grid[i] as usize * (max as usize + 1) + neighbors.filter(|n| counted.contains(n)).count()],
+						None => {
+							let roll = if stochastic { Rng::keyed(key, &[instance as u64, v.x() as u64, v.y() as u64]).next_f64() } else { 0.0 };
+							rules.next_state_from(grid[i], neighbors, roll)
+						}
+					};
+				}
+			}
+		});
+
+		std::mem::swap(&mut self.cells, &mut self.spare);
+		self.generation += 1;
+	}
+
+	/// Ticks every instance `n` times.
+	pub fn tick_n(&mut self, n: usize) {
+		for _ in 0..n { self.tick(); }
+	}
+
+	/// Get how many generations the batch has been ticked through.
+	pub fn generation(&self) -> u64 {
+		self.generation
+	}
+
+	/// Get how many instances there are.
+	pub fn len(&self) -> usize {
+		self.count
+	}
+
+	/// Checks whether there aren't any instances at all.
+	pub fn is_empty(&self) -> bool {
+		self.count == 0
+	}
+
+	/// Get the rules every instance follows.
+	pub fn rules(&self) -> &AutomataRules {
+		&self.rules
+	}
+
+	/// Get the bounds (the size of the grid) every instance has.
+	pub fn bounds(&self) -> &Vec2 {
+		&self.bounds
+	}
+
+	/// Get every cell of an instance, one row at a time, or `None` if there's no such instance.
+	pub fn instance(&self, instance: usize) -> Option<&[u8]> {
+		let area = self.bounds.x() * self.bounds.y();
+		(instance < self.count).then(|| &self.cells[instance * area..(instance + 1) * area])
+	}
+
+	/// Kills every cell of an instance and then brings the given cells to life, just like starting a new `Automaton`. Cells outside the grid, and instances that don't exist, are ignored.
+	pub fn set_instance(&mut self, instance: usize, start_cells: &[Vec2]) {
+		if instance >= self.count { return; }
+
+		let (width, area) = (self.bounds.x(), self.bounds.x() * self.bounds.y());
+		let live = self.rules.states() - 1;
+		let grid = &mut self.cells[instance * area..(instance + 1) * area];
+
+		grid.fill(0);
+		for v in start_cells.iter().filter(|v| v.x() < width && v.y() < self.bounds.y()) { grid[v.y() * width + v.x()] = live; }
+	}
+
+	/// Get the state of a cell in an instance, or `None` if the cell is outside the grid or there's no such instance.
+	pub fn get_cell(&self, instance: usize, v: &Vec2) -> Option<u8> {
+		let width = self.bounds.x();
+		self.instance(instance).filter(|_| v.x() < width && v.y() < self.bounds.y()).map(|grid| grid[v.y() * width + v.x()])
+	}
+
+	/// Sets the state of a cell in an instance. States too big for the rules are clamped to alive, and cells outside the grid (or instances that don't exist) are ignored.
+	pub fn set_cell(&mut self, instance: usize, v: Vec2, state: u8) {
+		let (width, area) = (self.bounds.x(), self.bounds.x() * self.bounds.y());
+		if instance >= self.count || v.x() >= width || v.y() >= self.bounds.y() { return; }
+
+		self.cells[instance * area + v.y() * width + v.x()] = state.min(self.rules.states() - 1);
+	}
+
+	/// Get how many live or dying cells an instance has, or 0 if there's no such instance.
+	pub fn population(&self, instance: usize) -> usize {
+		self.instance(instance).map_or(0, |grid| grid.iter().filter(|s| **s > 0).count())
+	}
+
+	/// Copies an instance out into an `Automaton` of its own, for taking a closer look at the interesting ones. This gives `None` if there's no such instance.
+	/// The automaton starts out at the batch's generation, seeded with 0.
+	pub fn to_automaton(&self, instance: usize) -> Option<Automaton> {
+		let grid = self.instance(instance)?;
+		let width = self.bounds.x();
+		let mut automaton = Automaton::new(self.rules.clone(), self.bounds, Vec::new()).ok()?;
+
+		for (i, state) in grid.iter().enumerate().filter(|(_, s)| **s > 0) { automaton.set_cell(Vec2::new(i % width, i / width), *state); }
+		automaton.set_generation(self.generation);
+		Some(automaton)
+	}
+}
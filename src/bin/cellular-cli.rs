@@ -0,0 +1,181 @@
+//! A little command-line runner for quick experiments with 2D automata, without needing to write a Rust program.
+//! 
+//! ```text
+//! cellular-cli --rule B3/S23 --size 64x64 --density 0.3 --ticks 200 --gif life.gif
+//! ```
+
+//--> Imports <--
+
+use cellular_snapp::AutomataRules;
+use cellular_snapp::flat::{self, Automaton, Pattern, Vec2};
+use cellular_snapp::palette::{Gradient, Palette};
+use cellular_snapp::random::Rng;
+use image::codecs::gif::{GifEncoder, Repeat};
+use image::{Delay, Frame, Rgba, RgbaImage};
+use std::fs::{self, File};
+use std::process::ExitCode;
+
+//--> Structs <--
+
+/// Everything the user asked for on the command line.
+struct Options {
+	rule: AutomataRules,
+	size: Vec2,
+	seed_file: Option<String>,
+	density: f64,
+	random_seed: u64,
+	ticks: usize,
+	ascii: bool,
+	rle: Option<String>,
+	png: Option<String>,
+	gif: Option<String>,
+	scale: u32,
+	gradient: Gradient
+}
+
+//--> Functions <--
+
+const USAGE: &str = "usage: cellular-cli [options]
+
+  --rule <RULE>         rule string, like B3/S23 (default: B3/S23)
+  --size <WxH>          size of the grid (default: 64x64)
+  --seed <FILE>         RLE file to start from, placed in the middle of the grid
+  --density <P>         chance of each cell starting alive when there's no seed file (default: 0.5)
+  --random-seed <N>     seed for the random soup (default: 0)
+  --ticks <N>           how many ticks to run for (default: 100)
+  --ascii               print every generation as ASCII
+  --rle <FILE>          write the final generation as RLE
+  --png <FILE>          write the final generation as a PNG
+  --gif <FILE>          write every generation as an animated GIF
+  --scale <N>           pixels per cell in images (default: 4)
+  --palette <NAME>      colors for images: grayscale, viridis, or fire (default: grayscale)
+  --help                print this message";
+
+fn main() -> ExitCode {
+	match parse_args(std::env::args().skip(1)).and_then(run) {
+		Ok(()) => ExitCode::SUCCESS,
+		Err(e) => {
+			eprintln!("error: {}\n\n{}", e, USAGE);
+			ExitCode::FAILURE
+		}
+	}
+}
+
+/// Reads the command-line arguments into options.
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Options, String> {
+	let mut options = Options {
+		rule: "B3/S23".parse().unwrap(),
+		size: Vec2::new(64, 64),
+		seed_file: None,
+		density: 0.5,
+		random_seed: 0,
+		ticks: 100,
+		ascii: false,
+		rle: None,
+		png: None,
+		gif: None,
+		scale: 4,
+		gradient: Gradient::Grayscale
+	};
+
+	while let Some(arg) = args.next() {
+		let mut value = || args.next().ok_or_else(|| format!("{} needs a value", arg));
+
+		match arg.as_str() {
+			"--rule" => options.rule = value()?.parse().map_err(|e| format!("{}", e))?,
+			"--size" => {
+				let size = value()?;
+				let (w, h) = size.split_once('x').ok_or_else(|| format!("'{}' isn't a size like 64x64", size))?;
+				options.size = Vec2::new(parse_number(w)?, parse_number(h)?);
+			},
+			"--seed" => options.seed_file = Some(value()?),
+			"--density" => options.density = parse_number(&value()?)?,
+			"--random-seed" => options.random_seed = parse_number(&value()?)?,
+			"--ticks" => options.ticks = parse_number(&value()?)?,
+			"--ascii" => options.ascii = true,
+			"--rle" => options.rle = Some(value()?),
+			"--png" => options.png = Some(value()?),
+			"--gif" => options.gif = Some(value()?),
+			"--scale" => options.scale = parse_number(&value()?)?,
+			"--palette" => options.gradient = match value()?.as_str() {
+				"grayscale" => Gradient::Grayscale,
+				"viridis" => Gradient::Viridis,
+				"fire" => Gradient::Fire,
+				other => return Err(format!("'{}' isn't a palette", other))
+			},
+			"--help" => {
+				println!("{}", USAGE);
+				std::process::exit(0);
+			},
+			_ => return Err(format!("unknown option '{}'", arg))
+		}
+	}
+
+	Ok(options)
+}
+
+/// Parses a number out of an argument.
+fn parse_number<T: std::str::FromStr>(s: &str) -> Result<T, String> {
+	s.parse().map_err(|_| format!("'{}' isn't a valid number", s))
+}
+
+/// Runs the automaton and writes out whatever was asked for.
+fn run(options: Options) -> Result<(), String> {
+	let mut automaton = Automaton::new(options.rule.clone(), options.size, Vec::new()).map_err(|e| e.to_string())?;
+
+	match &options.seed_file {
+		Some(path) => {
+			let pattern = Pattern::from_rle(&fs::read_to_string(path).map_err(|e| e.to_string())?).map_err(|e| e.to_string())?;
+			let width = pattern.positions().iter().map(|v| v.x() + 1).max().unwrap_or(0);
+			let height = pattern.positions().iter().map(|v| v.y() + 1).max().unwrap_or(0);
+			let offset = Vec2::new(options.size.x().saturating_sub(width) / 2, options.size.y().saturating_sub(height) / 2);
+			pattern.stamp_into(&mut automaton, offset);
+		},
+		None => {
+			let seed = flat::random_seed(options.size, options.density, &mut Rng::new(options.random_seed));
+			Pattern::new("soup", seed).stamp_into(&mut automaton, Vec2::new(0, 0));
+		}
+	}
+
+	let palette = Palette::for_rules(&options.rule, options.gradient);
+	let mut frames = Vec::new();
+
+	for generation in 0..=options.ticks {
+		if generation > 0 { automaton.tick(); }
+
+		if options.ascii {
+			println!("generation {}\n{}", generation, automaton);
+		}
+
+		if options.gif.is_some() {
+			frames.push(Frame::from_parts(render_image(&automaton, &palette, options.scale), 0, 0, Delay::from_numer_denom_ms(100, 1)));
+		}
+	}
+
+	if let Some(path) = &options.rle {
+		fs::write(path, automaton.to_rle()).map_err(|e| e.to_string())?;
+	}
+
+	if let Some(path) = &options.png {
+		render_image(&automaton, &palette, options.scale).save(path).map_err(|e| e.to_string())?;
+	}
+
+	if let Some(path) = &options.gif {
+		let mut encoder = GifEncoder::new(File::create(path).map_err(|e| e.to_string())?);
+		encoder.set_repeat(Repeat::Infinite).map_err(|e| e.to_string())?;
+		encoder.encode_frames(frames).map_err(|e| e.to_string())?;
+	}
+
+	Ok(())
+}
+
+/// Draws the grid as an image, coloring every cell by its state.
+fn render_image(automaton: &Automaton, palette: &Palette, scale: u32) -> RgbaImage {
+	let (bounds, cells) = (automaton.get_bounds(), automaton.get_cells());
+	let scale = scale.max(1);
+
+	RgbaImage::from_fn(bounds.x() as u32 * scale, bounds.y() as u32 * scale, |px, py| {
+		let state = cells.get(&Vec2::new((px / scale) as usize, (py / scale) as usize)).copied().unwrap_or(0);
+		Rgba(palette.color(state))
+	})
+}
@@ -0,0 +1,125 @@
+//! A dense grid that packs each cell into a single bit, for automata with only two cell states.
+//! 
+//! Cells are stored a row at a time in `u64` words, so a whole word's worth of cells can have their neighbors counted at once.
+//! The counts are kept bit-sliced (one word per bit of the count), which lets a handful of bitwise operations stand in for 64 separate additions.
+
+//--> Imports <--
+
+use std::ops::Range;
+
+//--> Structs <--
+
+/// A grid of cells, one bit each. Rows run along x, and the bits past the end of each row are always 0.
+#[derive(Clone)]
+pub(crate) struct Bits {
+	width: usize,
+	row_words: usize,
+	words: Vec<u64>
+}
+
+//--> Functions <--
+
+impl Bits {
+	/// Creates a grid of dead cells with the given amount of rows, each `width` cells long.
+	pub(crate) fn new(width: usize, rows: usize) -> Bits {
+		let row_words = width.div_ceil(64);
+		Bits { width, row_words, words: vec![0; row_words * rows] }
+	}
+
+	/// Puts a grid back together from the words of every row, like the ones `step` gives.
+	pub(crate) fn from_words(width: usize, words: Vec<u64>) -> Bits {
+		Bits { width, row_words: width.div_ceil(64), words }
+	}
+
+	/// Takes the grid apart into the words of every row, so their memory can be used for another grid.
+	pub(crate) fn into_words(self) -> Vec<u64> {
+		self.words
+	}
+
+	/// Get whether a cell is alive.
+	pub(crate) fn get(&self, x: usize, row: usize) -> bool {
+		self.words[row * self.row_words + x / 64] >> (x % 64) & 1 != 0
+	}
+
+	/// Sets whether a cell is alive.
+	pub(crate) fn set(&mut self, x: usize, row: usize, alive: bool) {
+		let word = &mut self.words[row * self.row_words + x / 64];
+		if alive { *word |= 1 << (x % 64); } else { *word &= !(1 << (x % 64)); }
+	}
+
+	/// Get the position of every live cell, as `(x, row)`.
+	pub(crate) fn live(&self) -> Vec<(usize, usize)> {
+		let mut live = Vec::new();
+
+		for (i, word) in self.words.iter().enumerate() {
+			let mut word = *word;
+
+			while word != 0 {
+				let bit = word.trailing_zeros() as usize;
+				live.push(((i % self.row_words) * 64 + bit, i / self.row_words));
+				word &= word - 1;
+			}
+		}
+
+		live
+	}
+
+	/// Works out the next generation of a two-state rule that only counts neighbors, for just the given rows, and writes their words into `out`.
+	/// 
+	/// `table[n]` is what a dead cell with `n` live neighbors turns into, and `table[stride + n]` is what a live one turns into,
+	/// where `stride` is one more than the most neighbors a cell can have.
+	/// `neighbors(row)` lists the rows a row's neighbors are in, along with how far over along x (less than 64 cells either way) each of them is.
+	/// Rows outside the grid should be left out, and so should the row itself with an offset of 0, since that's the cell and not a neighbor.
+	pub(crate) fn step(&self, table: &[u8], stride: usize, rows: Range<usize>, neighbors: impl Fn(usize) -> Vec<(usize, i8)>, out: &mut [u64]) {
+		let (born, survive) = (&table[..stride], &table[stride..stride * 2]);
+		let planes = (usize::BITS - (stride - 1).leading_zeros()) as usize;
+		let first = rows.start;
+
+		for row in rows {
+			let next = &mut out[(row - first) * self.row_words..(row - first + 1) * self.row_words];
+
+			let sources = neighbors(row);
+
+			for w in 0..self.row_words {
+				// add every neighbor's bit into the bit-sliced counts, rippling the carry up through the planes
+				let mut count = [0u64; 8];
+
+				for (source, dx) in &sources {
+					let mut carry = self.shifted(*source, w, *dx);
+
+					for plane in count.iter_mut().take(planes) {
+						let next = *plane & carry;
+						*plane ^= carry;
+						carry = next;
+					}
+				}
+
+				let matching = |rule: &[u8]| rule.iter().enumerate().filter(|(_, next)| **next > 0).fold(0, |mask, (n, _)| {
+					mask | count.iter().take(planes).enumerate().fold(!0, |eq, (i, plane)| eq & if n >> i & 1 != 0 { *plane } else { !*plane })
+				});
+
+				let alive = self.words[row * self.row_words + w];
+				next[w] = (alive & matching(survive)) | (!alive & matching(born));
+			}
+
+			// rules that bring empty space to life would otherwise spill past the end of the row
+			if !self.width.is_multiple_of(64) {
+				next[self.row_words - 1] &= (1 << (self.width % 64)) - 1;
+			}
+		}
+	}
+
+	/// Get a word of a row, shifted so each bit lines up with the cell `dx` cells before it.
+	fn shifted(&self, row: usize, w: usize, dx: i8) -> u64 {
+		let start = row * self.row_words;
+		let word = self.words[start + w];
+
+		let k = dx.unsigned_abs() as u32;
+
+		match dx {
+			..0 => word << k | if w > 0 { self.words[start + w - 1] >> (64 - k) } else { 0 },
+			1.. => word >> k | if w + 1 < self.row_words { self.words[start + w + 1] << (64 - k) } else { 0 },
+			0 => word
+		}
+	}
+}
@@ -0,0 +1,418 @@
+//! This module has block (Margolus) automata, where the grid is split up into 2×2 blocks (or 2×2×2 in 3D) and every block is updated as a whole.
+//! 
+//! On even ticks the blocks start at the origin, and on odd ticks they're shifted over by one cell in every direction, so information can move between blocks.
+//! A block's cells are packed into a number (bit 0 is the cell nearest the origin, then x, then y, then z), which is looked up in the rule's table to get the block's new cells.
+//! If the table just shuffles blocks around (no two blocks turn into the same block), the automaton is reversible.
+//! 
+//! ```
+//! # use cellular_snapp::{flat::Vec2, block::{BlockAutomaton, BlockRules}};
+//! let mut billiards = BlockAutomaton::new(BlockRules::billiard_ball(), Vec2::new(8, 8), vec![Vec2::new(2, 2)]);
+//! assert!(billiards.get_rules().is_reversible());
+//! 
+//! // a lone ball travels diagonally, one cell per tick
+//! billiards.tick();
+//! billiards.tick();
+//! assert_eq!(billiards.get_cells()[&Vec2::new(4, 4)], 1);
+//! ```
+//! 
+//! Blocks that would stick out past the edge of the grid are left alone, which keeps reversible rules reversible.
+//! Reversible automata can be run backwards with `tick_backward`, which undoes a tick exactly.
+//! 
+//! There's also the HPP lattice gas, where particles fly around in four directions and bounce off each other when they meet head-on.
+//! It can be run as a block rule (`BlockRules::hpp_gas`), but `LatticeGas` gives every cell its own four particles, one per direction, which is the usual way to write it.
+//! 
+//! ```
+//! # use cellular_snapp::{flat::Vec2, block::LatticeGas};
+//! let mut gas = LatticeGas::new(Vec2::new(5, 5));
+//! gas.set_cell(Vec2::new(1, 2), LatticeGas::RIGHT);
+//! gas.set_cell(Vec2::new(3, 2), LatticeGas::LEFT);
+//! 
+//! // the particles meet in the middle, and then fly off up and down instead
+//! gas.tick();
+//! assert_eq!(gas.get_cell(&Vec2::new(2, 2)), Some(LatticeGas::RIGHT | LatticeGas::LEFT));
+//! gas.tick();
+//! assert_eq!(gas.get_cell(&Vec2::new(2, 1)), Some(LatticeGas::UP));
+//! assert_eq!(gas.get_cell(&Vec2::new(2, 3)), Some(LatticeGas::DOWN));
+//! assert_eq!(gas.particle_count(), 2);
+//! ```
+
+//--> Imports <--
+
+use crate::AutomataError;
+use crate::flat::Vec2;
+use crate::deep::Vec3;
+use std::collections::HashMap;
+
+//--> Structs <--
+
+/// The rules a 2D block automaton follows: what each of the 16 possible 2×2 blocks turns into.
+#[derive(Clone)]
+pub struct BlockRules {
+	table: [u8; 16]
+}
+
+/// The rules a 3D block automaton follows: what each of the 256 possible 2×2×2 blocks turns into.
+#[derive(Clone)]
+pub struct DeepBlockRules {
+	table: [u8; 256]
+}
+
+/// A 2D block automaton.
+pub struct BlockAutomaton {
+	rules: BlockRules,
+	bounds: Vec2,
+	cells: HashMap<Vec2, u8>,
+	odd: bool
+}
+
+/// A 3D block automaton.
+pub struct DeepBlockAutomaton {
+	rules: DeepBlockRules,
+	bounds: Vec3,
+	cells: HashMap<Vec3, u8>,
+	odd: bool
+}
+
+/// An HPP (Hardy, de Pazzis, and Pomeau) lattice gas. Every cell can hold up to four particles, one heading in each direction, kept as bits.
+/// 
+/// Every tick, two particles meeting head-on in a cell (with nothing else there) turn 90 degrees, and then every particle moves one cell the way it's heading.
+/// Particles that would fly off the edge of the grid bounce back instead, so none of them are ever lost.
+#[derive(Clone)]
+pub struct LatticeGas {
+	bounds: Vec2,
+	cells: HashMap<Vec2, u8>
+}
+
+//--> Functions <--
+
+impl BlockRules {
+	/// Creates new rules out of a block transition table. Only the bottom 4 bits of each entry are used.
+	pub fn new(table: [u8; 16]) -> BlockRules {
+		BlockRules { table: table.map(|b| b & 0b1111) }
+	}
+
+	/// Critters, a reversible rule that supports gliders. Blocks with exactly two live cells stay as they are,
+	/// every other block is inverted, and blocks that had three live cells are also turned around.
+	pub fn critters() -> BlockRules {
+		let mut table = [0; 16];
+
+		for (block, entry) in table.iter_mut().enumerate() {
+			let block = block as u8;
+
+			*entry = match block.count_ones() {
+				2 => block,
+				3 => rotate_half(!block & 0b1111),
+				_ => !block & 0b1111
+			};
+		}
+
+		BlockRules { table }
+	}
+
+	/// Fredkin and Toffoli's billiard ball model. A lone cell moves to the opposite corner of its block,
+	/// and two cells heading straight at each other bounce off to the other diagonal. Everything else stays put.
+	pub fn billiard_ball() -> BlockRules {
+		let mut table = [0; 16];
+
+		for (block, entry) in table.iter_mut().enumerate() {
+			let block = block as u8;
+
+			*entry = match block {
+				0b0001 | 0b0010 | 0b0100 | 0b1000 => rotate_half(block),
+				0b1001 => 0b0110,
+				0b0110 => 0b1001,
+				_ => block
+			};
+		}
+
+		BlockRules { table }
+	}
+
+	/// The HPP lattice gas as a block rule. Every particle moves to the opposite corner of its block,
+	/// except for two particles heading straight at each other, which bounce off to the other diagonal.
+	pub fn hpp_gas() -> BlockRules {
+		let mut table = [0; 16];
+
+		for (block, entry) in table.iter_mut().enumerate() {
+			let block = block as u8;
+
+			*entry = match block {
+				0b1001 => 0b0110,
+				0b0110 => 0b1001,
+				_ => rotate_half(block)
+			};
+		}
+
+		BlockRules { table }
+	}
+
+	/// Checks whether the rules are reversible, which is when no two blocks turn into the same block.
+	pub fn is_reversible(&self) -> bool {
+		let mut seen = [false; 16];
+		self.table.iter().all(|b| !std::mem::replace(&mut seen[*b as usize], true))
+	}
+
+	/// Get the table that undoes this one, if the rules are reversible.
+	fn inverse(&self) -> Option<[u8; 16]> {
+		let mut inverse = [0; 16];
+		for (block, next) in self.table.iter().enumerate() { inverse[*next as usize] = block as u8; }
+		self.is_reversible().then_some(inverse)
+	}
+}
+
+impl DeepBlockRules {
+	/// Creates new rules out of a block transition table.
+	pub fn new(table: [u8; 256]) -> DeepBlockRules {
+		DeepBlockRules { table }
+	}
+
+	/// Checks whether the rules are reversible, which is when no two blocks turn into the same block.
+	pub fn is_reversible(&self) -> bool {
+		let mut seen = [false; 256];
+		self.table.iter().all(|b| !std::mem::replace(&mut seen[*b as usize], true))
+	}
+
+	/// Get the table that undoes this one, if the rules are reversible.
+	fn inverse(&self) -> Option<[u8; 256]> {
+		let mut inverse = [0; 256];
+		for (block, next) in self.table.iter().enumerate() { inverse[*next as usize] = block as u8; }
+		self.is_reversible().then_some(inverse)
+	}
+}
+
+impl BlockAutomaton {
+	/// Creates a new 2D block automaton with the given rules and live cells. Starting cells outside the bounds are ignored.
+	pub fn new(rules: BlockRules, bounds: Vec2, start_cells: Vec<Vec2>) -> BlockAutomaton {
+		let mut cells = HashMap::new();
+
+		for x in 0..bounds.x() {
+			for y in 0..bounds.y() {
+				cells.insert(Vec2::new(x, y), 0);
+			}
+		}
+
+		for v in start_cells {
+			if let Some(cell) = cells.get_mut(&v) { *cell = 1; }
+		}
+
+		BlockAutomaton { rules, bounds, cells, odd: false }
+	}
+
+	/// Advances the automaton by one time step (or tick).
+	pub fn tick(&mut self) {
+		let table = self.rules.table;
+		self.update_blocks(&table);
+		self.odd = !self.odd;
+	}
+
+	/// Takes the automaton back by one time step, undoing a `tick` exactly.
+	/// Rules that aren't reversible can't be undone, so they give back a `NotReversible` error without changing anything.
+	/// 
+	/// ```
+	/// # use cellular_snapp::{flat::Vec2, block::{BlockAutomaton, BlockRules}};
+	/// let start = vec![Vec2::new(2, 2), Vec2::new(3, 2), Vec2::new(5, 6)];
+	/// let mut critters = BlockAutomaton::new(BlockRules::critters(), Vec2::new(10, 10), start);
+	/// let before = critters.get_cells();
+	/// 
+	/// for _ in 0..7 { critters.tick(); }
+	/// for _ in 0..7 { critters.tick_backward().unwrap(); }
+	/// assert!(critters.get_cells() == before);
+	/// ```
+	pub fn tick_backward(&mut self) -> Result<(), AutomataError> {
+		let inverse = self.rules.inverse().ok_or(AutomataError::NotReversible)?;
+		self.odd = !self.odd;
+		self.update_blocks(&inverse);
+		Ok(())
+	}
+
+	/// Replaces every block (starting from wherever this tick's blocks start) with what the given table says it turns into.
+	fn update_blocks(&mut self, table: &[u8; 16]) {
+		let shift = self.odd as usize;
+
+		for y in (shift..self.bounds.y().saturating_sub(1)).step_by(2) {
+			for x in (shift..self.bounds.x().saturating_sub(1)).step_by(2) {
+				let corners = [Vec2::new(x, y), Vec2::new(x + 1, y), Vec2::new(x, y + 1), Vec2::new(x + 1, y + 1)];
+				let block = corners.iter().enumerate().fold(0, |block, (i, v)| block | (self.cells[v] & 1) << i);
+				let next = table[block as usize];
+
+				for (i, v) in corners.into_iter().enumerate() {
+					self.cells.insert(v, (next >> i) & 1);
+				}
+			}
+		}
+	}
+
+	/// Get a copy of the automaton's internal state (the cells). Every cell is either 0 (dead) or 1 (alive).
+	pub fn get_cells(&self) -> HashMap<Vec2, u8> {
+		self.cells.clone()
+	}
+
+	/// Get the rules the automaton is currently following.
+	pub fn get_rules(&self) -> &BlockRules {
+		&self.rules
+	}
+
+	/// Get the bounds (the size of the grid) of the automaton.
+	pub fn get_bounds(&self) -> &Vec2 {
+		&self.bounds
+	}
+}
+
+impl DeepBlockAutomaton {
+	/// Creates a new 3D block automaton with the given rules and live cells. Starting cells outside the bounds are ignored.
+	pub fn new(rules: DeepBlockRules, bounds: Vec3, start_cells: Vec<Vec3>) -> DeepBlockAutomaton {
+		let mut cells = HashMap::new();
+
+		for x in 0..bounds.x() {
+			for y in 0..bounds.y() {
+				for z in 0..bounds.z() {
+					cells.insert(Vec3::new(x, y, z), 0);
+				}
+			}
+		}
+
+		for v in start_cells {
+			if let Some(cell) = cells.get_mut(&v) { *cell = 1; }
+		}
+
+		DeepBlockAutomaton { rules, bounds, cells, odd: false }
+	}
+
+	/// Advances the automaton by one time step (or tick).
+	pub fn tick(&mut self) {
+		let table = self.rules.table;
+		self.update_blocks(&table);
+		self.odd = !self.odd;
+	}
+
+	/// Takes the automaton back by one time step, undoing a `tick` exactly.
+	/// Rules that aren't reversible can't be undone, so they give back a `NotReversible` error without changing anything.
+	pub fn tick_backward(&mut self) -> Result<(), AutomataError> {
+		let inverse = self.rules.inverse().ok_or(AutomataError::NotReversible)?;
+		self.odd = !self.odd;
+		self.update_blocks(&inverse);
+		Ok(())
+	}
+
+	/// Replaces every block (starting from wherever this tick's blocks start) with what the given table says it turns into.
+	fn update_blocks(&mut self, table: &[u8; 256]) {
+		let shift = self.odd as usize;
+
+		for z in (shift..self.bounds.z().saturating_sub(1)).step_by(2) {
+			for y in (shift..self.bounds.y().saturating_sub(1)).step_by(2) {
+				for x in (shift..self.bounds.x().saturating_sub(1)).step_by(2) {
+					let corners = (0..8).map(|i| Vec3::new(x + (i & 1), y + (i >> 1 & 1), z + (i >> 2))).collect::<Vec<_>>();
+					let block = corners.iter().enumerate().fold(0, |block, (i, v)| block | (self.cells[v] & 1) << i);
+					let next = table[block as usize];
+
+					for (i, v) in corners.into_iter().enumerate() {
+						self.cells.insert(v, (next >> i) & 1);
+					}
+				}
+			}
+		}
+	}
+
+	/// Get a copy of the automaton's internal state (the cells). Every cell is either 0 (dead) or 1 (alive).
+	pub fn get_cells(&self) -> HashMap<Vec3, u8> {
+		self.cells.clone()
+	}
+
+	/// Get the rules the automaton is currently following.
+	pub fn get_rules(&self) -> &DeepBlockRules {
+		&self.rules
+	}
+
+	/// Get the bounds (the size of the grid) of the automaton.
+	pub fn get_bounds(&self) -> &Vec3 {
+		&self.bounds
+	}
+}
+
+impl LatticeGas {
+	/// The bit for a particle heading right, toward bigger x.
+	pub const RIGHT: u8 = 0b0001;
+	/// The bit for a particle heading down, toward bigger y.
+	pub const DOWN: u8 = 0b0010;
+	/// The bit for a particle heading left, toward smaller x.
+	pub const LEFT: u8 = 0b0100;
+	/// The bit for a particle heading up, toward smaller y.
+	pub const UP: u8 = 0b1000;
+
+	/// Creates a new lattice gas with no particles in it.
+	pub fn new(bounds: Vec2) -> LatticeGas {
+		let cells = (0..bounds.x()).flat_map(|x| (0..bounds.y()).map(move |y| (Vec2::new(x, y), 0))).collect();
+		LatticeGas { bounds, cells }
+	}
+
+	/// Advances the gas by one time step (or tick): particles collide, and then move.
+	pub fn tick(&mut self) {
+		let (width, height) = (self.bounds.x(), self.bounds.y());
+		let mut next: HashMap<Vec2, u8> = self.cells.keys().map(|v| (*v, 0)).collect();
+
+		for (v, particles) in &self.cells {
+			// the head-on collisions are the only ones that change anything, since every other collision would break momentum
+			let particles = match *particles {
+				p if p == LatticeGas::RIGHT | LatticeGas::LEFT => LatticeGas::DOWN | LatticeGas::UP,
+				p if p == LatticeGas::DOWN | LatticeGas::UP => LatticeGas::RIGHT | LatticeGas::LEFT,
+				p => p
+			};
+
+			for (bit, reverse, dx, dy) in [(LatticeGas::RIGHT, LatticeGas::LEFT, 1, 0), (LatticeGas::DOWN, LatticeGas::UP, 0, 1), (LatticeGas::LEFT, LatticeGas::RIGHT, -1, 0), (LatticeGas::UP, LatticeGas::DOWN, 0, -1)] {
+				if particles & bit == 0 { continue; }
+
+				match (v.x().checked_add_signed(dx), v.y().checked_add_signed(dy)) {
+					(Some(x), Some(y)) if x < width && y < height => *next.get_mut(&Vec2::new(x, y)).unwrap() |= bit,
+					_ => *next.get_mut(v).unwrap() |= reverse
+				}
+			}
+		}
+
+		self.cells = next;
+	}
+
+	/// Get the particles in a cell, as bits, or `None` if it's outside the grid.
+	pub fn get_cell(&self, v: &Vec2) -> Option<u8> {
+		self.cells.get(v).copied()
+	}
+
+	/// Sets the particles in a cell, as bits. Only the bottom 4 bits are used, and cells outside the grid are ignored.
+	pub fn set_cell(&mut self, v: Vec2, particles: u8) {
+		if let Some(cell) = self.cells.get_mut(&v) { *cell = particles & 0b1111; }
+	}
+
+	/// Get a copy of the particles in every cell, as bits.
+	pub fn get_cells(&self) -> HashMap<Vec2, u8> {
+		self.cells.clone()
+	}
+
+	/// Get the bounds (the size of the grid) of the gas.
+	pub fn get_bounds(&self) -> &Vec2 {
+		&self.bounds
+	}
+
+	/// Counts up every particle in the gas, which never changes.
+	pub fn particle_count(&self) -> usize {
+		self.cells.values().map(|p| p.count_ones() as usize).sum()
+	}
+
+	/// Works out how dense the gas is, averaged over squares of `size` by `size` cells, for drawing it without the noise of single particles.
+	/// Each square is keyed by its position in squares (so the square at `(1, 0)` starts at cell `(size, 0)`), and gets how many particles
+	/// it has out of the most it could hold, from 0 to 1. Squares hanging off the edge of the grid only count the cells inside it.
+	pub fn density_field(&self, size: usize) -> HashMap<Vec2, f64> {
+		let size = size.max(1);
+		let mut totals: HashMap<Vec2, (u32, u32)> = HashMap::new();
+
+		for (v, particles) in &self.cells {
+			let total = totals.entry(Vec2::new(v.x() / size, v.y() / size)).or_default();
+			*total = (total.0 + particles.count_ones(), total.1 + 4);
+		}
+
+		totals.into_iter().map(|(v, (particles, most))| (v, particles as f64 / most as f64)).collect()
+	}
+}
+
+/// Turns a 2×2 block around by 180 degrees, which swaps opposite corners.
+fn rotate_half(block: u8) -> u8 {
+	(block & 0b0001) << 3 | (block & 0b0010) << 1 | (block & 0b0100) >> 1 | (block & 0b1000) >> 3
+}
@@ -0,0 +1,376 @@
+//! This module lets every cell carry its own data, like a species, an energy level, or how old it is, without keeping maps keyed by position alongside the automaton.
+//! 
+//! Cells are anything that implements `CellState`, and the grid is made of them: each position holds a cell, or nothing if it's dead.
+//! The rules only ever see the state each cell says it's in (0 for dead, the highest state for alive, and dying in between),
+//! and the cell type says what a newborn looks like and how it changes when the rules move it along.
+//! 
+//! ```
+//! # use cellular_snapp::{presets, cellstate::{CellState, StateAutomaton}, flat::Vec2};
+//! #[derive(Clone, PartialEq, Debug)]
+//! struct Aged { age: u32 }
+//! 
+//! impl CellState for Aged {
+//!     fn state(&self) -> u8 { 1 }
+//!     fn born(_state: u8, _parents: &[&Aged]) -> Aged { Aged { age: 0 } }
+//!     fn update(&self, _state: u8) -> Aged { Aged { age: self.age + 1 } }
+//! }
+//! 
+//! let blinker = vec![Vec2::new(1, 2), Vec2::new(2, 2), Vec2::new(3, 2)].into_iter().map(|v| (v, Aged { age: 0 })).collect();
+//! let mut life = StateAutomaton::new(presets::life(), Vec2::new(5, 5), blinker).unwrap();
+//! for _ in 0..4 { life.tick(); }
+//! 
+//! // the middle of the blinker never goes anywhere, but its ends are born again every tick
+//! assert_eq!(life.get(&Vec2::new(2, 2)), Some(&Aged { age: 4 }));
+//! assert_eq!(life.get(&Vec2::new(1, 2)), Some(&Aged { age: 0 }));
+//! ```
+//! 
+//! Plain `u8` states are cells too, with nothing else to them, and that's what the cell type is if you don't say.
+//! These grids are simple ones with dead cells past the edges, which don't have walls, species, or any of the other extras `flat::Automaton` and `deep::Automaton` have,
+//! since the cells can carry whatever they need themselves.
+
+//--> Imports <--
+
+use crate::{AutomataError, AutomataRules, CellularAutomaton, flat, deep};
+use crate::flat::{IVec2, Vec2};
+use crate::deep::{IVec3, Vec3};
+use crate::random::Rng;
+use std::collections::HashMap;
+
+//--> Structs <--
+
+/// A flat automaton where every live or dying cell is a value of its own type, which gets carried along from tick to tick.
+#[derive(Clone)]
+pub struct StateAutomaton<S: CellState = u8> {
+	grid: Grid<S>,
+	bounds: Vec2,
+	offsets: Vec<IVec2>
+}
+
+/// A deep automaton where every live or dying cell is a value of its own type, which gets carried along from tick to tick.
+#[derive(Clone)]
+pub struct DeepStateAutomaton<S: CellState = u8> {
+	grid: Grid<S>,
+	bounds: Vec3,
+	offsets: Vec<IVec3>
+}
+
+/// The cells of a grid in one flat array, where dead cells are `None`, along with everything needed to tick them.
+#[derive(Clone)]
+struct Grid<S> {
+	rules: AutomataRules,
+	cells: Vec<Option<S>>,
+	rng: Rng,
+	generation: u64
+}
+
+//--> Traits <--
+
+/// Something that can be a cell. Dead cells aren't kept around, so there's no value for them.
+pub trait CellState: Clone {
+	/// Get the state the rules see the cell in. Anything too big for the rules counts as alive, and 0 means the cell is dead and gets thrown away.
+	fn state(&self) -> u8;
+
+	/// Creates a newborn cell in the given state (which is always alive), from its live and dying neighbors in the generation before it was born.
+	fn born(state: u8, parents: &[&Self]) -> Self;
+
+	/// Works out what a cell turns into after a tick where the rules put it in the given state, which might be the same one if it survived.
+	/// This only gets called for cells that are still live or dying afterwards. Whatever state the new cell says it's in is the state it's in from then on,
+	/// so cells with more than two states usually want to keep the one they're given.
+	fn update(&self, state: u8) -> Self;
+}
+
+//--> Functions <--
+
+impl<S: CellState> StateAutomaton<S> {
+	/// Creates a new automaton with the given rules, bounds, and starting cells. Cells outside the grid, or in state 0, are left out.
+	/// This fails for the same reasons `flat::Automaton::new` does.
+	pub fn new(rules: AutomataRules, bounds: Vec2, start_cells: Vec<(Vec2, S)>) -> Result<StateAutomaton<S>, AutomataError> {
+		rules.validate(flat::max_neighbors(rules.method())?, 2)?;
+
+		let offsets = flat::neighbor_offsets(rules.method());
+		let mut automaton = StateAutomaton { grid: Grid::new(rules, bounds.x() * bounds.y()), bounds, offsets };
+		for (v, cell) in start_cells { automaton.set(v, cell); }
+		Ok(automaton)
+	}
+
+	/// Advances the automaton by one tick. Cells that come to life are made with `born`, and every other cell that's still live or dying gets `update`d.
+	/// The state a cell says it's in is the one the rules see on the next tick, so cells can die (or start dying) on their own.
+	/// 
+	/// ```
+	/// # use cellular_snapp::{presets, cellstate::{CellState, StateAutomaton}, flat::Vec2};
+	/// // cells that die of old age after 3 ticks, even when the rules would keep them alive
+	/// #[derive(Clone)]
+	/// struct Mortal { age: u8 }
+	/// 
+	/// impl CellState for Mortal {
+	///     fn state(&self) -> u8 { if self.age < 3 { 1 } else { 0 } }
+	///     fn born(_state: u8, _parents: &[&Mortal]) -> Mortal { Mortal { age: 0 } }
+	///     fn update(&self, _state: u8) -> Mortal { Mortal { age: self.age + 1 } }
+	/// }
+	/// 
+	/// let block = [(1, 1), (2, 1), (1, 2), (2, 2)].map(|(x, y)| (Vec2::new(x, y), Mortal { age: 0 })).to_vec();
+	/// let mut life = StateAutomaton::new(presets::life(), Vec2::new(4, 4), block).unwrap();
+	/// 
+	/// life.tick_n(2);
+	/// assert_eq!(life.cells().count(), 4);
+	/// life.tick();
+	/// assert_eq!(life.cells().count(), 0);
+	/// assert_eq!(life.get_state(&Vec2::new(1, 1)), Some(0));
+	/// ```
+	pub fn tick(&mut self) {
+		let (bounds, offsets) = (self.bounds, &self.offsets);
+		let position = |i: usize| Vec2::new(i % bounds.x(), i / bounds.x());
+
+		self.grid.tick(
+			|i| { let v = position(i); offsets.iter().map(move |offset| v.checked_add(offset, &bounds).map(|n| n.y() * bounds.x() + n.x())) },
+			|i| { let v = position(i); vec![v.x() as u64, v.y() as u64] }
+		);
+	}
+
+	/// Ticks the automaton `n` times.
+	pub fn tick_n(&mut self, n: usize) {
+		for _ in 0..n { self.tick(); }
+	}
+
+	/// Seeds the random number generator used by stochastic rules. Automata start out seeded with 0.
+	pub fn seed_rng(&mut self, seed: u64) {
+		self.grid.rng = Rng::new(seed);
+	}
+
+	/// Get a cell, or `None` if it's dead or outside the grid.
+	pub fn get(&self, v: &Vec2) -> Option<&S> {
+		self.index_of(v).and_then(|i| self.grid.cells[i].as_ref())
+	}
+
+	/// Get the state the rules see a cell in, or `None` if it's outside the grid.
+	pub fn get_state(&self, v: &Vec2) -> Option<u8> {
+		self.index_of(v).map(|i| self.grid.state(i))
+	}
+
+	/// Puts a cell into the grid, replacing whatever was there. Cells in state 0 kill whatever was there instead, and cells outside the grid are ignored.
+	pub fn set(&mut self, v: Vec2, cell: S) {
+		if let Some(i) = self.index_of(&v) { self.grid.cells[i] = (cell.state() > 0).then_some(cell); }
+	}
+
+	/// Kills a cell.
+	pub fn kill(&mut self, v: &Vec2) {
+		if let Some(i) = self.index_of(v) { self.grid.cells[i] = None; }
+	}
+
+	/// Goes through every live or dying cell, row by row.
+	pub fn cells(&self) -> impl Iterator<Item = (Vec2, &S)> {
+		let width = self.bounds.x();
+		self.grid.cells.iter().enumerate().filter_map(move |(i, cell)| cell.as_ref().map(|cell| (Vec2::new(i % width, i / width), cell)))
+	}
+
+	/// Get the rules the automaton follows.
+	pub fn get_rules(&self) -> &AutomataRules {
+		&self.grid.rules
+	}
+
+	/// Get the bounds (the size of the grid).
+	pub fn get_bounds(&self) -> &Vec2 {
+		&self.bounds
+	}
+
+	/// Get how many generations the automaton has been ticked through.
+	pub fn get_generation(&self) -> u64 {
+		self.grid.generation
+	}
+
+	/// Copies the state of every cell into a plain `flat::Automaton`, for anything that only cares about states (like drawing or analysis).
+	pub fn to_automaton(&self) -> Result<flat::Automaton, AutomataError> {
+		let mut automaton = flat::Automaton::new(self.grid.rules.clone(), self.bounds, Vec::new())?;
+		for (v, cell) in self.cells() { automaton.set_cell(v, cell.state()); }
+		automaton.set_generation(self.grid.generation);
+		Ok(automaton)
+	}
+
+	/// Get where a cell is in the grid, or `None` if it's outside the grid.
+	fn index_of(&self, v: &Vec2) -> Option<usize> {
+		(v.x() < self.bounds.x() && v.y() < self.bounds.y()).then(|| v.y() * self.bounds.x() + v.x())
+	}
+}
+
+impl<S: CellState> DeepStateAutomaton<S> {
+	/// Creates a new automaton with the given rules, bounds, and starting cells. Cells outside the grid, or in state 0, are left out.
+	/// This fails for the same reasons `deep::Automaton::new` does.
+	/// 
+	/// ```
+	/// # use cellular_snapp::{presets, cellstate::DeepStateAutomaton, deep::Vec3};
+	/// let mut a: DeepStateAutomaton = DeepStateAutomaton::new(presets::rule_445(), Vec3::new(4, 4, 4), vec![(Vec3::new(1, 1, 1), 4)]).unwrap();
+	/// a.tick();
+	/// 
+	/// // a lone cell doesn't have the 4 neighbors it needs to survive, so it starts dying
+	/// assert_eq!(a.get(&Vec3::new(1, 1, 1)), Some(&3));
+	/// assert_eq!(a.cells().count(), 1);
+	/// ```
+	pub fn new(rules: AutomataRules, bounds: Vec3, start_cells: Vec<(Vec3, S)>) -> Result<DeepStateAutomaton<S>, AutomataError> {
+		rules.validate(deep::max_neighbors(rules.method())?, 3)?;
+
+		let offsets = deep::neighbor_offsets(rules.method());
+		let mut automaton = DeepStateAutomaton { grid: Grid::new(rules, bounds.x() * bounds.y() * bounds.z()), bounds, offsets };
+		for (v, cell) in start_cells { automaton.set(v, cell); }
+		Ok(automaton)
+	}
+
+	/// Advances the automaton by one tick. Cells that come to life are made with `born`, and every other cell that's still live or dying gets `update`d.
+	pub fn tick(&mut self) {
+		let (bounds, offsets) = (self.bounds, &self.offsets);
+		let position = |i: usize| Vec3::new(i % bounds.x(), i / bounds.x() % bounds.y(), i / (bounds.x() * bounds.y()));
+
+		self.grid.tick(
+			|i| { let v = position(i); offsets.iter().map(move |offset| v.checked_add(offset, &bounds).map(|n| (n.z() * bounds.y() + n.y()) * bounds.x() + n.x())) },
+			|i| { let v = position(i); vec![v.x() as u64, v.y() as u64, v.z() as u64] }
+		);
+	}
+
+	/// Ticks the automaton `n` times.
+	pub fn tick_n(&mut self, n: usize) {
+		for _ in 0..n { self.tick(); }
+	}
+
+	/// Seeds the random number generator used by stochastic rules. Automata start out seeded with 0.
+	pub fn seed_rng(&mut self, seed: u64) {
+		self.grid.rng = Rng::new(seed);
+	}
+
+	/// Get a cell, or `None` if it's dead or outside the grid.
+	pub fn get(&self, v: &Vec3) -> Option<&S> {
+		self.index_of(v).and_then(|i| self.grid.cells[i].as_ref())
+	}
+
+	/// Get the state the rules see a cell in, or `None` if it's outside the grid.
+	pub fn get_state(&self, v: &Vec3) -> Option<u8> {
+		self.index_of(v).map(|i| self.grid.state(i))
+	}
+
+	/// Puts a cell into the grid, replacing whatever was there. Cells in state 0 kill whatever was there instead, and cells outside the grid are ignored.
+	pub fn set(&mut self, v: Vec3, cell: S) {
+		if let Some(i) = self.index_of(&v) { self.grid.cells[i] = (cell.state() > 0).then_some(cell); }
+	}
+
+	/// Kills a cell.
+	pub fn kill(&mut self, v: &Vec3) {
+		if let Some(i) = self.index_of(v) { self.grid.cells[i] = None; }
+	}
+
+	/// Goes through every live or dying cell, row by row and then layer by layer.
+	pub fn cells(&self) -> impl Iterator<Item = (Vec3, &S)> {
+		let (width, height) = (self.bounds.x(), self.bounds.y());
+		self.grid.cells.iter().enumerate().filter_map(move |(i, cell)| cell.as_ref().map(|cell| (Vec3::new(i % width, i / width % height, i / (width * height)), cell)))
+	}
+
+	/// Get the rules the automaton follows.
+	pub fn get_rules(&self) -> &AutomataRules {
+		&self.grid.rules
+	}
+
+	/// Get the bounds (the size of the grid).
+	pub fn get_bounds(&self) -> &Vec3 {
+		&self.bounds
+	}
+
+	/// Get how many generations the automaton has been ticked through.
+	pub fn get_generation(&self) -> u64 {
+		self.grid.generation
+	}
+
+	/// Copies the state of every cell into a plain `deep::Automaton`, for anything that only cares about states (like drawing or analysis).
+	pub fn to_automaton(&self) -> Result<deep::Automaton, AutomataError> {
+		let mut automaton = deep::Automaton::new(self.grid.rules.clone(), self.bounds, Vec::new())?;
+		for (v, cell) in self.cells() { automaton.set_cell(v, cell.state()); }
+		automaton.set_generation(self.grid.generation);
+		Ok(automaton)
+	}
+
+	/// Get where a cell is in the grid, or `None` if it's outside the grid.
+	fn index_of(&self, v: &Vec3) -> Option<usize> {
+		(v.x() < self.bounds.x() && v.y() < self.bounds.y() && v.z() < self.bounds.z()).then(|| (v.z() * self.bounds.y() + v.y()) * self.bounds.x() + v.x())
+	}
+}
+
+impl<S: CellState> Grid<S> {
+	/// Creates a grid of `len` dead cells.
+	fn new(rules: AutomataRules, len: usize) -> Grid<S> {
+		Grid { rules, cells: vec![None; len], rng: Rng::new(0), generation: 0 }
+	}
+
+	/// Get the state the rules see a cell in, which is 0 for dead cells and never more than alive.
+	fn state(&self, i: usize) -> u8 {
+		self.cells[i].as_ref().map_or(0, |cell| cell.state().min(self.rules.states() - 1))
+	}
+
+	/// Ticks every cell, given where each cell's neighbors are in the grid (`None` for neighbors past the edge) and its coordinates for random rolls.
+	fn tick<N: Iterator<Item = Option<usize>> + Clone>(&mut self, neighbors: impl Fn(usize) -> N, coords: impl Fn(usize) -> Vec<u64>) {
+		let key = self.rng.next_u64();
+		let stochastic = self.rules.is_stochastic();
+
+		let next = (0..self.cells.len()).map(|i| {
+			let states = neighbors(i).map(|n| n.map_or(0, |n| self.state(n)));
+			let roll = if stochastic { Rng::keyed(key, &coords(i)).next_f64() } else { 0.0 };
+			let (state, next) = (self.state(i), self.rules.next_state_from(self.state(i), states, roll));
+
+			let cell = match (&self.cells[i], next) {
+				(_, 0) => None,
+				(Some(cell), _) if state > 0 => Some(cell.update(next)),
+				_ => {
+					let parents: Vec<&S> = neighbors(i).filter_map(|n| n.and_then(|n| self.cells[n].as_ref())).collect();
+					Some(S::born(next, &parents))
+				}
+			};
+
+			// whatever the cell says it's in is what it's in, so a cell that says it's dead is gone
+			cell.filter(|cell| cell.state() > 0)
+		}).collect();
+
+		self.cells = next;
+		self.generation += 1;
+	}
+}
+
+impl CellState for u8 {
+	fn state(&self) -> u8 { *self }
+
+	fn born(state: u8, _parents: &[&u8]) -> u8 { state }
+
+	fn update(&self, state: u8) -> u8 { state }
+}
+
+/// Lets automata of cells be used by anything written for any `CellularAutomaton`. Setting a cell's state makes a newborn in that state, without any parents.
+impl<S: CellState> CellularAutomaton for StateAutomaton<S> {
+	type Coord = Vec2;
+
+	fn tick(&mut self) { StateAutomaton::tick(self); }
+
+	fn live_cells(&self) -> HashMap<Vec2, u8> { self.cells().map(|(v, cell)| (v, cell.state().min(self.grid.rules.states() - 1))).collect() }
+
+	fn get_cell(&self, v: &Vec2) -> Option<u8> { self.get_state(v) }
+
+	fn set_cell(&mut self, v: Vec2, state: u8) { if state == 0 { self.kill(&v) } else { self.set(v, S::born(state, &[])) } }
+
+	fn bounds(&self) -> Vec2 { self.bounds }
+
+	fn generation(&self) -> u64 { self.grid.generation }
+
+	fn rules(&self) -> &AutomataRules { &self.grid.rules }
+}
+
+/// Lets automata of cells be used by anything written for any `CellularAutomaton`. Setting a cell's state makes a newborn in that state, without any parents.
+impl<S: CellState> CellularAutomaton for DeepStateAutomaton<S> {
+	type Coord = Vec3;
+
+	fn tick(&mut self) { DeepStateAutomaton::tick(self); }
+
+	fn live_cells(&self) -> HashMap<Vec3, u8> { self.cells().map(|(v, cell)| (v, cell.state().min(self.grid.rules.states() - 1))).collect() }
+
+	fn get_cell(&self, v: &Vec3) -> Option<u8> { self.get_state(v) }
+
+	fn set_cell(&mut self, v: Vec3, state: u8) { if state == 0 { self.kill(&v) } else { self.set(v, S::born(state, &[])) } }
+
+	fn bounds(&self) -> Vec3 { self.bounds }
+
+	fn generation(&self) -> u64 { self.grid.generation }
+
+	fn rules(&self) -> &AutomataRules { &self.grid.rules }
+}
@@ -0,0 +1,390 @@
+//! A compact binary format for saving an automaton partway through a run and picking it back up later.
+//! 
+//! A checkpoint starts with a header: the bytes `CELL`, a version byte, how many dimensions the grid has, the bounds, the generation,
+//! the random number generator's state, the backend, and the rules (as a rule string, plus everything a rule string leaves out, like neighbor weights and directions).
+//! After that come the edge state, the update scheme, every wall, and the trail (if trails are being tracked).
+//! Then come the cells, as runs: each run skips over some dead cells and then lists the states of some live or dying ones, in the order they're laid out row by row.
+//! Dead space costs a couple of bytes no matter how big it is, so huge mostly-empty grids stay small.
+//! 
+//! Numbers are little-endian, and counts are written as LEB128 varints.
+//! 
+//! There's also a simpler format for `to_bytes`, which has nothing but the bounds and every cell in the grid, as runs of the same state.
+
+//--> Imports <--
+
+use crate::{AutomataRules, Backend, DecayMode, Inheritance, UpdateScheme, Wall};
+use std::io::{self, Read, Write};
+
+//--> Structs <--
+
+/// Everything in a checkpoint except the cells.
+pub(crate) struct Header {
+	pub(crate) bounds: Vec<u64>,
+	pub(crate) generation: u64,
+	pub(crate) rng: u64,
+	pub(crate) backend: Backend,
+	pub(crate) rules: AutomataRules,
+	pub(crate) edge: u8,
+	pub(crate) scheme: UpdateScheme,
+	/// Every wall, as its index in the grid, what kind it is, and the state it's holding, sorted by index.
+	pub(crate) walls: Vec<(u64, Wall, u8)>,
+	/// How many ticks ago every cell in the trail was last alive, by index in the grid, if trails are being tracked.
+	pub(crate) trails: Option<Vec<(u64, u32)>>
+}
+
+/// Runs of cells in the order they're laid out, as each run's state and how many cells long it is.
+type Runs = Vec<(u8, usize)>;
+
+//--> Functions <--
+
+const MAGIC: &[u8; 4] = b"CELL";
+const VERSION: u8 = 2;
+
+/// The most cells a grid loaded from a checkpoint can have when its backend makes room for every cell, since a few bytes of bounds could otherwise ask for terabytes.
+const MOST_CELLS: u64 = 1 << 32;
+
+impl Header {
+	/// Writes the header out. Rules without a rule string that reads back in, like most transition tables, can't be saved.
+	pub(crate) fn write(&self, w: &mut impl Write) -> io::Result<()> {
+		let rule_string = self.rules.to_string();
+
+		if rule_string.parse::<AutomataRules>().is_err() {
+			return Err(io::Error::new(io::ErrorKind::InvalidInput, format!("the rules '{}' can't be written into a checkpoint", rule_string)));
+		}
+
+		w.write_all(MAGIC)?;
+		w.write_all(&[VERSION, self.bounds.len() as u8])?;
+		for b in &self.bounds { w.write_all(&b.to_le_bytes())?; }
+		w.write_all(&self.generation.to_le_bytes())?;
+		w.write_all(&self.rng.to_le_bytes())?;
+
+		let backend = match self.backend {
+			Backend::HashMap => 0,
+			Backend::Tree => 1,
+			Backend::BitPacked => 2,
+			Backend::Dense => 3,
+			Backend::Incremental => 4,
+			#[cfg(feature = "mmap")]
+			Backend::Mapped => 5
+		};
+
+		let decay_mode = match self.rules.decay_mode() {
+			DecayMode::Counted => 0,
+			DecayMode::Generations => 1,
+			DecayMode::Revivable => 2,
+			DecayMode::RevivableGenerations => 3
+		};
+
+		let inheritance = match self.rules.inheritance() {
+			Inheritance::Majority => 0,
+			Inheritance::MajorityOrMissing => 1
+		};
+
+		w.write_all(&[backend])?;
+		write_varint(w, rule_string.len() as u64)?;
+		w.write_all(rule_string.as_bytes())?;
+		w.write_all(&[decay_mode])?;
+		w.write_all(&self.rules.birth_chance().to_le_bytes())?;
+		w.write_all(&self.rules.survival_chance().to_le_bytes())?;
+		w.write_all(&[self.rules.species(), inheritance])?;
+
+		// weights are a byte for every state, and directions are a flag for every neighbor, first for being born and then for surviving
+		match self.rules.weight_table() {
+			Some(weights) => { w.write_all(&[1])?; w.write_all(weights)?; }
+			None => w.write_all(&[0])?
+		}
+
+		match self.rules.direction_masks() {
+			Some((birth, survival)) => {
+				write_varint(w, birth.len() as u64)?;
+				w.write_all(&birth.iter().chain(survival).map(|&d| d as u8).collect::<Vec<u8>>())?
+			}
+			None => write_varint(w, 0)?
+		}
+
+		w.write_all(&[self.edge])?;
+		match self.scheme {
+			UpdateScheme::Synchronous => w.write_all(&[0])?,
+			UpdateScheme::RandomAsync(seed) => { w.write_all(&[1])?; w.write_all(&seed.to_le_bytes())?; }
+			UpdateScheme::Sequential => w.write_all(&[2])?
+		}
+
+		write_varint(w, self.walls.len() as u64)?;
+		for (i, wall, state) in &self.walls {
+			let wall = match wall { Wall::Alive => 0, Wall::Dead => 1, Wall::Excluded => 2 };
+			write_varint(w, *i)?;
+			w.write_all(&[wall, *state])?;
+		}
+
+		match &self.trails {
+			Some(trails) => {
+				w.write_all(&[1])?;
+				write_varint(w, trails.len() as u64)?;
+				for (i, since) in trails {
+					write_varint(w, *i)?;
+					write_varint(w, *since as u64)?;
+				}
+				Ok(())
+			}
+			None => w.write_all(&[0])
+		}
+	}
+
+	/// Reads a header back in, making sure it's for a grid with the given amount of dimensions.
+	pub(crate) fn read(r: &mut impl Read, dimensions: usize) -> io::Result<Header> {
+		let mut magic = [0; 4];
+		r.read_exact(&mut magic)?;
+		if &magic != MAGIC { return Err(invalid("this isn't a checkpoint")); }
+
+		let [version, dims] = read_bytes(r)?;
+		// the first version didn't have weights or directions, which is the same as a checkpoint that has neither
+		if version != VERSION && version != 1 { return Err(invalid(&format!("checkpoint version {} isn't supported", version))); }
+		if dims as usize != dimensions { return Err(invalid(&format!("this checkpoint is for a {}D grid", dims))); }
+
+		let bounds = (0..dimensions).map(|_| read_u64(r)).collect::<io::Result<Vec<u64>>>()?;
+		let (generation, rng) = (read_u64(r)?, read_u64(r)?);
+
+		let backend = match read_bytes::<1>(r)?[0] {
+			0 => Backend::HashMap,
+			1 => Backend::Tree,
+			2 => Backend::BitPacked,
+			3 => Backend::Dense,
+			4 => Backend::Incremental,
+			#[cfg(feature = "mmap")]
+			5 => Backend::Mapped,
+			// mapped grids are laid out just like dense ones, so they can still be loaded without the feature
+			#[cfg(not(feature = "mmap"))]
+			5 => Backend::Dense,
+			b => return Err(invalid(&format!("unknown backend {}", b)))
+		};
+
+		let len = read_varint(r)?;
+		let rule_string = read_vec(r, len)?;
+		let rule_string = String::from_utf8(rule_string).map_err(|_| invalid("the rule string isn't UTF-8"))?;
+
+		let decay_mode = match read_bytes::<1>(r)?[0] {
+			0 => DecayMode::Counted,
+			1 => DecayMode::Generations,
+			2 => DecayMode::Revivable,
+			3 => DecayMode::RevivableGenerations,
+			d => return Err(invalid(&format!("unknown decay mode {}", d)))
+		};
+
+		let (birth, survival) = (f64::from_le_bytes(read_bytes(r)?), f64::from_le_bytes(read_bytes(r)?));
+		let [species, inheritance] = read_bytes(r)?;
+
+		let inheritance = match inheritance {
+			0 => Inheritance::Majority,
+			1 => Inheritance::MajorityOrMissing,
+			i => return Err(invalid(&format!("unknown inheritance {}", i)))
+		};
+
+		let mut rules = rule_string.parse::<AutomataRules>().map_err(|e| invalid(&e.to_string()))?
+			.with_decay_mode(decay_mode)
+			.with_chances(birth, survival)
+			.with_species(species, inheritance);
+
+		if version > 1 {
+			match read_bytes::<1>(r)?[0] {
+				0 => {}
+				1 => rules = rules.with_weight_table(read_bytes::<256>(r)?.to_vec()),
+				w => return Err(invalid(&format!("unknown weights flag {}", w)))
+			}
+
+			let neighbors = read_varint(r)?;
+			if neighbors > 255 { return Err(invalid("there are too many directions")); }
+
+			if neighbors > 0 {
+				let masks = read_vec(r, 2 * neighbors)?;
+				if masks.iter().any(|&d| d > 1) { return Err(invalid("directions have to be 0 or 1")); }
+
+				let (birth, survival) = masks.split_at(neighbors as usize);
+				rules = rules.with_direction_masks(birth.iter().map(|&d| d == 1).collect(), survival.iter().map(|&d| d == 1).collect());
+			}
+		}
+
+		let (mut edge, mut scheme, mut walls, mut trails) = (0, UpdateScheme::Synchronous, Vec::new(), None);
+
+		if version > 1 {
+			edge = read_bytes::<1>(r)?[0];
+			scheme = match read_bytes::<1>(r)?[0] {
+				0 => UpdateScheme::Synchronous,
+				1 => UpdateScheme::RandomAsync(read_u64(r)?),
+				2 => UpdateScheme::Sequential,
+				s => return Err(invalid(&format!("unknown update scheme {}", s)))
+			};
+
+			// counts aren't trusted enough to make room for up front, since a few bytes can claim billions of walls
+			for _ in 0..read_varint(r)? {
+				let i = read_varint(r)?;
+				let [wall, state] = read_bytes(r)?;
+				let wall = match wall {
+					0 => Wall::Alive,
+					1 => Wall::Dead,
+					2 => Wall::Excluded,
+					w => return Err(invalid(&format!("unknown wall {}", w)))
+				};
+				walls.push((i, wall, state));
+			}
+
+			if read_bytes::<1>(r)?[0] == 1 {
+				let mut trail = Vec::new();
+				for _ in 0..read_varint(r)? {
+					let (i, since) = (read_varint(r)?, read_varint(r)?);
+					trail.push((i, u32::try_from(since).map_err(|_| invalid("a trail is too old"))?));
+				}
+				trails = Some(trail);
+			}
+		}
+
+		Ok(Header { bounds, generation, rng, backend, rules, edge, scheme, walls, trails })
+	}
+}
+
+/// Works out how many cells a grid with the given bounds has, making sure the backend could actually make room for them.
+/// Every product of the bounds has to fit, not just the whole thing, since backends multiply some of them together before the rest (and one of them could be 0).
+pub(crate) fn area(bounds: &[u64], backend: Backend) -> io::Result<u64> {
+	let too_big = || invalid("the grid is too big");
+
+	let most = bounds.iter().try_fold(1u64, |n, b| n.checked_mul((*b).max(1))).ok_or_else(too_big)?;
+	let keeps_every_cell = !matches!(backend, Backend::HashMap | Backend::Tree);
+	if usize::try_from(most).is_err() || keeps_every_cell && most > MOST_CELLS { return Err(too_big()); }
+
+	Ok(bounds.iter().product())
+}
+
+/// Writes out every live or dying cell as runs. Cells are given as their index in the grid (counting row by row) and their state, sorted by index.
+pub(crate) fn write_cells(w: &mut impl Write, cells: &[(u64, u8)]) -> io::Result<()> {
+	let mut runs: Vec<(u64, Vec<u8>)> = Vec::new();
+	let mut next = 0;
+
+	for (i, s) in cells {
+		match runs.last_mut() {
+			Some((_, states)) if *i == next => states.push(*s),
+			_ => runs.push((i - next, vec![*s]))
+		}
+
+		next = i + 1;
+	}
+
+	write_varint(w, runs.len() as u64)?;
+
+	for (skip, states) in runs {
+		write_varint(w, skip)?;
+		write_varint(w, states.len() as u64)?;
+		w.write_all(&states)?;
+	}
+
+	Ok(())
+}
+
+/// Reads the cells back in, as their index in the grid and their state.
+pub(crate) fn read_cells(r: &mut impl Read) -> io::Result<Vec<(u64, u8)>> {
+	let mut cells = Vec::new();
+	let mut next: u64 = 0;
+
+	for _ in 0..read_varint(r)? {
+		next = next.checked_add(read_varint(r)?).ok_or_else(|| invalid("a run goes past the end of the grid"))?;
+
+		let len = read_varint(r)?;
+		for s in read_vec(r, len)? {
+			cells.push((next, s));
+			next = next.checked_add(1).ok_or_else(|| invalid("a run goes past the end of the grid"))?;
+		}
+	}
+
+	Ok(cells)
+}
+
+/// Writes a whole grid for `to_bytes`: how many dimensions it has, the bounds, and then every cell (dead ones included) row by row,
+/// as runs of the same state, each one being the state and then how long the run is.
+pub(crate) fn write_grid(bounds: &[usize], states: impl Iterator<Item = u8>) -> Vec<u8> {
+	let mut bytes = vec![bounds.len() as u8];
+	for b in bounds { write_varint(&mut bytes, *b as u64).unwrap(); }
+
+	let mut run: Option<(u8, u64)> = None;
+	for s in states {
+		match &mut run {
+			Some((state, len)) if *state == s => *len += 1,
+			_ => {
+				if let Some((state, len)) = run { bytes.push(state); write_varint(&mut bytes, len).unwrap(); }
+				run = Some((s, 1));
+			}
+		}
+	}
+
+	if let Some((state, len)) = run { bytes.push(state); write_varint(&mut bytes, len).unwrap(); }
+	bytes
+}
+
+/// Reads a grid written by `write_grid` back in, giving back the bounds and the runs of cells row by row (as each run's state and length), or what went wrong if it doesn't make sense.
+/// The runs are given back as they are instead of as every cell's state, since a few bytes can describe a grid far too big to hold a byte for every cell.
+pub(crate) fn read_grid(mut bytes: &[u8], dimensions: usize) -> Result<(Vec<usize>, Runs), String> {
+	let r = &mut bytes;
+	let [dims] = read_bytes(r).map_err(|_| String::from("there aren't any bytes"))?;
+	if dims as usize != dimensions { return Err(format!("the grid should have {} dimensions, but it has {}", dimensions, dims)); }
+
+	let bounds = (0..dimensions).map(|_| read_varint(r).map(|b| b as usize)).collect::<io::Result<Vec<usize>>>().map_err(|_| String::from("the bytes stop partway through the bounds"))?;
+	let area = bounds.iter().try_fold(1usize, |n, b| n.checked_mul(*b)).ok_or("the grid is too big")?;
+
+	let (mut runs, mut cells) = (Vec::new(), 0);
+	while !r.is_empty() {
+		let [state] = read_bytes(r).map_err(|e| e.to_string())?;
+		let len = read_varint(r).map_err(|_| String::from("the bytes stop partway through a run"))?;
+		if len > (area - cells) as u64 { return Err(String::from("a run goes past the end of the grid")); }
+		runs.push((state, len as usize));
+		cells += len as usize;
+	}
+
+	if cells != area { return Err(format!("the grid should have {} cells, but there are only {}", area, cells)); }
+	Ok((bounds, runs))
+}
+
+/// Reads the given amount of bytes. Only as much room as there are bytes to read gets made, so a length that's way too big can't make it run out of memory.
+fn read_vec(r: &mut impl Read, len: u64) -> io::Result<Vec<u8>> {
+	let mut bytes = Vec::new();
+	r.take(len).read_to_end(&mut bytes)?;
+	if (bytes.len() as u64) < len { return Err(io::Error::from(io::ErrorKind::UnexpectedEof)); }
+	Ok(bytes)
+}
+
+/// Makes an error for a checkpoint that doesn't make sense.
+pub(crate) fn invalid(reason: &str) -> io::Error {
+	io::Error::new(io::ErrorKind::InvalidData, format!("couldn't read the checkpoint: {}", reason))
+}
+
+/// Writes a count as a varint, 7 bits at a time with the top bit saying whether there's more.
+pub(crate) fn write_varint(w: &mut impl Write, mut n: u64) -> io::Result<()> {
+	loop {
+		let byte = (n & 0x7F) as u8;
+		n >>= 7;
+
+		if n == 0 { return w.write_all(&[byte]); }
+		w.write_all(&[byte | 0x80])?;
+	}
+}
+
+/// Reads a varint back in.
+pub(crate) fn read_varint(r: &mut impl Read) -> io::Result<u64> {
+	let mut n = 0;
+
+	for shift in (0..64).step_by(7) {
+		let [byte] = read_bytes(r)?;
+		n |= ((byte & 0x7F) as u64) << shift;
+		if byte & 0x80 == 0 { return Ok(n); }
+	}
+
+	Err(invalid("a number is too long"))
+}
+
+/// Reads a little-endian 64-bit number.
+fn read_u64(r: &mut impl Read) -> io::Result<u64> {
+	Ok(u64::from_le_bytes(read_bytes(r)?))
+}
+
+/// Reads exactly `N` bytes.
+fn read_bytes<const N: usize>(r: &mut impl Read) -> io::Result<[u8; N]> {
+	let mut bytes = [0; N];
+	r.read_exact(&mut bytes)?;
+	Ok(bytes)
+}
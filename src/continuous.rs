@@ -0,0 +1,191 @@
+//! This module has continuous-state automata, where every cell holds a value between 0 and 1 instead of a handful of states.
+//! 
+//! Rather than counting neighbors, each cell looks at how "full" two regions around it are: an inner disk, and an outer ring around that disk.
+//! A growth function turns those two fillings into a rate of change, which gets added to the cell (scaled by the time step) every tick.
+//! That's enough to express both SmoothLife and Lenia.
+//! 
+//! ```
+//! # use cellular_snapp::{flat::Vec2, continuous::{ContinuousAutomaton, ContinuousRules}};
+//! let blob = (10..22).flat_map(|x| (10..22).map(move |y| (Vec2::new(x, y), 1.0))).collect::<Vec<_>>();
+//! let mut lenia = ContinuousAutomaton::new(ContinuousRules::lenia(6.0, 0.15, 0.015, 0.1), Vec2::new(32, 32), blob);
+//! 
+//! for _ in 0..10 { lenia.tick(); }
+//! 
+//! // cells always stay between 0 and 1, and nothing outside the grid exists
+//! assert_eq!(lenia.get_cells().len(), 32 * 32);
+//! assert!(lenia.get_cells().values().all(|f| (0.0..=1.0).contains(f)));
+//! ```
+//! 
+//! The reference implementation here runs on the CPU and checks every cell in the kernel, so it's best kept to smaller grids and radii.
+
+//--> Imports <--
+
+use crate::flat::{IVec2, Vec2};
+use std::collections::HashMap;
+
+//--> Structs <--
+
+/// The rules a continuous automaton follows.
+#[derive(Clone)]
+pub struct ContinuousRules {
+	inner_radius: f32,
+	outer_radius: f32,
+	growth: Growth,
+	time_step: f32
+}
+
+/// A 2D continuous-state automaton.
+pub struct ContinuousAutomaton {
+	rules: ContinuousRules,
+	bounds: Vec2,
+	cells: HashMap<Vec2, f32>,
+	kernel: Vec<(IVec2, f32, f32)>
+}
+
+//--> Enums <--
+
+/// How the fillings of the inner disk and outer ring turn into a rate of change.
+/// Rates go from -1 (the cell empties as fast as possible) to 1 (the cell fills as fast as possible).
+#[derive(Clone)]
+pub enum Growth {
+	/// Stephan Rafler's SmoothLife. A cell is born if the outer filling falls in the birth interval, and survives if it falls in the survival interval.
+	/// How alive the cell is (its inner filling) blends smoothly between the two.
+	SmoothLife { birth: (f32, f32), survival: (f32, f32) },
+	/// Bert Chan's Lenia. The rate peaks when the outer filling is at `mu`, falling off like a bell curve `sigma` wide. The inner filling isn't used.
+	Lenia { mu: f32, sigma: f32 },
+	/// Your own growth function, given the inner and outer fillings.
+	Custom(fn(f32, f32) -> f32)
+}
+
+//--> Functions <--
+
+impl ContinuousRules {
+	/// Creates new rules. The inner disk covers everything closer than `inner_radius`, and the outer ring everything from there out to `outer_radius`.
+	/// Each tick, the growth function's rate multiplied by `time_step` is added to every cell.
+	pub fn new(inner_radius: f32, outer_radius: f32, growth: Growth, time_step: f32) -> ContinuousRules {
+		ContinuousRules { inner_radius, outer_radius, growth, time_step }
+	}
+
+	/// SmoothLife with the parameters from Rafler's paper, where the inner disk is a third of the outer radius.
+	pub fn smooth_life(outer_radius: f32, time_step: f32) -> ContinuousRules {
+		ContinuousRules::new(outer_radius / 3.0, outer_radius, Growth::SmoothLife { birth: (0.278, 0.365), survival: (0.267, 0.445) }, time_step)
+	}
+
+	/// Lenia with a single ring kernel of the given radius.
+	/// Something like `lenia(13.0, 0.15, 0.015, 0.1)` is a good place to start looking for gliders.
+	pub fn lenia(radius: f32, mu: f32, sigma: f32, time_step: f32) -> ContinuousRules {
+		ContinuousRules::new(0.0, radius, Growth::Lenia { mu, sigma }, time_step)
+	}
+
+	/// Works out the rate of change for a cell with the given inner and outer fillings.
+	fn rate(&self, inner: f32, outer: f32) -> f32 {
+		match &self.growth {
+			Growth::SmoothLife { birth, survival } => {
+				let alive = sigmoid(inner, 0.5, 0.147);
+				let (low, high) = (lerp(birth.0, survival.0, alive), lerp(birth.1, survival.1, alive));
+				2.0 * sigmoid(outer, low, 0.028) * (1.0 - sigmoid(outer, high, 0.028)) - 1.0
+			},
+			Growth::Lenia { mu, sigma } => 2.0 * (-(outer - mu).powi(2) / (2.0 * sigma * sigma)).exp() - 1.0,
+			Growth::Custom(f) => f(inner, outer)
+		}
+	}
+
+	/// Builds the kernel: every offset within the outer radius, with how much it counts towards the inner and outer fillings.
+	/// Both sets of weights add up to 1, so the fillings are averages.
+	fn kernel(&self) -> Vec<(IVec2, f32, f32)> {
+		let reach = self.outer_radius.ceil() as i64;
+		let mut kernel = Vec::new();
+
+		for x in -reach..=reach {
+			for y in -reach..=reach {
+				let r = ((x * x + y * y) as f32).sqrt();
+				if r >= self.outer_radius { continue; }
+
+				let inner = if r < self.inner_radius { 1.0 } else { 0.0 };
+				let outer = match self.growth {
+					// Lenia's ring fades in and out smoothly, peaking halfway across
+					Growth::Lenia { .. } if r >= self.inner_radius => {
+						let q = (r - self.inner_radius) / (self.outer_radius - self.inner_radius);
+						if q > 0.0 { (4.0 - 1.0 / (q * (1.0 - q))).exp() } else { 0.0 }
+					},
+					_ => if r >= self.inner_radius { 1.0 } else { 0.0 }
+				};
+
+				if inner > 0.0 || outer > 0.0 { kernel.push((IVec2::new(x, y), inner, outer)); }
+			}
+		}
+
+		let inner_sum: f32 = kernel.iter().map(|k| k.1).sum();
+		let outer_sum: f32 = kernel.iter().map(|k| k.2).sum();
+
+		for k in kernel.iter_mut() {
+			if inner_sum > 0.0 { k.1 /= inner_sum; }
+			if outer_sum > 0.0 { k.2 /= outer_sum; }
+		}
+
+		kernel
+	}
+}
+
+impl ContinuousAutomaton {
+	/// Creates a new continuous automaton. Every cell starts at 0, apart from the given starting cells.
+	/// Starting values are clamped between 0 and 1, and starting cells outside the bounds are ignored.
+	pub fn new(rules: ContinuousRules, bounds: Vec2, start_cells: Vec<(Vec2, f32)>) -> ContinuousAutomaton {
+		let mut cells = HashMap::new();
+
+		for x in 0..bounds.x() {
+			for y in 0..bounds.y() {
+				cells.insert(Vec2::new(x, y), 0.0);
+			}
+		}
+
+		for (v, f) in start_cells {
+			if let Some(cell) = cells.get_mut(&v) { *cell = f.clamp(0.0, 1.0); }
+		}
+
+		let kernel = rules.kernel();
+		ContinuousAutomaton { rules, bounds, cells, kernel }
+	}
+
+	/// Advances the automaton by one time step (or tick). Anything outside the grid counts as empty.
+	pub fn tick(&mut self) {
+		self.cells = self.cells.iter().map(|(v, f)| {
+			let (mut inner, mut outer) = (0.0, 0.0);
+
+			for (offset, inner_weight, outer_weight) in &self.kernel {
+				if let Some(n) = v.checked_add_offset(offset).and_then(|n| self.cells.get(&n)) {
+					inner += n * inner_weight;
+					outer += n * outer_weight;
+				}
+			}
+
+			let next = f + self.rules.time_step * self.rules.rate(inner, outer);
+			(*v, next.clamp(0.0, 1.0))
+		}).collect();
+	}
+
+	/// Get a copy of the automaton's internal state (the cells).
+	pub fn get_cells(&self) -> HashMap<Vec2, f32> {
+		self.cells.clone()
+	}
+
+	/// Get the rules the automaton is currently following.
+	pub fn get_rules(&self) -> &ContinuousRules {
+		&self.rules
+	}
+
+	/// Get the bounds (the size of the grid) of the automaton.
+	pub fn get_bounds(&self) -> &Vec2 {
+		&self.bounds
+	}
+}
+
+/// A smooth step from 0 to 1, centered on `edge` and about `steepness` wide.
+fn sigmoid(x: f32, edge: f32, steepness: f32) -> f32 {
+	1.0 / (1.0 + (-(x - edge) * 4.0 / steepness).exp())
+}
+
+/// Blends between `a` and `b`.
+fn lerp(a: f32, b: f32, t: f32) -> f32 {
+	a + (b - a) * t
+}
@@ -1,8 +1,9 @@
-//! This module exists to help you create 3D (aka flat) cellular automata.
+//! This module exists to help you create 3D (aka deep) cellular automata.
 //! 
 //! To start, you'll want to decide on your rules and create an AutomataRules object containing them.
 //! 
 //! ```
+//! # use cellular_snapp::{AutomataRules, Rule, Method};
 //! let rules = AutomataRules::new(Rule::Single(4), Rule::Single(4), 5, Method::Moore);
 //! ```
 //! 
@@ -10,23 +11,147 @@
 
 //--> Imports <--
 
-use crate::{AutomataRules, Method, Rule};
+use crate::{Anchor, AutomataError, AutomataRules, Backend, BlendMode, CellView, CellularAutomaton, ConflictPolicy, CoordinateWidth, GrowthPolicy, MaskOp, Method, Occupancy, Profiler, Progress, Spare, UpdateScheme, Wall, hash_cells, in_slabs, in_slabs_mut, line, wrap_coordinate};
+use crate::flat;
+use crate::random::Rng;
+#[cfg(feature = "npy")]
+use crate::npy;
+#[cfg(feature = "profiling")]
+use crate::TickProfile;
+use crate::checkpoint;
+use crate::events::{self, Event};
+use crate::replay::ReplayLog;
+use crate::tree::Tree;
+use crate::bits::Bits;
+use crate::dense::Dense;
+use crate::incremental::Incremental;
 use std::hash::Hash;
-use std::ops::{Add, Sub};
-use std::default::Default;
-use std::collections::HashMap;
+use std::borrow::BorrowMut;
+use std::ops::{Add, ControlFlow, Index, IndexMut, Range, Sub};
+use std::num::TryFromIntError;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::io::{self, Read, Write};
+use std::sync::Arc;
 
 //--> Structs <--
 
 /// A position on a 3D grid, or the size of a 3D grid.
-#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Clone)]
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy, Default, Debug)]
 pub struct Vec3 { x: usize, y: usize, z: usize }
 
+/// A position on an unbounded 3D grid. Unlike Vec3, this can go negative.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Default, Debug)]
+pub struct IVec3 { x: i64, y: i64, z: i64 }
+
+/// A box-shaped region of a 3D grid, from `min` up to (but not including) `max`.
+#[derive(PartialEq, Eq, Hash, Clone, Default, Debug)]
+pub struct Box3 { min: Vec3, max: Vec3 }
+
 /// The humble 3D cellular automaton.
+#[derive(Clone)]
 pub struct Automaton {
 	rules: AutomataRules,
 	bounds: Vec3,
-	cells: HashMap<Vec3, u8>
+	cells: Cells,
+	previous: Option<Cells>,
+	spare: Spare<Cells>,
+	species: HashMap<Vec3, u8>,
+	walls: HashMap<Vec3, (Wall, u8)>,
+	edge: u8,
+	ages: Option<HashMap<Vec3, u32>>,
+	trails: Option<HashMap<Vec3, u32>>,
+	heat: Option<(f64, HashMap<Vec3, f64>)>,
+	offsets: Vec<IVec3>,
+	rng: Rng,
+	scheme: UpdateScheme,
+	profile: Profiler,
+	schedule: Option<(Vec<AutomataRules>, usize)>,
+	events: Option<Vec<Event>>,
+	threads: usize,
+	generation: u64
+}
+
+/// A 3D cellular automaton without any bounds, so structures can keep on growing forever.
+/// 
+/// Only live and dying cells are stored, so memory and tick time scale with the size of the pattern instead of the size of the grid.
+pub struct UnboundedAutomaton {
+	rules: AutomataRules,
+	cells: HashMap<IVec3, u8>,
+	species: HashMap<IVec3, u8>,
+	offsets: Vec<IVec3>,
+	limits: (Option<usize>, Option<(IVec3, IVec3)>, GrowthPolicy),
+	width: CoordinateWidth,
+	rng: Rng
+}
+
+/// A snapshot of every cell of an automaton at one generation, made by `Automaton::frame`, which never changes once it's made.
+/// It comes wrapped in an `Arc`, so a render thread can hang onto it (and clone it around for next to nothing) while the automaton keeps ticking on another thread.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Frame {
+	bounds: Vec3,
+	generation: u64,
+	cells: Vec<u8>
+}
+
+/// An endless stream of generations, made by `Automaton::generations` or `Automaton::generations_mut`.
+/// Every call to `next` ticks the automaton and hands back a snapshot of it, so this never runs out on its own. Use `take` to stop it.
+pub struct Generations<A: BorrowMut<Automaton>> {
+	automaton: A
+}
+
+/// A reusable shape made out of cells.
+/// 
+/// The coordinates in a pattern are relative to wherever you end up placing it, so the same shape can be stamped all over a grid.
+/// Cells can optionally carry their own state value. Cells without one are placed fully alive.
+#[derive(Clone)]
+pub struct Pattern {
+	name: String,
+	cells: Vec<(Vec3, Option<u8>)>
+}
+
+//--> Enums <--
+
+/// One of the three axes of a 3D grid, used to say what a rotation spins around.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Axis {
+	/// The x axis, running left to right.
+	X,
+	/// The y axis, running top to bottom.
+	Y,
+	/// The z axis, running front to back.
+	Z
+}
+
+/// Symmetry to give a random soup, since symmetric soups tend to grow into the most striking patterns.
+/// Everything is mirrored or rotated around the middle of the grid.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Symmetry {
+	/// The right half is the left half flipped over along x.
+	MirrorX,
+	/// The bottom half is the top half flipped over along y.
+	MirrorY,
+	/// The back half is the front half flipped over along z.
+	MirrorZ,
+	/// Flipped over along all three axes, so all eight corners mirror each other.
+	MirrorXYZ,
+	/// The same after every quarter turn around an axis. Turning only lines up with the grid when it's square across the axis, so cells that would turn out of bounds are left out.
+	FourFold(Axis),
+	/// The same after every quarter turn around an axis and every flip across it.
+	EightFold(Axis)
+}
+
+/// Where an automaton keeps its cells, depending on its backend.
+#[derive(Clone)]
+enum Cells {
+	Map(HashMap<Vec3, u8>),
+	Tree(Tree<3>),
+	/// The bits are laid out one z-layer after another, so this also keeps the grid's height around to work out which row a cell is in.
+	Bits(Bits, usize),
+	/// Laid out the same way as the bits.
+	Dense(Dense, usize),
+	/// Laid out the same way as the bits too, and this also keeps the grid's bounds and neighbor offsets around, to work out whose counts change when a cell gets set.
+	Incremental(Incremental, Vec3, Vec<IVec3>)
 }
 
 //--> Functions <--
@@ -34,6 +159,221 @@ pub struct Automaton {
 impl Vec3 {
 	/// Creates a new 3D position.
 	pub fn new(x: usize, y: usize, z: usize) -> Vec3 { Vec3 { x, y, z } }
+
+	/// Get the x component.
+	pub fn x(&self) -> usize { self.x }
+
+	/// Get the y component.
+	pub fn y(&self) -> usize { self.y }
+
+	/// Get the z component.
+	pub fn z(&self) -> usize { self.z }
+
+	/// Adds another position to this one, or returns None if that would go past the largest possible usize.
+	pub fn checked_add_position(&self, other: &Vec3) -> Option<Vec3> {
+		Some(Vec3 {
+			x: self.x.checked_add(other.x)?,
+			y: self.y.checked_add(other.y)?,
+			z: self.z.checked_add(other.z)?
+		})
+	}
+
+	/// Moves this position by a signed offset, or returns None if that would take it below zero (or past the largest possible usize).
+	pub fn checked_add_offset(&self, offset: &IVec3) -> Option<Vec3> {
+		Some(Vec3 {
+			x: usize::try_from(i64::try_from(self.x).ok()?.checked_add(offset.x)?).ok()?,
+			y: usize::try_from(i64::try_from(self.y).ok()?.checked_add(offset.y)?).ok()?,
+			z: usize::try_from(i64::try_from(self.z).ok()?.checked_add(offset.z)?).ok()?
+		})
+	}
+
+	/// Moves this position by a signed offset, or returns None if that would take it off a grid with the given bounds.
+	pub fn checked_add(&self, offset: &IVec3, bounds: &Vec3) -> Option<Vec3> {
+		self.checked_add_offset(offset).filter(|v| v.x < bounds.x && v.y < bounds.y && v.z < bounds.z)
+	}
+
+	/// Moves this position by a signed offset, stopping at the edges of a grid with the given bounds instead of going past them.
+	pub fn saturating_add(&self, offset: &IVec3, bounds: &Vec3) -> Vec3 {
+		let along = |v: usize, offset: i64, size: usize| (v as i128 + offset as i128).clamp(0, size.saturating_sub(1) as i128) as usize;
+		Vec3 {
+			x: along(self.x, offset.x, bounds.x),
+			y: along(self.y, offset.y, bounds.y),
+			z: along(self.z, offset.z, bounds.z)
+		}
+	}
+
+	/// Moves this position by a signed offset, wrapping around to the other side of a grid with the given bounds like on a torus.
+	pub fn wrapping_add(&self, offset: &IVec3, bounds: &Vec3) -> Vec3 {
+		let along = |v: usize, offset: i64, size: usize| (v as i128 + offset as i128).rem_euclid(size.max(1) as i128) as usize;
+		Vec3 {
+			x: along(self.x, offset.x, bounds.x),
+			y: along(self.y, offset.y, bounds.y),
+			z: along(self.z, offset.z, bounds.z)
+		}
+	}
+}
+
+impl Box3 {
+	/// Creates a new region from `min` up to (but not including) `max`.
+	pub fn new(min: Vec3, max: Vec3) -> Box3 { Box3 { min, max } }
+
+	/// Get the corner of the region closest to the origin, which is inside the region.
+	pub fn min(&self) -> &Vec3 { &self.min }
+
+	/// Get the corner of the region furthest from the origin, which is just outside the region.
+	pub fn max(&self) -> &Vec3 { &self.max }
+
+	/// Checks whether a position is inside the region.
+	pub fn contains(&self, v: &Vec3) -> bool {
+		v.x >= self.min.x && v.y >= self.min.y && v.z >= self.min.z && v.x < self.max.x && v.y < self.max.y && v.z < self.max.z
+	}
+
+	/// Checks whether there aren't any positions inside the region at all.
+	pub fn is_empty(&self) -> bool {
+		self.min.x >= self.max.x || self.min.y >= self.max.y || self.min.z >= self.max.z
+	}
+
+	/// Goes through every position inside the region, one layer at a time (by z), and row by row within each layer.
+	pub fn iter_positions(&self) -> impl Iterator<Item = Vec3> {
+		let (min, max) = (self.min, self.max);
+		(min.z..max.z).flat_map(move |z| (min.y..max.y).flat_map(move |y| (min.x..max.x).map(move |x| Vec3::new(x, y, z))))
+	}
+
+	/// Get the part of the region that's also inside another region, or `None` if they don't overlap.
+	pub fn intersect(&self, other: &Box3) -> Option<Box3> {
+		let overlap = Box3::new(
+			Vec3::new(self.min.x.max(other.min.x), self.min.y.max(other.min.y), self.min.z.max(other.min.z)),
+			Vec3::new(self.max.x.min(other.max.x), self.max.y.min(other.max.y), self.max.z.min(other.max.z))
+		);
+
+		(!overlap.is_empty()).then_some(overlap)
+	}
+
+	/// Grows the region by `n` cells on every side, except past 0, since positions can't be negative.
+	pub fn expand(&self, n: usize) -> Box3 {
+		Box3::new(
+			Vec3::new(self.min.x.saturating_sub(n), self.min.y.saturating_sub(n), self.min.z.saturating_sub(n)),
+			Vec3::new(self.max.x.saturating_add(n), self.max.y.saturating_add(n), self.max.z.saturating_add(n))
+		)
+	}
+}
+
+impl IVec3 {
+	/// Creates a new signed 3D position.
+	pub fn new(x: i64, y: i64, z: i64) -> IVec3 { IVec3 { x, y, z } }
+
+	/// Get the x component.
+	pub fn x(&self) -> i64 { self.x }
+
+	/// Get the y component.
+	pub fn y(&self) -> i64 { self.y }
+
+	/// Get the z component.
+	pub fn z(&self) -> i64 { self.z }
+
+	/// Adds another position to this one, or returns None if that would go past the largest or smallest coordinate there can be.
+	pub fn checked_add(&self, rhs: &IVec3) -> Option<IVec3> {
+		Some(IVec3 {
+			x: self.x.checked_add(rhs.x)?,
+			y: self.y.checked_add(rhs.y)?,
+			z: self.z.checked_add(rhs.z)?
+		})
+	}
+
+	/// Subtracts another position from this one, or returns None if that would go past the largest or smallest coordinate there can be.
+	pub fn checked_sub(&self, rhs: &IVec3) -> Option<IVec3> {
+		Some(IVec3 {
+			x: self.x.checked_sub(rhs.x)?,
+			y: self.y.checked_sub(rhs.y)?,
+			z: self.z.checked_sub(rhs.z)?
+		})
+	}
+
+	/// Adds another position to this one, stopping at the largest or smallest coordinate there can be instead of going past it.
+	pub fn saturating_add(&self, rhs: &IVec3) -> IVec3 {
+		IVec3 {
+			x: self.x.saturating_add(rhs.x),
+			y: self.y.saturating_add(rhs.y),
+			z: self.z.saturating_add(rhs.z)
+		}
+	}
+
+	/// Adds another position to this one, wrapping around from the largest coordinate there can be to the smallest (and the other way around).
+	pub fn wrapping_add(&self, rhs: &IVec3) -> IVec3 {
+		IVec3 {
+			x: self.x.wrapping_add(rhs.x),
+			y: self.y.wrapping_add(rhs.y),
+			z: self.z.wrapping_add(rhs.z)
+		}
+	}
+}
+
+impl Symmetry {
+	/// Get every way a cell gets mirrored or turned, including leaving it alone, as a 3x3 matrix written out a row at a time.
+	fn transforms(&self) -> Vec<[i64; 9]> {
+		let flip = |x: i64, y: i64, z: i64| [x, 0, 0, 0, y, 0, 0, 0, z];
+
+		// a quarter turn around the axis, and which axis gets flipped for the mirrored turns
+		let (turn, mirror) = match self {
+			Symmetry::FourFold(axis) | Symmetry::EightFold(axis) => match axis {
+				Axis::X => ([1, 0, 0, 0, 0, -1, 0, 1, 0], flip(1, -1, 1)),
+				Axis::Y => ([0, 0, 1, 0, 1, 0, -1, 0, 0], flip(-1, 1, 1)),
+				Axis::Z => ([0, -1, 0, 1, 0, 0, 0, 0, 1], flip(-1, 1, 1))
+			},
+			_ => (flip(1, 1, 1), flip(1, 1, 1))
+		};
+
+		let mut turns = vec![flip(1, 1, 1)];
+		for _ in 0..3 { turns.push(mul(&turn, turns.last().unwrap())); }
+
+		match self {
+			Symmetry::MirrorX => vec![flip(1, 1, 1), flip(-1, 1, 1)],
+			Symmetry::MirrorY => vec![flip(1, 1, 1), flip(1, -1, 1)],
+			Symmetry::MirrorZ => vec![flip(1, 1, 1), flip(1, 1, -1)],
+			Symmetry::MirrorXYZ => (0..8).map(|i| flip(1 - 2 * (i & 1), 1 - (i & 2), 1 - (i & 4) / 2)).collect(),
+			Symmetry::FourFold(_) => turns,
+			Symmetry::EightFold(_) => turns.iter().flat_map(|t| [*t, mul(&mirror, t)]).collect()
+		}
+	}
+}
+
+/// Adding positions panics if it would go past the largest or smallest coordinate there can be, even in release builds.
+/// Use `checked_add`, `saturating_add`, or `wrapping_add` to pick what happens instead.
+impl Add for IVec3 {
+	type Output = IVec3;
+	fn add(self, rhs: IVec3) -> IVec3 {
+		self.checked_add(&rhs).expect("position went past the largest or smallest coordinate there can be")
+	}
+}
+
+/// Subtracting positions panics if it would go past the largest or smallest coordinate there can be, even in release builds.
+/// Use `checked_sub` to get `None` instead.
+impl Sub for IVec3 {
+	type Output = IVec3;
+	fn sub(self, rhs: IVec3) -> IVec3 {
+		self.checked_sub(&rhs).expect("position went past the largest or smallest coordinate there can be")
+	}
+}
+
+impl From<Vec3> for IVec3 {
+	fn from(v: Vec3) -> IVec3 {
+		IVec3 {
+			x: v.x as i64,
+			y: v.y as i64,
+			z: v.z as i64
+		}
+	}
+}
+
+impl TryFrom<IVec3> for Vec3 {
+	type Error = TryFromIntError;
+	fn try_from(v: IVec3) -> Result<Vec3, TryFromIntError> {
+		Ok(Vec3 {
+			x: usize::try_from(v.x)?,
+			y: usize::try_from(v.y)?,
+			z: usize::try_from(v.z)?
+		})
+	}
 }
 
 impl Add for Vec3 {
@@ -58,193 +398,2167 @@ impl Sub for Vec3 {
 	}
 }
 
-impl Default for Vec3 {
-	fn default() -> Vec3 { Vec3 { x: 0, y: 0, z: 0 } }
-}
-
 impl Automaton {
 	/// Creates a new deep (3D) automaton with the given rules, bounds, and starting cells.
 	/// This can fail if your survival and birth rules exceeds the amount of neighbors a cell could have, given your chosen neighbor counting method.
-	/// If that happens, this function will error out and return the maximum amount of neighbors.
-	pub fn new(rules: AutomataRules, bounds: Vec3, start_cells: Vec<Vec3>) -> Result<Automaton, u8> {
-		let other_rules = rules.clone();
-		let mut a = Automaton { rules, bounds, cells: HashMap::new() };
-
-		let max_neighbors: u8 = match a.rules.neighbor_method {
-			Method::Moore => 26,
-			Method::VonNeumann => 6
-		};
+	/// If that happens, this function will error out with the maximum amount of neighbors.
+	pub fn new(rules: AutomataRules, bounds: Vec3, start_cells: Vec<Vec3>) -> Result<Automaton, AutomataError> {
+		Automaton::with_backend(rules, bounds, start_cells, Backend::HashMap)
+	}
 
-		match other_rules.to_survive {
-			Rule::Single(s) => if s > max_neighbors { return Err(max_neighbors) },
-			Rule::Range(r) => if r.start > max_neighbors || r.end > max_neighbors { return Err(max_neighbors) },
-			Rule::Many(m) => for s in m {
-				if s > max_neighbors { return Err(max_neighbors) }
-			}
-		}
+	/// Creates a new deep (3D) automaton just like `new`, except the cells are kept in the given backend.
+	/// This also fails if the backend can't hold cells for the rules, like a bit-packed grid with more than two cell states.
+	pub fn with_backend(rules: AutomataRules, bounds: Vec3, start_cells: Vec<Vec3>, backend: Backend) -> Result<Automaton, AutomataError> {
+		rules.validate(max_neighbors(&rules.neighbor_method)?, 3)?;
+		rules.check_backend(backend)?;
 
-		match other_rules.to_be_born {
-			Rule::Single(s) => if s > max_neighbors { return Err(max_neighbors) },
-			Rule::Range(r) => if r.start > max_neighbors || r.end > max_neighbors { return Err(max_neighbors) },
-			Rule::Many(m) => for s in m {
-				if s > max_neighbors { return Err(max_neighbors) }
-			}
+		let mut cells = Cells::try_new(backend, &bounds, &rules)?;
+		for v in start_cells.into_iter().filter(|v| v.x < bounds.x && v.y < bounds.y && v.z < bounds.z) {
+			cells.set(v, rules.cell_states - 1);
 		}
 
-		for x in 0..a.bounds.x {
-			for y in 0..a.bounds.y {
-				for z in 0..a.bounds.z {
-					let v = Vec3::new(x, y, z);
+		let offsets = neighbor_offsets(&rules.neighbor_method);
+		Ok(Automaton { rules, bounds, cells, previous: None, spare: Spare(None), species: HashMap::new(), walls: HashMap::new(), edge: 0, ages: None, trails: None, heat: None, offsets, rng: Rng::new(0), scheme: UpdateScheme::Synchronous, profile: Profiler::default(), schedule: None, events: None, threads: 1, generation: 0 })
+	}
 
-					if start_cells.contains(&v) {
-						a.cells.insert(v, a.rules.cell_states - 1);
-					} else {
-						a.cells.insert(v, 0);
-					}
-				}
-			}
-		}
+	/// Sets how many threads each tick gets split up between. The grid is cut into chunks of layers along z, one per thread,
+	/// and each chunk is ticked on its own scoped thread from the standard library, so you get multithreading without pulling in rayon.
+	/// Automata start out with one thread, which ticks everything on the thread calling `tick`.
+	pub fn threads(mut self, threads: usize) -> Automaton {
+		self.threads = threads.max(1);
+		self
+	}
 
-		Ok(a)
+	/// Get how many threads each tick gets split up between.
+	pub fn get_threads(&self) -> usize {
+		self.threads
+	}
+
+	/// Get how long each part of the last `tick` (or `tick_region`) took, and how many cells it looked at. This needs the `profiling` feature.
+	/// Before the first tick, everything is 0.
+	#[cfg(feature = "profiling")]
+	pub fn last_tick_profile(&self) -> &TickProfile {
+		self.profile.last()
+	}
+
+	/// Get how many generations the automaton has been ticked through. Ticking counts up and `tick_backward` counts down,
+	/// but `tick_region` doesn't count, since only part of the grid moves on.
+	pub fn get_generation(&self) -> u64 {
+		self.generation
+	}
+
+	/// Sets how many generations the automaton has been ticked through, like when it's being synced to one somewhere else.
+	pub(crate) fn set_generation(&mut self, generation: u64) {
+		self.generation = generation;
+	}
+
+	/// Turns the automaton into an endless iterator of generations. Each item is a snapshot of the automaton right after a tick,
+	/// so the first one is one generation along from where the automaton is now.
+	pub fn generations(self) -> Generations<Automaton> {
+		Generations { automaton: self }
+	}
+
+	/// Like `generations`, except the automaton is only borrowed, so it's still around (and as far along as the iterator got it) afterwards.
+	pub fn generations_mut(&mut self) -> Generations<&mut Automaton> {
+		Generations { automaton: self }
 	}
 
 	/// Advances the automaton by one time step (or tick).
+	/// 
+	/// The grid gets changed in place instead of being built again from nothing, so ticking doesn't allocate much of anything.
+	/// Rules that only count neighbors on the bit-packed and dense backends tick a row at a time into a second grid, which gets kept around afterwards
+	/// and written over by the tick after that, so those backends hold onto twice the memory of their grid.
 	pub fn tick(&mut self) {
-		let neighbor_counts = self.cells.iter().map(|(v, _)| {
-			let mut count = 0;
-			let mut poss_neighbors = Vec::new();
-
-			// primary directions (up, down, left, right, front, back)
-
-			// only modify x-axis
-			poss_neighbors.push(Vec3::new(v.x - 1, v.y, v.z));
-			poss_neighbors.push(Vec3::new(v.x + 1, v.y, v.z));
-
-			// only modify y-axis
-			poss_neighbors.push(Vec3::new(v.x, v.y - 1, v.z));
-			poss_neighbors.push(Vec3::new(v.x, v.y + 1, v.z));
-
-			// only modify z-axis
-			poss_neighbors.push(Vec3::new(v.x, v.y, v.z - 1));
-			poss_neighbors.push(Vec3::new(v.x, v.y, v.z + 1));
-
-			// secondary directions if using Moore
-			if let Method::Moore = self.rules.neighbor_method {
-				// only keep x-axis
-				poss_neighbors.push(Vec3::new(v.x, v.y - 1, v.z - 1));
-				poss_neighbors.push(Vec3::new(v.x, v.y - 1, v.z + 1));
-				poss_neighbors.push(Vec3::new(v.x, v.y + 1, v.z - 1));
-				poss_neighbors.push(Vec3::new(v.x, v.y + 1, v.z + 1));
-
-				// only keep y-axis
-				poss_neighbors.push(Vec3::new(v.x - 1, v.y, v.z - 1));
-				poss_neighbors.push(Vec3::new(v.x - 1, v.y, v.z + 1));
-				poss_neighbors.push(Vec3::new(v.x + 1, v.y, v.z - 1));
-				poss_neighbors.push(Vec3::new(v.x + 1, v.y, v.z + 1));
-
-				// only keep z-axis
-				poss_neighbors.push(Vec3::new(v.x - 1, v.y - 1, v.z));
-				poss_neighbors.push(Vec3::new(v.x - 1, v.y + 1, v.z));
-				poss_neighbors.push(Vec3::new(v.x + 1, v.y - 1, v.z));
-				poss_neighbors.push(Vec3::new(v.x + 1, v.y + 1, v.z));
-
-				// change all axes
-				poss_neighbors.push(Vec3::new(v.x - 1, v.y - 1, v.z - 1));
-				poss_neighbors.push(Vec3::new(v.x - 1, v.y - 1, v.z + 1));
-				poss_neighbors.push(Vec3::new(v.x - 1, v.y + 1, v.z - 1));
-				poss_neighbors.push(Vec3::new(v.x - 1, v.y + 1, v.z + 1));
-				poss_neighbors.push(Vec3::new(v.x + 1, v.y - 1, v.z - 1));
-				poss_neighbors.push(Vec3::new(v.x + 1, v.y - 1, v.z + 1));
-				poss_neighbors.push(Vec3::new(v.x + 1, v.y + 1, v.z - 1));
-				poss_neighbors.push(Vec3::new(v.x + 1, v.y + 1, v.z + 1));
-			}
-
-			for poss_neighbor in poss_neighbors {
-				if let Some(s) = self.cells.get(&poss_neighbor) {
-					if s > &0 {
-						count += 1;
-					}
+		self.follow_schedule(self.generation);
+		self.profile.begin();
+		let before = (self.rules.species > 1).then(|| self.cells.clone().into_map());
+		let logged = self.events.is_some().then(|| self.cells.live());
+		self.hide_walls();
+		self.profile.mark();
+
+		match self.previous.take() {
+			Some(previous) => {
+				let (next, stepped) = self.step(&self.cells, None);
+				self.profile.stepped(stepped);
+				let next = self.second_order(next, &previous);
+				self.previous = Some(std::mem::replace(&mut self.cells, next));
+				self.profile.swapped();
+			},
+			None => {
+				let key = self.rng.next_u64();
+				match self.scheme {
+					UpdateScheme::Synchronous => self.step_in_place(key),
+					_ => self.step_async(&Box3::new(Vec3::new(0, 0, 0), self.bounds), key)
 				}
 			}
+		}
 
-			(v.clone(), count)
-		}).collect::<HashMap<Vec3, u8>>();
-
-		self.cells.iter_mut().for_each(|(v, s)| {
-			if s == &0 {
-				// cell is dead
-				match self.rules.to_be_born {
-					Rule::Single(ref goal) => {
-						if let Some(neighbor_count) = neighbor_counts.get(v) {
-							if neighbor_count == goal {
-								// cell will be born
-								*s = self.rules.cell_states - 1;
-							}
-						}
-					},
-					Rule::Range(ref goal_range) => {
-						if let Some(neighbor_count) = neighbor_counts.get(v) {
-							if goal_range.contains(neighbor_count) {
-								// cell will be born
-								*s = self.rules.cell_states - 1;
-							}
-						}
-					},
-					Rule::Many(ref goals) => {
-						if let Some(neighbor_count) = neighbor_counts.get(v) {
-							if goals.contains(neighbor_count) {
-								// cell will be born
-								*s = self.rules.cell_states - 1;
-							}
-						}
-					}
-				}
-			} else if s == &(self.rules.cell_states - 1) {
-				// cell is alive
-				match self.rules.to_survive {
-					Rule::Single(ref goal) => {
-						if let Some(neighbor_count) = neighbor_counts.get(v) {
-							if neighbor_count != goal {
-								// cell will start dying now
-								*s -= 1;
-							}
-						} else {
-							// cell should not exist
-							*s = 0;
-						}
-					},
-					Rule::Range(ref goal_range) => {
-						if let Some(neighbor_count) = neighbor_counts.get(v) {
-							if !goal_range.contains(neighbor_count) {
-								// cell will start dying now
-								*s -= 1;
-							}
-						} else {
-							// cell should not exist
-							*s = 0;
-						}
-					},
-					Rule::Many(ref goals) => {
-						if let Some(neighbor_count) = neighbor_counts.get(v) {
-							if !goals.contains(neighbor_count) {
-								// cell will start dying now
-								*s -= 1;
-							}
-						} else {
-							// cell should not exist
-							*s = 0;
-						}
-					}
-				}
-			} else {
-				// cell is dying
-				*s -= 1;
+		if let Some(before) = before {
+			self.species = self.rules.inherit_species(&before, &self.cells.live(), &self.species, |v| self.offsets.iter().filter_map(move |offset| v.checked_add_offset(offset)));
+		}
+
+		self.show_walls();
+		self.generation += 1;
+		self.track_cells(&Box3::new(Vec3::new(0, 0, 0), self.bounds));
+		if let Some(before) = logged { self.log_tick(&before); }
+		self.profile.finish();
+	}
+
+	/// Advances only the cells inside the given box by one tick, leaving everything outside it as it was.
+	/// Cells along the faces of the box still see their neighbors outside it, so a UI can keep just the visible part of a huge grid moving.
+	pub fn tick_region(&mut self, region: &Box3) {
+		self.follow_schedule(self.generation);
+		self.profile.begin();
+		let before = (self.rules.species > 1).then(|| self.cells.live());
+		let logged = self.events.is_some().then(|| self.cells.live());
+		self.hide_walls();
+		let key = self.previous.is_none().then(|| self.rng.next_u64());
+		self.profile.mark();
+
+		let next: HashMap<Vec3, u8> = match key {
+			Some(key) if self.scheme != UpdateScheme::Synchronous => {
+				self.step_async(region, key);
+				HashMap::new()
+			},
+			_ => {
+				let offsets = &self.offsets;
+
+				let region = self.clipped(region);
+				let (min, max) = (&region.min, &region.max);
+				in_slabs(max.z - min.z, self.threads, |slab| {
+					slab.flat_map(|dz| (min.y..max.y).flat_map(move |y| (min.x..max.x).map(move |x| Vec3::new(x, y, min.z + dz))))
+						.map(|v| { let state = self.next_state(&self.cells, offsets, &v, key); (v, state) })
+						.collect()
+				}).into_iter().collect()
 			}
+		};
+
+		let next = match &mut self.previous {
+			Some(previous) => {
+				let earlier = next.keys().map(|v| (*v, previous.get(v))).collect();
+				let next = self.rules.second_order(next, &earlier);
+				for v in next.keys() { previous.set(*v, self.cells.get(v)); }
+				next
+			},
+			None => next
+		};
+
+		self.profile.stepped(next.len());
+		for (v, state) in next { self.cells.set(v, state); }
+		self.cells.unsettle();
+		self.profile.swapped();
+
+		if let Some(before) = before {
+			self.species = self.rules.inherit_species(&before, &self.cells.live(), &self.species, |v| self.offsets.iter().filter_map(move |offset| v.checked_add_offset(offset)));
+		}
+
+		self.show_walls();
+		self.track_cells(region);
+		if let Some(before) = logged { self.log_tick(&before); }
+		self.profile.finish();
+	}
+
+	/// Takes the automaton back by one time step, undoing a `tick`.
+	/// This only works in second-order mode, since that's the only time the last generation is remembered (and the rules can be run backwards exactly).
+	/// Walls can't be run backwards either, since they change which cells the rules get to touch.
+	/// Otherwise, this gives back a `NotReversible` error without changing anything.
+	pub fn tick_backward(&mut self) -> Result<(), AutomataError> {
+		if !self.walls.is_empty() { return Err(AutomataError::NotReversible); }
+
+		// the rules to run backwards are the ones the last tick was made with
+		if self.previous.is_some() { self.follow_schedule(self.generation.saturating_sub(1)); }
+		let Some(previous) = self.previous.take() else { return Err(AutomataError::NotReversible); };
+		let logged = self.events.is_some().then(|| self.cells.live());
+
+		let before = self.second_order(self.step(&previous, None).0, &self.cells);
+		self.cells = previous;
+		self.previous = Some(before);
+		self.show_walls();
+		self.generation = self.generation.saturating_sub(1);
+		self.track_cells(&Box3::new(Vec3::new(0, 0, 0), self.bounds));
+		if let Some(before) = logged { self.log_tick(&before); }
+		Ok(())
+	}
+
+	/// Ticks the automaton `n` times.
+	pub fn tick_n(&mut self, n: usize) {
+		self.tick_n_with(n, |_| ControlFlow::Continue(()));
+	}
+
+	/// Ticks the automaton `n` times like `tick_n`, calling `progress` after every tick. Once the callback breaks, it stops right there.
+	/// Gives back how many ticks actually happened.
+	pub fn tick_n_with(&mut self, n: usize, mut progress: impl FnMut(Progress) -> ControlFlow<()>) -> usize {
+		for done in 1..=n {
+			self.tick();
+			if progress(Progress::new(done, n)).is_break() { return done; }
+		}
+
+		n
+	}
+
+	/// Ticks the automaton (up to `max_ticks` times) until it comes back to a generation it's already been through, which means it's settled down into a still life or an oscillator for good.
+	/// Gives back how many ticks it took to get to the first generation of that cycle, or `None` if it didn't settle down in time.
+	/// The automaton is left one cycle along from there.
+	/// 
+	/// ```
+	/// # use cellular_snapp::presets;
+	/// # use cellular_snapp::deep::{Automaton, Vec3};
+	/// // a lone cell can't survive in 445, so it dies off one state at a time and leaves an empty grid that never changes
+	/// let mut lonely = Automaton::new(presets::rule_445(), Vec3::new(8, 8, 8), vec![Vec3::new(3, 3, 3)]).unwrap();
+	/// assert_eq!(lonely.run_until_stable(10), Some(4));
+	/// assert_eq!(lonely.get_generation(), 5);
+	/// ```
+	pub fn run_until_stable(&mut self, max_ticks: usize) -> Option<usize> {
+		self.run_until_stable_with(max_ticks, |_| ControlFlow::Continue(()))
+	}
+
+	/// Like `run_until_stable`, calling `progress` after every tick. Once the callback breaks, it stops right there and gives back `None`.
+	pub fn run_until_stable_with(&mut self, max_ticks: usize, mut progress: impl FnMut(Progress) -> ControlFlow<()>) -> Option<usize> {
+		let mut seen = HashMap::from([(self.state_hash(), 0)]);
+
+		for done in 1..=max_ticks {
+			self.tick();
+			if let Some(first) = seen.insert(self.state_hash(), done) { return Some(first); }
+			if progress(Progress::new(done, max_ticks)).is_break() { return None; }
+		}
+
+		None
+	}
+
+	/// Turns second-order mode on or off.
+	/// 
+	/// In second-order mode, a cell's next state is what the rules say minus its state from the generation before (wrapping around the amount of cell states),
+	/// which is Fredkin's trick for making any rule reversible. When it's turned on, the generation before is taken to be entirely dead.
+	/// Birth and survival chances are ignored in second-order mode, since random rules can't be run backwards.
+	pub fn set_second_order(&mut self, enabled: bool) {
+		self.previous = match (enabled, self.previous.take()) {
+			(true, Some(previous)) => Some(previous),
+			(true, None) => Some(Cells::new(self.cells.backend(), &self.bounds, &self.rules)),
+			(false, _) => None
+		};
+	}
+
+	/// Sets the order cells get updated in during each tick. Anything but `Synchronous` updates the cells one at a time on a single thread (whatever `threads` says),
+	/// which is a lot slower, and every cell of the grid gets looked at, not just the ones near live cells.
+	/// Second-order mode always updates every cell at once, whatever the scheme is.
+	pub fn set_update_scheme(&mut self, scheme: UpdateScheme) {
+		self.scheme = scheme;
+	}
+
+	/// Get the order cells get updated in during each tick.
+	pub fn get_update_scheme(&self) -> UpdateScheme {
+		self.scheme
+	}
+
+	/// Checks whether the automaton is in second-order mode.
+	pub fn is_second_order(&self) -> bool {
+		self.previous.is_some()
+	}
+
+	/// Turns age tracking on or off. While it's on, the automaton counts how many ticks each live or dying cell has been around since it was born,
+	/// so renderers can color cells by age instead of by how far along they are in dying. When it's turned on, every cell starts out at age 0.
+	/// 
+	/// A cell only stops aging once it's fully dead, and going back with `tick_backward` counts as a tick too, since ages from before aren't remembered.
+	/// 
+	/// ```
+	/// # use cellular_snapp::{presets, deep::{Automaton, Vec3}};
+	/// let cube = vec![Vec3::new(4, 4, 4), Vec3::new(5, 5, 5)];
+	/// let mut automaton = Automaton::new(presets::rule_445(), Vec3::new(10, 10, 10), cube).unwrap();
+	/// automaton.set_age_tracking(true);
+	/// automaton.tick();
+	/// 
+	/// // neither cell has 4 neighbors, so they're dying, but they still get older until they're fully dead
+	/// assert_eq!(automaton.cell_age(&Vec3::new(4, 4, 4)), Some(1));
+	/// for _ in 0..3 { automaton.tick(); }
+	/// assert_eq!(automaton.cell_age(&Vec3::new(4, 4, 4)), None);
+	/// ```
+	pub fn set_age_tracking(&mut self, enabled: bool) {
+		self.ages = match (enabled, self.ages.take()) {
+			(true, Some(ages)) => Some(ages),
+			(true, None) => Some(self.cells.live().into_keys().map(|v| (v, 0)).collect()),
+			(false, _) => None
+		};
+	}
+
+	/// Checks whether the automaton is tracking the ages of its cells.
+	pub fn is_tracking_age(&self) -> bool {
+		self.ages.is_some()
+	}
+
+	/// Get how many ticks a live or dying cell has been around since it was born.
+	/// This is `None` for dead cells, cells outside the grid, and every cell when age tracking is off.
+	pub fn cell_age(&self, v: &Vec3) -> Option<u32> {
+		let ages = self.ages.as_ref()?;
+		(self.get_cell(v)? > 0).then(|| ages.get(v).copied().unwrap_or(0))
+	}
+
+	/// Get every live or dying cell inside the given region like `live_cells_in`, along with its age. Every cell is age 0 when age tracking is off.
+	pub fn live_cells_with_age_in(&self, region: &Box3) -> HashMap<Vec3, (u8, u32)> {
+		self.live_cells_in(region).into_iter().map(|(v, s)| (v, (s, self.ages.as_ref().and_then(|ages| ages.get(&v)).copied().unwrap_or(0)))).collect()
+	}
+
+	/// Turns trail tracking on or off. While it's on, the automaton remembers how many ticks ago every dead cell was last live or dying,
+	/// so renderers can fade out where structures have just been without diffing every frame. When it's turned on, there's no trail yet.
+	/// Cells that were never alive while it was on aren't part of the trail, and just like with ages, going back with `tick_backward` counts as a tick too.
+	pub fn set_trail_tracking(&mut self, enabled: bool) {
+		self.trails = match (enabled, self.trails.take()) {
+			(true, Some(trails)) => Some(trails),
+			(true, None) => Some(self.cells.live().into_keys().map(|v| (v, 0)).collect()),
+			(false, _) => None
+		};
+	}
+
+	/// Checks whether the automaton is tracking trails.
+	pub fn is_tracking_trails(&self) -> bool {
+		self.trails.is_some()
+	}
+
+	/// Get every dead cell that was last live or dying at most `window` ticks ago, along with how many ticks ago that was (which is at least 1).
+	/// This is empty when trail tracking is off.
+	pub fn trail(&self, window: u32) -> HashMap<Vec3, u32> {
+		let Some(trails) = &self.trails else { return HashMap::new(); };
+		trails.iter().filter(|(_, since)| (1..=window).contains(*since)).map(|(v, since)| (*v, *since)).collect()
+	}
+
+	/// Turns the activity heatmap on or off. While it's on, the automaton keeps a running tally of how often every cell has been live or dying,
+	/// which is great for long-exposure trails that show where a pattern has been. When it's turned on, the current generation counts as the first one.
+	/// 
+	/// Every tick, the whole heatmap is multiplied by the decay factor before live and dying cells add 1 to their spots,
+	/// so a decay of 1 just counts generations, and anything less makes old activity fade away. Giving `None` turns the heatmap off and throws it away.
+	/// 
+	pub fn set_heatmap(&mut self, decay: Option<f64>) {
+		self.heat = decay.map(|decay| match self.heat.take() {
+			Some((_, heat)) => (decay, heat),
+			None => (decay, self.cells.live().into_keys().map(|v| (v, 1.0)).collect())
 		});
 	}
 
+	/// Get the activity heatmap, with the heat of every cell that's been live or dying since it was turned on. This is empty when the heatmap is off.
+	pub fn heatmap(&self) -> HashMap<Vec3, f64> {
+		self.heat.as_ref().map(|(_, heat)| heat.clone()).unwrap_or_default()
+	}
+
+	/// Turns the event log on or off. While it's on, the automaton keeps a list of every tick, rule change, and edit until it gets drained.
+	/// Turning it off throws away everything that hasn't been drained yet. See the `events` module for more.
+	pub fn set_event_log(&mut self, enabled: bool) {
+		self.events = match (enabled, self.events.take()) {
+			(true, events) => Some(events.unwrap_or_default()),
+			(false, _) => None
+		};
+	}
+
+	/// Checks whether the automaton is keeping an event log.
+	pub fn is_logging_events(&self) -> bool {
+		self.events.is_some()
+	}
+
+	/// Takes every event logged since the last time they were drained, oldest first. This is empty when the event log is off.
+	pub fn drain_events(&mut self) -> Vec<Event> {
+		self.events.as_mut().map(std::mem::take).unwrap_or_default()
+	}
+
+	/// Drains every event like `drain_events`, and writes them out as JSON lines, one event per line.
+	/// The events are drained even if writing them fails.
+	pub fn drain_events_to(&mut self, writer: impl Write) -> io::Result<()> {
+		events::write_json_lines(&self.drain_events(), writer)
+	}
+
+	/// Adds an event to the log, if it's on.
+	fn log(&mut self, event: Event) {
+		if let Some(events) = &mut self.events { events.push(event); }
+	}
+
+	/// Logs a tick, given every cell that was live or dying before it.
+	fn log_tick(&mut self, before: &HashMap<Vec3, u8>) {
+		let after = self.cells.live();
+		let births = after.keys().filter(|v| !before.contains_key(v)).count();
+		let deaths = before.keys().filter(|v| !after.contains_key(v)).count();
+		self.log(Event::Tick { generation: self.generation, births, deaths });
+	}
+
+	/// Updates the ages, trails, and the heatmap of every cell in the region that just ticked.
+	/// Live and dying cells get a tick older (newborns start at 0) and add to their heat, and cells that died are forgotten about, except by their trails.
+	fn track_cells(&mut self, region: &Box3) {
+		if self.ages.is_none() && self.trails.is_none() && self.heat.is_none() { return; }
+
+		let live = self.live_cells_in(region);
+
+		if let Some(ages) = &mut self.ages {
+			ages.retain(|v, _| !region.contains(v) || live.contains_key(v));
+			for v in live.keys() { ages.entry(*v).and_modify(|age| *age = age.saturating_add(1)).or_insert(0); }
+		}
+
+		if let Some(trails) = &mut self.trails {
+			for (v, since) in trails.iter_mut().filter(|(v, _)| region.contains(v)) { *since = if live.contains_key(v) { 0 } else { since.saturating_add(1) }; }
+			for v in live.keys() { trails.insert(*v, 0); }
+		}
+
+		if let Some((decay, heat)) = &mut self.heat {
+			for (_, h) in heat.iter_mut().filter(|(v, _)| region.contains(v)) { *h *= *decay; }
+			for v in live.keys() { *heat.entry(*v).or_insert(0.0) += 1.0; }
+		}
+	}
+
+	/// Works out what the rules say every cell of the given grid turns into.
+	/// Random rolls are only made if given a key, and otherwise every chance passes.
+	/// This also gives back how many cells it looked at.
+	fn step(&self, cells: &Cells, key: Option<u64>) -> (Cells, usize) {
+		if let Some(next) = self.bulk_step(cells, None) { return (next, self.bounds.x * self.bounds.y * self.bounds.z); }
+
+		let mut out = cells.clone();
+		let changes = self.changes(cells, key);
+		let stepped = changes.len();
+		for (v, state) in changes { out.set(v, state); }
+		(out, stepped)
+	}
+
+	/// Moves the automaton's own grid on one generation, reusing memory instead of building a whole new grid wherever it can.
+	/// The backends that tick whole rows at once write into the grid from the generation before last, and everything else gets changed in place.
+	fn step_in_place(&mut self, key: u64) {
+		if self.incremental_step() { return; }
+		let spare = self.spare.0.take();
+
+		match self.bulk_step(&self.cells, spare) {
+			Some(next) => {
+				self.profile.stepped(self.bounds.x * self.bounds.y * self.bounds.z);
+				self.spare.0 = Some(std::mem::replace(&mut self.cells, next));
+			},
+			None => {
+				let changes = self.changes(&self.cells, Some(key));
+				self.profile.stepped(changes.len());
+				for (v, state) in changes {
+					if self.cells.get(&v) != state { self.cells.set(v, state); }
+				}
+				self.cells.unsettle();
+			}
+		}
+
+		self.profile.swapped();
+	}
+
+	/// Ticks only the cells whose state or neighbor count changed since last tick, if the rules only count neighbors and the grid keeps its counts up to date.
+	/// Gives back whether it did, since otherwise the grid has to be ticked some other way.
+	fn incremental_step(&mut self) -> bool {
+		let max = self.offsets.len() as u8;
+		let Cells::Incremental(grid, ..) = &mut self.cells else { return false; };
+		let Some(table) = (self.edge == 0).then(|| self.rules.count_table(max)).flatten() else { return false; };
+
+		// every change gets worked out before any of them are made, so every cell sees the same generation
+		let dirty = grid.take_dirty();
+		let grid = &*grid;
+		let changes: Vec<(usize, u8)> = in_slabs(dirty.len(), self.threads, |slab| dirty[slab].iter().filter_map(|i| {
+			let state = grid.get(*i);
+			let next = table[state as usize * (max as usize + 1) + grid.count(*i) as usize];
+			(next != state).then_some((*i, next))
+		}).collect());
+
+		self.profile.stepped(dirty.len());
+		for (i, state) in changes { self.cells.set(cell_at(&self.bounds, i), state); }
+		self.profile.swapped();
+		true
+	}
+
+	/// Updates every cell inside the given region one at a time, in whatever order the update scheme says, so each cell sees the ones that went before it.
+	fn step_async(&mut self, region: &Box3, key: u64) {
+		let mut order: Vec<Vec3> = self.clipped(region).iter_positions().collect();
+
+		if let UpdateScheme::RandomAsync(seed) = self.scheme {
+			let mut rng = Rng::keyed(seed, &[self.generation]);
+			for i in (1..order.len()).rev() { order.swap(i, rng.below(i as u64 + 1) as usize); }
+		}
+
+		// working out the next state of each cell and putting it in place happen together, so it's all stepping
+		let stepped = order.len();
+		for v in order {
+			let state = self.next_state(&self.cells, &self.offsets, &v, Some(key));
+			if self.cells.get(&v) != state { self.cells.set(v, state); }
+		}
+		self.cells.unsettle();
+		self.profile.stepped(stepped);
+	}
+
+	/// Ticks the whole grid a row at a time, if the rules only count neighbors and the backend keeps rows together, writing into the memory of the spare grid if it's got any.
+	/// Gives back nothing if the grid has to be ticked a cell at a time instead, like when a memory-mapped grid can't get a file to tick into.
+	fn bulk_step(&self, cells: &Cells, spare: Option<Cells>) -> Option<Cells> {
+		let offsets = &self.offsets;
+		let (max, height) = (offsets.len() as u8, self.bounds.y);
+		let rows = |row: usize| offsets.iter().filter_map(|offset| {
+			let (y, z) = ((row % height) as i64 + offset.y, (row / height) as i64 + offset.z);
+			let inside = y >= 0 && z >= 0 && (y as usize) < self.bounds.y && (z as usize) < self.bounds.z;
+			inside.then(|| (z as usize * height + y as usize, offset.x as i8))
+		}).collect();
+
+		// every chunk of layers reads the layers just past its edges (its halo) straight from the last generation, which all the threads share,
+		// so the chunks can be worked out separately, each straight into its own part of the next generation
+		let (width, depth) = (self.bounds.x, self.bounds.z);
+		let table = (self.edge == 0).then(|| self.rules.count_table(max)).flatten()?;
+
+		match cells {
+			Cells::Bits(bits, _) => {
+				let mut words = match spare { Some(Cells::Bits(spare, _)) => spare.into_words(), _ => Vec::new() };
+				words.resize(width.div_ceil(64) * height * depth, 0);
+				in_slabs_mut(&mut words, width.div_ceil(64) * height, self.threads, |layers, out| bits.step(&table, max as usize + 1, layers.start * height..layers.end * height, rows, out));
+				Some(Cells::Bits(Bits::from_words(width, words), height))
+			},
+			Cells::Dense(dense, _) => {
+				let mut next = dense.next_grid(match spare { Some(Cells::Dense(spare, _)) => Some(spare), _ => None })?;
+				in_slabs_mut(next.as_mut_slice(), width * height, self.threads, |layers, out| dense.step(&table, max as usize + 1, self.rules.counted_states(), layers.start * height..layers.end * height, rows, out));
+				Some(Cells::Dense(next, height))
+			},
+			_ => None
+		}
+	}
+
+	/// Works out the next state of every cell of the given grid that could change, one cell at a time.
+	fn changes(&self, cells: &Cells, key: Option<u64>) -> Vec<(Vec3, u8)> {
+		let offsets = &self.offsets;
+		let (width, height, depth) = (self.bounds.x, self.bounds.y, self.bounds.z);
+
+		let next = |v: &Vec3| self.next_state(cells, offsets, v, key);
+		let every_cell = |layers: Range<usize>| layers.flat_map(|z| (0..height).flat_map(move |y| (0..width).map(move |x| Vec3::new(x, y, z)))).map(|v| { let state = next(&v); (v, state) }).collect();
+
+		// only cells next to something can change, unless the rules bring empty space to life or the edge isn't dead
+		if self.edge > 0 || self.rules.next_state_from(0, offsets.iter().map(|_| 0), 0.0) != 0 {
+			in_slabs(depth, self.threads, every_cell)
+		} else {
+			let candidates: Vec<Vec3> = cells.live().into_keys()
+				.flat_map(|v| offsets.iter().filter_map(move |offset| v.checked_add_offset(offset)).chain(std::iter::once(v)))
+				.filter(|v| v.x < width && v.y < height && v.z < depth)
+				.collect::<HashSet<Vec3>>()
+				.into_iter()
+				.collect();
+
+			in_slabs(candidates.len(), self.threads, |slab| candidates[slab].iter().map(|v| (*v, next(v))).collect())
+		}
+	}
+
+	/// Works out what the rules say a single cell of the given grid turns into.
+	fn next_state(&self, cells: &Cells, offsets: &[IVec3], v: &Vec3, key: Option<u64>) -> u8 {
+		// out-of-bounds neighbors are in the edge state, so every neighbor stays in the same spot
+		let neighbors = offsets.iter()
+			.map(|offset| v.checked_add_offset(offset).filter(|n| n.x < self.bounds.x && n.y < self.bounds.y && n.z < self.bounds.z).map_or(self.edge, |neighbor| cells.get(&neighbor)));
+
+		let roll = match key {
+			Some(key) if self.rules.is_stochastic() => Rng::keyed(key, &[v.x as u64, v.y as u64, v.z as u64]).next_f64(),
+			_ => 0.0
+		};
+
+		self.rules.next_state_from(cells.get(v), neighbors, roll)
+	}
+
+	/// Takes the generation before away from the given next generation, for second-order mode.
+	fn second_order(&self, next: Cells, previous: &Cells) -> Cells {
+		let backend = next.backend();
+		Cells::from_map(backend, &self.bounds, &self.rules, self.rules.second_order(next.into_map(), &previous.clone().into_map()))
+	}
+
+	/// Seeds the random number generator used by stochastic rules. Automata start out seeded with 0.
+	/// Two automata with the same rules, cells, and seed will always tick the same way.
+	pub fn seed_rng(&mut self, seed: u64) {
+		self.rng = Rng::new(seed);
+	}
+
 	/// Get a copy of the automaton's internal state (the cells).
+	/// Every cell in the grid is included whichever backend it's kept in, so on huge grids you'll want `live_cells_in` instead.
+	/// Hash maps come out in a different order every run, so use `live_cells_sorted` or `iter_cells` when the order matters.
 	pub fn get_cells(&self) -> HashMap<Vec3, u8> {
-		self.cells.clone()
+		self.iter_cells().collect()
+	}
+
+	/// Get every live or dying cell inside the given region. With the tree backend, this only looks at the parts of the tree that overlap the region.
+	pub fn live_cells_in(&self, region: &Box3) -> HashMap<Vec3, u8> {
+		match &self.cells {
+			Cells::Map(map) => map.iter().filter(|(v, s)| **s > 0 && region.contains(v)).map(|(v, s)| (*v, *s)).collect(),
+			Cells::Tree(tree) => {
+				let (min, max) = (&region.min, &region.max);
+				tree.query(&[min.x, min.y, min.z], &[max.x, max.y, max.z]).into_iter().map(|([x, y, z], s)| (Vec3::new(x, y, z), s)).collect()
+			},
+			_ => self.cells.live().into_iter().filter(|(v, _)| region.contains(v)).collect()
+		}
+	}
+
+	/// Get every cell inside the given box, dead ones included, which is handy for drawing just the part of the grid that's on screen.
+	/// Only the part of the box that's inside the grid is included.
+	pub fn get_cells_in(&self, region: &Box3) -> HashMap<Vec3, u8> {
+		self.clipped(region).iter_positions().map(|v| { let state = self.cells.get(&v); (v, state) }).collect()
+	}
+
+	/// Shrinks the grid down by `factor` in each direction for drawing a minimap, giving back a block for every `factor` by `factor` by `factor` cube of cells,
+	/// row by row and then layer by layer. Each block has its position in the shrunken grid and how much of it is live or dying, from 0 to 1.
+	/// Blocks along the far faces can be cut off by the bounds, and only count the cells they actually cover.
+	/// This only goes through the live and dying cells, without building a map of every cell first.
+	pub fn downsample(&self, factor: usize) -> Vec<(Vec3, f32)> {
+		let factor = factor.max(1);
+		let (width, height, depth) = (self.bounds.x.div_ceil(factor), self.bounds.y.div_ceil(factor), self.bounds.z.div_ceil(factor));
+
+		let mut live = vec![0usize; width * height * depth];
+		for v in self.cells.live().into_keys() { live[(v.z / factor * height + v.y / factor) * width + v.x / factor] += 1; }
+
+		live.into_iter().enumerate().map(|(i, count)| {
+			let block = Vec3::new(i % width, i / width % height, i / (width * height));
+			let cells = (self.bounds.x - block.x * factor).min(factor) * (self.bounds.y - block.y * factor).min(factor) * (self.bounds.z - block.z * factor).min(factor);
+			(block, count as f32 / cells as f32)
+		}).collect()
+	}
+
+	/// Get the part of a region that's inside the grid, which might not have anything in it.
+	fn clipped(&self, region: &Box3) -> Box3 {
+		region.intersect(&Box3::new(Vec3::new(0, 0, 0), self.bounds)).unwrap_or_default()
+	}
+
+	/// Get every live and dying cell, sorted row by row and then layer by layer (by z, then y, and then x), which is the order the grid is laid out in. Unlike the hash maps the other getters give back,
+	/// this comes out in the same order every time, so it's the one to use for exporting cells, hashing or comparing runs in tests, or adding anything up where the order matters.
+	/// 
+	/// ```
+	/// # use cellular_snapp::{presets, deep::{Automaton, Vec3}};
+	/// let cube = Automaton::new(presets::rule_445(), Vec3::new(8, 8, 8), vec![Vec3::new(3, 1, 0), Vec3::new(1, 2, 5)]).unwrap();
+	/// let live = cube.live_cells_sorted();
+	/// assert_eq!(live[0].0, Vec3::new(3, 1, 0));
+	/// assert_eq!(live[1].0, Vec3::new(1, 2, 5));
+	/// ```
+	pub fn live_cells_sorted(&self) -> Vec<(Vec3, u8)> {
+		let mut live: Vec<(Vec3, u8)> = self.cells.live().into_iter().collect();
+		live.sort_unstable_by_key(|(v, _)| (v.z, v.y, v.x));
+		live
+	}
+
+	/// Goes through every cell in the grid, dead ones included, row by row and then layer by layer, without building a hash map of the whole grid first.
+	pub fn iter_cells(&self) -> impl Iterator<Item = (Vec3, u8)> + '_ {
+		(0..self.bounds.z).flat_map(move |z| (0..self.bounds.y).flat_map(move |y| (0..self.bounds.x).map(move |x| Vec3::new(x, y, z)))).map(move |v| (v, self.cells.get(&v)))
+	}
+
+	/// Goes through every cell in the grid like `iter_cells`, except with what each cell's state means instead of the state itself.
+	pub fn iter_cell_views(&self) -> impl Iterator<Item = (Vec3, CellView)> + '_ {
+		self.iter_cells().map(move |(v, state)| (v, self.rules.view(state)))
+	}
+
+	/// Get the smallest and largest corners of the box around every live and dying cell, which are both inside the box (unlike a `Box3`),
+	/// or `None` if every cell is dead. This is handy for keeping a camera on a structure that's growing.
+	pub fn live_bounds(&self) -> Option<(Vec3, Vec3)> {
+		let live = self.cells.live();
+		let mut live = live.keys();
+		let first = *live.next()?;
+
+		Some(live.fold((first, first), |(min, max), v| {
+			(
+				Vec3::new(min.x.min(v.x), min.y.min(v.y), min.z.min(v.z)),
+				Vec3::new(max.x.max(v.x), max.y.max(v.y), max.z.max(v.z))
+			)
+		}))
 	}
-}
\ No newline at end of file
+
+	/// Get the average position of every live and dying cell, each counting the same, or `None` if every cell is dead.
+	pub fn center_of_mass(&self) -> Option<(f64, f64, f64)> {
+		let live = self.cells.live();
+		if live.is_empty() { return None; }
+
+		// adding up whole numbers gives exactly the same answer whatever order the cells come in
+		let (x, y, z) = live.keys().fold((0u128, 0u128, 0u128), |(x, y, z), v| (x + v.x as u128, y + v.y as u128, z + v.z as u128));
+		let count = live.len() as f64;
+		Some((x as f64 / count, y as f64 / count, z as f64 / count))
+	}
+
+	/// Get a hash of every live and dying cell, which is the same for the same cells no matter the backend, the platform, or the version of Rust.
+	/// Two automata with the same cells always hash the same, so this is handy for spotting cycles or throwing out soups that have already turned up.
+	pub fn state_hash(&self) -> u64 {
+		hash_cells(self.cells.live().into_iter().map(|(v, s)| ([v.x as u64, v.y as u64, v.z as u64], s)))
+	}
+
+	/// Get a hash of every live and dying cell like `state_hash`, except the cells are moved so their bounding box starts at the origin first.
+	/// That way, the same pattern hashes the same wherever it is in the grid.
+	pub fn normalized_state_hash(&self) -> u64 {
+		let live = self.cells.live();
+		let min = Vec3::new(live.keys().map(|v| v.x).min().unwrap_or(0), live.keys().map(|v| v.y).min().unwrap_or(0), live.keys().map(|v| v.z).min().unwrap_or(0));
+		hash_cells(live.into_iter().map(|(v, s)| ([(v.x - min.x) as u64, (v.y - min.y) as u64, (v.z - min.z) as u64], s)))
+	}
+
+	/// Finds every group of live and dying cells that are connected to each other, where two cells count as connected if they're neighbors by the given method
+	/// (so `Method::VonNeumann` only connects cells that share a face, and `Method::Moore` connects them at the corners too).
+	/// Groups come biggest first, and the cells in each group are sorted by z, then y, then x.
+	pub fn components(&self, method: &Method) -> Vec<Vec<Vec3>> {
+		let offsets = neighbor_offsets(method);
+		let live: HashSet<Vec3> = self.cells.live().into_keys().collect();
+		let mut seen = HashSet::new();
+		let mut components = Vec::new();
+
+		for start in &live {
+			if !seen.insert(*start) { continue; }
+
+			// flood out from the first cell of every group that hasn't been found yet
+			let mut stack = vec![*start];
+			let mut component = Vec::new();
+
+			while let Some(v) = stack.pop() {
+				component.push(v);
+
+				for n in offsets.iter().filter_map(|offset| v.checked_add_offset(offset)) {
+					if live.contains(&n) && seen.insert(n) { stack.push(n); }
+				}
+			}
+
+			component.sort_by_key(|v| (v.z, v.y, v.x));
+			components.push(component);
+		}
+
+		components.sort_by(|a, b| b.len().cmp(&a.len()).then_with(|| (a[0].z, a[0].y, a[0].x).cmp(&(b[0].z, b[0].y, b[0].x))));
+		components
+	}
+
+	/// Finds every group of connected live and dying cells just like `components`, but only gives back how many cells are in each one and the box around it.
+	pub fn component_boxes(&self, method: &Method) -> Vec<(usize, Box3)> {
+		self.components(method).into_iter().map(|component| {
+			let (mut min, mut max) = (component[0], component[0]);
+
+			for v in &component {
+				min = Vec3::new(min.x.min(v.x), min.y.min(v.y), min.z.min(v.z));
+				max = Vec3::new(max.x.max(v.x), max.y.max(v.y), max.z.max(v.z));
+			}
+
+			(component.len(), Box3::new(min, Vec3::new(max.x + 1, max.y + 1, max.z + 1)))
+		}).collect()
+	}
+
+	/// Get which backend the automaton keeps its cells in.
+	pub fn get_backend(&self) -> Backend {
+		self.cells.backend()
+	}
+
+	/// Get the rules the automaton is currently following.
+	pub fn get_rules(&self) -> &AutomataRules {
+		&self.rules
+	}
+
+	/// Get the bounds (the size of the grid) of the automaton.
+	pub fn get_bounds(&self) -> &Vec3 {
+		&self.bounds
+	}
+
+	/// Get how many cells wide (along x) the grid is.
+	pub fn width(&self) -> usize {
+		self.bounds.x
+	}
+
+	/// Get how many cells tall (along y) the grid is.
+	pub fn height(&self) -> usize {
+		self.bounds.y
+	}
+
+	/// Get how many cells deep (along z) the grid is.
+	pub fn depth(&self) -> usize {
+		self.bounds.z
+	}
+
+	/// Get a copy of the layer of cells where z is the given value, as a flat frame with x and y staying just where they are, or `None` if it's past the grid.
+	/// This is handy for paging through a volume one layer at a time, like the slices of a scan.
+	/// 
+	/// ```
+	/// # use cellular_snapp::{presets, deep::{Automaton, Vec3}, flat::Vec2};
+	/// let grid = Automaton::new(presets::rule_445(), Vec3::new(4, 3, 2), vec![Vec3::new(1, 2, 1), Vec3::new(3, 0, 0)]).unwrap();
+	/// 
+	/// assert_eq!(grid.slice_z(1).unwrap().get_cell(&Vec2::new(1, 2)), Some(4));
+	/// assert_eq!(grid.slice_z(0).unwrap().cells().iter().filter(|s| **s > 0).count(), 1);
+	/// assert_eq!(grid.slice_x(3).unwrap().bounds(), &Vec2::new(3, 2));
+	/// assert!(grid.slice_z(2).is_none());
+	/// ```
+	pub fn slice_z(&self, z: usize) -> Option<flat::Frame> {
+		self.slice(z < self.bounds.z, flat::Vec2::new(self.bounds.x, self.bounds.y), |x, y| Vec3::new(x, y, z))
+	}
+
+	/// Get a copy of the layer of cells where y is the given value, as a flat frame where x stays put and z becomes y, or `None` if it's past the grid.
+	pub fn slice_y(&self, y: usize) -> Option<flat::Frame> {
+		self.slice(y < self.bounds.y, flat::Vec2::new(self.bounds.x, self.bounds.z), |x, z| Vec3::new(x, y, z))
+	}
+
+	/// Get a copy of the layer of cells where x is the given value, as a flat frame where y becomes x and z becomes y, or `None` if it's past the grid.
+	pub fn slice_x(&self, x: usize) -> Option<flat::Frame> {
+		self.slice(x < self.bounds.x, flat::Vec2::new(self.bounds.y, self.bounds.z), |y, z| Vec3::new(x, y, z))
+	}
+
+	/// Copies a layer of cells into a flat frame of the given size, where `at` says which cell of the grid each cell of the layer is.
+	fn slice(&self, inside: bool, size: flat::Vec2, at: impl Fn(usize, usize) -> Vec3) -> Option<flat::Frame> {
+		if !inside { return None; }
+
+		let cells = (0..size.y()).flat_map(|y| (0..size.x()).map(move |x| (x, y))).map(|(x, y)| self.cells.get(&at(x, y))).collect();
+		Some(flat::Frame::new(size, self.generation, cells))
+	}
+
+	/// Get the state of every cell as one flat slice of bytes, or `None` if the automaton isn't on the dense backend.
+	/// Cells are laid out a row at a time with x going fastest, then y, then z, so the cell at `(x, y, z)` is at `(z * height + y) * width + x`.
+	/// That's exactly the layout of a single-channel 3D texture, so renderers can copy the whole grid over in one go.
+	/// 
+	/// ```
+	/// # use cellular_snapp::{AutomataRules, Backend, Method, deep::{Automaton, Vec3}};
+	/// let rules = AutomataRules::new(4..=5, 4, 2, Method::Moore);
+	/// let grid = Automaton::with_backend(rules, Vec3::new(4, 3, 2), vec![Vec3::new(1, 2, 1)], Backend::Dense).unwrap();
+	/// let cells = grid.as_slice().unwrap();
+	/// 
+	/// assert_eq!(cells.len(), grid.width() * grid.height() * grid.depth());
+	/// assert_eq!(cells[(grid.height() + 2) * grid.width() + 1], 1);
+	/// ```
+	pub fn as_slice(&self) -> Option<&[u8]> {
+		match &self.cells {
+			Cells::Dense(dense, _) => Some(dense.as_slice()),
+			_ => None
+		}
+	}
+
+	/// Like `as_slice`, except the states can be changed in place.
+	/// Nothing gets checked, so every state written has to be one the rules have (or ticking might panic), and species and ages don't hear about the changes.
+	pub fn as_mut_slice(&mut self) -> Option<&mut [u8]> {
+		match &mut self.cells {
+			Cells::Dense(dense, _) => Some(dense.as_mut_slice()),
+			_ => None
+		}
+	}
+
+	/// Takes a snapshot of every cell, for handing over to another thread (like a renderer) without holding onto the automaton.
+	/// This copies the whole grid, which is a single copy on the dense backend, and only the cells that aren't dead need writing in on the others.
+	pub fn frame(&self) -> Arc<Frame> {
+		let cells = match &self.cells {
+			Cells::Dense(dense, _) => dense.as_slice().to_vec(),
+			cells => {
+				let mut states = vec![0; self.bounds.x * self.bounds.y * self.bounds.z];
+				for (v, s) in cells.live() { states[(v.z * self.bounds.y + v.y) * self.bounds.x + v.x] = s; }
+				states
+			}
+		};
+
+		Arc::new(Frame { bounds: self.bounds, generation: self.generation, cells })
+	}
+
+	/// Get the state of a single cell, or `None` if it's outside the grid.
+	pub fn get_cell(&self, v: &Vec3) -> Option<u8> {
+		(v.x < self.bounds.x && v.y < self.bounds.y && v.z < self.bounds.z).then(|| self.cells.get(v))
+	}
+
+	/// Get what a single cell's state means (whether it's dead, alive, or dying), or `None` if it's outside the grid.
+	pub fn get_cell_view(&self, v: &Vec3) -> Option<CellView> {
+		self.get_cell(v).map(|state| self.rules.view(state))
+	}
+
+	/// Sets the state of a single cell. States too big for the rules are clamped to alive, and cells outside the grid are ignored.
+	pub fn set_cell(&mut self, v: Vec3, state: u8) {
+		if self.walls.contains_key(&v) { return; }
+		let max_state = self.rules.cell_states - 1;
+		if state == 0 { self.species.remove(&v); }
+
+		if v.x < self.bounds.x && v.y < self.bounds.y && v.z < self.bounds.z {
+			let (from, to) = (self.cells.get(&v), state.min(max_state));
+			if from != to { self.log(Event::Edit { generation: self.generation, position: [v.x, v.y, v.z], from, to }); }
+			self.cells.set(v, to);
+
+			// cells being set alive are newborns, and live cells being set to another live state keep their age
+			if let Some(ages) = &mut self.ages {
+				if state == 0 { ages.remove(&v); } else { ages.entry(v).or_insert(0); }
+			}
+
+			// cells being killed were still alive this generation, so their trail starts on the next tick
+			if let Some(trails) = &mut self.trails {
+				if state > 0 { trails.insert(v, 0); }
+			}
+		}
+	}
+
+	/// Gives memory back after lots of cells have died. The hash map backend only keeps live and dying cells, but hash maps never shrink on their own,
+	/// so a grid that was mostly alive once holds onto that memory until this shrinks it down to fit (along with the species, ages, trails, and heatmap).
+	/// This also throws away the spare grid that the bit-packed and dense backends tick into, which just gets made again on the next tick.
+	pub fn compact(&mut self) {
+		for cells in std::iter::once(&mut self.cells).chain(self.previous.iter_mut()) { cells.compact(); }
+		self.spare.0 = None;
+		self.species.shrink_to_fit();
+		if let Some(ages) = &mut self.ages { ages.shrink_to_fit(); }
+		if let Some(trails) = &mut self.trails { trails.shrink_to_fit(); }
+		if let Some((_, heat)) = &mut self.heat { heat.shrink_to_fit(); }
+	}
+
+	/// Turns a cell into a wall, which the rules never change. Live walls get set fully alive and dead walls get killed,
+	/// while excluded walls keep the state they had. Cells outside the grid are ignored.
+	/// 
+	/// Walls can't be changed with `set_cell` (or anything that uses it, like `paste`) until they're taken away again.
+	/// They get saved in checkpoints and replays.
+	pub fn set_wall(&mut self, v: Vec3, wall: Wall) {
+		let Some(state) = self.get_cell(&v) else { return; };
+		let state = match wall { Wall::Alive => self.rules.cell_states - 1, Wall::Dead => 0, Wall::Excluded => state };
+
+		self.walls.remove(&v);
+		self.set_cell(v, state);
+		self.walls.insert(v, (wall, state));
+	}
+
+	/// Takes a wall away, so the cell there follows the rules again.
+	pub fn remove_wall(&mut self, v: &Vec3) {
+		self.walls.remove(v);
+	}
+
+	/// Takes away every wall.
+	pub fn clear_walls(&mut self) {
+		self.walls.clear();
+	}
+
+	/// Get what kind of wall a cell is, or `None` if it isn't one.
+	pub fn get_wall(&self, v: &Vec3) -> Option<Wall> {
+		self.walls.get(v).map(|(wall, _)| *wall)
+	}
+
+	/// Sets the state every cell past the edge of the grid is in, as far as the cells along the edge are concerned, just like the flat `set_edge_state`.
+	/// Edges start out dead, and states too big for the rules are clamped to alive.
+	/// While the edge isn't dead, every cell gets ticked one at a time. The edge state gets saved in checkpoints and replays.
+	pub fn set_edge_state(&mut self, state: u8) {
+		self.edge = state.min(self.rules.cell_states - 1);
+	}
+
+	/// Get the state every cell past the edge of the grid is in.
+	pub fn get_edge_state(&self) -> u8 {
+		self.edge
+	}
+
+	/// Get the species of every live or dying cell. Cells start out as species 0 unless you say otherwise.
+	pub fn get_species(&self) -> HashMap<Vec3, u8> {
+		self.cells.live().into_keys().map(|v| { let species = self.species.get(&v).copied().unwrap_or(0); (v, species) }).collect()
+	}
+
+	/// Sets the species of a single live or dying cell. Species too big for the rules are clamped to the last one, and dead cells are ignored.
+	pub fn set_species(&mut self, v: Vec3, species: u8) {
+		if self.cells.get(&v) > 0 {
+			self.species.insert(v, species.min(self.rules.species - 1));
+		}
+	}
+
+	/// Swaps out the automaton's rules while keeping the current grid.
+	/// The new rules are checked against the neighbor method and the backend just like in `with_backend`, and the automaton is left untouched if they don't fit.
+	/// 
+	/// If the amount of cell states changes, live cells stay alive and dying cells are clamped so they're still dying. This also stops any rule schedule.
+	pub fn set_rules(&mut self, rules: AutomataRules) -> Result<(), AutomataError> {
+		self.swap_rules(rules)?;
+		self.schedule = None;
+		Ok(())
+	}
+
+	/// Checks whether the automaton could switch over to the given rules.
+	fn check_rules(&self, rules: &AutomataRules) -> Result<(), AutomataError> {
+		rules.validate(max_neighbors(&rules.neighbor_method)?, 3)?;
+		rules.check_backend(self.cells.backend())
+	}
+
+	/// Swaps out the rules like `set_rules`, without touching the rule schedule.
+	fn swap_rules(&mut self, rules: AutomataRules) -> Result<(), AutomataError> {
+		self.check_rules(&rules)?;
+
+		for cells in std::iter::once(&mut self.cells).chain(self.previous.iter_mut()) {
+			cells.map_states(|s| rules.carry_state(&self.rules, s));
+			cells.recount(&rules);
+		}
+
+		for species in self.species.values_mut() {
+			*species = (*species).min(rules.species - 1);
+		}
+
+		self.edge = rules.carry_state(&self.rules, self.edge);
+
+		self.offsets = neighbor_offsets(&rules.neighbor_method);
+		self.rules = rules;
+		self.log(Event::RulesChanged { generation: self.generation, rules: self.rules.to_string() });
+		Ok(())
+	}
+
+	/// Makes the automaton follow a repeating schedule of rules instead of the same rules every tick, where each set of rules in the list gets `ticks_each` ticks in a row before the next one takes over.
+	/// The tick from generation `g` uses `rules[(g / ticks_each) % rules.len()]`, so with two sets of rules and a `ticks_each` of 1, the first ticks even generations and the second ticks odd ones.
+	/// 
+	/// Every set of rules in the schedule gets checked the same way `set_rules` checks them, and the schedule is left alone if any of them don't fit.
+	/// The rules swap over at the start of each tick, just like with `set_rules`, so the automaton's rules are whichever ones made the last tick.
+	/// An empty list (or a `ticks_each` of 0) stops the schedule.
+	pub fn set_rule_schedule(&mut self, rules: Vec<AutomataRules>, ticks_each: usize) -> Result<(), AutomataError> {
+		if rules.is_empty() || ticks_each == 0 {
+			self.schedule = None;
+			return Ok(());
+		}
+
+		for r in &rules { self.check_rules(r)?; }
+
+		self.schedule = Some((rules, ticks_each));
+		Ok(())
+	}
+
+	/// Get the rule schedule and how many ticks each set of rules in it gets, or `None` if the automaton just follows its own rules.
+	pub fn get_rule_schedule(&self) -> Option<(&[AutomataRules], usize)> {
+		self.schedule.as_ref().map(|(rules, each)| (rules.as_slice(), *each))
+	}
+
+	/// Swaps in the scheduled rules for the tick that makes the given generation, if there's a schedule and they aren't already in.
+	fn follow_schedule(&mut self, generation: u64) {
+		let Some((rules, each)) = &self.schedule else { return; };
+		let next = &rules[(generation / *each as u64 % rules.len() as u64) as usize];
+
+		// every set of rules in the schedule was checked when it was set, so swapping can't fail
+		if *next != self.rules { let _ = self.swap_rules(next.clone()); }
+	}
+
+	/// Changes the size of the grid, keeping the given anchor in place.
+	/// New space is filled with dead cells, and any cells that end up outside of the new bounds are thrown away.
+	pub fn resize(&mut self, new_bounds: Vec3, anchor: Anchor) {
+		let shift = (
+			anchor.shift(self.bounds.x, new_bounds.x),
+			anchor.shift(self.bounds.y, new_bounds.y),
+			anchor.shift(self.bounds.z, new_bounds.z)
+		);
+		self.move_cells(new_bounds, shift);
+	}
+
+	/// Shrinks (or grows) the grid so it just fits every live or dying cell, plus the given amount of dead cells as padding on every side.
+	/// If there are no live cells at all, you're left with a grid that's nothing but padding.
+	pub fn crop_to_live_cells(&mut self, padding: usize) {
+		let (min, max) = match self.live_bounds() {
+			Some(corners) => corners,
+			None => {
+				self.move_cells(Vec3::new(padding * 2, padding * 2, padding * 2), (0, 0, 0));
+				return;
+			}
+		};
+
+		let new_bounds = Vec3::new(
+			max.x - min.x + 1 + padding * 2,
+			max.y - min.y + 1 + padding * 2,
+			max.z - min.z + 1 + padding * 2
+		);
+		let shift = (padding as i64 - min.x as i64, padding as i64 - min.y as i64, padding as i64 - min.z as i64);
+		self.move_cells(new_bounds, shift);
+	}
+
+	/// Rotates the whole grid a quarter turn around the given axis (the same way `Pattern::rotate90` does), so the sizes along the other two axes swap.
+	/// Species, walls, ages, trails, and the heatmap all turn along with the cells, but neighborhoods stay put, so lopsided ones tick differently afterwards.
+	/// 
+	/// ```
+	/// # use cellular_snapp::{AutomataRules, Method, deep::{Automaton, Axis, Vec3}};
+	/// let rules = AutomataRules::new(4..=5, 5, 2, Method::Moore);
+	/// let mut automaton = Automaton::new(rules, Vec3::new(8, 4, 2), vec![Vec3::new(7, 0, 1)]).unwrap();
+	/// automaton.rotate90(Axis::Z);
+	/// assert_eq!(automaton.get_bounds(), &Vec3::new(4, 8, 2));
+	/// assert_eq!(automaton.get_cell(&Vec3::new(3, 7, 1)), Some(1));
+	/// ```
+	pub fn rotate90(&mut self, axis: Axis) {
+		let Vec3 { x, y, z } = self.bounds;
+		match axis {
+			Axis::X => self.remap_cells(Vec3::new(x, z, y), |v| Vec3::new(v.x, z - 1 - v.z, v.y)),
+			Axis::Y => self.remap_cells(Vec3::new(z, y, x), |v| Vec3::new(v.z, v.y, x - 1 - v.x)),
+			Axis::Z => self.remap_cells(Vec3::new(y, x, z), |v| Vec3::new(y - 1 - v.y, v.x, v.z))
+		}
+	}
+
+	/// Mirrors the whole grid along the x axis. Everything that `rotate90` turns gets flipped too.
+	pub fn mirror_x(&mut self) {
+		let x = self.bounds.x;
+		self.remap_cells(self.bounds, |v| Vec3::new(x - 1 - v.x, v.y, v.z));
+	}
+
+	/// Mirrors the whole grid along the y axis. Everything that `rotate90` turns gets flipped too.
+	pub fn mirror_y(&mut self) {
+		let y = self.bounds.y;
+		self.remap_cells(self.bounds, |v| Vec3::new(v.x, y - 1 - v.y, v.z));
+	}
+
+	/// Mirrors the whole grid along the z axis. Everything that `rotate90` turns gets flipped too.
+	pub fn mirror_z(&mut self) {
+		let z = self.bounds.z;
+		self.remap_cells(self.bounds, |v| Vec3::new(v.x, v.y, z - 1 - v.z));
+	}
+
+	/// Copies every cell inside the given box into a pattern, dead ones included, with the box's smallest corner as the pattern's origin.
+	/// Only the part of the box that's inside the grid gets copied.
+	pub fn copy_region(&self, region: &Box3) -> Pattern {
+		let cells = self.get_cells_in(region).into_iter().map(|(v, s)| (v - region.min, s)).collect();
+		Pattern::with_states("", cells)
+	}
+
+	/// Pastes a pattern into the automaton with the pattern's origin at the given offset, combining its cells with the ones already there.
+	/// Cells without their own state are pasted fully alive, and any cells that would land outside of the bounds are skipped.
+	pub fn paste(&mut self, pattern: &Pattern, offset: Vec3, blend_mode: BlendMode) {
+		let max_state = self.rules.cell_states - 1;
+
+		for (v, s) in &pattern.cells {
+			let Some(pos) = v.checked_add_position(&offset) else { continue; };
+
+			if let Some(existing) = self.get_cell(&pos) {
+				let state = blend_mode.blend(existing, s.map_or(max_state, |s| s.min(max_state)));
+				self.set_cell(pos, state);
+			}
+		}
+	}
+
+	/// Kills every cell inside the given region.
+	pub fn clear_region(&mut self, region: &Box3) {
+		for v in self.live_cells_in(region).into_keys() { self.set_cell(v, 0); }
+	}
+
+	/// Sets every cell inside the given region to the same state, like to wall off part of the grid. States too big for the rules are clamped to alive,
+	/// and the part of the region outside the grid is skipped.
+	pub fn fill_region(&mut self, region: &Box3, state: u8) {
+		for v in self.clipped(region).iter_positions() { self.set_cell(v, state); }
+	}
+
+	/// Sets every cell along a straight line from `a` to `b` (both ends included) to the same state, like dragging a pencil through the grid in a pattern editor.
+	/// The line steps one cell at a time along whichever axis it covers the most of, so there aren't any gaps. States too big for the rules are clamped to alive,
+	/// and the part of the line outside the grid is skipped.
+	/// 
+	/// ```
+	/// # use cellular_snapp::{presets, deep::{Automaton, Box3, Vec3}};
+	/// let mut a = Automaton::new(presets::rule_445(), Vec3::new(8, 8, 8), Vec::new()).unwrap();
+	/// a.paint_line(Vec3::new(0, 0, 0), Vec3::new(7, 7, 3), 4);
+	/// assert_eq!(a.live_cells_sorted().len(), 8);
+	/// 
+	/// // a ball of radius 1 is the center cell and the six cells touching its faces
+	/// a.paint_sphere(Vec3::new(4, 4, 6), 1, 4);
+	/// assert_eq!(a.live_cells_in(&Box3::new(Vec3::new(3, 3, 5), Vec3::new(6, 6, 8))).len(), 7);
+	/// 
+	/// a.paint_box(Vec3::new(7, 0, 0), Vec3::new(6, 1, 0), 4);
+	/// assert_eq!(a.live_cells_in(&Box3::new(Vec3::new(6, 0, 0), Vec3::new(8, 2, 1))).len(), 4);
+	/// ```
+	pub fn paint_line(&mut self, a: Vec3, b: Vec3, state: u8) {
+		for v in line([a.x, a.y, a.z], [b.x, b.y, b.z], [self.bounds.x, self.bounds.y, self.bounds.z]) { self.set_cell(Vec3::new(v[0], v[1], v[2]), state); }
+	}
+
+	/// Sets every cell within `radius` of `center` to the same state, which paints a filled ball (a radius of 0 is just the center cell).
+	/// States too big for the rules are clamped to alive, and the part of the ball outside the grid is skipped.
+	pub fn paint_sphere(&mut self, center: Vec3, radius: usize, state: u8) {
+		let region = Box3::new(
+			Vec3::new(center.x.saturating_sub(radius), center.y.saturating_sub(radius), center.z.saturating_sub(radius)),
+			Vec3::new(center.x.saturating_add(radius).saturating_add(1), center.y.saturating_add(radius).saturating_add(1), center.z.saturating_add(radius).saturating_add(1))
+		);
+		let inside = |v: &Vec3| [v.x.abs_diff(center.x), v.y.abs_diff(center.y), v.z.abs_diff(center.z)].iter().map(|d| (*d as u128).pow(2)).sum::<u128>() <= (radius as u128).pow(2);
+
+		for v in self.clipped(&region).iter_positions().filter(inside) { self.set_cell(v, state); }
+	}
+
+	/// Sets every cell in the box with `a` and `b` as opposite corners (both included, whichever way round they are) to the same state.
+	/// States too big for the rules are clamped to alive, and the part of the box outside the grid is skipped.
+	pub fn paint_box(&mut self, a: Vec3, b: Vec3, state: u8) {
+		let region = Box3::new(
+			Vec3::new(a.x.min(b.x), a.y.min(b.y), a.z.min(b.z)),
+			Vec3::new(a.x.max(b.x).saturating_add(1), a.y.max(b.y).saturating_add(1), a.z.max(b.z).saturating_add(1))
+		);
+		self.fill_region(&region, state);
+	}
+
+	/// Combines the live cells of a pattern (with its origin at the given offset) with the live and dying cells already there.
+	/// Every cell it brings to life is fully alive, and any part of the mask outside of the bounds is skipped.
+	pub fn apply_mask(&mut self, mask: &Pattern, offset: Vec3, op: MaskOp) {
+		let max_state = self.rules.cell_states - 1;
+		let inside: HashSet<Vec3> = mask.positions().into_iter().filter_map(|v| v.checked_add_position(&offset)).filter(|v| self.get_cell(v).is_some()).collect();
+
+		match op {
+			MaskOp::And => {
+				for v in self.cells.live().into_keys().filter(|v| !inside.contains(v)) { self.set_cell(v, 0); }
+			}
+			MaskOp::Or => {
+				for v in inside { if self.cells.get(&v) == 0 { self.set_cell(v, max_state); } }
+			}
+			MaskOp::Xor => {
+				for v in inside { self.set_cell(v, if self.cells.get(&v) == 0 { max_state } else { 0 }); }
+			}
+		}
+	}
+
+	/// Merges another automaton's live and dying cells into this one, with the other automaton's origin at the given offset.
+	/// Wherever both automata have a live or dying cell, the conflict policy picks which one stays. Cells that come from the other automaton bring their species with them,
+	/// and their states are clamped to fit these rules. Any cells that would land outside of the bounds are skipped.
+	pub fn merge(&mut self, other: &Automaton, offset: Vec3, conflict_policy: ConflictPolicy) {
+		let max_state = self.rules.cell_states - 1;
+
+		for (v, theirs) in other.cells.live() {
+			let Some(pos) = v.checked_add_position(&offset) else { continue; };
+			let theirs = theirs.min(max_state);
+
+			if let Some(mine) = self.get_cell(&pos).filter(|mine| conflict_policy.takes_other(*mine, theirs)) {
+				if mine > 0 { self.set_cell(pos, 0); }
+				self.set_cell(pos, theirs);
+				if let Some(species) = other.species.get(&v) { self.set_species(pos, *species); }
+			}
+		}
+	}
+
+	/// Panics with a message saying where things went wrong if a cell being indexed is outside the grid.
+	fn check_index(&self, v: &Vec3) {
+		if !(v.x < self.bounds.x && v.y < self.bounds.y && v.z < self.bounds.z) { panic!("cell ({}, {}, {}) is outside the {}x{}x{} grid", v.x, v.y, v.z, self.bounds.x, self.bounds.y, self.bounds.z); }
+	}
+
+	/// Kills excluded walls for the length of a tick, so their neighbors can't see them.
+	fn hide_walls(&mut self) {
+		for (v, (wall, _)) in &self.walls {
+			if *wall == Wall::Excluded { self.cells.set(*v, 0); }
+		}
+	}
+
+	/// Puts every wall back the way it's supposed to look, undoing whatever the rules did to it.
+	fn show_walls(&mut self) {
+		let max_state = self.rules.cell_states - 1;
+
+		for (v, (wall, state)) in &self.walls {
+			let state = match wall { Wall::Alive => max_state, Wall::Dead => 0, Wall::Excluded => (*state).min(max_state) };
+			self.cells.set(*v, state);
+		}
+	}
+
+	/// Rebuilds the grid with new bounds, moving every cell by the given shift.
+	fn move_cells(&mut self, new_bounds: Vec3, shift: (i64, i64, i64)) {
+		let backend = self.cells.backend();
+		let cells = std::mem::replace(&mut self.cells, Cells::Map(HashMap::new()));
+
+		self.cells = Cells::from_map(backend, &new_bounds, &self.rules, shift_tracked(cells.into_map(), &new_bounds, shift));
+		self.previous = self.previous.take().map(|p| Cells::from_map(backend, &new_bounds, &self.rules, shift_tracked(p.into_map(), &new_bounds, shift)));
+		self.species = shift_tracked(std::mem::take(&mut self.species), &new_bounds, shift).into_iter().filter(|(v, _)| self.cells.get(v) > 0).collect();
+		self.walls = shift_tracked(std::mem::take(&mut self.walls), &new_bounds, shift);
+		self.ages = self.ages.take().map(|ages| shift_tracked(ages, &new_bounds, shift));
+		self.trails = self.trails.take().map(|trails| shift_tracked(trails, &new_bounds, shift));
+		self.heat = self.heat.take().map(|(decay, heat)| (decay, shift_tracked(heat, &new_bounds, shift)));
+		self.bounds = new_bounds;
+	}
+
+	/// Moves every cell to wherever `map` sends it inside the new bounds, for turning and flipping the grid.
+	/// Every cell has to end up somewhere different, or some of them get lost.
+	fn remap_cells(&mut self, new_bounds: Vec3, map: impl Fn(&Vec3) -> Vec3) {
+		let backend = self.cells.backend();
+		let remap = |cells: Cells| Cells::from_map(backend, &new_bounds, &self.rules, cells.into_map().into_iter().map(|(v, s)| (map(&v), s)).collect());
+
+		let cells = std::mem::replace(&mut self.cells, Cells::Map(HashMap::new()));
+		self.cells = remap(cells);
+		self.previous = self.previous.take().map(remap);
+		self.species = std::mem::take(&mut self.species).into_iter().map(|(v, s)| (map(&v), s)).collect();
+		self.walls = std::mem::take(&mut self.walls).into_iter().map(|(v, w)| (map(&v), w)).collect();
+		self.ages = self.ages.take().map(|ages| ages.into_iter().map(|(v, a)| (map(&v), a)).collect());
+		self.trails = self.trails.take().map(|trails| trails.into_iter().map(|(v, t)| (map(&v), t)).collect());
+		self.heat = self.heat.take().map(|(decay, heat)| (decay, heat.into_iter().map(|(v, h)| (map(&v), h)).collect()));
+		self.bounds = new_bounds;
+	}
+
+	/// Saves the automaton into a compact binary checkpoint: the rules, the bounds, the backend, the generation, the random number generator,
+	/// and every live or dying cell, with runs of dead cells squashed down to their length. It can go anywhere that can be written to, not just files.
+	/// 
+	/// Walls, the edge state, the update scheme, and trails get saved along with them. Ages and the heatmap aren't saved, since they only keep track of the run.
+	/// Everything else that changes how the run carries on has to be saved for it to pick up exactly where it left off, so automata with the generation before (in second-order mode),
+	/// more than one species, or a rule schedule give an `InvalidInput` error, and so do rules that can't be written as a rule string, like transition tables.
+	/// 
+	/// ```
+	/// # use cellular_snapp::{presets, random::Rng, deep::{self, Automaton, Vec3}};
+	/// let seed = deep::random_seed(Vec3::new(12, 12, 12), 0.3, &mut Rng::new(1));
+	/// let mut clouds = Automaton::new(presets::clouds(), Vec3::new(12, 12, 12), seed).unwrap();
+	/// clouds.tick();
+	/// 
+	/// let mut checkpoint = Vec::new();
+	/// clouds.save_checkpoint(&mut checkpoint).unwrap();
+	/// assert!(Automaton::load_checkpoint(checkpoint.as_slice()).unwrap().get_cells() == clouds.get_cells());
+	/// ```
+	pub fn save_checkpoint(&self, mut writer: impl Write) -> io::Result<()> {
+		let unsaved = if self.previous.is_some() { Some("the generation before") }
+			else if self.rules.species() > 1 { Some("species") }
+			else if self.schedule.is_some() { Some("rule schedules") }
+			else { None };
+		if let Some(what) = unsaved { return Err(io::Error::new(io::ErrorKind::InvalidInput, format!("{} can't be written into a checkpoint", what))); }
+
+		let index = |v: &Vec3| (v.z as u64 * self.bounds.y as u64 + v.y as u64) * self.bounds.x as u64 + v.x as u64;
+		let mut cells = self.cells.live().into_iter().map(|(v, s)| (index(&v), s)).collect::<Vec<(u64, u8)>>();
+		cells.sort_unstable();
+
+		let mut walls = self.walls.iter().map(|(v, (wall, state))| (index(v), *wall, *state)).collect::<Vec<(u64, Wall, u8)>>();
+		walls.sort_unstable_by_key(|(i, _, _)| *i);
+		let trails = self.trails.as_ref().map(|trails| trails.iter().map(|(v, since)| (index(v), *since)).collect());
+
+		let header = checkpoint::Header { bounds: vec![self.bounds.x as u64, self.bounds.y as u64, self.bounds.z as u64], generation: self.generation, rng: self.rng.state(), backend: self.cells.backend(), rules: self.rules.clone(), edge: self.edge, scheme: self.scheme, walls, trails };
+		header.write(&mut writer)?;
+		checkpoint::write_cells(&mut writer, &cells)
+	}
+
+	/// Loads an automaton back from a checkpoint made by `save_checkpoint`, so it carries on exactly where it left off.
+	/// Checkpoints that don't make sense give an `InvalidData` error, and so do ones for grids too big to make room for, like a dense grid with more than 2^32 cells.
+	pub fn load_checkpoint(mut reader: impl Read) -> io::Result<Automaton> {
+		let header = checkpoint::Header::read(&mut reader, 3)?;
+		let area = checkpoint::area(&header.bounds, header.backend)?;
+		let bounds = Vec3::new(header.bounds[0] as usize, header.bounds[1] as usize, header.bounds[2] as usize);
+		let states = header.rules.cell_states;
+
+		let position = |i: u64| {
+			if i >= area { return Err(checkpoint::invalid("a cell is outside the grid")); }
+			Ok(Vec3::new((i % bounds.x as u64) as usize, (i / bounds.x as u64 % bounds.y as u64) as usize, (i / (bounds.x as u64 * bounds.y as u64)) as usize))
+		};
+		let state = |s: u8| if s < states { Ok(s) } else { Err(checkpoint::invalid(&format!("state {} is too big for the rules", s))) };
+
+		// everything gets checked against the grid before the grid gets made, so a checkpoint that doesn't make sense can't make room for a huge one first
+		let cells = checkpoint::read_cells(&mut reader)?.into_iter().map(|(i, s)| Ok((position(i)?, state(s)?))).collect::<io::Result<Vec<(Vec3, u8)>>>()?;
+		let walls = header.walls.into_iter().map(|(i, wall, s)| Ok((position(i)?, (wall, state(s)?)))).collect::<io::Result<HashMap<Vec3, (Wall, u8)>>>()?;
+		let trails = header.trails.map(|trails| trails.into_iter().map(|(i, since)| Ok((position(i)?, since))).collect::<io::Result<HashMap<Vec3, u32>>>()).transpose()?;
+
+		let mut automaton = Automaton::with_backend(header.rules, bounds, Vec::new(), header.backend).map_err(|e| checkpoint::invalid(&e.to_string()))?;
+		automaton.generation = header.generation;
+		automaton.rng = Rng::new(header.rng);
+		automaton.set_edge_state(header.edge);
+		automaton.scheme = header.scheme;
+
+		for (v, s) in cells { automaton.cells.set(v, s); }
+		automaton.walls = walls;
+		automaton.trails = trails;
+
+		Ok(automaton)
+	}
+
+	/// Squashes the whole grid down into bytes: the bounds, and then every cell row by row and layer by layer as runs of the same state, so big stretches of dead (or live) cells only take a few bytes.
+	/// Only the cells are kept, not the rules, the generation, or anything else a checkpoint has, which makes it handy for storing lots of snapshots of the same run.
+	/// 
+	/// ```
+	/// # use cellular_snapp::{presets, deep::{Automaton, Vec3}};
+	/// let mut cube = Automaton::new(presets::rule_445(), Vec3::new(32, 32, 32), (14..18).flat_map(|x| (14..18).map(move |y| Vec3::new(x, y, 16))).collect()).unwrap();
+	/// for _ in 0..3 { cube.tick(); }
+	/// 
+	/// let bytes = cube.to_bytes();
+	/// assert!(bytes.len() < 1000);
+	/// let loaded = Automaton::from_bytes(presets::rule_445(), &bytes).unwrap();
+	/// assert_eq!(loaded.live_cells_sorted(), cube.live_cells_sorted());
+	/// ```
+	pub fn to_bytes(&self) -> Vec<u8> {
+		checkpoint::write_grid(&[self.bounds.x, self.bounds.y, self.bounds.z], self.iter_cells().map(|(_, s)| s))
+	}
+
+	/// Creates a new automaton with the given rules out of bytes made by `to_bytes`. Bytes that don't make sense give back an `InvalidPattern` error,
+	/// and states too big for the rules give back an `InvalidState` error.
+	pub fn from_bytes(rules: AutomataRules, bytes: &[u8]) -> Result<Automaton, AutomataError> {
+		let (bounds, runs) = checkpoint::read_grid(bytes, 3).map_err(AutomataError::InvalidPattern)?;
+		let mut a = Automaton::new(rules, Vec3::new(bounds[0], bounds[1], bounds[2]), Vec::new())?;
+
+		let mut start = 0;
+		for (s, len) in runs {
+			if s >= a.rules.cell_states { return Err(AutomataError::InvalidState(s)); }
+			if s > 0 {
+				for i in start..start + len { a.cells.set(Vec3::new(i % bounds[0], i / bounds[0] % bounds[1], i / (bounds[0] * bounds[1])), s); }
+			}
+			start += len;
+		}
+
+		Ok(a)
+	}
+
+	/// Captures everything needed to replay the run from here: the rules, the bounds, every live or dying cell, and the random number generator's seed.
+	/// Walls, the edge state, and the update scheme go in it too. See the `replay` module for more.
+	/// 
+	/// Rule schedules can't go in a log, so automata following one give back an `InvalidReplay` error.
+	pub fn replay_log(&self) -> Result<ReplayLog, AutomataError> {
+		if self.schedule.is_some() { return Err(AutomataError::InvalidReplay("rule schedules can't be written into a replay log".to_string())); }
+
+		let cells = self.get_species().into_iter().map(|(v, species)| ([v.x, v.y, v.z], self.cells.get(&v), species)).collect();
+		let walls = self.walls.iter().map(|(v, (wall, state))| ([v.x, v.y, v.z], *wall, *state)).collect();
+		Ok(ReplayLog::new(self.rules.clone(), [self.bounds.x, self.bounds.y, self.bounds.z], cells, self.rng.state()).with_walls(walls, self.edge).with_update_scheme(self.scheme))
+	}
+
+	/// Rebuilds an automaton from a replay log, so it ticks exactly the way the original did from where the log was made. The generation starts back at 0.
+	pub fn from_replay(log: &ReplayLog) -> Result<Automaton, AutomataError> {
+		Automaton::from_replay_with_backend(log, Backend::HashMap)
+	}
+
+	/// Rebuilds an automaton from a replay log just like `from_replay`, except the cells are kept in the given backend.
+	/// Every backend ticks the same way, so this is how to check a run against one made with a different backend.
+	pub fn from_replay_with_backend(log: &ReplayLog, backend: Backend) -> Result<Automaton, AutomataError> {
+		let [width, height, depth] = log.bounds();
+		let mut automaton = Automaton::with_backend(log.rules().clone(), Vec3::new(width, height, depth), Vec::new(), backend)?;
+
+		for ([x, y, z], state, species) in log.cells() {
+			if !(*x < width && *y < height && *z < depth) { return Err(AutomataError::InvalidReplay(format!("the cell at {}, {}, {} is outside the grid", x, y, z))); }
+			if *state >= automaton.rules.cell_states { return Err(AutomataError::InvalidState(*state)); }
+
+			automaton.set_cell(Vec3::new(*x, *y, *z), *state);
+			if *species > 0 { automaton.set_species(Vec3::new(*x, *y, *z), *species); }
+		}
+
+		for ([x, y, z], wall, state) in log.walls() {
+			if !(*x < width && *y < height && *z < depth) { return Err(AutomataError::InvalidReplay(format!("the wall at {}, {}, {} is outside the grid", x, y, z))); }
+			if *state >= automaton.rules.cell_states { return Err(AutomataError::InvalidState(*state)); }
+			automaton.walls.insert(Vec3::new(*x, *y, *z), (*wall, *state));
+		}
+
+		automaton.set_edge_state(log.edge_state());
+		automaton.set_update_scheme(log.update_scheme());
+		automaton.seed_rng(log.seed());
+		Ok(automaton)
+	}
+
+	/// Draws one z-layer of the grid as text, one line per row.
+	/// Each cell's state is used as an index into the charset, so `charset[0]` is used for dead cells, and states past the end of the charset use its last character.
+	/// Layers outside of the grid just come out empty.
+	pub fn render_ascii_layer(&self, z: usize, charset: &[char]) -> String {
+		if z >= self.bounds.z { return String::new() }
+
+		let mut out = String::with_capacity((self.bounds.x + 1) * self.bounds.y);
+
+		for y in 0..self.bounds.y {
+			for x in 0..self.bounds.x {
+				let state = self.cells.get(&Vec3::new(x, y, z)) as usize;
+				out.push(charset.get(state).or(charset.last()).copied().unwrap_or(' '));
+			}
+
+			out.push('\n');
+		}
+
+		out
+	}
+}
+
+/// Sums the automaton up in a line: its rules, bounds, backend, and how many cells are live or dying.
+/// The alternate form (`{:#?}`) draws every z-layer of the grid underneath too, with `#` for live cells, `+` for dying cells, and `.` for dead cells.
+impl fmt::Debug for Automaton {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "Automaton {{ rules: {}, bounds: {}x{}x{}, backend: {:?}, live_cells: {} }}", self.rules, self.bounds.x, self.bounds.y, self.bounds.z, self.get_backend(), self.cells.live().len())?;
+
+		if f.alternate() {
+			let mut charset = vec!['.'];
+			charset.resize(self.rules.cell_states as usize - 1, '+');
+			charset.push('#');
+
+			for z in 0..self.bounds.z { write!(f, "\n{}", self.render_ascii_layer(z, &charset))?; }
+		}
+
+		Ok(())
+	}
+}
+
+/// Lets deep automata be used by anything written for any `CellularAutomaton`.
+impl CellularAutomaton for Automaton {
+	type Coord = Vec3;
+
+	fn tick(&mut self) { Automaton::tick(self) }
+
+	fn live_cells(&self) -> HashMap<Vec3, u8> { self.live_cells_in(&Box3::new(Vec3::new(0, 0, 0), self.bounds)) }
+
+	fn get_cell(&self, v: &Vec3) -> Option<u8> { Automaton::get_cell(self, v) }
+
+	fn set_cell(&mut self, v: Vec3, state: u8) { Automaton::set_cell(self, v, state) }
+
+	fn bounds(&self) -> Vec3 { self.bounds }
+
+	fn generation(&self) -> u64 { self.generation }
+
+	fn rules(&self) -> &AutomataRules { &self.rules }
+}
+
+/// Two automata are equal when they have the same rules, bounds, and cells, even if they keep their cells in different backends.
+impl PartialEq for Automaton {
+	fn eq(&self, other: &Automaton) -> bool {
+		self.rules == other.rules && self.bounds == other.bounds && self.cells.live() == other.cells.live()
+	}
+}
+
+/// Get the state of a cell by its position, like `automaton[(x, y, z)]`. This panics if the cell is outside the grid, so use `get_cell` when it might be.
+impl Index<Vec3> for Automaton {
+	type Output = u8;
+
+	fn index(&self, v: Vec3) -> &u8 {
+		// the backends that don't keep bytes around (like bit-packed grids) hand back a reference into this instead
+		static STATES: [u8; 256] = { let mut states = [0; 256]; let mut s = 0; while s < 256 { states[s] = s as u8; s += 1; } states };
+
+		self.check_index(&v);
+		&STATES[self.cells.get(&v) as usize]
+	}
+}
+
+impl Index<(usize, usize, usize)> for Automaton {
+	type Output = u8;
+
+	fn index(&self, (x, y, z): (usize, usize, usize)) -> &u8 { &self[Vec3::new(x, y, z)] }
+}
+
+/// Changes the state of a cell by its position, like `automaton[(x, y, z)] = 1`. This panics if the cell is outside the grid.
+/// 
+/// States are written straight into the grid without being clamped like `set_cell` does, and only the hash map and dense backends keep a byte per cell to write into,
+/// so this panics with the other backends too. The hash map backend makes an entry for the cell even if it's dead, which stays around until `compact` gets rid of it.
+/// Changes made this way don't show up in the event log either.
+impl IndexMut<Vec3> for Automaton {
+	fn index_mut(&mut self, v: Vec3) -> &mut u8 {
+		self.check_index(&v);
+
+		match &mut self.cells {
+			Cells::Map(map) => map.entry(v).or_insert(0),
+			Cells::Dense(dense, height) => dense.get_mut(v.x, v.z * *height + v.y),
+			cells => panic!("the {:?} backend can't be indexed mutably, use set_cell instead", cells.backend())
+		}
+	}
+}
+
+impl IndexMut<(usize, usize, usize)> for Automaton {
+	fn index_mut(&mut self, (x, y, z): (usize, usize, usize)) -> &mut u8 { &mut self[Vec3::new(x, y, z)] }
+}
+
+impl Frame {
+	/// Get the bounds (the size of the grid) the automaton had.
+	pub fn bounds(&self) -> &Vec3 {
+		&self.bounds
+	}
+
+	/// Get the generation the snapshot was taken at.
+	pub fn generation(&self) -> u64 {
+		self.generation
+	}
+
+	/// Get the state of every cell, laid out row by row and then layer by layer, just like `as_slice`.
+	pub fn cells(&self) -> &[u8] {
+		&self.cells
+	}
+
+	/// Get the state of a single cell, or `None` if it's outside the grid.
+	pub fn get_cell(&self, v: &Vec3) -> Option<u8> {
+		(v.x < self.bounds.x && v.y < self.bounds.y && v.z < self.bounds.z).then(|| self.cells[(v.z * self.bounds.y + v.y) * self.bounds.x + v.x])
+	}
+}
+
+impl<A: BorrowMut<Automaton>> Iterator for Generations<A> {
+	type Item = Automaton;
+
+	fn next(&mut self) -> Option<Automaton> {
+		let automaton = self.automaton.borrow_mut();
+		automaton.tick();
+		Some(automaton.clone())
+	}
+}
+
+/// Generates a random soup filling the given bounds, where every cell has a `density` chance of being alive.
+/// The same random number generator seed always gives the same soup.
+pub fn random_seed(bounds: Vec3, density: f64, rng: &mut Rng) -> Vec<Vec3> {
+	let mut seed = Vec::new();
+
+	for z in 0..bounds.z {
+		for y in 0..bounds.y {
+			for x in 0..bounds.x {
+				if rng.chance(density) { seed.push(Vec3::new(x, y, z)); }
+			}
+		}
+	}
+
+	seed
+}
+
+/// Generates a noisy ball of cells in the middle of the given bounds, the usual way to start off a 3D automaton.
+/// Every cell within `radius` of the center is alive if the Perlin noise there, scaled to between 0 and 1, is at least `threshold`,
+/// so a threshold of 0.5 fills about half the ball in smooth blobs. The same seed always gives the same ball.
+/// 
+/// ```
+/// # use cellular_snapp::{presets, deep::{seed_noise_sphere, Automaton, Vec3}};
+/// let bounds = Vec3::new(24, 24, 24);
+/// let seed = seed_noise_sphere(bounds, 8.0, 0.5, 42);
+/// 
+/// assert!(!seed.is_empty());
+/// assert_eq!(seed, seed_noise_sphere(bounds, 8.0, 0.5, 42));
+/// 
+/// let automaton = Automaton::new(presets::clouds(), bounds, seed).unwrap();
+/// ```
+#[cfg(feature = "noise")]
+pub fn seed_noise_sphere(bounds: Vec3, radius: f64, threshold: f64, seed: u64) -> Vec<Vec3> {
+	// noise is always 0 on whole numbers, so this keeps the samples from lining up with them
+	const SCALE: f64 = 0.173;
+
+	let noise = crate::noise::Perlin::new(seed);
+	let center = (bounds.x as f64 / 2.0, bounds.y as f64 / 2.0, bounds.z as f64 / 2.0);
+	let mut cells = Vec::new();
+
+	for z in 0..bounds.z {
+		for y in 0..bounds.y {
+			for x in 0..bounds.x {
+				let (dx, dy, dz) = (x as f64 + 0.5 - center.0, y as f64 + 0.5 - center.1, z as f64 + 0.5 - center.2);
+				if dx * dx + dy * dy + dz * dz > radius * radius { continue; }
+
+				if (noise.get(x as f64 * SCALE, y as f64 * SCALE, z as f64 * SCALE) + 1.0) / 2.0 >= threshold { cells.push(Vec3::new(x, y, z)); }
+			}
+		}
+	}
+
+	cells
+}
+
+/// Generates a random soup filling the given bounds with the given symmetry, where every cell has about a `density` chance of being alive.
+/// The same random number generator seed always gives the same soup.
+/// 
+/// ```
+/// # use cellular_snapp::{random::Rng, deep::{symmetric_seed, Symmetry, Vec3}};
+/// let seed = symmetric_seed(Vec3::new(8, 8, 8), 0.3, Symmetry::MirrorXYZ, &mut Rng::new(42));
+/// 
+/// for v in &seed {
+///     assert!(seed.contains(&Vec3::new(7 - v.x(), 7 - v.y(), 7 - v.z())));
+/// }
+/// ```
+pub fn symmetric_seed(bounds: Vec3, density: f64, symmetry: Symmetry, rng: &mut Rng) -> Vec<Vec3> {
+	// working in doubled coordinates from the middle of the grid keeps everything in whole numbers, even when the middle falls between cells
+	let size = [bounds.x as i64, bounds.y as i64, bounds.z as i64];
+	let transforms = symmetry.transforms();
+	let mut seed = Vec::new();
+
+	for z in 0..bounds.z {
+		for y in 0..bounds.y {
+			for x in 0..bounds.x {
+				let c = [2 * x as i64 - (size[0] - 1), 2 * y as i64 - (size[1] - 1), 2 * z as i64 - (size[2] - 1)];
+
+				let mut orbit = transforms.iter().filter_map(|m| {
+					let t: [i64; 3] = std::array::from_fn(|i| m[i * 3] * c[0] + m[i * 3 + 1] * c[1] + m[i * 3 + 2] * c[2] + size[i] - 1);
+					(0..3).all(|i| t[i] % 2 == 0 && (0..2 * size[i]).contains(&t[i])).then(|| Vec3::new(t[0] as usize / 2, t[1] as usize / 2, t[2] as usize / 2))
+				}).collect::<Vec<Vec3>>();
+
+				orbit.sort_unstable_by_key(|v| (v.z, v.y, v.x));
+				orbit.dedup();
+
+				// only the first cell of each group gets a roll, and the rest copy it
+				if orbit[0] == Vec3::new(x, y, z) && rng.chance(density) { seed.extend(orbit); }
+			}
+		}
+	}
+
+	seed.sort_unstable_by_key(|v| (v.z, v.y, v.x));
+	seed
+}
+
+/// Multiplies two 3x3 matrices written out a row at a time.
+fn mul(a: &[i64; 9], b: &[i64; 9]) -> [i64; 9] {
+	std::array::from_fn(|i| (0..3).map(|k| a[i / 3 * 3 + k] * b[k * 3 + i % 3]).sum())
+}
+
+/// Moves the cells, ages, trails, or heat of every cell by the given shift, throwing away whatever doesn't fit in the new bounds.
+fn shift_tracked<T>(old: HashMap<Vec3, T>, new_bounds: &Vec3, shift: (i64, i64, i64)) -> HashMap<Vec3, T> {
+	old.into_iter().filter_map(|(v, t)| {
+		let (x, y, z) = (v.x as i64 + shift.0, v.y as i64 + shift.1, v.z as i64 + shift.2);
+		(x >= 0 && (x as usize) < new_bounds.x && y >= 0 && (y as usize) < new_bounds.y && z >= 0 && (z as usize) < new_bounds.z).then(|| (Vec3::new(x as usize, y as usize, z as usize), t))
+	}).collect()
+}
+
+/// Get the relative positions of every neighbor a cell has with the given neighbor method.
+pub(crate) fn neighbor_offsets(method: &Method) -> Vec<IVec3> {
+	let reach = method.reach();
+	let mut offsets = Vec::new();
+
+	for x in -reach..=reach {
+		for y in -reach..=reach {
+			for z in -reach..=reach {
+				let (near, steps) = (x.abs() <= 1 && y.abs() <= 1 && z.abs() <= 1, x.abs() + y.abs() + z.abs());
+
+				let is_neighbor = match method {
+					Method::Moore => near && steps > 0,
+					Method::VonNeumann => steps == 1,
+					Method::FacesAndEdges => near && (1..=2).contains(&steps),
+					Method::Euclidean(radius) => steps > 0 && ((x * x + y * y + z * z) as f64) <= radius * radius
+				};
+
+				if is_neighbor { offsets.push(IVec3::new(x, y, z)); }
+			}
+		}
+	}
+
+	offsets
+}
+
+/// Get the maximum amount of neighbors a cell can have with the given neighbor method, which fails if there are too many to count.
+pub(crate) fn max_neighbors(method: &Method) -> Result<u8, AutomataError> {
+	match method {
+		Method::Moore => Ok(26),
+		Method::VonNeumann => Ok(6),
+		Method::FacesAndEdges => Ok(18),
+		Method::Euclidean(_) => {
+			let neighbors = neighbor_offsets(method).len();
+			u8::try_from(neighbors).map_err(|_| AutomataError::NeighborhoodTooBig(neighbors))
+		}
+	}
+}
+
+/// Get where a cell is in the incremental backend's array, which goes row by row, and then layer by layer.
+fn index_of(bounds: &Vec3, v: &Vec3) -> usize {
+	(v.z * bounds.y + v.y) * bounds.x + v.x
+}
+
+/// Get which cell is at an index of the incremental backend's array.
+fn cell_at(bounds: &Vec3, i: usize) -> Vec3 {
+	Vec3::new(i % bounds.x, i / bounds.x % bounds.y, i / (bounds.x * bounds.y))
+}
+
+/// Get the index of every neighbor of a cell that's inside the grid, for the incremental backend.
+fn neighbor_indices<'a>(bounds: &'a Vec3, offsets: &'a [IVec3], v: Vec3) -> impl Iterator<Item = usize> + 'a {
+	offsets.iter().filter_map(move |offset| v.checked_add_offset(offset)).filter(|n| n.x < bounds.x && n.y < bounds.y && n.z < bounds.z).map(|n| index_of(bounds, &n))
+}
+
+impl Cells {
+	/// Creates a grid full of dead cells for the given backend, which counts neighbors the way the given rules do if it keeps counts.
+	/// This fails if the backend keeps its cells in a memory-mapped file and the file can't be made.
+	fn try_new(backend: Backend, bounds: &Vec3, rules: &AutomataRules) -> Result<Cells, AutomataError> {
+		Ok(match backend {
+			Backend::HashMap => Cells::Map(HashMap::new()),
+			Backend::Tree => Cells::Tree(Tree::new([bounds.x, bounds.y, bounds.z])),
+			Backend::BitPacked => Cells::Bits(Bits::new(bounds.x, bounds.y * bounds.z), bounds.y),
+			Backend::Dense => Cells::Dense(Dense::new(bounds.x, bounds.y * bounds.z), bounds.y),
+			#[cfg(feature = "mmap")]
+			Backend::Mapped => Cells::Dense(Dense::mapped(bounds.x, bounds.y * bounds.z).map_err(|e| AutomataError::MappingFailed(e.to_string()))?, bounds.y),
+			Backend::Incremental => Cells::Incremental(Incremental::new(bounds.x * bounds.y * bounds.z, rules.counted_states()), *bounds, neighbor_offsets(&rules.neighbor_method))
+		})
+	}
+
+	/// Creates a grid full of dead cells like `try_new`, except a grid that can't get a memory-mapped file gets kept in memory instead.
+	fn new(backend: Backend, bounds: &Vec3, rules: &AutomataRules) -> Cells {
+		Cells::try_new(backend, bounds, rules).unwrap_or_else(|_| Cells::new(Backend::Dense, bounds, rules))
+	}
+
+	/// Fills a grid for the given backend from a map of cells, which can leave dead cells out.
+	fn from_map(backend: Backend, bounds: &Vec3, rules: &AutomataRules, map: HashMap<Vec3, u8>) -> Cells {
+		let mut cells = Cells::new(backend, bounds, rules);
+		for (v, s) in map { cells.set(v, s); }
+		cells
+	}
+
+	/// Get which backend this is.
+	fn backend(&self) -> Backend {
+		match self {
+			Cells::Map(_) => Backend::HashMap,
+			Cells::Tree(_) => Backend::Tree,
+			Cells::Bits(..) => Backend::BitPacked,
+			#[cfg(feature = "mmap")]
+			Cells::Dense(dense, _) if dense.is_mapped() => Backend::Mapped,
+			Cells::Dense(..) => Backend::Dense,
+			Cells::Incremental(..) => Backend::Incremental
+		}
+	}
+
+	/// Get the state of a cell. Cells that aren't stored are dead.
+	fn get(&self, v: &Vec3) -> u8 {
+		match self {
+			Cells::Map(map) => map.get(v).copied().unwrap_or(0),
+			Cells::Tree(tree) => tree.get(&[v.x, v.y, v.z]),
+			Cells::Bits(bits, height) => bits.get(v.x, v.z * height + v.y) as u8,
+			Cells::Dense(dense, height) => dense.get(v.x, v.z * height + v.y),
+			Cells::Incremental(grid, bounds, _) => grid.get(index_of(bounds, v))
+		}
+	}
+
+	/// Sets the state of a cell, which has to be inside the grid.
+	fn set(&mut self, v: Vec3, state: u8) {
+		match self {
+			Cells::Map(map) => if state == 0 { map.remove(&v); } else { map.insert(v, state); },
+			Cells::Tree(tree) => tree.set([v.x, v.y, v.z], state),
+			Cells::Bits(bits, height) => bits.set(v.x, v.z * *height + v.y, state > 0),
+			Cells::Dense(dense, height) => dense.set(v.x, v.z * *height + v.y, state),
+			Cells::Incremental(grid, bounds, offsets) => grid.set(index_of(bounds, &v), state, neighbor_indices(bounds, offsets, v))
+		}
+	}
+
+	/// Changes the state of every stored cell.
+	fn map_states(&mut self, f: impl Fn(u8) -> u8) {
+		match self {
+			Cells::Map(map) => map.retain(|_, s| { *s = f(*s); *s > 0 }),
+			Cells::Tree(tree) => for (p, s) in tree.cells() { tree.set(p, f(s)); },
+			Cells::Bits(bits, _) => for (x, row) in bits.live() { bits.set(x, row, f(1) > 0); },
+			Cells::Dense(dense, _) => dense.map_states(f),
+			Cells::Incremental(grid, bounds, offsets) => for i in 0..bounds.x * bounds.y * bounds.z {
+				grid.set(i, f(grid.get(i)), neighbor_indices(bounds, offsets, cell_at(bounds, i)));
+			}
+		}
+	}
+
+	/// Starts counting neighbors the way the given rules do, if the grid keeps counts.
+	fn recount(&mut self, rules: &AutomataRules) {
+		if let Cells::Incremental(grid, bounds, offsets) = self {
+			*offsets = neighbor_offsets(&rules.neighbor_method);
+			let (bounds, offsets) = (&*bounds, &*offsets);
+			grid.recount(rules.counted_states(), |i| neighbor_indices(bounds, offsets, cell_at(bounds, i)));
+		}
+	}
+
+	/// Gets rid of any dead cells the hash map backend is still holding onto, and shrinks it down to fit.
+	fn compact(&mut self) {
+		if let Cells::Map(map) = self {
+			map.retain(|_, s| *s > 0);
+			map.shrink_to_fit();
+		}
+	}
+
+	/// Forgets which cells would stay put, if the grid keeps track of that, for after it's been ticked without going through its counts.
+	fn unsettle(&mut self) {
+		if let Cells::Incremental(grid, ..) = self { grid.mark_all(); }
+	}
+
+	/// Get every live or dying cell.
+	fn live(&self) -> HashMap<Vec3, u8> {
+		match self {
+			Cells::Map(map) => map.iter().filter(|(_, s)| **s > 0).map(|(v, s)| (*v, *s)).collect(),
+			Cells::Tree(tree) => tree.cells().into_iter().map(|([x, y, z], s)| (Vec3::new(x, y, z), s)).collect(),
+			Cells::Bits(bits, height) => bits.live().into_iter().map(|(x, row)| (Vec3::new(x, row % height, row / height), 1)).collect(),
+			Cells::Dense(dense, height) => dense.live().into_iter().map(|(x, row, s)| (Vec3::new(x, row % height, row / height), s)).collect(),
+			Cells::Incremental(grid, bounds, _) => grid.live().into_iter().map(|(i, s)| (cell_at(bounds, i), s)).collect()
+		}
+	}
+
+	/// Turns the grid into a map of every stored cell, which leaves dead cells out (except ones written into the hash map backend by indexing).
+	fn into_map(self) -> HashMap<Vec3, u8> {
+		match self {
+			Cells::Map(map) => map,
+			sparse => sparse.live()
+		}
+	}
+}
+
+impl UnboundedAutomaton {
+	/// Creates a new unbounded 3D automaton with the given rules and starting cells.
+	/// Just like with `Automaton::new`, this fails if your rules ask for more neighbors than a cell could have.
+	pub fn new(rules: AutomataRules, start_cells: Vec<IVec3>) -> Result<UnboundedAutomaton, AutomataError> {
+		rules.validate(max_neighbors(&rules.neighbor_method)?, 3)?;
+
+		let live = rules.cell_states - 1;
+		let cells = start_cells.into_iter().map(|v| (v, live)).collect();
+
+		let offsets = neighbor_offsets(&rules.neighbor_method);
+		Ok(UnboundedAutomaton { rules, cells, species: HashMap::new(), offsets, limits: (None, None, GrowthPolicy::Error), width: CoordinateWidth::I64, rng: Rng::new(0) })
+	}
+
+	/// Advances the automaton by one time step (or tick).
+	/// This only fails if the growth limits say to, in which case it gives back an `OutgrewLimits` error and nothing changes.
+	/// Nothing can go past the largest or smallest coordinate there can be, so a pattern that flies that far dies off at the edge instead of wrapping around to the other side.
+	pub fn tick(&mut self) -> Result<(), AutomataError> {
+		let offsets = &self.offsets;
+		let neighbor = |v: &IVec3, offset: &IVec3| self.neighbor(v, offset);
+
+		// Dead cells can only change if they're next to something, so only those (and the cells themselves) need checking.
+		let candidates: HashSet<IVec3> = self.cells.keys()
+			.flat_map(|v| offsets.iter().filter_map(move |offset| neighbor(v, offset)).chain(std::iter::once(v.clone())))
+			.collect();
+
+		let mut rng = self.rng.clone();
+		let key = rng.next_u64();
+
+		let mut next = candidates.into_iter().filter_map(|v| {
+			let neighbors = offsets.iter().map(|offset| neighbor(&v, offset).and_then(|n| self.cells.get(&n).copied()).unwrap_or(0));
+			let roll = if self.rules.is_stochastic() { Rng::keyed(key, &[v.x as u64, v.y as u64, v.z as u64]).next_f64() } else { 0.0 };
+			let state = self.rules.next_state_from(self.cells.get(&v).copied().unwrap_or(0), neighbors, roll);
+			if state > 0 { Some((v, state)) } else { None }
+		}).collect();
+
+		self.limit(&mut next)?;
+		self.rng = rng;
+		let before = std::mem::replace(&mut self.cells, next);
+
+		if self.rules.species > 1 {
+			let neighbor = |v: &IVec3, offset: &IVec3| self.neighbor(v, offset);
+			self.species = self.rules.inherit_species(&before, &self.cells, &self.species, |v| self.offsets.iter().filter_map(move |offset| neighbor(&v, offset)));
+		}
+
+		Ok(())
+	}
+
+	/// Limits how big the automaton can grow, so rules that explode can't eat up all the memory of a long-running program.
+	/// `max_cells` is the most live and dying cells there can be, and `area` is a box (from its first corner up to but not including its second) that every cell has to stay inside.
+	/// Whenever a tick would go past either of them, the policy says what happens. Automata start out without any limits.
+	/// 
+	/// Cells already past the limits get dealt with right away, just like at the end of a tick, except with `GrowthPolicy::Error`, which leaves them be until the next tick fails.
+	/// 
+	/// ```
+	/// # use cellular_snapp::{AutomataRules, GrowthPolicy, Method, deep::{UnboundedAutomaton, IVec3}};
+	/// // every cell survives, and every cell next to exactly one is born, so this grows forever
+	/// let rules = AutomataRules::new(0..=6, 1, 2, Method::VonNeumann);
+	/// let mut a = UnboundedAutomaton::new(rules, vec![IVec3::new(0, 0, 0)]).unwrap();
+	/// 
+	/// // but only the newborns inside the box live, and only 3 of those
+	/// a.set_growth_limits(Some(3), Some((IVec3::new(0, 0, 0), IVec3::new(2, 2, 2))), GrowthPolicy::ClampAndKill);
+	/// a.tick().unwrap();
+	/// 
+	/// assert_eq!(a.occupancy().cells(), 3);
+	/// assert_eq!(a.occupancy().bounds(), Some(&(IVec3::new(0, 0, 0), IVec3::new(1, 1, 0))));
+	/// ```
+	pub fn set_growth_limits(&mut self, max_cells: Option<usize>, area: Option<(IVec3, IVec3)>, policy: GrowthPolicy) {
+		self.limits = (max_cells, area, policy);
+		if policy == GrowthPolicy::Error { return; }
+
+		let mut cells = HashMap::new();
+		for (v, s) in std::mem::take(&mut self.cells) {
+			let state = cells.entry(self.wrapped(v)).or_insert(0);
+			*state = s.max(*state);
+		}
+
+		// every cell is already here, so nothing counts as a newborn
+		self.cells = cells.clone();
+		let _ = self.limit(&mut cells);
+		self.species = std::mem::take(&mut self.species).into_iter().map(|(v, s)| (self.wrapped(v), s)).filter(|(v, _)| cells.contains_key(v)).collect();
+		self.cells = cells;
+	}
+
+	/// Get how many cells there are, the box around them, and roughly how much memory they take up.
+	pub fn occupancy(&self) -> Occupancy<IVec3> {
+		let bounds = self.cells.keys().fold(None, |bounds: Option<(IVec3, IVec3)>, v| Some(match bounds {
+			Some((min, max)) => (IVec3::new(min.x.min(v.x), min.y.min(v.y), min.z.min(v.z)), IVec3::new(max.x.max(v.x), max.y.max(v.y), max.z.max(v.z))),
+			None => (v.clone(), v.clone())
+		}));
+
+		let memory = (self.cells.capacity() + self.species.capacity()) * (std::mem::size_of::<(IVec3, u8)>() + 1);
+		Occupancy::new(self.cells.len(), bounds, memory)
+	}
+
+	/// Sets how far coordinates can go. A pattern that flies past the edge of the width dies off there, just like at the edge of an `i64`,
+	/// and cells that are already past it die right away. Automata start out with `CoordinateWidth::I64`.
+	pub fn set_coordinate_width(&mut self, width: CoordinateWidth) {
+		self.width = width;
+		let fits = |v: &IVec3| width.fits(v.x) && width.fits(v.y) && width.fits(v.z);
+		self.cells.retain(|v, _| fits(v));
+		self.species.retain(|v, _| fits(v));
+	}
+
+	/// Get how far coordinates can go.
+	pub fn get_coordinate_width(&self) -> CoordinateWidth {
+		self.width
+	}
+
+	/// Finds the neighbor of a cell at an offset, wrapped into the growth limits' box, or `None` if that's past the edge of the coordinates.
+	fn neighbor(&self, v: &IVec3, offset: &IVec3) -> Option<IVec3> {
+		let v = self.wrapped(v.checked_add(offset)?);
+		if self.width.fits(v.x) && self.width.fits(v.y) && self.width.fits(v.z) { Some(v) } else { None }
+	}
+
+	/// Wraps a cell around into the growth limits' box, if they say to wrap and the box isn't empty.
+	fn wrapped(&self, v: IVec3) -> IVec3 {
+		match &self.limits {
+			(_, Some((min, max)), GrowthPolicy::Wrap) if min.x < max.x && min.y < max.y && min.z < max.z => {
+				IVec3::new(wrap_coordinate(v.x, min.x, max.x), wrap_coordinate(v.y, min.y, max.y), wrap_coordinate(v.z, min.z, max.z))
+			},
+			_ => v
+		}
+	}
+
+	/// Makes the next generation fit inside the growth limits, or gives back an error if the policy says to.
+	/// Anything that isn't already alive counts as a newborn, and those are the first to go.
+	fn limit(&self, next: &mut HashMap<IVec3, u8>) -> Result<(), AutomataError> {
+		let (max_cells, area, policy) = &self.limits;
+		let outside = |v: &IVec3| area.as_ref().is_some_and(|(min, max)| v.x < min.x || v.y < min.y || v.z < min.z || v.x >= max.x || v.y >= max.y || v.z >= max.z);
+
+		if *policy == GrowthPolicy::Error {
+			let over = max_cells.is_some_and(|max| next.len() > max) || next.keys().any(outside);
+			return if over { Err(AutomataError::OutgrewLimits) } else { Ok(()) };
+		}
+
+		next.retain(|v, _| !outside(v));
+		if let Some(excess) = max_cells.and_then(|max| next.len().checked_sub(max)).filter(|excess| *excess > 0) {
+			let mut doomed: Vec<IVec3> = next.keys().cloned().collect();
+			doomed.sort_by_key(|v| (self.cells.contains_key(v), std::cmp::Reverse((v.z, v.y, v.x))));
+			for v in doomed.into_iter().take(excess) { next.remove(&v); }
+		}
+
+		Ok(())
+	}
+
+	/// Seeds the random number generator used by stochastic rules. Automata start out seeded with 0.
+	pub fn seed_rng(&mut self, seed: u64) {
+		self.rng = Rng::new(seed);
+	}
+
+	/// Get a copy of the automaton's live and dying cells. Anything not in here is dead.
+	pub fn get_cells(&self) -> HashMap<IVec3, u8> {
+		self.cells.clone()
+	}
+
+	/// Get the species of every live or dying cell. Cells start out as species 0 unless you say otherwise.
+	pub fn get_species(&self) -> HashMap<IVec3, u8> {
+		self.cells.keys().map(|v| (v.clone(), self.species.get(v).copied().unwrap_or(0))).collect()
+	}
+
+	/// Sets the species of a single live or dying cell. Species too big for the rules are clamped to the last one, and dead cells are ignored.
+	pub fn set_species(&mut self, v: IVec3, species: u8) {
+		if self.cells.contains_key(&v) {
+			self.species.insert(v, species.min(self.rules.species - 1));
+		}
+	}
+
+	/// Get the rules the automaton is currently following.
+	pub fn get_rules(&self) -> &AutomataRules {
+		&self.rules
+	}
+}
+
+impl Pattern {
+	/// Creates a new pattern out of a name and a set of relative coordinates, all of which will be placed as live cells.
+	pub fn new(name: &str, cells: Vec<Vec3>) -> Pattern {
+		Pattern { name: name.to_string(), cells: cells.into_iter().map(|v| (v, None)).collect() }
+	}
+
+	/// Creates a new pattern where every cell carries its own state value.
+	/// When stamped, states are clamped to whatever the automaton's maximum state is, and cells with a state of 0 clear whatever they land on.
+	pub fn with_states(name: &str, cells: Vec<(Vec3, u8)>) -> Pattern {
+		Pattern { name: name.to_string(), cells: cells.into_iter().map(|(v, s)| (v, Some(s))).collect() }
+	}
+
+	/// Get the name of the pattern.
+	pub fn name(&self) -> &str {
+		&self.name
+	}
+
+	/// Get the positions of every cell in the pattern that isn't dead, which you can hand straight to `Automaton::new` as a seed.
+	pub fn positions(&self) -> Vec<Vec3> {
+		self.cells.iter().filter(|(_, s)| *s != Some(0)).map(|(v, _)| *v).collect()
+	}
+
+	/// Get the smallest and largest corners of the box containing the pattern, or None if the pattern is empty.
+	fn corners(&self) -> Option<(Vec3, Vec3)> {
+		let mut cells = self.cells.iter();
+		let (first, _) = cells.next()?;
+		let (mut min, mut max) = (*first, *first);
+
+		for (v, _) in cells {
+			min.x = min.x.min(v.x);
+			min.y = min.y.min(v.y);
+			min.z = min.z.min(v.z);
+			max.x = max.x.max(v.x);
+			max.y = max.y.max(v.y);
+			max.z = max.z.max(v.z);
+		}
+
+		Some((min, max))
+	}
+
+	/// Applies a transformation to every cell, given the corners of the pattern's bounding box.
+	fn transform(&self, f: impl Fn(&Vec3, &Vec3, &Vec3) -> Vec3) -> Pattern {
+		match self.corners() {
+			Some((min, max)) => Pattern {
+				name: self.name.clone(),
+				cells: self.cells.iter().map(|(v, s)| (f(v, &min, &max), *s)).collect()
+			},
+			None => self.clone()
+		}
+	}
+
+	/// Rotates the pattern a quarter turn around the given axis, inside its bounding box.
+	/// The smallest corner of the bounding box stays put.
+	pub fn rotate90(&self, axis: Axis) -> Pattern {
+		match axis {
+			Axis::X => self.transform(|v, min, max| Vec3::new(v.x, min.y + (max.z - v.z), min.z + (v.y - min.y))),
+			Axis::Y => self.transform(|v, min, max| Vec3::new(min.x + (v.z - min.z), v.y, min.z + (max.x - v.x))),
+			Axis::Z => self.transform(|v, min, max| Vec3::new(min.x + (max.y - v.y), min.y + (v.x - min.x), v.z))
+		}
+	}
+
+	/// Mirrors the pattern along the x axis.
+	pub fn mirror_x(&self) -> Pattern {
+		self.transform(|v, min, max| Vec3::new(min.x + max.x - v.x, v.y, v.z))
+	}
+
+	/// Mirrors the pattern along the y axis.
+	pub fn mirror_y(&self) -> Pattern {
+		self.transform(|v, min, max| Vec3::new(v.x, min.y + max.y - v.y, v.z))
+	}
+
+	/// Mirrors the pattern along the z axis.
+	pub fn mirror_z(&self) -> Pattern {
+		self.transform(|v, min, max| Vec3::new(v.x, v.y, min.z + max.z - v.z))
+	}
+
+	/// Moves every cell in the pattern by the given offset.
+	pub fn translate(&self, offset: Vec3) -> Pattern {
+		Pattern {
+			name: self.name.clone(),
+			cells: self.cells.iter().map(|(v, s)| (*v + offset, *s)).collect()
+		}
+	}
+
+	/// Places the pattern into an automaton, with the pattern's origin at the given offset.
+	/// Any cells that would land outside of the automaton's bounds are skipped.
+	pub fn stamp_into(&self, automaton: &mut Automaton, offset: Vec3) {
+		let max_state = automaton.rules.cell_states - 1;
+
+		for (v, s) in &self.cells {
+			let Some(pos) = v.checked_add_position(&offset) else { continue; };
+
+			if pos.x < automaton.bounds.x && pos.y < automaton.bounds.y && pos.z < automaton.bounds.z {
+				automaton.cells.set(pos, s.map_or(max_state, |s| s.min(max_state)));
+			}
+		}
+	}
+}
+
+//--> Interop <--
+
+#[cfg(feature = "glam")]
+impl From<glam::UVec3> for Vec3 {
+	fn from(v: glam::UVec3) -> Vec3 { Vec3::new(v.x as usize, v.y as usize, v.z as usize) }
+}
+
+/// Note that components bigger than `u32::MAX` get truncated.
+#[cfg(feature = "glam")]
+impl From<Vec3> for glam::UVec3 {
+	fn from(v: Vec3) -> glam::UVec3 { glam::UVec3::new(v.x as u32, v.y as u32, v.z as u32) }
+}
+
+#[cfg(feature = "glam")]
+impl From<glam::I64Vec3> for IVec3 {
+	fn from(v: glam::I64Vec3) -> IVec3 { IVec3::new(v.x, v.y, v.z) }
+}
+
+#[cfg(feature = "glam")]
+impl From<IVec3> for glam::I64Vec3 {
+	fn from(v: IVec3) -> glam::I64Vec3 { glam::I64Vec3::new(v.x, v.y, v.z) }
+}
+
+#[cfg(feature = "nalgebra")]
+impl From<nalgebra::Vector3<usize>> for Vec3 {
+	fn from(v: nalgebra::Vector3<usize>) -> Vec3 { Vec3::new(v.x, v.y, v.z) }
+}
+
+#[cfg(feature = "nalgebra")]
+impl From<Vec3> for nalgebra::Vector3<usize> {
+	fn from(v: Vec3) -> nalgebra::Vector3<usize> { nalgebra::Vector3::new(v.x, v.y, v.z) }
+}
+
+#[cfg(feature = "nalgebra")]
+impl From<nalgebra::Vector3<i64>> for IVec3 {
+	fn from(v: nalgebra::Vector3<i64>) -> IVec3 { IVec3::new(v.x, v.y, v.z) }
+}
+
+#[cfg(feature = "nalgebra")]
+impl From<IVec3> for nalgebra::Vector3<i64> {
+	fn from(v: IVec3) -> nalgebra::Vector3<i64> { nalgebra::Vector3::new(v.x, v.y, v.z) }
+}
+
+#[cfg(feature = "ndarray")]
+impl Automaton {
+	/// Copies the current state into an ndarray.
+	/// The array is indexed `[[z, y, x]]`, so each outer slice is one z-layer, which is what plotting tools expect.
+	pub fn to_array3(&self) -> ndarray::Array3<u8> {
+		let mut array = ndarray::Array3::zeros((self.bounds.z, self.bounds.y, self.bounds.x));
+
+		for (v, s) in self.cells.live() {
+			array[[v.z, v.y, v.x]] = s;
+		}
+
+		array
+	}
+
+	/// Creates a new automaton from an ndarray of cell states, indexed the same way `to_array3` does it.
+	/// The bounds are taken from the shape of the array, and states are clamped to whatever the rules allow.
+	pub fn from_array3(rules: AutomataRules, array: &ndarray::Array3<u8>) -> Result<Automaton, AutomataError> {
+		let (z, y, x) = array.dim();
+		let mut a = Automaton::new(rules, Vec3::new(x, y, z), Vec::new())?;
+		let max_state = a.rules.cell_states - 1;
+
+		for ((z, y, x), s) in array.indexed_iter() {
+			a.cells.set(Vec3::new(x, y, z), (*s).min(max_state));
+		}
+
+		Ok(a)
+	}
+}
+
+#[cfg(feature = "npy")]
+impl Automaton {
+	/// Writes the current state out as a NumPy `.npy` file of bytes, so `numpy.load` gives back an array of cell states.
+	/// The array is indexed `[z, y, x]`, just like `to_array3`.
+	pub fn to_npy(&self, mut writer: impl Write) -> io::Result<()> {
+		let (x, y) = (self.bounds.x, self.bounds.y);
+		let mut states = vec![0; x * y * self.bounds.z];
+		for (v, s) in self.cells.live() { states[(v.z * y + v.y) * x + v.x] = s; }
+
+		npy::write(&mut writer, &[self.bounds.z, y, x], &states)
+	}
+
+	/// Creates a new automaton from a NumPy `.npy` file holding a 3D array of `uint8` (or `bool`) cell states, indexed the same way `to_npy` does it.
+	/// The bounds are taken from the shape of the array, and states are clamped to whatever the rules allow.
+	/// Files that can't be read, or that hold anything but a 3D array of bytes, give back an `InvalidPattern` error.
+	pub fn from_npy(rules: AutomataRules, mut reader: impl Read) -> Result<Automaton, AutomataError> {
+		let (shape, states) = npy::read(&mut reader).map_err(AutomataError::InvalidPattern)?;
+		let [z, y, x] = shape[..] else { return Err(AutomataError::InvalidPattern(format!("the array should have 3 dimensions, but it has {}", shape.len()))); };
+
+		let mut a = Automaton::new(rules, Vec3::new(x, y, z), Vec::new())?;
+		let max_state = a.rules.cell_states - 1;
+
+		for (i, s) in states.into_iter().enumerate().filter(|(_, s)| *s > 0) {
+			a.cells.set(Vec3::new(i % x, i / x % y, i / (x * y)), s.min(max_state));
+		}
+
+		Ok(a)
+	}
+}
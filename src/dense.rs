@@ -0,0 +1,371 @@
+//! A dense grid that keeps every cell's state in one flat array, a row at a time.
+//! 
+//! Ticking works on whole rows instead of single cells: every neighbor's row is turned into 0s and 1s (whether each cell counts as a neighbor)
+//! and added into a row of neighbor counts. Then each cell's next state is looked up from its state and count.
+//! With two states where only live cells count, like Life, the rows of states already are 0s and 1s, so they get added straight in.
+//! 
+//! On x86_64, all of that goes 16 cells at a time with SSE2, which every x86_64 CPU has: rows get turned into 0s and 1s with a compare, and added in with `paddb`.
+//! If the CPU has SSSE3 too (which gets checked while running), and each state's part of the rule table fits in a register, next states get looked up with `pshufb`,
+//! one shuffle for every state. Anywhere else, it all goes one cell at a time. `cargo bench --bench dense` shows how long 1024x1024 soups take to tick.
+//! 
+//! Really wide grids get ticked in tiles, a few thousand cells of every row at a time, so the rows around the one being worked on are still in the cache when they're needed again
+//! (and for grids kept in a memory-mapped file, still paged in).
+
+//--> Imports <--
+
+use std::ops::{Deref, DerefMut, Range, RangeInclusive};
+#[cfg(feature = "mmap")]
+use std::io;
+#[cfg(all(feature = "mmap", unix))]
+use crate::mapped::Mapped;
+
+//--> Structs <--
+
+/// A grid of cell states. Rows run along x.
+#[derive(Clone)]
+pub(crate) struct Dense {
+	width: usize,
+	cells: Storage
+}
+
+/// A rule's table of next states, ready to look whole rows of cells up in.
+struct Lookup<'a> {
+	table: &'a [u8],
+	stride: usize,
+	/// The table padded out to 256 entries, so indexing it with a u8 can't go out of bounds and doesn't need checking. This is only there if the table fits.
+	small: Option<[u8; 256]>,
+	/// Every state's part of the table padded out to a whole register, for looking up a register of cells at once with SSSE3 shuffles.
+	/// This is only there if the CPU has SSSE3, every state's part fits in a register, and there aren't so many states that going through all of them is slower.
+	#[cfg(target_arch = "x86_64")]
+	shuffles: Option<Vec<[u8; LANES]>>
+}
+
+//--> Enums <--
+
+/// Where a grid keeps its cells.
+enum Storage {
+	Memory(Vec<u8>),
+	#[cfg(all(feature = "mmap", unix))]
+	Mapped(Mapped)
+}
+
+//--> Functions <--
+
+/// How many cells get worked on at once, which fills a 128-bit SSE2 register.
+#[cfg(target_arch = "x86_64")]
+const LANES: usize = 16;
+
+/// The most states a rule can have for its next states to be looked up with shuffles, since every state takes a shuffle of its own.
+#[cfg(target_arch = "x86_64")]
+const SHUFFLED_STATES: usize = 8;
+
+/// How many cells of each row get ticked before moving on to the next row. The sums and the few rows around them easily fit in the cache at this size.
+const TILE: usize = 4096;
+
+impl Dense {
+	/// Creates a grid of dead cells with the given amount of rows, each `width` cells long.
+	pub(crate) fn new(width: usize, rows: usize) -> Dense {
+		Dense { width, cells: Storage::Memory(vec![0; width * rows]) }
+	}
+
+	/// Creates a grid of dead cells like `new`, except the cells are kept in a memory-mapped file. This fails if the file can't be made, or if this isn't Unix.
+	#[cfg(feature = "mmap")]
+	pub(crate) fn mapped(width: usize, rows: usize) -> io::Result<Dense> {
+		#[cfg(unix)]
+		return Ok(Dense { width, cells: Storage::Mapped(Mapped::new(width * rows)?) });
+		#[cfg(not(unix))]
+		return Err(io::Error::new(io::ErrorKind::Unsupported, "memory-mapped grids only work on Unix"));
+	}
+
+	/// Checks whether the cells are kept in a memory-mapped file.
+	#[cfg(feature = "mmap")]
+	pub(crate) fn is_mapped(&self) -> bool {
+		#[cfg(unix)]
+		return matches!(self.cells, Storage::Mapped(_));
+		#[cfg(not(unix))]
+		return false;
+	}
+
+	/// Get a grid the same size as this one, kept the same way, for `step` to write the next generation into.
+	/// The spare grid's memory gets used if it's got the right kind, and whatever cells it had are left for `step` to write over.
+	/// This gives back `None` if a memory-mapped grid needs a new file and it can't be made.
+	pub(crate) fn next_grid(&self, spare: Option<Dense>) -> Option<Dense> {
+		let len = self.cells.len();
+
+		let cells = match (&self.cells, spare.map(|spare| spare.cells)) {
+			(Storage::Memory(_), Some(Storage::Memory(mut spare))) => { spare.resize(len, 0); Storage::Memory(spare) },
+			#[cfg(all(feature = "mmap", unix))]
+			(Storage::Mapped(_), Some(Storage::Mapped(spare))) if spare.len() == len => Storage::Mapped(spare),
+			#[cfg(all(feature = "mmap", unix))]
+			(Storage::Mapped(_), _) => Storage::Mapped(Mapped::new(len).ok()?),
+			_ => Storage::Memory(vec![0; len])
+		};
+
+		Some(Dense { width: self.width, cells })
+	}
+
+	/// Get the states of every cell, row after row.
+	pub(crate) fn as_slice(&self) -> &[u8] {
+		&self.cells
+	}
+
+	/// Get the states of every cell, row after row, so they can be changed in place.
+	pub(crate) fn as_mut_slice(&mut self) -> &mut [u8] {
+		&mut self.cells
+	}
+
+	/// Get the state of a cell.
+	pub(crate) fn get(&self, x: usize, row: usize) -> u8 {
+		self.cells[row * self.width + x]
+	}
+
+	/// Get a cell's state so it can be changed in place.
+	pub(crate) fn get_mut(&mut self, x: usize, row: usize) -> &mut u8 {
+		&mut self.cells[row * self.width + x]
+	}
+
+	/// Sets the state of a cell.
+	pub(crate) fn set(&mut self, x: usize, row: usize, state: u8) {
+		self.cells[row * self.width + x] = state;
+	}
+
+	/// Get the position and state of every cell that isn't dead, as `(x, row, state)`.
+	pub(crate) fn live(&self) -> Vec<(usize, usize, u8)> {
+		self.cells.iter().enumerate().filter(|(_, s)| **s > 0).map(|(i, s)| (i % self.width, i / self.width, *s)).collect()
+	}
+
+	/// Changes the state of every cell.
+	pub(crate) fn map_states(&mut self, f: impl Fn(u8) -> u8) {
+		for s in self.cells.iter_mut() { *s = f(*s); }
+	}
+
+	/// Works out the next generation of a rule that only counts neighbors, for just the given rows, and writes their states into `out`.
+	/// 
+	/// `table[state * stride + n]` is the next state of a cell with `n` counted neighbors, where `stride` is one more than the most neighbors a cell can have,
+	/// and cells with states in `counted` count as neighbors.
+	/// `neighbors(row)` lists the rows a row's neighbors are in, along with how far over along x each of them is.
+	/// Rows outside the grid should be left out, and so should the row itself with an offset of 0, since that's the cell and not a neighbor.
+	pub(crate) fn step(&self, table: &[u8], stride: usize, counted: RangeInclusive<u8>, rows: Range<usize>, neighbors: impl Fn(usize) -> Vec<(usize, i8)>, out: &mut [u8]) {
+		let w = self.width;
+		let first = rows.start;
+		let mut sums = vec![0; w.min(TILE)];
+
+		let lookup = Lookup::new(table, stride);
+
+		// whether a cell in each state counts as a neighbor, as a 0 or a 1 that can be added straight into the sums
+		let counts: [u8; 256] = std::array::from_fn(|state| counted.contains(&(state as u8)) as u8);
+		// with two states where only live cells count, a row of states already is a row of 0s and 1s
+		let raw = (0..table.len() / stride).all(|state| counts[state] as usize == state);
+
+		let mut masked: Vec<(usize, Vec<u8>)> = Vec::new();
+		let mut spare: Vec<Vec<u8>> = Vec::new();
+
+		for start in (0..w).step_by(TILE) {
+			let end = (start + TILE).min(w);
+			let sums = &mut sums[..end - start];
+
+			// every cell a tile's neighbors can be in, since they're at most an i8 away along x
+			let (left, right) = (start.saturating_sub(128), (end + 128).min(w));
+			spare.extend(masked.drain(..).map(|(_, row)| row));
+
+			for row in rows.clone() {
+				sums.fill(0);
+				let sources = neighbors(row);
+
+				// each source row gets turned into 0s and 1s once, and kept for as long as the rows after it still need it
+				if !raw {
+					let mut i = 0;
+					while i < masked.len() {
+						if sources.iter().any(|(source, _)| *source == masked[i].0) { i += 1; } else { spare.push(masked.swap_remove(i).1); }
+					}
+
+					for (source, _) in &sources {
+						if masked.iter().any(|(r, _)| r == source) { continue; }
+						let mut counted_row = spare.pop().unwrap_or_default();
+						count_row(&mut counted_row, &self.cells[source * w + left..source * w + right], &counted);
+						masked.push((*source, counted_row));
+					}
+				}
+
+				for (source, dx) in &sources {
+					// shifting a row over just means adding it a few spots off, and the cells that fall off the edge were out of bounds anyway
+					let dx = *dx as isize;
+					let (from, to) = ((start as isize).max(-dx), (end as isize).min(w as isize - dx));
+					if from >= to { continue; }
+
+					let (from_source, to_source) = ((from + dx) as usize, (to + dx) as usize);
+					let source = match raw {
+						true => &self.cells[source * w + from_source..source * w + to_source],
+						false => &masked.iter().find(|(r, _)| r == source).expect("every source row was just counted").1[from_source - left..to_source - left]
+					};
+
+					add_row(&mut sums[(from as usize - start)..(to as usize - start)], source);
+				}
+
+				let states = &self.cells[row * w + start..row * w + end];
+				let next = &mut out[(row - first) * w + start..(row - first) * w + end];
+
+				lookup.row(next, states, sums);
+			}
+		}
+	}
+}
+
+impl<'a> Lookup<'a> {
+	/// Gets a table with `stride` entries for every state ready to look rows up in.
+	fn new(table: &'a [u8], stride: usize) -> Lookup<'a> {
+		let small = (table.len() <= 256).then(|| {
+			let mut small = [0; 256];
+			small[..table.len()].copy_from_slice(table);
+			small
+		});
+
+		#[cfg(target_arch = "x86_64")]
+		let shuffles = (std::arch::is_x86_feature_detected!("ssse3") && stride <= LANES && table.len() / stride <= SHUFFLED_STATES).then(|| {
+			table.chunks(stride).map(|part| {
+				let mut padded = [0; LANES];
+				padded[..part.len()].copy_from_slice(part);
+				padded
+			}).collect()
+		});
+
+		Lookup {
+			table,
+			stride,
+			small,
+			#[cfg(target_arch = "x86_64")]
+			shuffles
+		}
+	}
+
+	/// Looks up the next state of every cell in a row from its state and how many neighbors it has.
+	fn row(&self, next: &mut [u8], states: &[u8], sums: &[u8]) {
+		#[cfg(target_arch = "x86_64")]
+		// the shuffles are only there if the CPU has SSSE3
+		let done = self.shuffles.as_ref().map_or(0, |shuffles| unsafe { shuffle_row(next, states, sums, shuffles) });
+		#[cfg(not(target_arch = "x86_64"))]
+		let done = 0;
+
+		let (next, states, sums) = (&mut next[done..], &states[done..], &sums[done..]);
+		match &self.small {
+			Some(small) => for ((next, state), sum) in next.iter_mut().zip(states).zip(sums) {
+				*next = small[state.wrapping_mul(self.stride as u8).wrapping_add(*sum) as usize];
+			},
+			None => for ((next, state), sum) in next.iter_mut().zip(states).zip(sums) {
+				*next = self.table[*state as usize * self.stride + *sum as usize];
+			}
+		}
+	}
+}
+
+/// Looks up the next states of a row a register of cells at a time, giving back how many cells it got through (it leaves the last few that don't fill a register).
+/// Every state's part of the table gets shuffled into place by the neighbor counts, and only kept for the cells in that state.
+/// 
+/// The CPU has to have SSSE3, and `shuffles` needs a part for every state in `states`, which have to be the same length as `sums` and `next`.
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "ssse3")]
+unsafe fn shuffle_row(next: &mut [u8], states: &[u8], sums: &[u8], shuffles: &[[u8; LANES]]) -> usize {
+	use std::arch::x86_64::{__m128i, _mm_and_si128, _mm_cmpeq_epi8, _mm_loadu_si128, _mm_or_si128, _mm_set1_epi8, _mm_setzero_si128, _mm_shuffle_epi8, _mm_storeu_si128};
+
+	let done = next.len() - next.len() % LANES;
+	for i in (0..done).step_by(LANES) {
+		let (state, sum) = (_mm_loadu_si128(states.as_ptr().add(i) as *const __m128i), _mm_loadu_si128(sums.as_ptr().add(i) as *const __m128i));
+		let mut out = _mm_setzero_si128();
+
+		for (s, part) in shuffles.iter().enumerate() {
+			let here = _mm_cmpeq_epi8(state, _mm_set1_epi8(s as i8));
+			out = _mm_or_si128(out, _mm_and_si128(here, _mm_shuffle_epi8(_mm_loadu_si128(part.as_ptr() as *const __m128i), sum)));
+		}
+
+		_mm_storeu_si128(next.as_mut_ptr().add(i) as *mut __m128i, out);
+	}
+
+	done
+}
+
+/// Turns a row of states into 0s and 1s (whether each cell counts as a neighbor), replacing whatever was in `out`.
+/// On x86_64 this goes a whole register of cells at a time, by checking that each state minus the first counted one is no bigger than the last minus the first.
+fn count_row(out: &mut Vec<u8>, states: &[u8], counted: &RangeInclusive<u8>) {
+	let (first, span) = (*counted.start(), counted.end().wrapping_sub(*counted.start()));
+	out.clear();
+	out.resize(states.len(), 0);
+
+	#[cfg(target_arch = "x86_64")]
+	let done = {
+		use std::arch::x86_64::{__m128i, _mm_and_si128, _mm_cmpeq_epi8, _mm_loadu_si128, _mm_min_epu8, _mm_set1_epi8, _mm_storeu_si128, _mm_sub_epi8};
+
+		let done = states.len() - states.len() % LANES;
+
+		// SSE2 is part of every x86_64 CPU, and the loads and stores are unaligned ones that stay inside both rows
+		unsafe {
+			let (first, span, one) = (_mm_set1_epi8(first as i8), _mm_set1_epi8(span as i8), _mm_set1_epi8(1));
+			for i in (0..done).step_by(LANES) {
+				let above = _mm_sub_epi8(_mm_loadu_si128(states.as_ptr().add(i) as *const __m128i), first);
+				let inside = _mm_cmpeq_epi8(_mm_min_epu8(above, span), above);
+				_mm_storeu_si128(out.as_mut_ptr().add(i) as *mut __m128i, _mm_and_si128(inside, one));
+			}
+		}
+		done
+	};
+	#[cfg(not(target_arch = "x86_64"))]
+	let done = 0;
+
+	for (out, state) in out[done..].iter_mut().zip(&states[done..]) { *out = (state.wrapping_sub(first) <= span) as u8; }
+}
+
+/// Adds one row of 0s and 1s (whether each neighbor counts) into a row of sums. On x86_64 this goes a whole register of cells at a time.
+fn add_row(sums: &mut [u8], row: &[u8]) {
+	let len = sums.len().min(row.len());
+
+	#[cfg(target_arch = "x86_64")]
+	let done = {
+		use std::arch::x86_64::{__m128i, _mm_add_epi8, _mm_loadu_si128, _mm_storeu_si128};
+		let done = len - len % LANES;
+
+		// SSE2 is part of every x86_64 CPU, and the loads and stores are unaligned ones that stay inside both rows
+		unsafe {
+			for i in (0..done).step_by(LANES) {
+				let sum = sums.as_mut_ptr().add(i) as *mut __m128i;
+				_mm_storeu_si128(sum, _mm_add_epi8(_mm_loadu_si128(sum), _mm_loadu_si128(row.as_ptr().add(i) as *const __m128i)));
+			}
+		}
+		done
+	};
+	#[cfg(not(target_arch = "x86_64"))]
+	let done = 0;
+
+	for (s, r) in sums[done..len].iter_mut().zip(&row[done..len]) { *s += *r; }
+}
+
+/// Copying a memory-mapped grid copies it into a new file of its own, or into memory if the file can't be made.
+impl Clone for Storage {
+	fn clone(&self) -> Storage {
+		match self {
+			Storage::Memory(cells) => Storage::Memory(cells.clone()),
+			#[cfg(all(feature = "mmap", unix))]
+			Storage::Mapped(cells) => cells.try_clone().map_or_else(|_| Storage::Memory(cells.to_vec()), Storage::Mapped)
+		}
+	}
+}
+
+impl Deref for Storage {
+	type Target = [u8];
+
+	fn deref(&self) -> &[u8] {
+		match self {
+			Storage::Memory(cells) => cells,
+			#[cfg(all(feature = "mmap", unix))]
+			Storage::Mapped(cells) => cells
+		}
+	}
+}
+
+impl DerefMut for Storage {
+	fn deref_mut(&mut self) -> &mut [u8] {
+		match self {
+			Storage::Memory(cells) => cells,
+			#[cfg(all(feature = "mmap", unix))]
+			Storage::Mapped(cells) => cells
+		}
+	}
+}
@@ -0,0 +1,88 @@
+//! This module keeps an audit trail of everything that happens to an automaton: every tick, every time the rules change, and every cell that gets set by hand.
+//! 
+//! Logging is off until it's turned on with `set_event_log`. After that, the automaton keeps every event in order until they're drained,
+//! either as a list of `Event`s or straight into anything that can be written to as JSON lines, one event per line.
+//! Replaying the edits and rule changes of a session against the same starting grid (and the same seed) goes through the same generations again,
+//! which is handy for reproducing bugs someone ran into in a front-end, as long as nothing else changed the automaton along the way.
+//! 
+//! Only ticks, `set_cell` (and what uses it), and rule changes get logged. Writing to cells by indexing, resizing, cropping, rotating and flipping,
+//! putting up or taking down walls, and seeding the random number generator all go unlogged, so sessions that use them can't be reproduced from the log.
+//! Rule changes are logged as rule strings, which leave out anything a rule string can't hold, like neighbor weights and directions.
+//! 
+//! ```
+//! # use cellular_snapp::{presets, events::Event, flat::{Automaton, Vec2}};
+//! let mut life = Automaton::new(presets::life(), Vec2::new(8, 8), vec![Vec2::new(2, 3), Vec2::new(3, 3), Vec2::new(4, 3)]).unwrap();
+//! life.set_event_log(true);
+//! 
+//! life.tick();
+//! life.set_cell(Vec2::new(6, 6), 1);
+//! life.set_rules(presets::high_life()).unwrap();
+//! 
+//! assert_eq!(life.drain_events(), vec![
+//!     Event::Tick { generation: 1, births: 2, deaths: 2 },
+//!     Event::Edit { generation: 1, position: [6, 6, 0], from: 0, to: 1 },
+//!     Event::RulesChanged { generation: 1, rules: String::from("B36/S23") }
+//! ]);
+//! 
+//! life.tick();
+//! let mut lines = Vec::new();
+//! life.drain_events_to(&mut lines).unwrap();
+//! assert_eq!(String::from_utf8(lines).unwrap(), "{\"event\":\"tick\",\"generation\":2,\"births\":2,\"deaths\":3}\n");
+//! ```
+
+//--> Imports <--
+
+use std::io::{self, Write};
+
+//--> Enums <--
+
+/// Something that happened to an automaton. Positions are `[x, y, z]`, where z is always 0 for flat automata.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum Event {
+	/// The automaton was ticked (forwards, backwards, or just in a region), ending up at `generation`.
+	/// Births are cells that were dead and now aren't, and deaths are cells that weren't dead and now are, so dying cells don't count as deaths until they're fully dead.
+	Tick { generation: u64, births: usize, deaths: usize },
+	/// The rules were swapped out for new ones, written as a rule string. This happens with `set_rules`, and every time a rule schedule moves on to the next rules.
+	/// Rule strings don't have everything, like chances, weights, and directions, so rules that only differ in those come out the same.
+	RulesChanged { generation: u64, rules: String },
+	/// A cell was changed by hand from one state to another, with `set_cell` or anything that uses it (like `paste` or `fill_region`).
+	/// Setting a cell to the state it was already in doesn't count.
+	Edit { generation: u64, position: [usize; 3], from: u8, to: u8 }
+}
+
+//--> Functions <--
+
+impl Event {
+	/// Get the generation the automaton was at when this happened.
+	pub fn generation(&self) -> u64 {
+		match self {
+			Event::Tick { generation, .. } | Event::RulesChanged { generation, .. } | Event::Edit { generation, .. } => *generation
+		}
+	}
+
+	/// Writes the event out as a single JSON object, without a newline, like `{"event":"edit","generation":4,"position":[1,2,0],"from":0,"to":1}`.
+	pub fn to_json(&self) -> String {
+		match self {
+			Event::Tick { generation, births, deaths } => format!("{{\"event\":\"tick\",\"generation\":{},\"births\":{},\"deaths\":{}}}", generation, births, deaths),
+			Event::RulesChanged { generation, rules } => format!("{{\"event\":\"rules\",\"generation\":{},\"rules\":\"{}\"}}", generation, escape(rules)),
+			Event::Edit { generation, position: [x, y, z], from, to } => format!("{{\"event\":\"edit\",\"generation\":{},\"position\":[{},{},{}],\"from\":{},\"to\":{}}}", generation, x, y, z, from, to)
+		}
+	}
+}
+
+/// Writes events out as JSON lines, one object per line in the order they happened.
+pub fn write_json_lines(events: &[Event], mut writer: impl Write) -> io::Result<()> {
+	for event in events { writeln!(writer, "{}", event.to_json())?; }
+	Ok(())
+}
+
+/// Escapes a string so it can go inside the quotes of a JSON string.
+fn escape(s: &str) -> String {
+	s.chars().map(|c| match c {
+		'"' => String::from("\\\""),
+		'\\' => String::from("\\\\"),
+		'\n' => String::from("\\n"),
+		c if c.is_control() => format!("\\u{:04x}", c as u32),
+		c => c.to_string()
+	}).collect()
+}
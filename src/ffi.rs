@@ -0,0 +1,171 @@
+//! This module exposes the automata over a plain C interface, so they can be embedded in engines that can't use Rust directly.
+//! 
+//! Build with the `ffi` feature to get a shared library, and then declare these on the C side:
+//! 
+//! ```c
+//! typedef struct CellularAutomaton CellularAutomaton;
+//! 
+//! CellularAutomaton *cellular_new_2d(const char *rule, size_t width, size_t height, const uint32_t *seed, size_t seed_len);
+//! CellularAutomaton *cellular_new_3d(const char *rule, size_t width, size_t height, size_t depth, const uint32_t *seed, size_t seed_len);
+//! void cellular_tick(CellularAutomaton *automaton);
+//! void cellular_get_bounds(const CellularAutomaton *automaton, size_t *width, size_t *height, size_t *depth);
+//! const uint8_t *cellular_get_state_buffer(CellularAutomaton *automaton, size_t *len);
+//! void cellular_free(CellularAutomaton *automaton);
+//! ```
+//! 
+//! Seeds are flat lists of coordinates (x0, y0, x1, y1, and so on, with a z for 3D), and `seed_len` is the amount of numbers in that list.
+//! The state buffer has one byte per cell, with x changing fastest, then y, then z.
+//! It stays valid until the next call to `cellular_tick`, `cellular_get_state_buffer`, or `cellular_free` on the same automaton.
+
+//--> Imports <--
+
+use crate::{AutomataRules, flat, deep};
+use std::ffi::{c_char, CStr};
+use std::ptr;
+
+//--> Structs <--
+
+/// An automaton handed out over FFI, along with the buffer its state gets copied into.
+pub struct CellularAutomaton {
+	automaton: Dimension,
+	buffer: Vec<u8>
+}
+
+//--> Enums <--
+
+/// Which kind of automaton is behind a handle.
+enum Dimension {
+	Flat(flat::Automaton),
+	Deep(deep::Automaton)
+}
+
+//--> Functions <--
+
+/// Reads a rule string and seed coordinates from C, returning None if either isn't usable.
+unsafe fn read_args<'a>(rule: *const c_char, seed: *const u32, seed_len: usize) -> Option<(AutomataRules, &'a [u32])> {
+	if rule.is_null() || (seed.is_null() && seed_len > 0) { return None }
+
+	let rules = CStr::from_ptr(rule).to_str().ok()?.parse().ok()?;
+	let seed = if seed_len == 0 { &[][..] } else { std::slice::from_raw_parts(seed, seed_len) };
+
+	Some((rules, seed))
+}
+
+/// Boxes up an automaton and hands it over to C.
+fn into_handle(automaton: Dimension) -> *mut CellularAutomaton {
+	Box::into_raw(Box::new(CellularAutomaton { automaton, buffer: Vec::new() }))
+}
+
+/// Creates a new 2D automaton, returning null if the rule string, seed, or rules themselves aren't valid.
+/// 
+/// # Safety
+/// 
+/// `rule` must be a valid null-terminated string, and `seed` must point to at least `seed_len` numbers (or be null if `seed_len` is 0).
+#[no_mangle]
+pub unsafe extern "C" fn cellular_new_2d(rule: *const c_char, width: usize, height: usize, seed: *const u32, seed_len: usize) -> *mut CellularAutomaton {
+	let Some((rules, seed)) = read_args(rule, seed, seed_len) else { return ptr::null_mut() };
+	let seed = seed.chunks_exact(2).map(|c| flat::Vec2::new(c[0] as usize, c[1] as usize)).collect();
+
+	match flat::Automaton::new(rules, flat::Vec2::new(width, height), seed) {
+		Ok(a) => into_handle(Dimension::Flat(a)),
+		Err(_) => ptr::null_mut()
+	}
+}
+
+/// Creates a new 3D automaton, returning null if the rule string, seed, or rules themselves aren't valid.
+/// 
+/// # Safety
+/// 
+/// `rule` must be a valid null-terminated string, and `seed` must point to at least `seed_len` numbers (or be null if `seed_len` is 0).
+#[no_mangle]
+pub unsafe extern "C" fn cellular_new_3d(rule: *const c_char, width: usize, height: usize, depth: usize, seed: *const u32, seed_len: usize) -> *mut CellularAutomaton {
+	let Some((rules, seed)) = read_args(rule, seed, seed_len) else { return ptr::null_mut() };
+	let seed = seed.chunks_exact(3).map(|c| deep::Vec3::new(c[0] as usize, c[1] as usize, c[2] as usize)).collect();
+
+	match deep::Automaton::new(rules, deep::Vec3::new(width, height, depth), seed) {
+		Ok(a) => into_handle(Dimension::Deep(a)),
+		Err(_) => ptr::null_mut()
+	}
+}
+
+/// Advances an automaton by one tick.
+/// 
+/// # Safety
+/// 
+/// `automaton` must be null or a pointer returned by `cellular_new_2d` or `cellular_new_3d` that hasn't been freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn cellular_tick(automaton: *mut CellularAutomaton) {
+	let Some(a) = automaton.as_mut() else { return };
+
+	match &mut a.automaton {
+		Dimension::Flat(f) => f.tick(),
+		Dimension::Deep(d) => d.tick()
+	}
+}
+
+/// Writes the size of an automaton's grid into the given pointers. Depth is always 1 for 2D automata.
+/// Any of the output pointers can be null if you don't care about that value.
+/// 
+/// # Safety
+/// 
+/// `automaton` must be null or a live automaton pointer, and the output pointers must be null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn cellular_get_bounds(automaton: *const CellularAutomaton, width: *mut usize, height: *mut usize, depth: *mut usize) {
+	let Some(a) = automaton.as_ref() else { return };
+
+	let (w, h, d) = match &a.automaton {
+		Dimension::Flat(f) => (f.get_bounds().x(), f.get_bounds().y(), 1),
+		Dimension::Deep(d) => (d.get_bounds().x(), d.get_bounds().y(), d.get_bounds().z())
+	};
+
+	if let Some(width) = width.as_mut() { *width = w; }
+	if let Some(height) = height.as_mut() { *height = h; }
+	if let Some(depth) = depth.as_mut() { *depth = d; }
+}
+
+/// Copies an automaton's state into its buffer and returns a pointer to it, writing the buffer's length into `len`.
+/// Returns null if `automaton` is null.
+/// 
+/// # Safety
+/// 
+/// `automaton` must be null or a live automaton pointer, and `len` must be null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn cellular_get_state_buffer(automaton: *mut CellularAutomaton, len: *mut usize) -> *const u8 {
+	let Some(a) = automaton.as_mut() else { return ptr::null() };
+
+	match &a.automaton {
+		Dimension::Flat(f) => {
+			let (w, h) = (f.get_bounds().x(), f.get_bounds().y());
+			a.buffer.clear();
+			a.buffer.resize(w * h, 0);
+
+			for (v, s) in f.get_cells() {
+				a.buffer[v.y() * w + v.x()] = s;
+			}
+		},
+		Dimension::Deep(d) => {
+			let (w, h, dp) = (d.get_bounds().x(), d.get_bounds().y(), d.get_bounds().z());
+			a.buffer.clear();
+			a.buffer.resize(w * h * dp, 0);
+
+			for (v, s) in d.get_cells() {
+				a.buffer[(v.z() * h + v.y()) * w + v.x()] = s;
+			}
+		}
+	}
+
+	if let Some(len) = len.as_mut() { *len = a.buffer.len(); }
+	a.buffer.as_ptr()
+}
+
+/// Frees an automaton. Passing null does nothing.
+/// 
+/// # Safety
+/// 
+/// `automaton` must be null or a live automaton pointer, and must not be used again afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn cellular_free(automaton: *mut CellularAutomaton) {
+	if !automaton.is_null() {
+		drop(Box::from_raw(automaton));
+	}
+}
@@ -3,13 +3,15 @@
 //! To start, you'll want to decide on your rules and create an AutomataRules object containing them.
 //! 
 //! ```
-//! let rules = AutomataRules::new(Rule::Range(3..5), Rule::Single(3), 2, Method::Moore);
+//! # use cellular_snapp::{AutomataRules, Method};
+//! let rules = AutomataRules::new(2..=3, 3, 2, Method::Moore);
 //! ```
 //! 
 //! If you didn't know, those are the rules for Conway's Game of Life. Anyways, now we'll want to decide on our starting state, or seed.
 //! This will be a vector of 2-component vectors.
 //! 
 //! ```
+//! # use cellular_snapp::flat::Vec2;
 //! let seed = vec![Vec2::new(1, 0), Vec2::new(2, 1), Vec2::new(0, 2), Vec2::new(1, 2), Vec2::new(2, 2)];
 //! ```
 //! 
@@ -17,30 +19,240 @@
 //! After all, we don't have infinite memory.
 //! 
 //! ```
+//! # use cellular_snapp::{AutomataRules, Method, flat::{Automaton, Vec2}};
+//! # let rules = AutomataRules::new(2..=3, 3, 2, Method::Moore);
+//! # let seed = vec![Vec2::new(1, 0), Vec2::new(2, 1), Vec2::new(0, 2), Vec2::new(1, 2), Vec2::new(2, 2)];
 //! let mut life = Automaton::new(rules, Vec2::new(50, 50), seed);
 //! ```
 //! 
-//! Now, you have a cellular automaton running Conway's Game of Life. You can advance the automaton by calling `life.tick()` (or `life.par_tick()` if you have rayon), and get the current internal state by calling `life.get_cells()`.
+//! Now, you have a cellular automaton running Conway's Game of Life. You can advance the automaton by calling `life.tick()` (and spread each tick over more threads with `threads`), and get the current internal state by calling `life.get_cells()`.
+//! 
+//! If you'd rather not write out coordinates by hand every time, you can wrap them up in a Pattern and stamp it wherever you like, rotated or mirrored as needed.
+//! 
+//! ```
+//! # use cellular_snapp::{AutomataRules, Method, flat::{Automaton, Pattern, Vec2}};
+//! # let rules = AutomataRules::new(2..=3, 3, 2, Method::Moore);
+//! let glider = Pattern::new("glider", vec![Vec2::new(1, 0), Vec2::new(2, 1), Vec2::new(0, 2), Vec2::new(1, 2), Vec2::new(2, 2)]);
+//! let mut life = Automaton::new(rules, Vec2::new(50, 50), Vec::new()).unwrap();
+//! glider.stamp_into(&mut life, Vec2::new(10, 10));
+//! glider.rotate90().mirror_x().stamp_into(&mut life, Vec2::new(30, 30));
+//! ```
+//! 
+//! Bounded grids are a bit of a death trap for spaceships though, since they just crash into the walls. If you want your gliders to keep flying, use an UnboundedAutomaton instead.
+//! 
+//! ```
+//! # use cellular_snapp::{presets, flat::{UnboundedAutomaton, IVec2}};
+//! let glider = vec![IVec2::new(1, 0), IVec2::new(2, 1), IVec2::new(0, 2), IVec2::new(1, 2), IVec2::new(2, 2)];
+//! let mut life = UnboundedAutomaton::new(presets::life(), glider).unwrap();
+//! for _ in 0..400 { life.tick().unwrap(); }
+//! assert!(life.get_cells().contains_key(&IVec2::new(101, 102)));
+//! ```
+//! 
+//! When you're debugging a rule, it's often easiest to just print the grid out. Dead cells are `.`, dying cells are `+`, and live cells are `#`.
+//! 
+//! ```
+//! # use cellular_snapp::{presets, flat::{Automaton, Vec2}};
+//! let life = Automaton::new(presets::life(), Vec2::new(4, 3), vec![Vec2::new(1, 1), Vec2::new(2, 1)]).unwrap();
+//! assert_eq!(life.to_string(), "....\n.##.\n....\n");
+//! assert_eq!(life.render_ascii(&[' ', 'O']), "    \n OO \n    \n");
+//! ```
+//! 
+//! Runs are reproducible, bit for bit: the same rules, starting cells, random seed (from `seed_rng`), and amount of ticks always end up with the same cells,
+//! whichever backend they're kept in, however many threads tick them, and on every platform. Random rolls are worked out from each cell's position,
+//! so the order cells get ticked in doesn't matter. The hash maps cells come back in aren't ordered though, so use `live_cells_sorted` when the order matters.
 
 //--> Imports <--
 
-use crate::{AutomataRules, Method, Rule};
+use crate::{Anchor, AutomataError, AutomataRules, Backend, BlendMode, CellView, CellularAutomaton, ConflictPolicy, CoordinateWidth, GrowthPolicy, MaskOp, Method, Occupancy, Profiler, Progress, Spare, UpdateScheme, Wall, hash_cells, in_slabs, in_slabs_mut, line, wrap_coordinate};
+use crate::random::Rng;
+#[cfg(feature = "npy")]
+use crate::npy;
+#[cfg(feature = "profiling")]
+use crate::TickProfile;
+use crate::checkpoint;
+use crate::events::{self, Event};
+use crate::replay::ReplayLog;
+use crate::tree::Tree;
+use crate::bits::Bits;
+use crate::dense::Dense;
+use crate::incremental::Incremental;
 use std::hash::Hash;
-use std::ops::{Add, Sub};
-use std::default::Default;
-use std::collections::HashMap;
+use std::borrow::BorrowMut;
+use std::ops::{Add, ControlFlow, Index, IndexMut, Range, Sub};
+use std::num::TryFromIntError;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::io::{self, Read, Write};
+use std::sync::Arc;
 
 //--> Structs <--
 
 /// A position on a 2D grid, or the size of a 2D grid.
-#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Clone)]
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy, Default, Debug)]
 pub struct Vec2 { x: usize, y: usize }
 
+/// A position on an unbounded 2D grid. Unlike Vec2, this can go negative.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Default, Debug)]
+pub struct IVec2 { x: i64, y: i64 }
+
+/// A rectangular region of a 2D grid, from `min` up to (but not including) `max`.
+#[derive(PartialEq, Eq, Hash, Clone, Default, Debug)]
+pub struct Rect2 { min: Vec2, max: Vec2 }
+
 /// The humble 2D cellular automaton.
+#[derive(Clone)]
 pub struct Automaton {
 	rules: AutomataRules,
 	bounds: Vec2,
-	cells: HashMap<Vec2, u8>
+	cells: Cells,
+	previous: Option<Cells>,
+	spare: Spare<Cells>,
+	species: HashMap<Vec2, u8>,
+	walls: HashMap<Vec2, (Wall, u8)>,
+	edge: u8,
+	ages: Option<HashMap<Vec2, u32>>,
+	trails: Option<HashMap<Vec2, u32>>,
+	heat: Option<(f64, HashMap<Vec2, f64>)>,
+	resources: Option<Resources>,
+	offsets: Vec<IVec2>,
+	regions: Vec<(Rect2, AutomataRules)>,
+	birth_field: Option<Field>,
+	survival_field: Option<Field>,
+	rng: Rng,
+	scheme: UpdateScheme,
+	profile: Profiler,
+	schedule: Option<(Vec<AutomataRules>, usize)>,
+	events: Option<Vec<Event>>,
+	threads: usize,
+	generation: u64
+}
+
+/// A number for every cell of a grid, like a temperature, that changes how likely cells are to be born or survive there.
+/// Fields are just functions of a cell's position, so they cost nothing to store, even on huge grids.
+#[derive(Clone)]
+pub struct Field {
+	value: Arc<dyn Fn(&Vec2) -> f64 + Send + Sync>
+}
+
+/// How much of a resource every cell of a grid has, along with how much a birth costs and how fast dead cells get it back.
+/// Only cells below capacity are kept, along with how far below capacity they are.
+#[derive(Clone)]
+struct Resources {
+	capacity: f64,
+	cost: f64,
+	regrowth: f64,
+	used: HashMap<Vec2, f64>
+}
+
+/// A 2D cellular automaton without any bounds, so gliders and spaceships can fly off forever.
+/// 
+/// Only live and dying cells are stored, so memory and tick time scale with the size of the pattern instead of the size of the grid.
+pub struct UnboundedAutomaton {
+	rules: AutomataRules,
+	cells: HashMap<IVec2, u8>,
+	species: HashMap<IVec2, u8>,
+	offsets: Vec<IVec2>,
+	limits: (Option<usize>, Option<(IVec2, IVec2)>, GrowthPolicy),
+	width: CoordinateWidth,
+	rng: Rng
+}
+
+/// A small 2D automaton whose size is fixed at compile time, with its cells kept in an array instead of on the heap.
+/// Ticking never allocates, so tiny grids can run on microcontrollers without an allocator (as long as the rules get made somewhere that has one).
+/// 
+/// It ticks exactly the way an `Automaton` with the same rules, cells, and seed would, but there's nothing extra like species, second-order mode, or ages.
+/// The whole grid gets copied onto the stack every tick, so keep it small.
+/// 
+/// ```
+/// # use cellular_snapp::{presets, flat::{Automaton, FixedAutomaton, Vec2}};
+/// let glider = presets::glider().positions();
+/// let mut fixed = FixedAutomaton::<8, 8>::new(presets::life(), &glider).unwrap();
+/// let mut life = Automaton::new(presets::life(), Vec2::new(8, 8), glider).unwrap();
+/// 
+/// for _ in 0..4 {
+///     fixed.tick();
+///     life.tick();
+/// }
+/// 
+/// assert_eq!(fixed.get_cell(&Vec2::new(2, 3)), Some(1));
+/// assert!((0..8).all(|y| (0..8).all(|x| fixed.get_cell(&Vec2::new(x, y)) == life.get_cell(&Vec2::new(x, y)))));
+/// ```
+#[derive(Clone)]
+pub struct FixedAutomaton<const W: usize, const H: usize> {
+	rules: AutomataRules,
+	cells: [[u8; W]; H],
+	rng: Rng,
+	generation: u64
+}
+
+/// A snapshot of every cell of an automaton at one generation, made by `Automaton::frame`, which never changes once it's made.
+/// It comes wrapped in an `Arc`, so a render thread can hang onto it (and clone it around for next to nothing) while the automaton keeps ticking on another thread.
+/// 
+/// ```
+/// # use cellular_snapp::{presets, flat::{Automaton, Vec2}};
+/// let mut life = Automaton::new(presets::life(), Vec2::new(16, 16), presets::glider().positions()).unwrap();
+/// let frame = life.frame();
+/// 
+/// let renderer = std::thread::spawn({
+///     let frame = frame.clone();
+///     move || frame.cells().iter().filter(|s| **s > 0).count()
+/// });
+/// 
+/// life.tick();
+/// assert_eq!(renderer.join().unwrap(), 5);
+/// assert_eq!(frame.generation(), 0);
+/// assert_eq!(frame.get_cell(&Vec2::new(1, 0)), Some(1));
+/// ```
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Frame {
+	bounds: Vec2,
+	generation: u64,
+	cells: Vec<u8>
+}
+
+/// An endless stream of generations, made by `Automaton::generations` or `Automaton::generations_mut`.
+/// Every call to `next` ticks the automaton and hands back a snapshot of it, so this never runs out on its own. Use `take` to stop it.
+pub struct Generations<A: BorrowMut<Automaton>> {
+	automaton: A
+}
+
+/// A reusable shape made out of cells, like a glider or a glider gun.
+/// 
+/// The coordinates in a pattern are relative to wherever you end up placing it, so the same glider can be stamped all over a grid.
+/// Cells can optionally carry their own state value. Cells without one are placed fully alive.
+#[derive(Clone)]
+pub struct Pattern {
+	name: String,
+	cells: Vec<(Vec2, Option<u8>)>
+}
+
+//--> Enums <--
+
+/// Symmetry to give a random soup, since symmetric soups tend to grow into the most striking patterns.
+/// Everything is mirrored or rotated around the middle of the grid.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Symmetry {
+	/// The right half is the left half flipped over along x.
+	MirrorX,
+	/// The bottom half is the top half flipped over along y.
+	MirrorY,
+	/// Flipped over along both x and y, so all four quarters mirror each other.
+	MirrorXY,
+	/// The same after every quarter turn. Turning only lines up with the grid when it's square, so cells that would turn out of bounds are left out.
+	FourFold,
+	/// The same after every quarter turn and every flip, so all eight halves of the quarters mirror each other.
+	EightFold
+}
+
+/// Where an automaton keeps its cells, depending on its backend.
+#[derive(Clone)]
+enum Cells {
+	Map(HashMap<Vec2, u8>),
+	Tree(Tree<2>),
+	Bits(Bits),
+	Dense(Dense),
+	/// This also keeps the grid's bounds and neighbor offsets around, to work out whose counts change when a cell gets set.
+	Incremental(Incremental, Vec2, Vec<IVec2>)
 }
 
 //--> Functions <--
@@ -48,6 +260,213 @@ pub struct Automaton {
 impl Vec2 {
 	/// Creates a new 2D position.
 	pub fn new(x: usize, y: usize) -> Vec2 { Vec2 { x, y } }
+
+	/// Get the x component.
+	pub fn x(&self) -> usize { self.x }
+
+	/// Get the y component.
+	pub fn y(&self) -> usize { self.y }
+
+	/// Adds another position to this one, or returns None if that would go past the largest possible usize.
+	pub fn checked_add_position(&self, other: &Vec2) -> Option<Vec2> {
+		Some(Vec2 {
+			x: self.x.checked_add(other.x)?,
+			y: self.y.checked_add(other.y)?
+		})
+	}
+
+	/// Moves this position by a signed offset, or returns None if that would take it below zero (or past the largest possible usize).
+	pub fn checked_add_offset(&self, offset: &IVec2) -> Option<Vec2> {
+		Some(Vec2 {
+			x: usize::try_from(i64::try_from(self.x).ok()?.checked_add(offset.x)?).ok()?,
+			y: usize::try_from(i64::try_from(self.y).ok()?.checked_add(offset.y)?).ok()?
+		})
+	}
+
+	/// Moves this position by a signed offset, or returns None if that would take it off a grid with the given bounds.
+	/// 
+	/// ```
+	/// # use cellular_snapp::flat::{IVec2, Vec2};
+	/// let (bounds, v) = (Vec2::new(8, 8), Vec2::new(6, 2));
+	/// let step = IVec2::new(3, -3);
+	/// assert_eq!(v.checked_add(&step, &bounds), None);
+	/// assert_eq!(v.saturating_add(&step, &bounds), Vec2::new(7, 0));
+	/// assert_eq!(v.wrapping_add(&step, &bounds), Vec2::new(1, 7));
+	/// ```
+	pub fn checked_add(&self, offset: &IVec2, bounds: &Vec2) -> Option<Vec2> {
+		self.checked_add_offset(offset).filter(|v| v.x < bounds.x && v.y < bounds.y)
+	}
+
+	/// Moves this position by a signed offset, stopping at the edges of a grid with the given bounds instead of going past them.
+	pub fn saturating_add(&self, offset: &IVec2, bounds: &Vec2) -> Vec2 {
+		let along = |v: usize, offset: i64, size: usize| (v as i128 + offset as i128).clamp(0, size.saturating_sub(1) as i128) as usize;
+		Vec2 {
+			x: along(self.x, offset.x, bounds.x),
+			y: along(self.y, offset.y, bounds.y)
+		}
+	}
+
+	/// Moves this position by a signed offset, wrapping around to the other side of a grid with the given bounds like on a torus.
+	pub fn wrapping_add(&self, offset: &IVec2, bounds: &Vec2) -> Vec2 {
+		let along = |v: usize, offset: i64, size: usize| (v as i128 + offset as i128).rem_euclid(size.max(1) as i128) as usize;
+		Vec2 {
+			x: along(self.x, offset.x, bounds.x),
+			y: along(self.y, offset.y, bounds.y)
+		}
+	}
+}
+
+impl Rect2 {
+	/// Creates a new region from `min` up to (but not including) `max`.
+	pub fn new(min: Vec2, max: Vec2) -> Rect2 { Rect2 { min, max } }
+
+	/// Get the corner of the region closest to the origin, which is inside the region.
+	pub fn min(&self) -> &Vec2 { &self.min }
+
+	/// Get the corner of the region furthest from the origin, which is just outside the region.
+	pub fn max(&self) -> &Vec2 { &self.max }
+
+	/// Checks whether a position is inside the region.
+	pub fn contains(&self, v: &Vec2) -> bool {
+		v.x >= self.min.x && v.y >= self.min.y && v.x < self.max.x && v.y < self.max.y
+	}
+
+	/// Checks whether there aren't any positions inside the region at all.
+	pub fn is_empty(&self) -> bool {
+		self.min.x >= self.max.x || self.min.y >= self.max.y
+	}
+
+	/// Goes through every position inside the region, row by row.
+	pub fn iter_positions(&self) -> impl Iterator<Item = Vec2> {
+		let (min, max) = (self.min, self.max);
+		(min.y..max.y).flat_map(move |y| (min.x..max.x).map(move |x| Vec2::new(x, y)))
+	}
+
+	/// Get the part of the region that's also inside another region, or `None` if they don't overlap.
+	/// 
+	/// ```
+	/// # use cellular_snapp::flat::{Rect2, Vec2};
+	/// let a = Rect2::new(Vec2::new(0, 0), Vec2::new(4, 4));
+	/// let b = Rect2::new(Vec2::new(2, 3), Vec2::new(8, 8));
+	/// assert_eq!(a.intersect(&b), Some(Rect2::new(Vec2::new(2, 3), Vec2::new(4, 4))));
+	/// assert_eq!(a.intersect(&b).unwrap().iter_positions().count(), 2);
+	/// 
+	/// // growing a region by one on every side stops at the edge of the grid
+	/// assert_eq!(b.expand(1), Rect2::new(Vec2::new(1, 2), Vec2::new(9, 9)));
+	/// assert_eq!(a.intersect(&Rect2::new(Vec2::new(4, 0), Vec2::new(8, 4))), None);
+	/// ```
+	pub fn intersect(&self, other: &Rect2) -> Option<Rect2> {
+		let overlap = Rect2::new(
+			Vec2::new(self.min.x.max(other.min.x), self.min.y.max(other.min.y)),
+			Vec2::new(self.max.x.min(other.max.x), self.max.y.min(other.max.y))
+		);
+
+		(!overlap.is_empty()).then_some(overlap)
+	}
+
+	/// Grows the region by `n` cells on every side, except past 0, since positions can't be negative.
+	pub fn expand(&self, n: usize) -> Rect2 {
+		Rect2::new(
+			Vec2::new(self.min.x.saturating_sub(n), self.min.y.saturating_sub(n)),
+			Vec2::new(self.max.x.saturating_add(n), self.max.y.saturating_add(n))
+		)
+	}
+}
+
+impl IVec2 {
+	/// Creates a new signed 2D position.
+	pub fn new(x: i64, y: i64) -> IVec2 { IVec2 { x, y } }
+
+	/// Get the x component.
+	pub fn x(&self) -> i64 { self.x }
+
+	/// Get the y component.
+	pub fn y(&self) -> i64 { self.y }
+
+	/// Adds another position to this one, or returns None if that would go past the largest or smallest coordinate there can be.
+	pub fn checked_add(&self, rhs: &IVec2) -> Option<IVec2> {
+		Some(IVec2 {
+			x: self.x.checked_add(rhs.x)?,
+			y: self.y.checked_add(rhs.y)?
+		})
+	}
+
+	/// Subtracts another position from this one, or returns None if that would go past the largest or smallest coordinate there can be.
+	pub fn checked_sub(&self, rhs: &IVec2) -> Option<IVec2> {
+		Some(IVec2 {
+			x: self.x.checked_sub(rhs.x)?,
+			y: self.y.checked_sub(rhs.y)?
+		})
+	}
+
+	/// Adds another position to this one, stopping at the largest or smallest coordinate there can be instead of going past it.
+	pub fn saturating_add(&self, rhs: &IVec2) -> IVec2 {
+		IVec2 {
+			x: self.x.saturating_add(rhs.x),
+			y: self.y.saturating_add(rhs.y)
+		}
+	}
+
+	/// Adds another position to this one, wrapping around from the largest coordinate there can be to the smallest (and the other way around).
+	pub fn wrapping_add(&self, rhs: &IVec2) -> IVec2 {
+		IVec2 {
+			x: self.x.wrapping_add(rhs.x),
+			y: self.y.wrapping_add(rhs.y)
+		}
+	}
+}
+
+impl Symmetry {
+	/// Get every way a cell gets mirrored or turned, including leaving it alone, as `[a, b, c, d]` where `(x, y)` goes to `(ax + by, cx + dy)`.
+	fn transforms(&self) -> Vec<[i64; 4]> {
+		let turns = vec![[1, 0, 0, 1], [0, -1, 1, 0], [-1, 0, 0, -1], [0, 1, -1, 0]];
+
+		match self {
+			Symmetry::MirrorX => vec![[1, 0, 0, 1], [-1, 0, 0, 1]],
+			Symmetry::MirrorY => vec![[1, 0, 0, 1], [1, 0, 0, -1]],
+			Symmetry::MirrorXY => vec![[1, 0, 0, 1], [-1, 0, 0, 1], [1, 0, 0, -1], [-1, 0, 0, -1]],
+			Symmetry::FourFold => turns,
+			// flipping along x after each turn gives the other four
+			Symmetry::EightFold => turns.iter().flat_map(|&[a, b, c, d]| [[a, b, c, d], [-a, -b, c, d]]).collect()
+		}
+	}
+}
+
+/// Adding positions panics if it would go past the largest or smallest coordinate there can be, even in release builds.
+/// Use `checked_add`, `saturating_add`, or `wrapping_add` to pick what happens instead.
+impl Add for IVec2 {
+	type Output = IVec2;
+	fn add(self, rhs: IVec2) -> IVec2 {
+		self.checked_add(&rhs).expect("position went past the largest or smallest coordinate there can be")
+	}
+}
+
+/// Subtracting positions panics if it would go past the largest or smallest coordinate there can be, even in release builds.
+/// Use `checked_sub` to get `None` instead.
+impl Sub for IVec2 {
+	type Output = IVec2;
+	fn sub(self, rhs: IVec2) -> IVec2 {
+		self.checked_sub(&rhs).expect("position went past the largest or smallest coordinate there can be")
+	}
+}
+
+impl From<Vec2> for IVec2 {
+	fn from(v: Vec2) -> IVec2 {
+		IVec2 {
+			x: v.x as i64,
+			y: v.y as i64
+		}
+	}
+}
+
+impl TryFrom<IVec2> for Vec2 {
+	type Error = TryFromIntError;
+	fn try_from(v: IVec2) -> Result<Vec2, TryFromIntError> {
+		Ok(Vec2 {
+			x: usize::try_from(v.x)?,
+			y: usize::try_from(v.y)?
+		})
+	}
 }
 
 impl Add for Vec2 {
@@ -70,160 +489,3014 @@ impl Sub for Vec2 {
 	}
 }
 
-impl Default for Vec2 {
-	fn default() -> Vec2 { Vec2 { x: 0, y: 0 } }
-}
-
 impl Automaton {
 	/// Creates a new flat (2D) automaton with the given rules, bounds, and starting cells.
 	/// This can fail if your survival and birth rules exceeds the amount of neighbors a cell could have, given your chosen neighbor counting method.
-	/// If that happens, this function will error out and return the maximum amount of neighbors.
-	pub fn new(rules: AutomataRules, bounds: Vec2, start_cells: Vec<Vec2>) -> Result<Automaton, u8> {
-		let other_rules = rules.clone();
-		let mut a = Automaton { rules, bounds, cells: HashMap::new() };
-
-		let max_neighbors: u8 = match a.rules.neighbor_method {
-			Method::Moore => 8,
-			Method::VonNeumann => 4
-		};
+	/// If that happens, this function will error out with the maximum amount of neighbors.
+	pub fn new(rules: AutomataRules, bounds: Vec2, start_cells: Vec<Vec2>) -> Result<Automaton, AutomataError> {
+		Automaton::with_backend(rules, bounds, start_cells, Backend::HashMap)
+	}
 
-		match other_rules.to_survive {
-			Rule::Single(s) => if s > max_neighbors { return Err(max_neighbors) },
-			Rule::Range(r) => if r.start > max_neighbors || r.end > max_neighbors { return Err(max_neighbors) },
-			Rule::Many(m) => for s in m {
-				if s > max_neighbors { return Err(max_neighbors) }
-			}
+	/// Creates a new flat (2D) automaton just like `new`, except the cells are kept in the given backend.
+	/// This also fails if the backend can't hold cells for the rules, like a bit-packed grid with more than two cell states.
+	/// 
+	/// ```
+	/// # use cellular_snapp::{presets, Backend, flat::{Automaton, Rect2, Vec2}};
+	/// // a glider in the corner of a grid with a trillion cells, which wouldn't fit in memory with the default backend
+	/// let glider = presets::glider().positions();
+	/// let mut life = Automaton::with_backend(presets::life(), Vec2::new(1_000_000, 1_000_000), glider, Backend::Tree).unwrap();
+	/// for _ in 0..4 { life.tick(); }
+	/// 
+	/// assert_eq!(life.live_cells_in(&Rect2::new(Vec2::new(0, 0), Vec2::new(10, 10))).len(), 5);
+	/// ```
+	pub fn with_backend(rules: AutomataRules, bounds: Vec2, start_cells: Vec<Vec2>, backend: Backend) -> Result<Automaton, AutomataError> {
+		rules.validate(max_neighbors(&rules.neighbor_method)?, 2)?;
+		rules.check_backend(backend)?;
+
+		let mut cells = Cells::try_new(backend, &bounds, &rules)?;
+		for v in start_cells.into_iter().filter(|v| v.x < bounds.x && v.y < bounds.y) {
+			cells.set(v, rules.cell_states - 1);
 		}
 
-		match other_rules.to_be_born {
-			Rule::Single(s) => if s > max_neighbors { return Err(max_neighbors) },
-			Rule::Range(r) => if r.start > max_neighbors || r.end > max_neighbors { return Err(max_neighbors) },
-			Rule::Many(m) => for s in m {
-				if s > max_neighbors { return Err(max_neighbors) }
+		let offsets = neighbor_offsets(&rules.neighbor_method);
+		Ok(Automaton { rules, bounds, cells, previous: None, spare: Spare(None), species: HashMap::new(), walls: HashMap::new(), edge: 0, ages: None, trails: None, heat: None, resources: None, offsets, regions: Vec::new(), birth_field: None, survival_field: None, rng: Rng::new(0), scheme: UpdateScheme::Synchronous, profile: Profiler::default(), schedule: None, events: None, threads: 1, generation: 0 })
+	}
+
+	/// Sets how many threads each tick gets split up between. The grid is cut into horizontal slabs of rows, one per thread,
+	/// and each slab is ticked on its own scoped thread from the standard library, so you get multithreading without pulling in rayon.
+	/// Automata start out with one thread, which ticks everything on the thread calling `tick`.
+	/// 
+	/// Random rolls are tied to each cell's position, so the amount of threads never changes how an automaton ticks.
+	/// 
+	/// ```
+	/// # use cellular_snapp::{presets, flat::{Automaton, Vec2}};
+	/// let seed = presets::r_pentomino().translate(Vec2::new(30, 30)).positions();
+	/// let mut one = Automaton::new(presets::life(), Vec2::new(64, 64), seed.clone()).unwrap();
+	/// let mut four = Automaton::new(presets::life(), Vec2::new(64, 64), seed).unwrap().threads(4);
+	/// 
+	/// for _ in 0..50 { one.tick(); four.tick(); }
+	/// assert!(one.get_cells() == four.get_cells());
+	/// ```
+	pub fn threads(mut self, threads: usize) -> Automaton {
+		self.threads = threads.max(1);
+		self
+	}
+
+	/// Get how many threads each tick gets split up between.
+	pub fn get_threads(&self) -> usize {
+		self.threads
+	}
+
+	/// Get how long each part of the last `tick` (or `tick_region`) took, and how many cells it looked at. This needs the `profiling` feature.
+	/// Before the first tick, everything is 0.
+	/// 
+	/// ```
+	/// # use cellular_snapp::{presets, flat::{Automaton, Vec2}};
+	/// let mut life = Automaton::new(presets::life(), Vec2::new(64, 64), presets::glider().positions()).unwrap();
+	/// life.tick();
+	/// 
+	/// // the hash map backend only looks at the cells next to something live
+	/// let profile = life.last_tick_profile();
+	/// assert!(profile.cells() < 64 * 64);
+	/// assert!(profile.stepping() <= profile.total());
+	/// ```
+	#[cfg(feature = "profiling")]
+	pub fn last_tick_profile(&self) -> &TickProfile {
+		self.profile.last()
+	}
+
+	/// Get how many generations the automaton has been ticked through. Ticking counts up and `tick_backward` counts down,
+	/// but `tick_region` doesn't count, since only part of the grid moves on.
+	pub fn get_generation(&self) -> u64 {
+		self.generation
+	}
+
+	/// Sets how many generations the automaton has been ticked through, like when it's being synced to one somewhere else.
+	pub(crate) fn set_generation(&mut self, generation: u64) {
+		self.generation = generation;
+	}
+
+	/// Turns the automaton into an endless iterator of generations. Each item is a snapshot of the automaton right after a tick,
+	/// so the first one is one generation along from where the automaton is now.
+	/// 
+	/// ```
+	/// # use cellular_snapp::{presets, flat::{Automaton, Vec2}};
+	/// let life = Automaton::new(presets::life(), Vec2::new(16, 16), presets::glider().positions()).unwrap();
+	/// 
+	/// // grab every 4th generation, when the glider is back in its starting shape
+	/// let frames: Vec<Automaton> = life.generations().step_by(4).take(3).collect();
+	/// assert!(frames.iter().all(|frame| frame.get_cells().values().filter(|s| **s > 0).count() == 5));
+	/// ```
+	pub fn generations(self) -> Generations<Automaton> {
+		Generations { automaton: self }
+	}
+
+	/// Like `generations`, except the automaton is only borrowed, so it's still around (and as far along as the iterator got it) afterwards.
+	pub fn generations_mut(&mut self) -> Generations<&mut Automaton> {
+		Generations { automaton: self }
+	}
+
+	/// Advances the automaton by one time step (or tick).
+	/// 
+	/// The grid gets changed in place instead of being built again from nothing, so ticking doesn't allocate much of anything.
+	/// Rules that only count neighbors on the bit-packed and dense backends tick a row at a time into a second grid, which gets kept around afterwards
+	/// and written over by the tick after that, so those backends hold onto twice the memory of their grid.
+	pub fn tick(&mut self) {
+		self.follow_schedule(self.generation);
+		self.profile.begin();
+		let before = (self.rules.species > 1).then(|| self.cells.clone().into_map());
+		let unborn = self.resources.is_some().then(|| self.cells.live());
+		let logged = self.events.is_some().then(|| self.cells.live());
+		self.hide_walls();
+		self.profile.mark();
+
+		match self.previous.take() {
+			Some(previous) => {
+				let (next, stepped) = self.step(&self.cells, None);
+				self.profile.stepped(stepped);
+				let next = self.second_order(next, &previous);
+				self.previous = Some(std::mem::replace(&mut self.cells, next));
+				self.profile.swapped();
+			},
+			None => {
+				let key = self.rng.next_u64();
+				match self.scheme {
+					UpdateScheme::Synchronous => self.step_in_place(key),
+					_ => self.step_async(&Rect2::new(Vec2::new(0, 0), self.bounds), key)
+				}
 			}
 		}
 
-		for x in 0..a.bounds.x {
-			for y in 0..a.bounds.y {
-				let v = Vec2::new(x, y);
+		if let Some(before) = before {
+			self.species = self.rules.inherit_species(&before, &self.cells.live(), &self.species, |v| self.offsets.iter().filter_map(move |offset| v.checked_add_offset(offset)));
+		}
 
-				if start_cells.contains(&v) {
-					a.cells.insert(v, a.rules.cell_states - 1);
-				} else {
-					a.cells.insert(v, 0);
-				}
+		if let Some(before) = unborn { self.use_resources(&before); }
+		self.show_walls();
+		self.generation += 1;
+		self.track_cells(&Rect2::new(Vec2::new(0, 0), self.bounds));
+		if let Some(before) = logged { self.log_tick(&before); }
+		self.profile.finish();
+	}
+
+	/// Advances only the cells inside the given region by one tick, leaving everything outside it as it was.
+	/// Cells along the edges of the region still see their neighbors outside it, so a UI can keep just the visible part of a huge grid moving.
+	/// 
+	/// ```
+	/// # use cellular_snapp::{presets, Backend, flat::{Automaton, Rect2, Vec2}};
+	/// let blinkers = vec![Vec2::new(1, 2), Vec2::new(2, 2), Vec2::new(3, 2), Vec2::new(11, 2), Vec2::new(12, 2), Vec2::new(13, 2)];
+	/// let mut life = Automaton::with_backend(presets::life(), Vec2::new(16, 5), blinkers, Backend::Tree).unwrap();
+	/// life.tick_region(&Rect2::new(Vec2::new(0, 0), Vec2::new(8, 5)));
+	/// 
+	/// // the blinker inside the region flipped over, and the one outside it didn't
+	/// assert_eq!(life.get_cell(&Vec2::new(2, 1)), Some(1));
+	/// assert_eq!(life.get_cell(&Vec2::new(12, 1)), Some(0));
+	/// ```
+	pub fn tick_region(&mut self, region: &Rect2) {
+		self.follow_schedule(self.generation);
+		self.profile.begin();
+		let before = (self.rules.species > 1).then(|| self.cells.live());
+		let unborn = self.resources.is_some().then(|| self.cells.live());
+		let logged = self.events.is_some().then(|| self.cells.live());
+		self.hide_walls();
+		let key = self.previous.is_none().then(|| self.rng.next_u64());
+		self.profile.mark();
+
+		let next: HashMap<Vec2, u8> = match key {
+			Some(key) if self.scheme != UpdateScheme::Synchronous => {
+				self.step_async(region, key);
+				HashMap::new()
+			},
+			_ => {
+				let offsets = &self.offsets;
+
+				let region = self.clipped(region);
+				let (min, max) = (&region.min, &region.max);
+				in_slabs(max.y - min.y, self.threads, |slab| {
+					slab.flat_map(|dy| (min.x..max.x).map(move |x| Vec2::new(x, min.y + dy))).map(|v| { let state = self.next_state(&self.cells, offsets, &v, key); (v, state) }).collect()
+				}).into_iter().collect()
 			}
+		};
+
+		let next = match &mut self.previous {
+			Some(previous) => {
+				let earlier = next.keys().map(|v| (*v, previous.get(v))).collect();
+				let next = self.rules.second_order(next, &earlier);
+				for v in next.keys() { previous.set(*v, self.cells.get(v)); }
+				next
+			},
+			None => next
+		};
+
+		self.profile.stepped(next.len());
+		for (v, state) in next { self.cells.set(v, state); }
+		self.cells.unsettle();
+		self.profile.swapped();
+
+		if let Some(before) = before {
+			self.species = self.rules.inherit_species(&before, &self.cells.live(), &self.species, |v| self.offsets.iter().filter_map(move |offset| v.checked_add_offset(offset)));
 		}
 
-		Ok(a)
+		if let Some(before) = unborn { self.use_resources(&before); }
+		self.show_walls();
+		self.track_cells(region);
+		if let Some(before) = logged { self.log_tick(&before); }
+		self.profile.finish();
 	}
 
-	/// Advances the automaton by one time step (or tick).
-	pub fn tick(&mut self) {
-		let neighbor_counts = self.cells.iter().map(|(v, _)| {
-			let mut count = 0;
-			let mut poss_neighbors = Vec::new();
-
-			// primary directions (up, down, left, right)
-			poss_neighbors.push(Vec2::new(v.x, v.y - 1));
-			poss_neighbors.push(Vec2::new(v.x, v.y + 1));
-			poss_neighbors.push(Vec2::new(v.x - 1, v.y));
-			poss_neighbors.push(Vec2::new(v.x + 1, v.y));
-
-			// secondary directions (up-left, up-right, down-left, down-right) if using Moore
-			if let Method::Moore = self.rules.neighbor_method {
-				poss_neighbors.push(Vec2::new(v.x - 1, v.y - 1));
-				poss_neighbors.push(Vec2::new(v.x - 1, v.y + 1));
-				poss_neighbors.push(Vec2::new(v.x + 1, v.y - 1));
-				poss_neighbors.push(Vec2::new(v.x + 1, v.y + 1));
-			}
-
-			for poss_neighbor in poss_neighbors {
-				if let Some(s) = self.cells.get(&poss_neighbor) {
-					if s > &0 {
-						count += 1;
-					}
-				}
+	/// Takes the automaton back by one time step, undoing a `tick`.
+	/// This only works in second-order mode, since that's the only time the last generation is remembered (and the rules can be run backwards exactly).
+	/// Walls and resources can't be run backwards either, since they change which cells the rules get to touch.
+	/// Otherwise, this gives back a `NotReversible` error without changing anything.
+	pub fn tick_backward(&mut self) -> Result<(), AutomataError> {
+		if !self.walls.is_empty() || self.resources.is_some() { return Err(AutomataError::NotReversible); }
+
+		// the rules to run backwards are the ones the last tick was made with
+		if self.previous.is_some() { self.follow_schedule(self.generation.saturating_sub(1)); }
+		let Some(previous) = self.previous.take() else { return Err(AutomataError::NotReversible); };
+		let logged = self.events.is_some().then(|| self.cells.live());
+
+		let before = self.second_order(self.step(&previous, None).0, &self.cells);
+		self.cells = previous;
+		self.previous = Some(before);
+		self.show_walls();
+		self.generation = self.generation.saturating_sub(1);
+		self.track_cells(&Rect2::new(Vec2::new(0, 0), self.bounds));
+		if let Some(before) = logged { self.log_tick(&before); }
+		Ok(())
+	}
+
+	/// Ticks the automaton `n` times.
+	pub fn tick_n(&mut self, n: usize) {
+		self.tick_n_with(n, |_| ControlFlow::Continue(()));
+	}
+
+	/// Ticks the automaton `n` times like `tick_n`, calling `progress` after every tick. Once the callback breaks, it stops right there.
+	/// Gives back how many ticks actually happened.
+	pub fn tick_n_with(&mut self, n: usize, mut progress: impl FnMut(Progress) -> ControlFlow<()>) -> usize {
+		for done in 1..=n {
+			self.tick();
+			if progress(Progress::new(done, n)).is_break() { return done; }
+		}
+
+		n
+	}
+
+	/// Ticks the automaton (up to `max_ticks` times) until it comes back to a generation it's already been through, which means it's settled down into a still life or an oscillator for good.
+	/// Gives back how many ticks it took to get to the first generation of that cycle, or `None` if it didn't settle down in time.
+	/// The automaton is left one cycle along from there.
+	/// 
+	/// ```
+	/// # use cellular_snapp::presets;
+	/// # use cellular_snapp::flat::{Automaton, Vec2};
+	/// let mut blinker = Automaton::new(presets::life(), Vec2::new(8, 8), vec![Vec2::new(1, 3), Vec2::new(2, 3), Vec2::new(3, 3)]).unwrap();
+	/// assert_eq!(blinker.run_until_stable(10), Some(0));
+	/// assert_eq!(blinker.get_generation(), 2);
+	/// 
+	/// // the single cell dies straight away, and the empty grid that's left never changes
+	/// let mut lonely = Automaton::new(presets::life(), Vec2::new(8, 8), vec![Vec2::new(1, 3)]).unwrap();
+	/// assert_eq!(lonely.run_until_stable(10), Some(1));
+	/// ```
+	pub fn run_until_stable(&mut self, max_ticks: usize) -> Option<usize> {
+		self.run_until_stable_with(max_ticks, |_| ControlFlow::Continue(()))
+	}
+
+	/// Like `run_until_stable`, calling `progress` after every tick. Once the callback breaks, it stops right there and gives back `None`.
+	pub fn run_until_stable_with(&mut self, max_ticks: usize, mut progress: impl FnMut(Progress) -> ControlFlow<()>) -> Option<usize> {
+		let mut seen = HashMap::from([(self.state_hash(), 0)]);
+
+		for done in 1..=max_ticks {
+			self.tick();
+			if let Some(first) = seen.insert(self.state_hash(), done) { return Some(first); }
+			if progress(Progress::new(done, max_ticks)).is_break() { return None; }
+		}
+
+		None
+	}
+
+	/// Turns second-order mode on or off.
+	/// 
+	/// In second-order mode, a cell's next state is what the rules say minus its state from the generation before (wrapping around the amount of cell states),
+	/// which is Fredkin's trick for making any rule reversible. When it's turned on, the generation before is taken to be entirely dead.
+	/// Birth and survival chances are ignored in second-order mode, since random rules can't be run backwards.
+	/// 
+	/// ```
+	/// # use cellular_snapp::{presets, AutomataError, Wall, flat::{Automaton, Vec2}};
+	/// let mut a = Automaton::new(presets::life(), Vec2::new(16, 16), presets::r_pentomino().translate(Vec2::new(6, 6)).positions()).unwrap();
+	/// a.set_second_order(true);
+	/// 
+	/// let start = a.get_cells();
+	/// for _ in 0..10 { a.tick(); }
+	/// for _ in 0..10 { a.tick_backward().unwrap(); }
+	/// assert!(a.get_cells() == start);
+	/// 
+	/// // but not with walls in the way
+	/// a.set_wall(Vec2::new(0, 0), Wall::Alive);
+	/// a.tick();
+	/// assert_eq!(a.tick_backward(), Err(AutomataError::NotReversible));
+	/// ```
+	pub fn set_second_order(&mut self, enabled: bool) {
+		self.previous = match (enabled, self.previous.take()) {
+			(true, Some(previous)) => Some(previous),
+			(true, None) => Some(Cells::new(self.cells.backend(), &self.bounds, &self.rules)),
+			(false, _) => None
+		};
+	}
+
+	/// Sets the order cells get updated in during each tick. Anything but `Synchronous` updates the cells one at a time on a single thread (whatever `threads` says),
+	/// which is a lot slower, and every cell of the grid gets looked at, not just the ones near live cells.
+	/// Second-order mode always updates every cell at once, whatever the scheme is.
+	/// 
+	/// ```
+	/// # use cellular_snapp::{presets, UpdateScheme, flat::{Automaton, Vec2}};
+	/// let mut life = Automaton::new(presets::life(), Vec2::new(8, 8), vec![Vec2::new(2, 3), Vec2::new(3, 3), Vec2::new(4, 3)]).unwrap();
+	/// life.set_update_scheme(UpdateScheme::Sequential);
+	/// life.tick();
+	/// 
+	/// // the top of the blinker is born, but by the time the bottom gets its turn, the left end has already died, so it only has 2 neighbors
+	/// assert_eq!(life.get_cell(&Vec2::new(3, 2)), Some(1));
+	/// assert_eq!(life.get_cell(&Vec2::new(3, 4)), Some(0));
+	/// ```
+	pub fn set_update_scheme(&mut self, scheme: UpdateScheme) {
+		self.scheme = scheme;
+	}
+
+	/// Get the order cells get updated in during each tick.
+	pub fn get_update_scheme(&self) -> UpdateScheme {
+		self.scheme
+	}
+
+	/// Checks whether the automaton is in second-order mode.
+	pub fn is_second_order(&self) -> bool {
+		self.previous.is_some()
+	}
+
+	/// Turns age tracking on or off. While it's on, the automaton counts how many ticks each live or dying cell has been around since it was born,
+	/// so renderers can color cells by age instead of by how far along they are in dying. When it's turned on, every cell starts out at age 0.
+	/// 
+	/// A cell only stops aging once it's fully dead, and going back with `tick_backward` counts as a tick too, since ages from before aren't remembered.
+	/// 
+	/// ```
+	/// # use cellular_snapp::{presets, flat::{Automaton, Vec2}};
+	/// let mut life = Automaton::new(presets::life(), Vec2::new(8, 8), vec![Vec2::new(2, 3), Vec2::new(3, 3), Vec2::new(4, 3)]).unwrap();
+	/// life.set_age_tracking(true);
+	/// for _ in 0..4 { life.tick(); }
+	/// 
+	/// // the middle of a blinker never dies, but its ends keep getting born again
+	/// assert_eq!(life.cell_age(&Vec2::new(3, 3)), Some(4));
+	/// assert_eq!(life.cell_age(&Vec2::new(2, 3)), Some(0));
+	/// assert_eq!(life.cell_age(&Vec2::new(3, 2)), None);
+	/// ```
+	pub fn set_age_tracking(&mut self, enabled: bool) {
+		self.ages = match (enabled, self.ages.take()) {
+			(true, Some(ages)) => Some(ages),
+			(true, None) => Some(self.cells.live().into_keys().map(|v| (v, 0)).collect()),
+			(false, _) => None
+		};
+	}
+
+	/// Checks whether the automaton is tracking the ages of its cells.
+	pub fn is_tracking_age(&self) -> bool {
+		self.ages.is_some()
+	}
+
+	/// Get how many ticks a live or dying cell has been around since it was born.
+	/// This is `None` for dead cells, cells outside the grid, and every cell when age tracking is off.
+	pub fn cell_age(&self, v: &Vec2) -> Option<u32> {
+		let ages = self.ages.as_ref()?;
+		(self.get_cell(v)? > 0).then(|| ages.get(v).copied().unwrap_or(0))
+	}
+
+	/// Get every live or dying cell inside the given region like `live_cells_in`, along with its age. Every cell is age 0 when age tracking is off.
+	pub fn live_cells_with_age_in(&self, region: &Rect2) -> HashMap<Vec2, (u8, u32)> {
+		self.live_cells_in(region).into_iter().map(|(v, s)| (v, (s, self.ages.as_ref().and_then(|ages| ages.get(&v)).copied().unwrap_or(0)))).collect()
+	}
+
+	/// Turns trail tracking on or off. While it's on, the automaton remembers how many ticks ago every dead cell was last live or dying,
+	/// so renderers can fade out where structures have just been without diffing every frame. When it's turned on, there's no trail yet.
+	/// Cells that were never alive while it was on aren't part of the trail, and just like with ages, going back with `tick_backward` counts as a tick too.
+	/// 
+	/// ```
+	/// # use cellular_snapp::{presets, flat::{Automaton, Vec2}};
+	/// let mut life = Automaton::new(presets::life(), Vec2::new(8, 8), vec![Vec2::new(2, 3), Vec2::new(3, 3), Vec2::new(2, 6)]).unwrap();
+	/// life.set_trail_tracking(true);
+	/// for _ in 0..3 { life.tick(); }
+	/// 
+	/// // all three cells died of loneliness on the first tick
+	/// assert_eq!(life.trail(3)[&Vec2::new(2, 6)], 3);
+	/// assert_eq!(life.trail(3).len(), 3);
+	/// assert!(life.trail(2).is_empty());
+	/// ```
+	pub fn set_trail_tracking(&mut self, enabled: bool) {
+		self.trails = match (enabled, self.trails.take()) {
+			(true, Some(trails)) => Some(trails),
+			(true, None) => Some(self.cells.live().into_keys().map(|v| (v, 0)).collect()),
+			(false, _) => None
+		};
+	}
+
+	/// Checks whether the automaton is tracking trails.
+	pub fn is_tracking_trails(&self) -> bool {
+		self.trails.is_some()
+	}
+
+	/// Get every dead cell that was last live or dying at most `window` ticks ago, along with how many ticks ago that was (which is at least 1).
+	/// This is empty when trail tracking is off.
+	pub fn trail(&self, window: u32) -> HashMap<Vec2, u32> {
+		let Some(trails) = &self.trails else { return HashMap::new(); };
+		trails.iter().filter(|(_, since)| (1..=window).contains(*since)).map(|(v, since)| (*v, *since)).collect()
+	}
+
+	/// Turns the activity heatmap on or off. While it's on, the automaton keeps a running tally of how often every cell has been live or dying,
+	/// which is great for long-exposure trails that show where a pattern has been. When it's turned on, the current generation counts as the first one.
+	/// 
+	/// Every tick, the whole heatmap is multiplied by the decay factor before live and dying cells add 1 to their spots,
+	/// so a decay of 1 just counts generations, and anything less makes old activity fade away. Giving `None` turns the heatmap off and throws it away.
+	/// 
+	/// ```
+	/// # use cellular_snapp::{presets, flat::{Automaton, Vec2}};
+	/// let mut life = Automaton::new(presets::life(), Vec2::new(8, 8), vec![Vec2::new(2, 3), Vec2::new(3, 3), Vec2::new(4, 3)]).unwrap();
+	/// life.set_heatmap(Some(1.0));
+	/// for _ in 0..4 { life.tick(); }
+	/// 
+	/// // the middle of a blinker is alive in all 5 generations, and its ends are only alive in every other one
+	/// let heat = life.heatmap();
+	/// assert_eq!(heat[&Vec2::new(3, 3)], 5.0);
+	/// assert_eq!(heat[&Vec2::new(2, 3)], 3.0);
+	/// assert_eq!(heat[&Vec2::new(3, 2)], 2.0);
+	/// ```
+	pub fn set_heatmap(&mut self, decay: Option<f64>) {
+		self.heat = decay.map(|decay| match self.heat.take() {
+			Some((_, heat)) => (decay, heat),
+			None => (decay, self.cells.live().into_keys().map(|v| (v, 1.0)).collect())
+		});
+	}
+
+	/// Get the activity heatmap, with the heat of every cell that's been live or dying since it was turned on. This is empty when the heatmap is off.
+	pub fn heatmap(&self) -> HashMap<Vec2, f64> {
+		self.heat.as_ref().map(|(_, heat)| heat.clone()).unwrap_or_default()
+	}
+
+	/// Turns resource mode on, or changes its rates if it's already on, for modelling growth that's held back by what's around, like slime mold or coral.
+	/// Every cell holds up to `capacity` of some resource, and starts out full when resource mode is turned on.
+	/// 
+	/// A cell can only be born if it and its neighbors hold at least `birth_cost` between them, and being born uses that much up,
+	/// taken from each of them in proportion to how much they have. Births that can't be paid for don't happen, and are paid for row by row (by y, and then x) when there isn't enough to go around.
+	/// After every tick, every dead cell gets `regrowth` back, up to its capacity. Live and dying cells don't use up or get back anything.
+	/// 
+	/// ```
+	/// # use cellular_snapp::{presets, flat::{Automaton, Vec2}};
+	/// let blinker = vec![Vec2::new(2, 3), Vec2::new(3, 3), Vec2::new(4, 3)];
+	/// let mut life = Automaton::new(presets::life(), Vec2::new(8, 8), blinker).unwrap();
+	/// life.set_resources(1.0, 4.5, 0.0);
+	/// 
+	/// // the first birth takes half of what's around it, and the two births use up so much between them that only one of the next two can be paid for
+	/// life.tick();
+	/// assert_eq!(life.live_cells_sorted().len(), 3);
+	/// assert_eq!(life.resource(&Vec2::new(3, 1)), Some(0.5));
+	/// life.tick();
+	/// assert_eq!(life.live_cells_sorted(), vec![(Vec2::new(2, 3), 1), (Vec2::new(3, 3), 1)]);
+	/// ```
+	pub fn set_resources(&mut self, capacity: f64, birth_cost: f64, regrowth: f64) {
+		let capacity = capacity.max(0.0);
+		let mut used = self.resources.take().map(|r| r.used).unwrap_or_default();
+		for u in used.values_mut() { *u = u.min(capacity); }
+
+		self.resources = Some(Resources { capacity, cost: birth_cost.max(0.0), regrowth: regrowth.max(0.0), used });
+	}
+
+	/// Turns resource mode off, and forgets how much of the resource every cell had.
+	pub fn clear_resources(&mut self) {
+		self.resources = None;
+	}
+
+	/// Get how much of the resource a cell has, or `None` if it's outside the grid or resource mode is off.
+	pub fn resource(&self, v: &Vec2) -> Option<f64> {
+		let resources = self.resources.as_ref()?;
+		self.get_cell(v)?;
+		Some(resources.capacity - resources.used.get(v).copied().unwrap_or(0.0))
+	}
+
+	/// Pays for every cell that was just born, given every cell that was live or dying before the tick, killing the ones that can't be paid for.
+	/// Then every dead cell gets some of the resource back.
+	fn use_resources(&mut self, before: &HashMap<Vec2, u8>) {
+		let Some(mut resources) = self.resources.take() else { return; };
+
+		let mut born: Vec<Vec2> = self.cells.live().into_keys().filter(|v| !before.contains_key(v)).collect();
+		born.sort_unstable_by_key(|v| (v.y, v.x));
+
+		for v in born {
+			let spots: Vec<Vec2> = std::iter::once(v).chain(self.offsets.iter().filter_map(|offset| v.checked_add_offset(offset)))
+				.filter(|n| n.x < self.bounds.x && n.y < self.bounds.y)
+				.collect();
+
+			let level = |n: &Vec2| resources.capacity - resources.used.get(n).copied().unwrap_or(0.0);
+			let available: f64 = spots.iter().map(level).sum();
+
+			if available < resources.cost {
+				self.cells.set(v, 0);
+				self.species.remove(&v);
+				continue;
 			}
 
-			(v.clone(), count)
-		}).collect::<HashMap<Vec2, u8>>();
+			// free births (or ones with nothing around to take) don't take anything
+			let share = if available > 0.0 { resources.cost / available } else { 0.0 };
+			let capacity = resources.capacity;
+			for n in spots {
+				let used = resources.used.entry(n).or_insert(0.0);
+				*used += (capacity - *used) * share;
+			}
+		}
 
-		self.cells.iter_mut().for_each(|(v, s)| {
-			if s == &0 {
-				// cell is dead
-				match self.rules.to_be_born {
-					Rule::Single(ref goal) => {
-						if let Some(neighbor_count) = neighbor_counts.get(v) {
-							if neighbor_count == goal {
-								// cell will be born
-								*s = self.rules.cell_states - 1;
-							}
-						}
-					},
-					Rule::Range(ref goal_range) => {
-						if let Some(neighbor_count) = neighbor_counts.get(v) {
-							if goal_range.contains(neighbor_count) {
-								// cell will be born
-								*s = self.rules.cell_states - 1;
-							}
-						}
-					},
-					Rule::Many(ref goals) => {
-						if let Some(neighbor_count) = neighbor_counts.get(v) {
-							if goals.contains(neighbor_count) {
-								// cell will be born
-								*s = self.rules.cell_states - 1;
-							}
-						}
-					}
-				}
-			} else if s == &(self.rules.cell_states - 1) {
-				// cell is alive
-				match self.rules.to_survive {
-					Rule::Single(ref goal) => {
-						if let Some(neighbor_count) = neighbor_counts.get(v) {
-							if neighbor_count != goal {
-								// cell will start dying now
-								*s -= 1;
-							}
-						} else {
-							// cell should not exist
-							*s = 0;
-						}
-					},
-					Rule::Range(ref goal_range) => {
-						if let Some(neighbor_count) = neighbor_counts.get(v) {
-							if !goal_range.contains(neighbor_count) {
-								// cell will start dying now
-								*s -= 1;
-							}
-						} else {
-							// cell should not exist
-							*s = 0;
-						}
-					},
-					Rule::Many(ref goals) => {
-						if let Some(neighbor_count) = neighbor_counts.get(v) {
-							if !goals.contains(neighbor_count) {
-								// cell will start dying now
-								*s -= 1;
-							}
-						} else {
-							// cell should not exist
-							*s = 0;
-						}
-					}
+		resources.used.retain(|v, used| {
+			if self.cells.get(v) == 0 { *used -= resources.regrowth; }
+			*used > 0.0
+		});
+
+		self.resources = Some(resources);
+	}
+
+	/// Turns the event log on or off. While it's on, the automaton keeps a list of every tick, rule change, and edit until it gets drained.
+	/// Turning it off throws away everything that hasn't been drained yet. See the `events` module for more.
+	pub fn set_event_log(&mut self, enabled: bool) {
+		self.events = match (enabled, self.events.take()) {
+			(true, events) => Some(events.unwrap_or_default()),
+			(false, _) => None
+		};
+	}
+
+	/// Checks whether the automaton is keeping an event log.
+	pub fn is_logging_events(&self) -> bool {
+		self.events.is_some()
+	}
+
+	/// Takes every event logged since the last time they were drained, oldest first. This is empty when the event log is off.
+	pub fn drain_events(&mut self) -> Vec<Event> {
+		self.events.as_mut().map(std::mem::take).unwrap_or_default()
+	}
+
+	/// Drains every event like `drain_events`, and writes them out as JSON lines, one event per line.
+	/// The events are drained even if writing them fails.
+	pub fn drain_events_to(&mut self, writer: impl Write) -> io::Result<()> {
+		events::write_json_lines(&self.drain_events(), writer)
+	}
+
+	/// Adds an event to the log, if it's on.
+	fn log(&mut self, event: Event) {
+		if let Some(events) = &mut self.events { events.push(event); }
+	}
+
+	/// Logs a tick, given every cell that was live or dying before it.
+	fn log_tick(&mut self, before: &HashMap<Vec2, u8>) {
+		let after = self.cells.live();
+		let births = after.keys().filter(|v| !before.contains_key(v)).count();
+		let deaths = before.keys().filter(|v| !after.contains_key(v)).count();
+		self.log(Event::Tick { generation: self.generation, births, deaths });
+	}
+
+	/// Updates the ages, trails, and the heatmap of every cell in the region that just ticked.
+	/// Live and dying cells get a tick older (newborns start at 0) and add to their heat, and cells that died are forgotten about, except by their trails.
+	fn track_cells(&mut self, region: &Rect2) {
+		if self.ages.is_none() && self.trails.is_none() && self.heat.is_none() { return; }
+
+		let live = self.live_cells_in(region);
+
+		if let Some(ages) = &mut self.ages {
+			ages.retain(|v, _| !region.contains(v) || live.contains_key(v));
+			for v in live.keys() { ages.entry(*v).and_modify(|age| *age = age.saturating_add(1)).or_insert(0); }
+		}
+
+		if let Some(trails) = &mut self.trails {
+			for (v, since) in trails.iter_mut().filter(|(v, _)| region.contains(v)) { *since = if live.contains_key(v) { 0 } else { since.saturating_add(1) }; }
+			for v in live.keys() { trails.insert(*v, 0); }
+		}
+
+		if let Some((decay, heat)) = &mut self.heat {
+			for (_, h) in heat.iter_mut().filter(|(v, _)| region.contains(v)) { *h *= *decay; }
+			for v in live.keys() { *heat.entry(*v).or_insert(0.0) += 1.0; }
+		}
+	}
+
+	/// Works out what the rules say every cell of the given grid turns into.
+	/// Random rolls are only made if given a key, and otherwise every chance passes.
+	/// This also gives back how many cells it looked at.
+	fn step(&self, cells: &Cells, key: Option<u64>) -> (Cells, usize) {
+		if let Some(next) = self.bulk_step(cells, None) { return (next, self.bounds.x * self.bounds.y); }
+
+		let mut out = cells.clone();
+		let changes = self.changes(cells, key);
+		let stepped = changes.len();
+		for (v, state) in changes { out.set(v, state); }
+		(out, stepped)
+	}
+
+	/// Moves the automaton's own grid on one generation, reusing memory instead of building a whole new grid wherever it can.
+	/// The backends that tick whole rows at once write into the grid from the generation before last, and everything else gets changed in place.
+	fn step_in_place(&mut self, key: u64) {
+		if self.incremental_step() { return; }
+		let spare = self.spare.0.take();
+
+		match self.bulk_step(&self.cells, spare) {
+			Some(next) => {
+				self.profile.stepped(self.bounds.x * self.bounds.y);
+				self.spare.0 = Some(std::mem::replace(&mut self.cells, next));
+			},
+			None => {
+				let changes = self.changes(&self.cells, Some(key));
+				self.profile.stepped(changes.len());
+				for (v, state) in changes {
+					if self.cells.get(&v) != state { self.cells.set(v, state); }
 				}
-			} else {
-				// cell is dying
-				*s -= 1;
+				self.cells.unsettle();
 			}
-		});
+		}
+
+		self.profile.swapped();
 	}
 
-	/// Get a copy of the automaton's internal state (the cells).
-	pub fn get_cells(&self) -> HashMap<Vec2, u8> {
-		self.cells.clone()
+	/// Ticks only the cells whose state or neighbor count changed since last tick, if the rules only count neighbors and the grid keeps its counts up to date.
+	/// Gives back whether it did, since otherwise the grid has to be ticked some other way.
+	fn incremental_step(&mut self) -> bool {
+		let max = self.offsets.len() as u8;
+		let uniform = self.regions.is_empty() && self.birth_field.is_none() && self.survival_field.is_none() && self.edge == 0;
+		let Cells::Incremental(grid, ..) = &mut self.cells else { return false; };
+		let Some(table) = uniform.then(|| self.rules.count_table(max)).flatten() else { return false; };
+
+		// every change gets worked out before any of them are made, so every cell sees the same generation
+		let dirty = grid.take_dirty();
+		let grid = &*grid;
+		let changes: Vec<(usize, u8)> = in_slabs(dirty.len(), self.threads, |slab| dirty[slab].iter().filter_map(|i| {
+			let state = grid.get(*i);
+			let next = table[state as usize * (max as usize + 1) + grid.count(*i) as usize];
+			(next != state).then_some((*i, next))
+		}).collect());
+
+		self.profile.stepped(dirty.len());
+		let width = self.bounds.x;
+		for (i, state) in changes { self.cells.set(Vec2::new(i % width, i / width), state); }
+		self.profile.swapped();
+		true
 	}
-}
\ No newline at end of file
+
+	/// Updates every cell inside the given region one at a time, in whatever order the update scheme says, so each cell sees the ones that went before it.
+	fn step_async(&mut self, region: &Rect2, key: u64) {
+		let mut order: Vec<Vec2> = self.clipped(region).iter_positions().collect();
+
+		if let UpdateScheme::RandomAsync(seed) = self.scheme {
+			let mut rng = Rng::keyed(seed, &[self.generation]);
+			for i in (1..order.len()).rev() { order.swap(i, rng.below(i as u64 + 1) as usize); }
+		}
+
+		// working out the next state of each cell and putting it in place happen together, so it's all stepping
+		let stepped = order.len();
+		for v in order {
+			let state = self.next_state(&self.cells, &self.offsets, &v, Some(key));
+			if self.cells.get(&v) != state { self.cells.set(v, state); }
+		}
+		self.cells.unsettle();
+		self.profile.stepped(stepped);
+	}
+
+	/// Ticks the whole grid a row at a time, if the rules only count neighbors and the backend keeps rows together, writing into the memory of the spare grid if it's got any.
+	/// Gives back nothing if the grid has to be ticked a cell at a time instead, like when a memory-mapped grid can't get a file to tick into.
+	fn bulk_step(&self, cells: &Cells, spare: Option<Cells>) -> Option<Cells> {
+		let offsets = &self.offsets;
+		let max = offsets.len() as u8;
+		let rows = |y: usize| offsets.iter().filter_map(|offset| {
+			let row = y as i64 + offset.y;
+			(row >= 0 && (row as usize) < self.bounds.y).then_some((row as usize, offset.x as i8))
+		}).collect();
+
+		// every slab of rows reads the rows just past its edges (its halo) straight from the last generation, which all the threads share,
+		// so the slabs can be worked out separately, each straight into its own part of the next generation
+		let (width, height) = (self.bounds.x, self.bounds.y);
+
+		let uniform = self.regions.is_empty() && self.birth_field.is_none() && self.survival_field.is_none() && self.edge == 0;
+		let table = uniform.then(|| self.rules.count_table(max)).flatten()?;
+
+		match cells {
+			Cells::Bits(bits) => {
+				let mut words = match spare { Some(Cells::Bits(spare)) => spare.into_words(), _ => Vec::new() };
+				words.resize(width.div_ceil(64) * height, 0);
+				in_slabs_mut(&mut words, width.div_ceil(64), self.threads, |slab, out| bits.step(&table, max as usize + 1, slab, rows, out));
+				Some(Cells::Bits(Bits::from_words(width, words)))
+			},
+			Cells::Dense(dense) => {
+				let mut next = dense.next_grid(match spare { Some(Cells::Dense(spare)) => Some(spare), _ => None })?;
+				in_slabs_mut(next.as_mut_slice(), width, self.threads, |slab, out| dense.step(&table, max as usize + 1, self.rules.counted_states(), slab, rows, out));
+				Some(Cells::Dense(next))
+			},
+			_ => None
+		}
+	}
+
+	/// Works out the next state of every cell of the given grid that could change, one cell at a time.
+	fn changes(&self, cells: &Cells, key: Option<u64>) -> Vec<(Vec2, u8)> {
+		let offsets = &self.offsets;
+		let (width, height) = (self.bounds.x, self.bounds.y);
+
+		let next = |v: &Vec2| self.next_state(cells, offsets, v, key);
+		let every_cell = |slab: Range<usize>| slab.flat_map(|y| (0..width).map(move |x| Vec2::new(x, y))).map(|v| { let state = next(&v); (v, state) }).collect();
+
+		// only cells next to something can change, unless the rules bring empty space to life or the edge isn't dead
+		if self.edge > 0 || std::iter::once(&self.rules).chain(self.regions.iter().map(|(_, rules)| rules)).any(|rules| rules.next_state_from(0, offsets.iter().map(|_| 0), 0.0) != 0) {
+			in_slabs(height, self.threads, every_cell)
+		} else {
+			let candidates: Vec<Vec2> = cells.live().into_keys()
+				.flat_map(|v| offsets.iter().filter_map(move |offset| v.checked_add_offset(offset)).chain(std::iter::once(v)))
+				.filter(|v| v.x < width && v.y < height)
+				.collect::<HashSet<Vec2>>()
+				.into_iter()
+				.collect();
+
+			in_slabs(candidates.len(), self.threads, |slab| candidates[slab].iter().map(|v| (*v, next(v))).collect())
+		}
+	}
+
+	/// Works out what the rules say a single cell of the given grid turns into.
+	fn next_state(&self, cells: &Cells, offsets: &[IVec2], v: &Vec2, key: Option<u64>) -> u8 {
+		// out-of-bounds neighbors are in the edge state, so every neighbor stays in the same spot
+		let neighbors = offsets.iter()
+			.map(|offset| v.checked_add_offset(offset).filter(|n| n.x < self.bounds.x && n.y < self.bounds.y).map_or(self.edge, |neighbor| cells.get(&neighbor)));
+
+		let rules = self.get_rules_at(v);
+		let scale = (self.birth_field.as_ref().map_or(1.0, |f| f.at(v)), self.survival_field.as_ref().map_or(1.0, |f| f.at(v)));
+		let fielded = self.birth_field.is_some() || self.survival_field.is_some();
+
+		let roll = match key {
+			Some(key) if rules.is_stochastic() || fielded => Rng::keyed(key, &[v.x as u64, v.y as u64]).next_f64(),
+			_ => 0.0
+		};
+
+		rules.next_state_scaled(cells.get(v), neighbors, roll, scale)
+	}
+
+	/// Takes the generation before away from the given next generation, for second-order mode.
+	fn second_order(&self, next: Cells, previous: &Cells) -> Cells {
+		let backend = next.backend();
+		Cells::from_map(backend, &self.bounds, &self.rules, self.rules.second_order(next.into_map(), &previous.clone().into_map()))
+	}
+
+	/// Seeds the random number generator used by stochastic rules. Automata start out seeded with 0.
+	/// Two automata with the same rules, cells, and seed will always tick the same way.
+	pub fn seed_rng(&mut self, seed: u64) {
+		self.rng = Rng::new(seed);
+	}
+
+	/// Get a copy of the automaton's internal state (the cells).
+	/// Every cell in the grid is included whichever backend it's kept in, so on huge grids you'll want `live_cells_in` instead.
+	/// Hash maps come out in a different order every run, so use `live_cells_sorted` or `iter_cells` when the order matters.
+	pub fn get_cells(&self) -> HashMap<Vec2, u8> {
+		self.iter_cells().collect()
+	}
+
+	/// Get every live or dying cell inside the given region. With the tree backend, this only looks at the parts of the tree that overlap the region.
+	pub fn live_cells_in(&self, region: &Rect2) -> HashMap<Vec2, u8> {
+		match &self.cells {
+			Cells::Map(map) => map.iter().filter(|(v, s)| **s > 0 && region.contains(v)).map(|(v, s)| (*v, *s)).collect(),
+			Cells::Tree(tree) => tree.query(&[region.min.x, region.min.y], &[region.max.x, region.max.y]).into_iter().map(|([x, y], s)| (Vec2::new(x, y), s)).collect(),
+			_ => self.cells.live().into_iter().filter(|(v, _)| region.contains(v)).collect()
+		}
+	}
+
+	/// Get every cell inside the given region, dead ones included, which is handy for drawing just the part of the grid that's on screen.
+	/// Only the part of the region that's inside the grid is included.
+	pub fn get_cells_in(&self, region: &Rect2) -> HashMap<Vec2, u8> {
+		self.clipped(region).iter_positions().map(|v| { let state = self.cells.get(&v); (v, state) }).collect()
+	}
+
+	/// Shrinks the grid down by `factor` in each direction for drawing a minimap, giving back a block for every `factor` by `factor` square of cells, row by row.
+	/// Each block has its position in the shrunken grid and how much of it is live or dying, from 0 to 1. Blocks along the far edges can be cut off by the bounds,
+	/// and only count the cells they actually cover. This only goes through the live and dying cells, without building a map of every cell first.
+	/// 
+	/// ```
+	/// # use cellular_snapp::{presets, flat::{Automaton, Rect2, Vec2}};
+	/// let mut life = Automaton::new(presets::life(), Vec2::new(6, 4), Vec::new()).unwrap();
+	/// life.fill_region(&Rect2::new(Vec2::new(0, 0), Vec2::new(2, 1)), 1);
+	/// life.set_cell(Vec2::new(5, 3), 1);
+	/// 
+	/// let minimap = life.downsample(4);
+	/// assert_eq!(minimap, vec![(Vec2::new(0, 0), 0.125), (Vec2::new(1, 0), 0.125)]);
+	/// ```
+	pub fn downsample(&self, factor: usize) -> Vec<(Vec2, f32)> {
+		let factor = factor.max(1);
+		let (width, height) = (self.bounds.x.div_ceil(factor), self.bounds.y.div_ceil(factor));
+
+		let mut live = vec![0usize; width * height];
+		for v in self.cells.live().into_keys() { live[v.y / factor * width + v.x / factor] += 1; }
+
+		live.into_iter().enumerate().map(|(i, count)| {
+			let block = Vec2::new(i % width, i / width);
+			let cells = (self.bounds.x - block.x * factor).min(factor) * (self.bounds.y - block.y * factor).min(factor);
+			(block, count as f32 / cells as f32)
+		}).collect()
+	}
+
+	/// Get the part of a region that's inside the grid, which might not have anything in it.
+	fn clipped(&self, region: &Rect2) -> Rect2 {
+		region.intersect(&Rect2::new(Vec2::new(0, 0), self.bounds)).unwrap_or_default()
+	}
+
+	/// Get every live and dying cell, sorted row by row (by y, and then x), which is the order the grid is laid out in. Unlike the hash maps the other getters give back,
+	/// this comes out in the same order every time, so it's the one to use for exporting cells, hashing or comparing runs in tests, or adding anything up where the order matters.
+	/// 
+	/// ```
+	/// # use cellular_snapp::{presets, flat::{Automaton, Vec2}};
+	/// let life = Automaton::new(presets::life(), Vec2::new(8, 8), vec![Vec2::new(1, 2), Vec2::new(3, 1)]).unwrap();
+	/// assert_eq!(life.live_cells_sorted(), vec![(Vec2::new(3, 1), 1), (Vec2::new(1, 2), 1)]);
+	/// ```
+	pub fn live_cells_sorted(&self) -> Vec<(Vec2, u8)> {
+		let mut live: Vec<(Vec2, u8)> = self.cells.live().into_iter().collect();
+		live.sort_unstable_by_key(|(v, _)| (v.y, v.x));
+		live
+	}
+
+	/// Goes through every cell in the grid, dead ones included, row by row, without building a hash map of the whole grid first.
+	pub fn iter_cells(&self) -> impl Iterator<Item = (Vec2, u8)> + '_ {
+		(0..self.bounds.y).flat_map(move |y| (0..self.bounds.x).map(move |x| Vec2::new(x, y))).map(move |v| (v, self.cells.get(&v)))
+	}
+
+	/// Goes through every cell in the grid like `iter_cells`, except with what each cell's state means instead of the state itself.
+	pub fn iter_cell_views(&self) -> impl Iterator<Item = (Vec2, CellView)> + '_ {
+		self.iter_cells().map(move |(v, state)| (v, self.rules.view(state)))
+	}
+
+	/// Get the smallest and largest corners of the box around every live and dying cell, which are both inside the box (unlike a `Rect2`),
+	/// or `None` if every cell is dead. This is handy for keeping a camera on something that's growing.
+	/// 
+	/// ```
+	/// # use cellular_snapp::{presets, flat::{Automaton, Vec2}};
+	/// let mut life = Automaton::new(presets::life(), Vec2::new(32, 32), presets::glider().translate(Vec2::new(4, 4)).positions()).unwrap();
+	/// assert_eq!(life.live_bounds(), Some((Vec2::new(4, 4), Vec2::new(6, 6))));
+	/// 
+	/// // gliders move one cell down and right every 4 ticks
+	/// let start = life.center_of_mass().unwrap();
+	/// for _ in 0..8 { life.tick(); }
+	/// let end = life.center_of_mass().unwrap();
+	/// assert_eq!((end.0 - start.0, end.1 - start.1), (2.0, 2.0));
+	/// ```
+	pub fn live_bounds(&self) -> Option<(Vec2, Vec2)> {
+		let live = self.cells.live();
+		let mut live = live.keys();
+		let first = *live.next()?;
+
+		Some(live.fold((first, first), |(min, max), v| {
+			(Vec2::new(min.x.min(v.x), min.y.min(v.y)), Vec2::new(max.x.max(v.x), max.y.max(v.y)))
+		}))
+	}
+
+	/// Get the average position of every live and dying cell, each counting the same, or `None` if every cell is dead.
+	/// Measuring how far this moves is an easy way to see how fast a spaceship is going.
+	pub fn center_of_mass(&self) -> Option<(f64, f64)> {
+		let live = self.cells.live();
+		if live.is_empty() { return None; }
+
+		// adding up whole numbers gives exactly the same answer whatever order the cells come in
+		let (x, y) = live.keys().fold((0u128, 0u128), |(x, y), v| (x + v.x as u128, y + v.y as u128));
+		let count = live.len() as f64;
+		Some((x as f64 / count, y as f64 / count))
+	}
+
+	/// Get a hash of every live and dying cell, which is the same for the same cells no matter the backend, the platform, or the version of Rust.
+	/// Two automata with the same cells always hash the same, so this is handy for spotting cycles or throwing out soups that have already turned up.
+	pub fn state_hash(&self) -> u64 {
+		hash_cells(self.cells.live().into_iter().map(|(v, s)| ([v.x as u64, v.y as u64], s)))
+	}
+
+	/// Get a hash of every live and dying cell like `state_hash`, except the cells are moved so their bounding box starts at the origin first.
+	/// That way, the same pattern hashes the same wherever it is in the grid.
+	/// 
+	/// ```
+	/// # use cellular_snapp::{presets, flat::{Automaton, Vec2}};
+	/// let here = Automaton::new(presets::life(), Vec2::new(32, 32), presets::glider().positions()).unwrap();
+	/// let mut there = Automaton::new(presets::life(), Vec2::new(32, 32), presets::glider().translate(Vec2::new(10, 3)).positions()).unwrap();
+	/// assert_ne!(here.state_hash(), there.state_hash());
+	/// assert_eq!(here.normalized_state_hash(), there.normalized_state_hash());
+	/// 
+	/// // a glider is back in the same shape every 4 ticks, just moved over
+	/// for _ in 0..4 { there.tick(); }
+	/// assert_eq!(here.normalized_state_hash(), there.normalized_state_hash());
+	/// ```
+	pub fn normalized_state_hash(&self) -> u64 {
+		let live = self.cells.live();
+		let min = Vec2::new(live.keys().map(|v| v.x).min().unwrap_or(0), live.keys().map(|v| v.y).min().unwrap_or(0));
+		hash_cells(live.into_iter().map(|(v, s)| ([(v.x - min.x) as u64, (v.y - min.y) as u64], s)))
+	}
+
+	/// Finds every spot in the grid where the pattern shows up, giving the top-left corner of the pattern's bounding box at each one.
+	/// Every cell in the pattern has to match (cells without their own state have to be fully alive), and every other cell inside the bounding box has to be dead,
+	/// so a block won't turn up in the middle of a bigger blob.
+	/// 
+	/// ```
+	/// # use cellular_snapp::{presets, flat::{Automaton, Pattern, Vec2}};
+	/// let gliders = presets::glider().positions().into_iter().flat_map(|v| [v + Vec2::new(2, 2), v + Vec2::new(20, 10)]).collect();
+	/// let life = Automaton::new(presets::life(), Vec2::new(32, 32), gliders).unwrap();
+	/// assert_eq!(life.find_pattern(&presets::glider()), vec![Vec2::new(2, 2), Vec2::new(20, 10)]);
+	/// ```
+	pub fn find_pattern(&self, pattern: &Pattern) -> Vec<Vec2> {
+		let Some((min, max)) = pattern.corners() else { return Vec::new() };
+		let max_state = self.rules.cell_states - 1;
+		let wanted: HashMap<Vec2, u8> = pattern.cells.iter().map(|(v, s)| (*v - min, s.map_or(max_state, |s| s.min(max_state)))).collect();
+		let size = max - min;
+
+		// every match has to line up one of the pattern's live cells with a cell in the grid, so only those spots need checking
+		let Some((anchor, state)) = wanted.iter().filter(|(_, s)| **s > 0).min() else { return Vec::new() };
+
+		let mut found: Vec<Vec2> = self.cells.live().into_iter()
+			.filter(|(v, s)| s == state && v.x >= anchor.x && v.y >= anchor.y)
+			.map(|(v, _)| v - *anchor)
+			.filter(|corner| corner.x + size.x < self.bounds.x && corner.y + size.y < self.bounds.y)
+			.filter(|corner| (0..=size.y).all(|y| (0..=size.x).all(|x| {
+				let v = Vec2::new(x, y);
+				self.cells.get(&(*corner + v)) == wanted.get(&v).copied().unwrap_or(0)
+			})))
+			.collect();
+
+		found.sort_by_key(|v| (v.y, v.x));
+		found
+	}
+
+	/// Finds every spot in the grid where the pattern shows up like `find_pattern`, in any of its 8 rotations and reflections.
+	/// Symmetric patterns that match the same spot more than one way are only listed once.
+	pub fn find_pattern_any_orientation(&self, pattern: &Pattern) -> Vec<Vec2> {
+		let mut found = Vec::new();
+		let mut turned = pattern.clone();
+
+		for _ in 0..4 {
+			found.extend(self.find_pattern(&turned));
+			found.extend(self.find_pattern(&turned.mirror_x()));
+			turned = turned.rotate90();
+		}
+
+		found.sort_by_key(|v| (v.y, v.x));
+		found.dedup();
+		found
+	}
+
+	/// Finds every group of live and dying cells that are connected to each other, where two cells count as connected if they're neighbors by the given method
+	/// (so `Method::VonNeumann` only connects cells that share a face, and `Method::Moore` connects them at the corners too).
+	/// Groups come biggest first, and the cells in each group are sorted by y and then x.
+	/// 
+	/// ```
+	/// # use cellular_snapp::{presets, Method, flat::{Automaton, Vec2}};
+	/// // a line of three cells, and a pair of cells that only touch at a corner
+	/// let cells = vec![Vec2::new(0, 0), Vec2::new(1, 0), Vec2::new(2, 0), Vec2::new(5, 5), Vec2::new(6, 6)];
+	/// let grid = Automaton::new(presets::life(), Vec2::new(8, 8), cells).unwrap();
+	/// 
+	/// assert_eq!(grid.components(&Method::Moore).len(), 2);
+	/// assert_eq!(grid.components(&Method::VonNeumann).len(), 3);
+	/// assert_eq!(grid.components(&Method::Moore)[0], vec![Vec2::new(0, 0), Vec2::new(1, 0), Vec2::new(2, 0)]);
+	/// ```
+	pub fn components(&self, method: &Method) -> Vec<Vec<Vec2>> {
+		let offsets = neighbor_offsets(method);
+		let live: HashSet<Vec2> = self.cells.live().into_keys().collect();
+		let mut seen = HashSet::new();
+		let mut components = Vec::new();
+
+		for start in &live {
+			if !seen.insert(*start) { continue; }
+
+			// flood out from the first cell of every group that hasn't been found yet
+			let mut stack = vec![*start];
+			let mut component = Vec::new();
+
+			while let Some(v) = stack.pop() {
+				component.push(v);
+
+				for n in offsets.iter().filter_map(|offset| v.checked_add_offset(offset)) {
+					if live.contains(&n) && seen.insert(n) { stack.push(n); }
+				}
+			}
+
+			component.sort_by_key(|v| (v.y, v.x));
+			components.push(component);
+		}
+
+		components.sort_by(|a, b| b.len().cmp(&a.len()).then_with(|| (a[0].y, a[0].x).cmp(&(b[0].y, b[0].x))));
+		components
+	}
+
+	/// Finds every group of connected live and dying cells just like `components`, but only gives back how many cells are in each one and the box around it.
+	pub fn component_boxes(&self, method: &Method) -> Vec<(usize, Rect2)> {
+		self.components(method).into_iter().map(|component| {
+			let (mut min, mut max) = (component[0], component[0]);
+
+			for v in &component {
+				min = Vec2::new(min.x.min(v.x), min.y.min(v.y));
+				max = Vec2::new(max.x.max(v.x), max.y.max(v.y));
+			}
+
+			(component.len(), Rect2::new(min, Vec2::new(max.x + 1, max.y + 1)))
+		}).collect()
+	}
+
+	/// Get which backend the automaton keeps its cells in.
+	pub fn get_backend(&self) -> Backend {
+		self.cells.backend()
+	}
+
+	/// Get the rules the automaton is currently following.
+	pub fn get_rules(&self) -> &AutomataRules {
+		&self.rules
+	}
+
+	/// Get the bounds (the size of the grid) of the automaton.
+	pub fn get_bounds(&self) -> &Vec2 {
+		&self.bounds
+	}
+
+	/// Get how many cells wide (along x) the grid is.
+	pub fn width(&self) -> usize {
+		self.bounds.x
+	}
+
+	/// Get how many cells tall (along y) the grid is.
+	pub fn height(&self) -> usize {
+		self.bounds.y
+	}
+
+	/// Get the state of every cell as one flat slice of bytes, or `None` if the automaton isn't on the dense backend.
+	/// Cells are laid out a row at a time with x going fastest, so the cell at `(x, y)` is at `y * width + x`.
+	/// That's exactly the layout of a single-channel texture, so renderers can copy the whole grid over in one go.
+	/// 
+	/// ```
+	/// # use cellular_snapp::{AutomataRules, Backend, Method, flat::{Automaton, Vec2}};
+	/// let life = Automaton::with_backend(AutomataRules::new(2..=3, 3, 2, Method::Moore), Vec2::new(4, 3), vec![Vec2::new(1, 2)], Backend::Dense).unwrap();
+	/// let cells = life.as_slice().unwrap();
+	/// 
+	/// assert_eq!(cells.len(), life.width() * life.height());
+	/// assert_eq!(cells[2 * life.width() + 1], 1);
+	/// ```
+	pub fn as_slice(&self) -> Option<&[u8]> {
+		match &self.cells {
+			Cells::Dense(dense) => Some(dense.as_slice()),
+			_ => None
+		}
+	}
+
+	/// Like `as_slice`, except the states can be changed in place.
+	/// Nothing gets checked, so every state written has to be one the rules have (or ticking might panic), and species and ages don't hear about the changes.
+	pub fn as_mut_slice(&mut self) -> Option<&mut [u8]> {
+		match &mut self.cells {
+			Cells::Dense(dense) => Some(dense.as_mut_slice()),
+			_ => None
+		}
+	}
+
+	/// Takes a snapshot of every cell, for handing over to another thread (like a renderer) without holding onto the automaton.
+	/// This copies the whole grid, which is a single copy on the dense backend, and only the cells that aren't dead need writing in on the others.
+	pub fn frame(&self) -> Arc<Frame> {
+		let cells = match &self.cells {
+			Cells::Dense(dense) => dense.as_slice().to_vec(),
+			cells => {
+				let mut states = vec![0; self.bounds.x * self.bounds.y];
+				for (v, s) in cells.live() { states[v.y * self.bounds.x + v.x] = s; }
+				states
+			}
+		};
+
+		Arc::new(Frame { bounds: self.bounds, generation: self.generation, cells })
+	}
+
+	/// Get the state of a single cell, or `None` if it's outside the grid.
+	pub fn get_cell(&self, v: &Vec2) -> Option<u8> {
+		(v.x < self.bounds.x && v.y < self.bounds.y).then(|| self.cells.get(v))
+	}
+
+	/// Get what a single cell's state means (whether it's dead, alive, or dying), or `None` if it's outside the grid.
+	/// 
+	/// ```
+	/// # use cellular_snapp::{presets, CellView, flat::{Automaton, Vec2}};
+	/// let mut brain = Automaton::new(presets::brians_brain(), Vec2::new(5, 5), vec![Vec2::new(2, 2)]).unwrap();
+	/// brain.tick();
+	/// assert_eq!(brain.get_cell_view(&Vec2::new(2, 2)), Some(CellView::Dying { remaining: 1 }));
+	/// assert_eq!(brain.get_cell_view(&Vec2::new(9, 9)), None);
+	/// ```
+	pub fn get_cell_view(&self, v: &Vec2) -> Option<CellView> {
+		self.get_cell(v).map(|state| self.rules.view(state))
+	}
+
+	/// Sets the state of a single cell. States too big for the rules are clamped to alive, and cells outside the grid are ignored.
+	pub fn set_cell(&mut self, v: Vec2, state: u8) {
+		if self.walls.contains_key(&v) { return; }
+		let max_state = self.rules.cell_states - 1;
+		if state == 0 { self.species.remove(&v); }
+
+		if v.x < self.bounds.x && v.y < self.bounds.y {
+			let (from, to) = (self.cells.get(&v), state.min(max_state));
+			if from != to { self.log(Event::Edit { generation: self.generation, position: [v.x, v.y, 0], from, to }); }
+			self.cells.set(v, to);
+
+			// cells being set alive are newborns, and live cells being set to another live state keep their age
+			if let Some(ages) = &mut self.ages {
+				if state == 0 { ages.remove(&v); } else { ages.entry(v).or_insert(0); }
+			}
+
+			// cells being killed were still alive this generation, so their trail starts on the next tick
+			if let Some(trails) = &mut self.trails {
+				if state > 0 { trails.insert(v, 0); }
+			}
+		}
+	}
+
+	/// Gives memory back after lots of cells have died. The hash map backend only keeps live and dying cells, but hash maps never shrink on their own,
+	/// so a grid that was mostly alive once holds onto that memory until this shrinks it down to fit (along with the species, ages, trails, and heatmap).
+	/// This also throws away the spare grid that the bit-packed and dense backends tick into, which just gets made again on the next tick.
+	/// 
+	/// ```
+	/// # use cellular_snapp::{presets, flat::{Automaton, Rect2, Vec2}};
+	/// let mut life = Automaton::new(presets::life(), Vec2::new(64, 64), Vec::new()).unwrap();
+	/// life.fill_region(&Rect2::new(Vec2::new(0, 0), Vec2::new(64, 64)), 1);
+	/// 
+	/// // everything dies of overcrowding but the corners, and dead cells are just left out
+	/// life.tick();
+	/// life.compact();
+	/// assert_eq!(life.get_cell(&Vec2::new(32, 32)), Some(0));
+	/// assert_eq!(life.live_cells_sorted().len(), 4);
+	/// ```
+	pub fn compact(&mut self) {
+		for cells in std::iter::once(&mut self.cells).chain(self.previous.iter_mut()) { cells.compact(); }
+		self.spare.0 = None;
+		self.species.shrink_to_fit();
+		if let Some(ages) = &mut self.ages { ages.shrink_to_fit(); }
+		if let Some(trails) = &mut self.trails { trails.shrink_to_fit(); }
+		if let Some((_, heat)) = &mut self.heat { heat.shrink_to_fit(); }
+	}
+
+	/// Turns a cell into a wall, which the rules never change. Live walls get set fully alive and dead walls get killed,
+	/// while excluded walls keep the state they had. Cells outside the grid are ignored.
+	/// 
+	/// Walls can't be changed with `set_cell` (or anything that uses it, like `paste`) until they're taken away again.
+	/// They get saved in checkpoints and replays.
+	/// 
+	/// ```
+	/// # use cellular_snapp::{presets, Wall, flat::{Automaton, Vec2}};
+	/// let blinker = vec![Vec2::new(1, 2), Vec2::new(2, 2), Vec2::new(3, 2)];
+	/// let mut walled = Automaton::new(presets::life(), Vec2::new(5, 5), blinker).unwrap();
+	/// let mut hidden = walled.clone();
+	/// 
+	/// // a live wall keeps the blinker going, even though its end never flips over
+	/// walled.set_wall(Vec2::new(3, 2), Wall::Alive);
+	/// walled.tick();
+	/// assert_eq!(walled.get_cell(&Vec2::new(2, 1)), Some(1));
+	/// assert_eq!(walled.get_cell(&Vec2::new(3, 2)), Some(1));
+	/// 
+	/// // but the rest of the blinker can't see an excluded wall, so it dies out around it
+	/// hidden.set_wall(Vec2::new(3, 2), Wall::Excluded);
+	/// hidden.tick();
+	/// assert_eq!(hidden.get_cell(&Vec2::new(2, 2)), Some(0));
+	/// assert_eq!(hidden.get_cell(&Vec2::new(3, 2)), Some(1));
+	/// ```
+	pub fn set_wall(&mut self, v: Vec2, wall: Wall) {
+		let Some(state) = self.get_cell(&v) else { return; };
+		let state = match wall { Wall::Alive => self.rules.cell_states - 1, Wall::Dead => 0, Wall::Excluded => state };
+
+		self.walls.remove(&v);
+		self.set_cell(v, state);
+		self.walls.insert(v, (wall, state));
+	}
+
+	/// Takes a wall away, so the cell there follows the rules again.
+	pub fn remove_wall(&mut self, v: &Vec2) {
+		self.walls.remove(v);
+	}
+
+	/// Takes away every wall.
+	pub fn clear_walls(&mut self) {
+		self.walls.clear();
+	}
+
+	/// Get what kind of wall a cell is, or `None` if it isn't one.
+	pub fn get_wall(&self, v: &Vec2) -> Option<Wall> {
+		self.walls.get(v).map(|(wall, _)| *wall)
+	}
+
+	/// Sets the state every cell past the edge of the grid is in, as far as the cells along the edge are concerned. Edges start out dead.
+	/// Some rules act completely differently with a live border around them, like vote rules, which fill in from a live edge instead of eating away at it.
+	/// States too big for the rules are clamped to alive.
+	/// 
+	/// Grids with an edge that isn't dead can't be ticked a row at a time, or only near live cells, so every cell gets ticked one at a time instead.
+	/// The edge state gets saved in checkpoints and replays.
+	/// 
+	/// ```
+	/// # use cellular_snapp::{presets, flat::{Automaton, Vec2}};
+	/// let mut life = Automaton::new(presets::life(), Vec2::new(5, 5), Vec::new()).unwrap();
+	/// life.set_edge_state(1);
+	/// life.tick();
+	/// 
+	/// // cells along the side have 3 live neighbors past the edge, so they're born, but corners have 5
+	/// assert_eq!(life.get_cell(&Vec2::new(2, 0)), Some(1));
+	/// assert_eq!(life.get_cell(&Vec2::new(0, 0)), Some(0));
+	/// assert_eq!(life.get_cell(&Vec2::new(2, 2)), Some(0));
+	/// ```
+	pub fn set_edge_state(&mut self, state: u8) {
+		self.edge = state.min(self.rules.cell_states - 1);
+	}
+
+	/// Get the state every cell past the edge of the grid is in.
+	pub fn get_edge_state(&self) -> u8 {
+		self.edge
+	}
+
+	/// Get the species of every live or dying cell. Cells start out as species 0 unless you say otherwise.
+	pub fn get_species(&self) -> HashMap<Vec2, u8> {
+		self.cells.live().into_keys().map(|v| { let species = self.species.get(&v).copied().unwrap_or(0); (v, species) }).collect()
+	}
+
+	/// Sets the species of a single live or dying cell. Species too big for the rules are clamped to the last one, and dead cells are ignored.
+	pub fn set_species(&mut self, v: Vec2, species: u8) {
+		if self.cells.get(&v) > 0 {
+			self.species.insert(v, species.min(self.rules.species - 1));
+		}
+	}
+
+	/// Swaps out the automaton's rules while keeping the current grid.
+	/// The new rules are checked against the neighbor method and the backend just like in `with_backend`, and the automaton is left untouched if they don't fit.
+	/// 
+	/// If the amount of cell states changes, live cells stay alive and dying cells are clamped so they're still dying. This also stops any rule schedule.
+	/// 
+	/// Rule regions have to keep fitting, so the amount of cell states and the neighbor method can't change while there are any.
+	pub fn set_rules(&mut self, rules: AutomataRules) -> Result<(), AutomataError> {
+		self.swap_rules(rules)?;
+		self.schedule = None;
+		Ok(())
+	}
+
+	/// Checks whether the automaton could switch over to the given rules.
+	fn check_rules(&self, rules: &AutomataRules) -> Result<(), AutomataError> {
+		rules.validate(max_neighbors(&rules.neighbor_method)?, 2)?;
+		rules.check_backend(self.cells.backend())?;
+
+		if !self.regions.is_empty() && (rules.cell_states != self.rules.cell_states || rules.neighbor_method != self.rules.neighbor_method) {
+			return Err(AutomataError::MismatchedRules(rules.to_string()));
+		}
+
+		Ok(())
+	}
+
+	/// Swaps out the rules like `set_rules`, without touching the rule schedule.
+	fn swap_rules(&mut self, rules: AutomataRules) -> Result<(), AutomataError> {
+		self.check_rules(&rules)?;
+
+		for cells in std::iter::once(&mut self.cells).chain(self.previous.iter_mut()) {
+			cells.map_states(|s| rules.carry_state(&self.rules, s));
+			cells.recount(&rules);
+		}
+
+		for species in self.species.values_mut() {
+			*species = (*species).min(rules.species - 1);
+		}
+
+		self.edge = rules.carry_state(&self.rules, self.edge);
+
+		self.offsets = neighbor_offsets(&rules.neighbor_method);
+		self.rules = rules;
+		self.log(Event::RulesChanged { generation: self.generation, rules: self.rules.to_string() });
+		Ok(())
+	}
+
+	/// Makes the automaton follow a repeating schedule of rules instead of the same rules every tick, where each set of rules in the list gets `ticks_each` ticks in a row before the next one takes over.
+	/// The tick from generation `g` uses `rules[(g / ticks_each) % rules.len()]`, so with two sets of rules and a `ticks_each` of 1, the first ticks even generations and the second ticks odd ones.
+	/// 
+	/// Every set of rules in the schedule gets checked the same way `set_rules` checks them, and the schedule is left alone if any of them don't fit.
+	/// The rules swap over at the start of each tick, just like with `set_rules`, so the automaton's rules are whichever ones made the last tick.
+	/// An empty list (or a `ticks_each` of 0) stops the schedule.
+	/// 
+	/// ```
+	/// # use cellular_snapp::{presets, flat::{Automaton, Vec2}};
+	/// // Life on even ticks and Seeds on odd ones
+	/// let mut a = Automaton::new(presets::life(), Vec2::new(16, 16), presets::glider().positions()).unwrap();
+	/// a.set_rule_schedule(vec![presets::life(), presets::seeds()], 1).unwrap();
+	/// 
+	/// let mut b = Automaton::new(presets::life(), Vec2::new(16, 16), presets::glider().positions()).unwrap();
+	/// for generation in 0..6 {
+	///     a.tick();
+	///     b.set_rules(if generation % 2 == 0 { presets::life() } else { presets::seeds() }).unwrap();
+	///     b.tick();
+	/// }
+	/// 
+	/// assert_eq!(a.live_cells_sorted(), b.live_cells_sorted());
+	/// assert_eq!(a.get_rules(), &presets::seeds());
+	/// ```
+	pub fn set_rule_schedule(&mut self, rules: Vec<AutomataRules>, ticks_each: usize) -> Result<(), AutomataError> {
+		if rules.is_empty() || ticks_each == 0 {
+			self.schedule = None;
+			return Ok(());
+		}
+
+		for r in &rules { self.check_rules(r)?; }
+
+		self.schedule = Some((rules, ticks_each));
+		Ok(())
+	}
+
+	/// Get the rule schedule and how many ticks each set of rules in it gets, or `None` if the automaton just follows its own rules.
+	pub fn get_rule_schedule(&self) -> Option<(&[AutomataRules], usize)> {
+		self.schedule.as_ref().map(|(rules, each)| (rules.as_slice(), *each))
+	}
+
+	/// Swaps in the scheduled rules for the tick that makes the given generation, if there's a schedule and they aren't already in.
+	fn follow_schedule(&mut self, generation: u64) {
+		let Some((rules, each)) = &self.schedule else { return; };
+		let next = &rules[(generation / *each as u64 % rules.len() as u64) as usize];
+
+		// every set of rules in the schedule was checked when it was set, so swapping can't fail
+		if *next != self.rules { let _ = self.swap_rules(next.clone()); }
+	}
+
+	/// Makes the cells inside the given region follow different rules from the rest of the grid, like a Life region bordering a Seeds region.
+	/// Regions added later go on top of earlier ones where they overlap, and cells outside every region follow the automaton's own rules.
+	/// 
+	/// Every cell counts all of its neighbors, even the ones across a region's border, and then decides what to do with its own region's rules.
+	/// For that to make sense, the region's rules need the same amount of cell states and the same neighbor method as the automaton's own rules,
+	/// and the automaton is left untouched if they don't. Species and second-order mode always go by the automaton's own rules.
+	/// 
+	/// Whole-row ticking on the bit-packed and dense backends can only use one rule, so ticks fall back to going a cell at a time while there are any regions.
+	/// 
+	/// ```
+	/// # use cellular_snapp::{presets, flat::{Automaton, Rect2, Vec2}};
+	/// // a Life blinker on the left, and the same three cells in Seeds on the right
+	/// let cells = vec![Vec2::new(2, 4), Vec2::new(3, 4), Vec2::new(4, 4), Vec2::new(12, 4), Vec2::new(13, 4), Vec2::new(14, 4)];
+	/// let mut a = Automaton::new(presets::life(), Vec2::new(16, 9), cells).unwrap();
+	/// a.add_rule_region(Rect2::new(Vec2::new(8, 0), Vec2::new(16, 9)), presets::seeds()).unwrap();
+	/// a.tick();
+	/// 
+	/// // the blinker flipped over, and every Seeds cell died while giving birth above and below
+	/// assert_eq!(a.get_cell(&Vec2::new(3, 3)), Some(1));
+	/// assert_eq!(a.get_cell(&Vec2::new(13, 4)), Some(0));
+	/// assert_eq!(a.get_cell(&Vec2::new(12, 3)), Some(1));
+	/// ```
+	pub fn add_rule_region(&mut self, region: Rect2, rules: AutomataRules) -> Result<(), AutomataError> {
+		rules.validate(max_neighbors(&rules.neighbor_method)?, 2)?;
+		rules.check_backend(self.cells.backend())?;
+
+		if rules.cell_states != self.rules.cell_states || rules.neighbor_method != self.rules.neighbor_method {
+			return Err(AutomataError::MismatchedRules(rules.to_string()));
+		}
+
+		self.regions.push((region, rules));
+		Ok(())
+	}
+
+	/// Takes away every rule region, so the whole grid follows the automaton's own rules again.
+	pub fn clear_rule_regions(&mut self) {
+		self.regions.clear();
+	}
+
+	/// Get the rules a cell follows, which are the rules of the topmost region it's in, or the automaton's own rules if it isn't in any.
+	pub fn get_rules_at(&self, v: &Vec2) -> &AutomataRules {
+		self.regions.iter().rev().find(|(region, _)| region.contains(v)).map_or(&self.rules, |(_, rules)| rules)
+	}
+
+	/// Sets a field that the birth chance gets multiplied by at every cell, or takes it away with `None`.
+	/// Where the field is 0 nothing is born at all, and anything over 1 makes births more likely, though never more than certain.
+	/// 
+	/// Fields make every rule roll for births and survival like stochastic rules do, so ticks go a cell at a time while there are any.
+	/// 
+	/// ```
+	/// # use cellular_snapp::{presets, flat::{self, Automaton, Field, Rect2, Vec2}, random::Rng};
+	/// let seed = flat::random_seed(Vec2::new(64, 16), 0.4, &mut Rng::new(7));
+	/// let mut life = Automaton::new(presets::life(), Vec2::new(64, 16), seed).unwrap();
+	/// 
+	/// // nothing gets born on the left edge, and births come easier further right
+	/// life.set_birth_field(Some(Field::linear(Vec2::new(0, 0), Vec2::new(63, 0), 0.0, 1.0)));
+	/// for _ in 0..10 { life.tick(); }
+	/// 
+	/// let left = life.live_cells_in(&Rect2::new(Vec2::new(0, 0), Vec2::new(16, 16))).len();
+	/// let right = life.live_cells_in(&Rect2::new(Vec2::new(48, 0), Vec2::new(64, 16))).len();
+	/// assert!(left < right);
+	/// ```
+	pub fn set_birth_field(&mut self, field: Option<Field>) {
+		self.birth_field = field;
+	}
+
+	/// Sets a field that the survival chance gets multiplied by at every cell, or takes it away with `None`. This works just like `set_birth_field`.
+	pub fn set_survival_field(&mut self, field: Option<Field>) {
+		self.survival_field = field;
+	}
+
+	/// Changes the size of the grid, keeping the given anchor in place.
+	/// New space is filled with dead cells, and any cells that end up outside of the new bounds are thrown away.
+	pub fn resize(&mut self, new_bounds: Vec2, anchor: Anchor) {
+		let shift = (anchor.shift(self.bounds.x, new_bounds.x), anchor.shift(self.bounds.y, new_bounds.y));
+		self.move_cells(new_bounds, shift);
+	}
+
+	/// Shrinks (or grows) the grid so it just fits every live or dying cell, plus the given amount of dead cells as padding on every side.
+	/// If there are no live cells at all, you're left with a grid that's nothing but padding.
+	pub fn crop_to_live_cells(&mut self, padding: usize) {
+		let (min, max) = match self.live_bounds() {
+			Some(corners) => corners,
+			None => {
+				self.move_cells(Vec2::new(padding * 2, padding * 2), (0, 0));
+				return;
+			}
+		};
+
+		let new_bounds = Vec2::new(max.x - min.x + 1 + padding * 2, max.y - min.y + 1 + padding * 2);
+		let shift = (padding as i64 - min.x as i64, padding as i64 - min.y as i64);
+		self.move_cells(new_bounds, shift);
+	}
+
+	/// Rotates the whole grid a quarter turn clockwise (with y pointing down, just like `Pattern::rotate90`), so the width and height swap.
+	/// Species, walls, ages, trails, the heatmap, and rule regions all turn along with the cells, with any part of a rule region outside the grid cut off first.
+	/// Fields stay put, since they only go by position, and so do neighborhoods, so lopsided ones (like hexagonal neighborhoods) tick differently afterwards.
+	/// 
+	/// ```
+	/// # use cellular_snapp::{presets, flat::{Automaton, Vec2}};
+	/// let mut life = Automaton::new(presets::life(), Vec2::new(10, 4), vec![Vec2::new(0, 0), Vec2::new(1, 0)]).unwrap();
+	/// life.rotate90();
+	/// assert_eq!(life.get_bounds(), &Vec2::new(4, 10));
+	/// assert_eq!(life.live_cells_sorted(), vec![(Vec2::new(3, 0), 1), (Vec2::new(3, 1), 1)]);
+	/// 
+	/// life.mirror_x();
+	/// assert_eq!(life.live_cells_sorted(), vec![(Vec2::new(0, 0), 1), (Vec2::new(0, 1), 1)]);
+	/// ```
+	pub fn rotate90(&mut self) {
+		let (width, height) = (self.bounds.x, self.bounds.y);
+		self.remap_cells(Vec2::new(height, width), |v| Vec2::new(height - 1 - v.y, v.x));
+	}
+
+	/// Mirrors the whole grid along the x axis, flipping it left to right. Everything that `rotate90` turns gets flipped too.
+	pub fn mirror_x(&mut self) {
+		let width = self.bounds.x;
+		self.remap_cells(self.bounds, |v| Vec2::new(width - 1 - v.x, v.y));
+	}
+
+	/// Mirrors the whole grid along the y axis, flipping it top to bottom. Everything that `rotate90` turns gets flipped too.
+	pub fn mirror_y(&mut self) {
+		let height = self.bounds.y;
+		self.remap_cells(self.bounds, |v| Vec2::new(v.x, height - 1 - v.y));
+	}
+
+	/// Copies every cell inside the given region into a pattern, dead ones included, with the region's top-left corner as the pattern's origin.
+	/// Only the part of the region that's inside the grid gets copied.
+	pub fn copy_region(&self, region: &Rect2) -> Pattern {
+		let cells = self.get_cells_in(region).into_iter().map(|(v, s)| (v - region.min, s)).collect();
+		Pattern::with_states("", cells)
+	}
+
+	/// Pastes a pattern into the automaton with the pattern's origin at the given offset, combining its cells with the ones already there.
+	/// Cells without their own state are pasted fully alive, and any cells that would land outside of the bounds are skipped.
+	/// 
+	/// ```
+	/// # use cellular_snapp::{presets, BlendMode, flat::{Automaton, Rect2, Vec2}};
+	/// let mut life = Automaton::new(presets::life(), Vec2::new(20, 10), presets::glider().positions()).unwrap();
+	/// let glider = life.copy_region(&Rect2::new(Vec2::new(0, 0), Vec2::new(3, 3)));
+	/// 
+	/// life.paste(&glider, Vec2::new(10, 5), BlendMode::Overwrite);
+	/// assert_eq!(life.live_cells_in(&Rect2::new(Vec2::new(10, 5), Vec2::new(13, 8))).len(), 5);
+	/// 
+	/// // pasting the same cells again with xor clears them right back out
+	/// life.paste(&glider, Vec2::new(10, 5), BlendMode::Xor);
+	/// assert!(life.live_cells_in(&Rect2::new(Vec2::new(10, 5), Vec2::new(13, 8))).is_empty());
+	/// ```
+	pub fn paste(&mut self, pattern: &Pattern, offset: Vec2, blend_mode: BlendMode) {
+		let max_state = self.rules.cell_states - 1;
+
+		for (v, s) in &pattern.cells {
+			let Some(pos) = v.checked_add_position(&offset) else { continue; };
+
+			if let Some(existing) = self.get_cell(&pos) {
+				let state = blend_mode.blend(existing, s.map_or(max_state, |s| s.min(max_state)));
+				self.set_cell(pos, state);
+			}
+		}
+	}
+
+	/// Kills every cell inside the given region.
+	pub fn clear_region(&mut self, region: &Rect2) {
+		for v in self.live_cells_in(region).into_keys() { self.set_cell(v, 0); }
+	}
+
+	/// Sets every cell inside the given region to the same state, like to wall off part of the grid. States too big for the rules are clamped to alive,
+	/// and the part of the region outside the grid is skipped.
+	pub fn fill_region(&mut self, region: &Rect2, state: u8) {
+		for v in self.clipped(region).iter_positions() { self.set_cell(v, state); }
+	}
+
+	/// Sets every cell along a straight line from `a` to `b` (both ends included) to the same state, like dragging a pencil across the grid in a pattern editor.
+	/// The line steps one cell at a time along whichever axis it covers more of, so there aren't any gaps. States too big for the rules are clamped to alive,
+	/// and the part of the line outside the grid is skipped.
+	/// 
+	/// ```
+	/// # use cellular_snapp::{presets, flat::{Automaton, Rect2, Vec2}};
+	/// let mut life = Automaton::new(presets::life(), Vec2::new(16, 16), Vec::new()).unwrap();
+	/// life.paint_line(Vec2::new(1, 1), Vec2::new(9, 5), 1);
+	/// assert_eq!(life.live_cells_sorted().len(), 9);
+	/// assert_eq!(life.get_cell(&Vec2::new(5, 3)), Some(1));
+	/// 
+	/// // a box and a circle, partly hanging off the edge of the grid
+	/// life.paint_box(Vec2::new(12, 12), Vec2::new(20, 20), 1);
+	/// assert_eq!(life.live_cells_in(&Rect2::new(Vec2::new(12, 12), Vec2::new(16, 16))).len(), 16);
+	/// life.paint_circle(Vec2::new(0, 15), 1, 1);
+	/// assert_eq!(life.live_cells_in(&Rect2::new(Vec2::new(0, 13), Vec2::new(3, 16))).len(), 3);
+	/// ```
+	pub fn paint_line(&mut self, a: Vec2, b: Vec2, state: u8) {
+		for v in line([a.x, a.y], [b.x, b.y], [self.bounds.x, self.bounds.y]) { self.set_cell(Vec2::new(v[0], v[1]), state); }
+	}
+
+	/// Sets every cell within `radius` of `center` to the same state, which paints a filled circle (a radius of 0 is just the center cell).
+	/// States too big for the rules are clamped to alive, and the part of the circle outside the grid is skipped.
+	pub fn paint_circle(&mut self, center: Vec2, radius: usize, state: u8) {
+		let region = Rect2::new(Vec2::new(center.x.saturating_sub(radius), center.y.saturating_sub(radius)), Vec2::new(center.x.saturating_add(radius).saturating_add(1), center.y.saturating_add(radius).saturating_add(1)));
+		let inside = |v: &Vec2| (v.x.abs_diff(center.x) as u128).pow(2) + (v.y.abs_diff(center.y) as u128).pow(2) <= (radius as u128).pow(2);
+
+		for v in self.clipped(&region).iter_positions().filter(inside) { self.set_cell(v, state); }
+	}
+
+	/// Sets every cell in the box with `a` and `b` as opposite corners (both included, whichever way round they are) to the same state.
+	/// States too big for the rules are clamped to alive, and the part of the box outside the grid is skipped.
+	pub fn paint_box(&mut self, a: Vec2, b: Vec2, state: u8) {
+		let region = Rect2::new(Vec2::new(a.x.min(b.x), a.y.min(b.y)), Vec2::new(a.x.max(b.x).saturating_add(1), a.y.max(b.y).saturating_add(1)));
+		self.fill_region(&region, state);
+	}
+
+	/// Combines the live cells of a pattern (with its origin at the given offset) with the live and dying cells already there.
+	/// Every cell it brings to life is fully alive, and any part of the mask outside of the bounds is skipped.
+	/// 
+	/// ```
+	/// # use cellular_snapp::{presets, MaskOp, flat::{Automaton, Pattern, Rect2, Vec2}};
+	/// let mut life = Automaton::new(presets::life(), Vec2::new(8, 8), Vec::new()).unwrap();
+	/// life.fill_region(&Rect2::new(Vec2::new(0, 0), Vec2::new(4, 4)), 1);
+	/// 
+	/// // only keep the cells on the diagonal
+	/// let diagonal = Pattern::new("diagonal", (0..8).map(|i| Vec2::new(i, i)).collect());
+	/// life.apply_mask(&diagonal, Vec2::new(0, 0), MaskOp::And);
+	/// assert_eq!(life.live_cells_in(&Rect2::new(Vec2::new(0, 0), Vec2::new(8, 8))).len(), 4);
+	/// 
+	/// life.clear_region(&Rect2::new(Vec2::new(0, 0), Vec2::new(2, 2)));
+	/// assert_eq!(life.live_cells_in(&Rect2::new(Vec2::new(0, 0), Vec2::new(8, 8))).len(), 2);
+	/// ```
+	pub fn apply_mask(&mut self, mask: &Pattern, offset: Vec2, op: MaskOp) {
+		let max_state = self.rules.cell_states - 1;
+		let inside: HashSet<Vec2> = mask.positions().into_iter().filter_map(|v| v.checked_add_position(&offset)).filter(|v| self.get_cell(v).is_some()).collect();
+
+		match op {
+			MaskOp::And => {
+				for v in self.cells.live().into_keys().filter(|v| !inside.contains(v)) { self.set_cell(v, 0); }
+			}
+			MaskOp::Or => {
+				for v in inside { if self.cells.get(&v) == 0 { self.set_cell(v, max_state); } }
+			}
+			MaskOp::Xor => {
+				for v in inside { self.set_cell(v, if self.cells.get(&v) == 0 { max_state } else { 0 }); }
+			}
+		}
+	}
+
+	/// Merges another automaton's live and dying cells into this one, with the other automaton's origin at the given offset.
+	/// Wherever both automata have a live or dying cell, the conflict policy picks which one stays. Cells that come from the other automaton bring their species with them,
+	/// and their states are clamped to fit these rules. Any cells that would land outside of the bounds are skipped.
+	/// 
+	/// ```
+	/// # use cellular_snapp::{presets, ConflictPolicy, flat::{Automaton, Vec2}};
+	/// let mut left = Automaton::new(presets::life(), Vec2::new(40, 20), presets::glider().positions()).unwrap();
+	/// let mut right = Automaton::new(presets::life(), Vec2::new(20, 20), presets::r_pentomino().positions()).unwrap();
+	/// for _ in 0..8 { left.tick(); right.tick(); }
+	/// 
+	/// let live = |a: &Automaton| a.get_cells().values().filter(|s| **s > 0).count();
+	/// let (before, merged) = (live(&left), live(&right));
+	/// left.merge(&right, Vec2::new(20, 0), ConflictPolicy::KeepMax);
+	/// assert_eq!(live(&left), before + merged);
+	/// ```
+	pub fn merge(&mut self, other: &Automaton, offset: Vec2, conflict_policy: ConflictPolicy) {
+		let max_state = self.rules.cell_states - 1;
+
+		for (v, theirs) in other.cells.live() {
+			let Some(pos) = v.checked_add_position(&offset) else { continue; };
+			let theirs = theirs.min(max_state);
+
+			if let Some(mine) = self.get_cell(&pos).filter(|mine| conflict_policy.takes_other(*mine, theirs)) {
+				if mine > 0 { self.set_cell(pos, 0); }
+				self.set_cell(pos, theirs);
+				if let Some(species) = other.species.get(&v) { self.set_species(pos, *species); }
+			}
+		}
+	}
+
+	/// Panics with a message saying where things went wrong if a cell being indexed is outside the grid.
+	fn check_index(&self, v: &Vec2) {
+		if !(v.x < self.bounds.x && v.y < self.bounds.y) { panic!("cell ({}, {}) is outside the {}x{} grid", v.x, v.y, self.bounds.x, self.bounds.y); }
+	}
+
+	/// Kills excluded walls for the length of a tick, so their neighbors can't see them.
+	fn hide_walls(&mut self) {
+		for (v, (wall, _)) in &self.walls {
+			if *wall == Wall::Excluded { self.cells.set(*v, 0); }
+		}
+	}
+
+	/// Puts every wall back the way it's supposed to look, undoing whatever the rules did to it.
+	fn show_walls(&mut self) {
+		let max_state = self.rules.cell_states - 1;
+
+		for (v, (wall, state)) in &self.walls {
+			let state = match wall { Wall::Alive => max_state, Wall::Dead => 0, Wall::Excluded => (*state).min(max_state) };
+			self.cells.set(*v, state);
+		}
+	}
+
+	/// Rebuilds the grid with new bounds, moving every cell by the given shift.
+	fn move_cells(&mut self, new_bounds: Vec2, shift: (i64, i64)) {
+		let backend = self.cells.backend();
+		let cells = std::mem::replace(&mut self.cells, Cells::Map(HashMap::new()));
+
+		self.cells = Cells::from_map(backend, &new_bounds, &self.rules, shift_tracked(cells.into_map(), &new_bounds, shift));
+		self.previous = self.previous.take().map(|p| Cells::from_map(backend, &new_bounds, &self.rules, shift_tracked(p.into_map(), &new_bounds, shift)));
+		self.species = shift_tracked(std::mem::take(&mut self.species), &new_bounds, shift).into_iter().filter(|(v, _)| self.cells.get(v) > 0).collect();
+		self.walls = shift_tracked(std::mem::take(&mut self.walls), &new_bounds, shift);
+		self.ages = self.ages.take().map(|ages| shift_tracked(ages, &new_bounds, shift));
+		self.trails = self.trails.take().map(|trails| shift_tracked(trails, &new_bounds, shift));
+		self.heat = self.heat.take().map(|(decay, heat)| (decay, shift_tracked(heat, &new_bounds, shift)));
+		if let Some(resources) = &mut self.resources { resources.used = shift_tracked(std::mem::take(&mut resources.used), &new_bounds, shift); }
+
+		// regions move along with the cells, and get cut down to whatever's left of them inside the new bounds
+		let moved = |v: &Vec2| Vec2::new((v.x as i64 + shift.0).clamp(0, new_bounds.x as i64) as usize, (v.y as i64 + shift.1).clamp(0, new_bounds.y as i64) as usize);
+		for (region, _) in self.regions.iter_mut() { *region = Rect2::new(moved(&region.min), moved(&region.max)); }
+
+		self.bounds = new_bounds;
+	}
+
+	/// Moves every cell to wherever `map` sends it inside the new bounds, for turning and flipping the grid.
+	/// Every cell has to end up somewhere different, or some of them get lost.
+	fn remap_cells(&mut self, new_bounds: Vec2, map: impl Fn(&Vec2) -> Vec2) {
+		let backend = self.cells.backend();
+		let remap = |cells: Cells| Cells::from_map(backend, &new_bounds, &self.rules, cells.into_map().into_iter().map(|(v, s)| (map(&v), s)).collect());
+
+		let cells = std::mem::replace(&mut self.cells, Cells::Map(HashMap::new()));
+		self.cells = remap(cells);
+		self.previous = self.previous.take().map(remap);
+		self.species = std::mem::take(&mut self.species).into_iter().map(|(v, s)| (map(&v), s)).collect();
+		self.walls = std::mem::take(&mut self.walls).into_iter().map(|(v, w)| (map(&v), w)).collect();
+		self.ages = self.ages.take().map(|ages| ages.into_iter().map(|(v, a)| (map(&v), a)).collect());
+		self.trails = self.trails.take().map(|trails| trails.into_iter().map(|(v, t)| (map(&v), t)).collect());
+		self.heat = self.heat.take().map(|(decay, heat)| (decay, heat.into_iter().map(|(v, h)| (map(&v), h)).collect()));
+		if let Some(resources) = &mut self.resources { resources.used = std::mem::take(&mut resources.used).into_iter().map(|(v, u)| (map(&v), u)).collect(); }
+
+		// regions get cut down to the grid (since the parts outside of it have nowhere to go), and then turned by their first and last cells,
+		// since their far corners are just outside of them
+		let grid = Rect2::new(Vec2::new(0, 0), self.bounds);
+		for (region, _) in self.regions.iter_mut() {
+			let Some(inside) = region.intersect(&grid) else {
+				*region = Rect2::new(Vec2::new(0, 0), Vec2::new(0, 0));
+				continue;
+			};
+
+			let (a, b) = (map(&inside.min), map(&Vec2::new(inside.max.x - 1, inside.max.y - 1)));
+			*region = Rect2::new(Vec2::new(a.x.min(b.x), a.y.min(b.y)), Vec2::new(a.x.max(b.x) + 1, a.y.max(b.y) + 1));
+		}
+
+		self.bounds = new_bounds;
+	}
+
+	/// Saves the automaton into a compact binary checkpoint: the rules, the bounds, the backend, the generation, the random number generator,
+	/// and every live or dying cell, with runs of dead cells squashed down to their length. It can go anywhere that can be written to, not just files.
+	/// 
+	/// Walls, the edge state, the update scheme, and trails get saved along with them. Ages and the heatmap aren't saved, since they only keep track of the run.
+	/// Everything else that changes how the run carries on has to be saved for it to pick up exactly where it left off, so automata with the generation before (in second-order mode),
+	/// more than one species, rule regions, birth or survival fields, resources, or a rule schedule give an `InvalidInput` error, and so do rules that can't be written as a rule string, like transition tables.
+	/// 
+	/// ```
+	/// # use cellular_snapp::{presets, flat::{Automaton, Vec2}};
+	/// let mut life = Automaton::new(presets::life(), Vec2::new(64, 64), presets::r_pentomino().translate(Vec2::new(30, 30)).positions()).unwrap();
+	/// for _ in 0..20 { life.tick(); }
+	/// 
+	/// let mut checkpoint = Vec::new();
+	/// life.save_checkpoint(&mut checkpoint).unwrap();
+	/// let mut resumed = Automaton::load_checkpoint(checkpoint.as_slice()).unwrap();
+	/// assert_eq!(resumed.get_generation(), 20);
+	/// 
+	/// life.tick();
+	/// resumed.tick();
+	/// assert!(resumed.get_cells() == life.get_cells());
+	/// ```
+	pub fn save_checkpoint(&self, mut writer: impl Write) -> io::Result<()> {
+		let unsaved = if self.previous.is_some() { Some("the generation before") }
+			else if self.rules.species() > 1 { Some("species") }
+			else if !self.regions.is_empty() { Some("rule regions") }
+			else if self.birth_field.is_some() || self.survival_field.is_some() { Some("birth and survival fields") }
+			else if self.resources.is_some() { Some("resources") }
+			else if self.schedule.is_some() { Some("rule schedules") }
+			else { None };
+		if let Some(what) = unsaved { return Err(io::Error::new(io::ErrorKind::InvalidInput, format!("{} can't be written into a checkpoint", what))); }
+
+		let index = |v: &Vec2| v.y as u64 * self.bounds.x as u64 + v.x as u64;
+		let mut cells = self.cells.live().into_iter().map(|(v, s)| (index(&v), s)).collect::<Vec<(u64, u8)>>();
+		cells.sort_unstable();
+
+		let mut walls = self.walls.iter().map(|(v, (wall, state))| (index(v), *wall, *state)).collect::<Vec<(u64, Wall, u8)>>();
+		walls.sort_unstable_by_key(|(i, _, _)| *i);
+		let trails = self.trails.as_ref().map(|trails| trails.iter().map(|(v, since)| (index(v), *since)).collect());
+
+		let header = checkpoint::Header { bounds: vec![self.bounds.x as u64, self.bounds.y as u64], generation: self.generation, rng: self.rng.state(), backend: self.cells.backend(), rules: self.rules.clone(), edge: self.edge, scheme: self.scheme, walls, trails };
+		header.write(&mut writer)?;
+		checkpoint::write_cells(&mut writer, &cells)
+	}
+
+	/// Loads an automaton back from a checkpoint made by `save_checkpoint`, so it carries on exactly where it left off.
+	/// Checkpoints that don't make sense give an `InvalidData` error, and so do ones for grids too big to make room for, like a dense grid with more than 2^32 cells.
+	pub fn load_checkpoint(mut reader: impl Read) -> io::Result<Automaton> {
+		let header = checkpoint::Header::read(&mut reader, 2)?;
+		let area = checkpoint::area(&header.bounds, header.backend)?;
+		let bounds = Vec2::new(header.bounds[0] as usize, header.bounds[1] as usize);
+		let states = header.rules.cell_states;
+
+		let position = |i: u64| {
+			if i >= area { return Err(checkpoint::invalid("a cell is outside the grid")); }
+			Ok(Vec2::new((i % bounds.x as u64) as usize, (i / bounds.x as u64) as usize))
+		};
+		let state = |s: u8| if s < states { Ok(s) } else { Err(checkpoint::invalid(&format!("state {} is too big for the rules", s))) };
+
+		// everything gets checked against the grid before the grid gets made, so a checkpoint that doesn't make sense can't make room for a huge one first
+		let cells = checkpoint::read_cells(&mut reader)?.into_iter().map(|(i, s)| Ok((position(i)?, state(s)?))).collect::<io::Result<Vec<(Vec2, u8)>>>()?;
+		let walls = header.walls.into_iter().map(|(i, wall, s)| Ok((position(i)?, (wall, state(s)?)))).collect::<io::Result<HashMap<Vec2, (Wall, u8)>>>()?;
+		let trails = header.trails.map(|trails| trails.into_iter().map(|(i, since)| Ok((position(i)?, since))).collect::<io::Result<HashMap<Vec2, u32>>>()).transpose()?;
+
+		let mut automaton = Automaton::with_backend(header.rules, bounds, Vec::new(), header.backend).map_err(|e| checkpoint::invalid(&e.to_string()))?;
+		automaton.generation = header.generation;
+		automaton.rng = Rng::new(header.rng);
+		automaton.set_edge_state(header.edge);
+		automaton.scheme = header.scheme;
+
+		for (v, s) in cells { automaton.cells.set(v, s); }
+		automaton.walls = walls;
+		automaton.trails = trails;
+
+		Ok(automaton)
+	}
+
+	/// Squashes the whole grid down into bytes: the bounds, and then every cell row by row as runs of the same state, so big stretches of dead (or live) cells only take a few bytes.
+	/// Only the cells are kept, not the rules, the generation, or anything else a checkpoint has, which makes it handy for storing lots of snapshots of the same run.
+	/// 
+	/// ```
+	/// # use cellular_snapp::{presets, flat::{Automaton, Vec2}};
+	/// let mut life = Automaton::new(presets::life(), Vec2::new(256, 256), presets::r_pentomino().translate(Vec2::new(120, 120)).positions()).unwrap();
+	/// for _ in 0..50 { life.tick(); }
+	/// 
+	/// let bytes = life.to_bytes();
+	/// assert!(bytes.len() < 1000);
+	/// let loaded = Automaton::from_bytes(presets::life(), &bytes).unwrap();
+	/// assert_eq!(loaded.live_cells_sorted(), life.live_cells_sorted());
+	/// ```
+	pub fn to_bytes(&self) -> Vec<u8> {
+		checkpoint::write_grid(&[self.bounds.x, self.bounds.y], self.iter_cells().map(|(_, s)| s))
+	}
+
+	/// Creates a new automaton with the given rules out of bytes made by `to_bytes`. Bytes that don't make sense give back an `InvalidPattern` error,
+	/// and states too big for the rules give back an `InvalidState` error.
+	pub fn from_bytes(rules: AutomataRules, bytes: &[u8]) -> Result<Automaton, AutomataError> {
+		let (bounds, runs) = checkpoint::read_grid(bytes, 2).map_err(AutomataError::InvalidPattern)?;
+		let mut a = Automaton::new(rules, Vec2::new(bounds[0], bounds[1]), Vec::new())?;
+
+		let mut start = 0;
+		for (s, len) in runs {
+			if s >= a.rules.cell_states { return Err(AutomataError::InvalidState(s)); }
+			if s > 0 {
+				for i in start..start + len { a.cells.set(Vec2::new(i % bounds[0], i / bounds[0]), s); }
+			}
+			start += len;
+		}
+
+		Ok(a)
+	}
+
+	/// Captures everything needed to replay the run from here: the rules, the bounds, every live or dying cell, and the random number generator's seed.
+	/// Walls, the edge state, and the update scheme go in it too. See the `replay` module for more.
+	/// 
+	/// Rule regions, birth and survival fields, resources, and rule schedules can't go in a log, so automata using any of them give back an `InvalidReplay` error.
+	pub fn replay_log(&self) -> Result<ReplayLog, AutomataError> {
+		let unsupported = if !self.regions.is_empty() { Some("rule regions") }
+			else if self.birth_field.is_some() || self.survival_field.is_some() { Some("birth and survival fields") }
+			else if self.resources.is_some() { Some("resources") }
+			else if self.schedule.is_some() { Some("rule schedules") }
+			else { None };
+		if let Some(what) = unsupported { return Err(AutomataError::InvalidReplay(format!("{} can't be written into a replay log", what))); }
+
+		let cells = self.get_species().into_iter().map(|(v, species)| ([v.x, v.y, 0], self.cells.get(&v), species)).collect();
+		let walls = self.walls.iter().map(|(v, (wall, state))| ([v.x, v.y, 0], *wall, *state)).collect();
+		Ok(ReplayLog::new(self.rules.clone(), [self.bounds.x, self.bounds.y, 1], cells, self.rng.state()).with_walls(walls, self.edge).with_update_scheme(self.scheme))
+	}
+
+	/// Rebuilds an automaton from a replay log, so it ticks exactly the way the original did from where the log was made. The generation starts back at 0.
+	pub fn from_replay(log: &ReplayLog) -> Result<Automaton, AutomataError> {
+		Automaton::from_replay_with_backend(log, Backend::HashMap)
+	}
+
+	/// Rebuilds an automaton from a replay log just like `from_replay`, except the cells are kept in the given backend.
+	/// Every backend ticks the same way, so this is how to check a run against one made with a different backend.
+	pub fn from_replay_with_backend(log: &ReplayLog, backend: Backend) -> Result<Automaton, AutomataError> {
+		let [width, height, depth] = log.bounds();
+		if depth != 1 { return Err(AutomataError::InvalidReplay(format!("this replay is for a grid {} cells deep", depth))); }
+
+		let mut automaton = Automaton::with_backend(log.rules().clone(), Vec2::new(width, height), Vec::new(), backend)?;
+
+		for ([x, y, z], state, species) in log.cells() {
+			if !(*x < width && *y < height && *z == 0) { return Err(AutomataError::InvalidReplay(format!("the cell at {}, {}, {} is outside the grid", x, y, z))); }
+			if *state >= automaton.rules.cell_states { return Err(AutomataError::InvalidState(*state)); }
+
+			automaton.set_cell(Vec2::new(*x, *y), *state);
+			if *species > 0 { automaton.set_species(Vec2::new(*x, *y), *species); }
+		}
+
+		for ([x, y, z], wall, state) in log.walls() {
+			if !(*x < width && *y < height && *z == 0) { return Err(AutomataError::InvalidReplay(format!("the wall at {}, {}, {} is outside the grid", x, y, z))); }
+			if *state >= automaton.rules.cell_states { return Err(AutomataError::InvalidState(*state)); }
+			automaton.walls.insert(Vec2::new(*x, *y), (*wall, *state));
+		}
+
+		automaton.set_edge_state(log.edge_state());
+		automaton.set_update_scheme(log.update_scheme());
+		automaton.seed_rng(log.seed());
+		Ok(automaton)
+	}
+
+	/// Writes out the whole grid as an RLE pattern, the format most Life patterns are shared in.
+	/// Automata with more than two cell states use Golly's multi-state letters, where live cells are `A` and dying cells count up from `B`.
+	pub fn to_rle(&self) -> String {
+		let mut tokens = Vec::new();
+		let mut last_row = 0;
+
+		for y in 0..self.bounds.y {
+			let row = (0..self.bounds.x).map(|x| self.cells.get(&Vec2::new(x, y))).collect::<Vec<u8>>();
+			let Some(end) = row.iter().rposition(|s| *s > 0) else { continue };
+
+			if y > last_row { tokens.push(rle_run(y - last_row, "$")); }
+			last_row = y;
+
+			let mut x = 0;
+			while x <= end {
+				let run = row[x..=end].iter().take_while(|s| **s == row[x]).count();
+				tokens.push(rle_run(run, &rle_tag(row[x], &self.rules)));
+				x += run;
+			}
+		}
+
+		tokens.push("!".to_string());
+
+		let mut rle = format!("x = {}, y = {}, rule = {}\n", self.bounds.x, self.bounds.y, self.rules);
+		let mut line_len = 0;
+
+		for token in tokens {
+			if line_len + token.len() > 70 {
+				rle.push('\n');
+				line_len = 0;
+			}
+
+			line_len += token.len();
+			rle.push_str(&token);
+		}
+
+		rle.push('\n');
+		rle
+	}
+
+	/// Writes out every live cell as a Life 1.06 pattern, one `x y` pair per line.
+	/// Life 1.06 only has live and dead cells, so dying cells are left out.
+	pub fn to_life106(&self) -> String {
+		let mut out = String::from("#Life 1.06\n");
+
+		for v in self.live_positions() {
+			out.push_str(&format!("{} {}\n", v.x, v.y));
+		}
+
+		out
+	}
+
+	/// Writes out the whole grid as a Life 1.05 pattern, in a single block starting at (0, 0).
+	/// Life 1.05 only has live and dead cells, so dying cells are left out.
+	pub fn to_life105(&self) -> String {
+		format!("#Life 1.05\n#P 0 0\n{}", self.render_live('*'))
+	}
+
+	/// Writes out the whole grid as a plaintext (`.cells`) pattern.
+	/// Plaintext only has live and dead cells, so dying cells are left out.
+	pub fn to_cells(&self) -> String {
+		self.render_live('O')
+	}
+
+	/// Get the positions of every live cell, sorted top to bottom and then left to right.
+	fn live_positions(&self) -> Vec<Vec2> {
+		let mut live = self.cells.live().into_iter().filter(|(_, s)| *s == self.rules.cell_states - 1).map(|(v, _)| v).collect::<Vec<Vec2>>();
+		live.sort_by_key(|v| (v.y, v.x));
+		live
+	}
+
+	/// Draws the grid with `.` for dead (and dying) cells and the given character for live cells.
+	fn render_live(&self, alive: char) -> String {
+		let max_state = self.rules.cell_states - 1;
+		let mut charset = vec!['.'; max_state as usize];
+		charset.push(alive);
+		self.render_ascii(&charset)
+	}
+
+	/// Draws the grid as text, one line per row.
+	/// Each cell's state is used as an index into the charset, so `charset[0]` is used for dead cells, and states past the end of the charset use its last character.
+	pub fn render_ascii(&self, charset: &[char]) -> String {
+		let mut out = String::with_capacity((self.bounds.x + 1) * self.bounds.y);
+
+		for y in 0..self.bounds.y {
+			for x in 0..self.bounds.x {
+				let state = self.cells.get(&Vec2::new(x, y)) as usize;
+				out.push(charset.get(state).or(charset.last()).copied().unwrap_or(' '));
+			}
+
+			out.push('\n');
+		}
+
+		out
+	}
+}
+
+/// Draws the grid as text, with `#` for live cells, `+` for dying cells, and `.` for dead cells.
+impl fmt::Display for Automaton {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		let mut charset = vec!['.'];
+		charset.resize(self.rules.cell_states as usize - 1, '+');
+		charset.push('#');
+
+		write!(f, "{}", self.render_ascii(&charset))
+	}
+}
+
+/// Sums the automaton up in a line: its rules, bounds, backend, and how many cells are live or dying.
+/// The alternate form (`{:#?}`) draws the whole grid underneath too, just like `Display`.
+impl fmt::Debug for Automaton {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "Automaton {{ rules: {}, bounds: {}x{}, backend: {:?}, live_cells: {} }}", self.rules, self.bounds.x, self.bounds.y, self.get_backend(), self.cells.live().len())?;
+		if f.alternate() { write!(f, "\n{}", self)?; }
+		Ok(())
+	}
+}
+
+/// Lets flat automata be used by anything written for any `CellularAutomaton`.
+impl CellularAutomaton for Automaton {
+	type Coord = Vec2;
+
+	fn tick(&mut self) { Automaton::tick(self) }
+
+	fn live_cells(&self) -> HashMap<Vec2, u8> { self.live_cells_in(&Rect2::new(Vec2::new(0, 0), self.bounds)) }
+
+	fn get_cell(&self, v: &Vec2) -> Option<u8> { Automaton::get_cell(self, v) }
+
+	fn set_cell(&mut self, v: Vec2, state: u8) { Automaton::set_cell(self, v, state) }
+
+	fn bounds(&self) -> Vec2 { self.bounds }
+
+	fn generation(&self) -> u64 { self.generation }
+
+	fn rules(&self) -> &AutomataRules { &self.rules }
+}
+
+/// Two automata are equal when they have the same rules, bounds, and cells, even if they keep their cells in different backends.
+/// 
+/// ```
+/// # use cellular_snapp::{presets, Backend, flat::{Automaton, Vec2}};
+/// let life = Automaton::new(presets::life(), Vec2::new(16, 16), presets::r_pentomino().positions()).unwrap();
+/// let mut fork = life.clone();
+/// assert_eq!(life, fork);
+/// 
+/// fork.tick();
+/// assert_ne!(life, fork);
+/// 
+/// let mut dense = Automaton::with_backend(presets::life(), Vec2::new(16, 16), presets::r_pentomino().positions(), Backend::Dense).unwrap();
+/// dense.tick();
+/// assert_eq!(fork, dense);
+/// ```
+impl PartialEq for Automaton {
+	fn eq(&self, other: &Automaton) -> bool {
+		self.rules == other.rules && self.bounds == other.bounds && self.cells.live() == other.cells.live()
+	}
+}
+
+/// Get the state of a cell by its position, like `automaton[(x, y)]`. This panics if the cell is outside the grid, so use `get_cell` when it might be.
+/// 
+/// ```
+/// # use cellular_snapp::{presets, flat::{Automaton, Vec2}};
+/// let mut life = Automaton::new(presets::life(), Vec2::new(8, 8), vec![Vec2::new(1, 1)]).unwrap();
+/// assert_eq!(life[(1, 1)], 1);
+/// 
+/// life[Vec2::new(2, 1)] = 1;
+/// assert_eq!(life[(2, 1)], 1);
+/// ```
+impl Index<Vec2> for Automaton {
+	type Output = u8;
+
+	fn index(&self, v: Vec2) -> &u8 {
+		// the backends that don't keep bytes around (like bit-packed grids) hand back a reference into this instead
+		static STATES: [u8; 256] = { let mut states = [0; 256]; let mut s = 0; while s < 256 { states[s] = s as u8; s += 1; } states };
+
+		self.check_index(&v);
+		&STATES[self.cells.get(&v) as usize]
+	}
+}
+
+impl Index<(usize, usize)> for Automaton {
+	type Output = u8;
+
+	fn index(&self, (x, y): (usize, usize)) -> &u8 { &self[Vec2::new(x, y)] }
+}
+
+/// Changes the state of a cell by its position, like `automaton[(x, y)] = 1`. This panics if the cell is outside the grid.
+/// 
+/// States are written straight into the grid without being clamped like `set_cell` does, and only the hash map and dense backends keep a byte per cell to write into,
+/// so this panics with the other backends too. The hash map backend makes an entry for the cell even if it's dead, which stays around until `compact` gets rid of it.
+/// Changes made this way don't show up in the event log either.
+impl IndexMut<Vec2> for Automaton {
+	fn index_mut(&mut self, v: Vec2) -> &mut u8 {
+		self.check_index(&v);
+
+		match &mut self.cells {
+			Cells::Map(map) => map.entry(v).or_insert(0),
+			Cells::Dense(dense) => dense.get_mut(v.x, v.y),
+			cells => panic!("the {:?} backend can't be indexed mutably, use set_cell instead", cells.backend())
+		}
+	}
+}
+
+impl IndexMut<(usize, usize)> for Automaton {
+	fn index_mut(&mut self, (x, y): (usize, usize)) -> &mut u8 { &mut self[Vec2::new(x, y)] }
+}
+
+impl Field {
+	/// Creates a field that's the same everywhere.
+	pub fn constant(value: f64) -> Field {
+		Field::from_fn(move |_| value)
+	}
+
+	/// Creates a field that goes smoothly from `start` at one cell to `end` at another, staying at `start` or `end` past either of them.
+	/// Cells off to the side of the line between them get the value of the closest spot on it, so the field runs in stripes.
+	pub fn linear(from: Vec2, to: Vec2, start: f64, end: f64) -> Field {
+		let (dx, dy) = (to.x as f64 - from.x as f64, to.y as f64 - from.y as f64);
+		let length = dx * dx + dy * dy;
+
+		Field::from_fn(move |v| {
+			let t = if length == 0.0 { 1.0 } else { (((v.x as f64 - from.x as f64) * dx + (v.y as f64 - from.y as f64) * dy) / length).clamp(0.0, 1.0) };
+			start + (end - start) * t
+		})
+	}
+
+	/// Creates a field out of any function of a cell's position. The function gets called from whichever threads the automaton ticks on.
+	pub fn from_fn(value: impl Fn(&Vec2) -> f64 + Send + Sync + 'static) -> Field {
+		Field { value: Arc::new(value) }
+	}
+
+	/// Get the field's value at a cell.
+	pub fn at(&self, v: &Vec2) -> f64 {
+		(self.value)(v)
+	}
+}
+
+impl Frame {
+	pub(crate) fn new(bounds: Vec2, generation: u64, cells: Vec<u8>) -> Frame {
+		Frame { bounds, generation, cells }
+	}
+
+	/// Get the bounds (the size of the grid) the automaton had.
+	pub fn bounds(&self) -> &Vec2 {
+		&self.bounds
+	}
+
+	/// Get the generation the snapshot was taken at.
+	pub fn generation(&self) -> u64 {
+		self.generation
+	}
+
+	/// Get the state of every cell, laid out one row at a time with x going fastest, just like `as_slice`.
+	pub fn cells(&self) -> &[u8] {
+		&self.cells
+	}
+
+	/// Get the state of a single cell, or `None` if it's outside the grid.
+	pub fn get_cell(&self, v: &Vec2) -> Option<u8> {
+		(v.x < self.bounds.x && v.y < self.bounds.y).then(|| self.cells[v.y * self.bounds.x + v.x])
+	}
+}
+
+impl<A: BorrowMut<Automaton>> Iterator for Generations<A> {
+	type Item = Automaton;
+
+	fn next(&mut self) -> Option<Automaton> {
+		let automaton = self.automaton.borrow_mut();
+		automaton.tick();
+		Some(automaton.clone())
+	}
+}
+
+/// Generates a random soup filling the given bounds, where every cell has a `density` chance of being alive.
+/// The same random number generator seed always gives the same soup.
+pub fn random_seed(bounds: Vec2, density: f64, rng: &mut Rng) -> Vec<Vec2> {
+	let mut seed = Vec::new();
+
+	for y in 0..bounds.y {
+		for x in 0..bounds.x {
+			if rng.chance(density) { seed.push(Vec2::new(x, y)); }
+		}
+	}
+
+	seed
+}
+
+/// Generates a random soup filling the given bounds with the given symmetry, where every cell has about a `density` chance of being alive.
+/// The same random number generator seed always gives the same soup.
+/// 
+/// ```
+/// # use cellular_snapp::{random::Rng, flat::{symmetric_seed, Symmetry, Vec2}};
+/// let seed = symmetric_seed(Vec2::new(16, 16), 0.5, Symmetry::EightFold, &mut Rng::new(42));
+/// 
+/// for v in &seed {
+///     assert!(seed.contains(&Vec2::new(15 - v.x(), v.y())));
+///     assert!(seed.contains(&Vec2::new(v.y(), v.x())));
+/// }
+/// ```
+pub fn symmetric_seed(bounds: Vec2, density: f64, symmetry: Symmetry, rng: &mut Rng) -> Vec<Vec2> {
+	// working in doubled coordinates from the middle of the grid keeps everything in whole numbers, even when the middle falls between cells
+	let (w, h) = (bounds.x as i64, bounds.y as i64);
+	let transforms = symmetry.transforms();
+	let mut seed = Vec::new();
+
+	for y in 0..bounds.y {
+		for x in 0..bounds.x {
+			let (cx, cy) = (2 * x as i64 - (w - 1), 2 * y as i64 - (h - 1));
+
+			let mut orbit = transforms.iter().filter_map(|[a, b, c, d]| {
+				let (tx, ty) = (a * cx + b * cy + w - 1, c * cx + d * cy + h - 1);
+				(tx % 2 == 0 && ty % 2 == 0 && (0..2 * w).contains(&tx) && (0..2 * h).contains(&ty)).then(|| Vec2::new(tx as usize / 2, ty as usize / 2))
+			}).collect::<Vec<Vec2>>();
+
+			orbit.sort_unstable_by_key(|v| (v.y, v.x));
+			orbit.dedup();
+
+			// only the first cell of each group gets a roll, and the rest copy it
+			if orbit[0] == Vec2::new(x, y) && rng.chance(density) { seed.extend(orbit); }
+		}
+	}
+
+	seed.sort_unstable_by_key(|v| (v.y, v.x));
+	seed
+}
+
+/// Writes a run of RLE tags, leaving the count off if it's just one.
+fn rle_run(count: usize, tag: &str) -> String {
+	if count == 1 { tag.to_string() } else { format!("{}{}", count, tag) }
+}
+
+/// Get the RLE tag for a cell state.
+fn rle_tag(state: u8, rules: &AutomataRules) -> String {
+	let cell_states = rules.cell_states;
+
+	if state == 0 {
+		if cell_states == 2 { "b" } else { "." }.to_string()
+	} else if cell_states == 2 {
+		"o".to_string()
+	} else {
+		// Golly numbers states from 1 (alive), counting up as cells die, but transition tables are written as-is
+		let golly = if rules.is_table() { state as u32 - 1 } else { (cell_states - state) as u32 - 1 };
+		let letter = char::from_u32('A' as u32 + golly % 24).unwrap();
+
+		match golly / 24 {
+			0 => letter.to_string(),
+			prefix => format!("{}{}", char::from_u32('p' as u32 + prefix - 1).unwrap(), letter)
+		}
+	}
+}
+
+/// Moves the cells, ages, trails, or heat of every cell by the given shift, throwing away whatever doesn't fit in the new bounds.
+fn shift_tracked<T>(old: HashMap<Vec2, T>, new_bounds: &Vec2, shift: (i64, i64)) -> HashMap<Vec2, T> {
+	old.into_iter().filter_map(|(v, t)| {
+		let (x, y) = (v.x as i64 + shift.0, v.y as i64 + shift.1);
+		(x >= 0 && (x as usize) < new_bounds.x && y >= 0 && (y as usize) < new_bounds.y).then(|| (Vec2::new(x as usize, y as usize), t))
+	}).collect()
+}
+
+/// Moves a bunch of coordinates so the smallest x and y both end up at 0.
+fn normalize(coords: Vec<IVec2>) -> Vec<Vec2> {
+	let min = coords.iter().fold(None, |min: Option<(i64, i64)>, v| Some(min.map_or((v.x, v.y), |(x, y)| (x.min(v.x), y.min(v.y)))));
+	let Some((x, y)) = min else { return Vec::new() };
+
+	coords.into_iter().map(|v| Vec2::new((v.x - x) as usize, (v.y - y) as usize)).collect()
+}
+
+/// Get the relative positions of every neighbor a cell has with the given neighbor method.
+pub(crate) fn neighbor_offsets(method: &Method) -> Vec<IVec2> {
+	let reach = method.reach();
+	let mut offsets = Vec::new();
+
+	for x in -reach..=reach {
+		for y in -reach..=reach {
+			if is_neighbor(method, x, y) { offsets.push(IVec2::new(x, y)); }
+		}
+	}
+
+	offsets
+}
+
+/// Checks whether a cell at the given offset is a neighbor with the given neighbor method.
+fn is_neighbor(method: &Method, x: i64, y: i64) -> bool {
+	let (near, center) = (x.abs() <= 1 && y.abs() <= 1, x == 0 && y == 0);
+
+	match method {
+		Method::Moore | Method::FacesAndEdges => near && !center,
+		Method::VonNeumann => x.abs() + y.abs() == 1,
+		Method::Euclidean(radius) => !center && ((x * x + y * y) as f64) <= radius * radius
+	}
+}
+
+/// Get the maximum amount of neighbors a cell can have with the given neighbor method, which fails if there are too many to count (or the method only works in 3D).
+pub(crate) fn max_neighbors(method: &Method) -> Result<u8, AutomataError> {
+	match method {
+		Method::Moore => Ok(8),
+		Method::VonNeumann => Ok(4),
+		Method::FacesAndEdges => Err(AutomataError::UnsupportedFacesAndEdges),
+		Method::Euclidean(_) => {
+			let neighbors = neighbor_offsets(method).len();
+			u8::try_from(neighbors).map_err(|_| AutomataError::NeighborhoodTooBig(neighbors))
+		}
+	}
+}
+
+/// Get the index of every neighbor of a cell that's inside the grid, for the incremental backend.
+fn neighbor_indices<'a>(bounds: &'a Vec2, offsets: &'a [IVec2], v: Vec2) -> impl Iterator<Item = usize> + 'a {
+	offsets.iter().filter_map(move |offset| v.checked_add_offset(offset)).filter(|n| n.x < bounds.x && n.y < bounds.y).map(|n| n.y * bounds.x + n.x)
+}
+
+impl Cells {
+	/// Creates a grid full of dead cells for the given backend, which counts neighbors the way the given rules do if it keeps counts.
+	/// This fails if the backend keeps its cells in a memory-mapped file and the file can't be made.
+	fn try_new(backend: Backend, bounds: &Vec2, rules: &AutomataRules) -> Result<Cells, AutomataError> {
+		Ok(match backend {
+			Backend::HashMap => Cells::Map(HashMap::new()),
+			Backend::Tree => Cells::Tree(Tree::new([bounds.x, bounds.y])),
+			Backend::BitPacked => Cells::Bits(Bits::new(bounds.x, bounds.y)),
+			Backend::Dense => Cells::Dense(Dense::new(bounds.x, bounds.y)),
+			#[cfg(feature = "mmap")]
+			Backend::Mapped => Cells::Dense(Dense::mapped(bounds.x, bounds.y).map_err(|e| AutomataError::MappingFailed(e.to_string()))?),
+			Backend::Incremental => Cells::Incremental(Incremental::new(bounds.x * bounds.y, rules.counted_states()), *bounds, neighbor_offsets(&rules.neighbor_method))
+		})
+	}
+
+	/// Creates a grid full of dead cells like `try_new`, except a grid that can't get a memory-mapped file gets kept in memory instead.
+	fn new(backend: Backend, bounds: &Vec2, rules: &AutomataRules) -> Cells {
+		Cells::try_new(backend, bounds, rules).unwrap_or_else(|_| Cells::new(Backend::Dense, bounds, rules))
+	}
+
+	/// Fills a grid for the given backend from a map of cells, which can leave dead cells out.
+	fn from_map(backend: Backend, bounds: &Vec2, rules: &AutomataRules, map: HashMap<Vec2, u8>) -> Cells {
+		let mut cells = Cells::new(backend, bounds, rules);
+		for (v, s) in map { cells.set(v, s); }
+		cells
+	}
+
+	/// Get which backend this is.
+	fn backend(&self) -> Backend {
+		match self {
+			Cells::Map(_) => Backend::HashMap,
+			Cells::Tree(_) => Backend::Tree,
+			Cells::Bits(_) => Backend::BitPacked,
+			#[cfg(feature = "mmap")]
+			Cells::Dense(dense) if dense.is_mapped() => Backend::Mapped,
+			Cells::Dense(_) => Backend::Dense,
+			Cells::Incremental(..) => Backend::Incremental
+		}
+	}
+
+	/// Get the state of a cell. Cells that aren't stored are dead.
+	fn get(&self, v: &Vec2) -> u8 {
+		match self {
+			Cells::Map(map) => map.get(v).copied().unwrap_or(0),
+			Cells::Tree(tree) => tree.get(&[v.x, v.y]),
+			Cells::Bits(bits) => bits.get(v.x, v.y) as u8,
+			Cells::Dense(dense) => dense.get(v.x, v.y),
+			Cells::Incremental(grid, bounds, _) => grid.get(v.y * bounds.x + v.x)
+		}
+	}
+
+	/// Sets the state of a cell, which has to be inside the grid.
+	fn set(&mut self, v: Vec2, state: u8) {
+		match self {
+			Cells::Map(map) => if state == 0 { map.remove(&v); } else { map.insert(v, state); },
+			Cells::Tree(tree) => tree.set([v.x, v.y], state),
+			Cells::Bits(bits) => bits.set(v.x, v.y, state > 0),
+			Cells::Dense(dense) => dense.set(v.x, v.y, state),
+			Cells::Incremental(grid, bounds, offsets) => grid.set(v.y * bounds.x + v.x, state, neighbor_indices(bounds, offsets, v))
+		}
+	}
+
+	/// Changes the state of every stored cell.
+	fn map_states(&mut self, f: impl Fn(u8) -> u8) {
+		match self {
+			Cells::Map(map) => map.retain(|_, s| { *s = f(*s); *s > 0 }),
+			Cells::Tree(tree) => for (p, s) in tree.cells() { tree.set(p, f(s)); },
+			Cells::Bits(bits) => for (x, row) in bits.live() { bits.set(x, row, f(1) > 0); },
+			Cells::Dense(dense) => dense.map_states(f),
+			Cells::Incremental(grid, bounds, offsets) => for i in 0..bounds.x * bounds.y {
+				let v = Vec2::new(i % bounds.x, i / bounds.x);
+				grid.set(i, f(grid.get(i)), neighbor_indices(bounds, offsets, v));
+			}
+		}
+	}
+
+	/// Starts counting neighbors the way the given rules do, if the grid keeps counts.
+	fn recount(&mut self, rules: &AutomataRules) {
+		if let Cells::Incremental(grid, bounds, offsets) = self {
+			*offsets = neighbor_offsets(&rules.neighbor_method);
+			let (bounds, offsets) = (&*bounds, &*offsets);
+			grid.recount(rules.counted_states(), |i| neighbor_indices(bounds, offsets, Vec2::new(i % bounds.x, i / bounds.x)));
+		}
+	}
+
+	/// Gets rid of any dead cells the hash map backend is still holding onto, and shrinks it down to fit.
+	fn compact(&mut self) {
+		if let Cells::Map(map) = self {
+			map.retain(|_, s| *s > 0);
+			map.shrink_to_fit();
+		}
+	}
+
+	/// Forgets which cells would stay put, if the grid keeps track of that, for after it's been ticked without going through its counts.
+	fn unsettle(&mut self) {
+		if let Cells::Incremental(grid, ..) = self { grid.mark_all(); }
+	}
+
+	/// Get every live or dying cell.
+	fn live(&self) -> HashMap<Vec2, u8> {
+		match self {
+			Cells::Map(map) => map.iter().filter(|(_, s)| **s > 0).map(|(v, s)| (*v, *s)).collect(),
+			Cells::Tree(tree) => tree.cells().into_iter().map(|([x, y], s)| (Vec2::new(x, y), s)).collect(),
+			Cells::Bits(bits) => bits.live().into_iter().map(|(x, y)| (Vec2::new(x, y), 1)).collect(),
+			Cells::Dense(dense) => dense.live().into_iter().map(|(x, y, s)| (Vec2::new(x, y), s)).collect(),
+			Cells::Incremental(grid, bounds, _) => grid.live().into_iter().map(|(i, s)| (Vec2::new(i % bounds.x, i / bounds.x), s)).collect()
+		}
+	}
+
+	/// Turns the grid into a map of every stored cell, which leaves dead cells out (except ones written into the hash map backend by indexing).
+	fn into_map(self) -> HashMap<Vec2, u8> {
+		match self {
+			Cells::Map(map) => map,
+			sparse => sparse.live()
+		}
+	}
+}
+
+impl<const W: usize, const H: usize> FixedAutomaton<W, H> {
+	/// Creates a new fixed-size automaton with the given cells alive. Cells outside the grid are ignored.
+	/// Just like with `Automaton::new`, this fails if your rules ask for more neighbors than a cell could have.
+	pub fn new(rules: AutomataRules, start_cells: &[Vec2]) -> Result<FixedAutomaton<W, H>, AutomataError> {
+		rules.validate(max_neighbors(&rules.neighbor_method)?, 2)?;
+
+		let mut cells = [[0; W]; H];
+		for v in start_cells.iter().filter(|v| v.x < W && v.y < H) {
+			cells[v.y][v.x] = rules.cell_states - 1;
+		}
+
+		Ok(FixedAutomaton { rules, cells, rng: Rng::new(0), generation: 0 })
+	}
+
+	/// Advances the automaton by one time step (or tick).
+	pub fn tick(&mut self) {
+		let key = self.rng.next_u64();
+		let (method, reach) = (&self.rules.neighbor_method, self.rules.neighbor_method.reach());
+		let mut next = self.cells;
+
+		for (y, row) in next.iter_mut().enumerate() {
+			for (x, cell) in row.iter_mut().enumerate() {
+				// these are in the same order as `neighbor_offsets`, so isotropic rules and rule tables see the same arrangement
+				let neighbors = (-reach..=reach).flat_map(|dx| (-reach..=reach).map(move |dy| (dx, dy)))
+					.filter(|(dx, dy)| is_neighbor(method, *dx, *dy))
+					.map(|(dx, dy)| match (x.checked_add_signed(dx as isize), y.checked_add_signed(dy as isize)) {
+						(Some(nx), Some(ny)) if nx < W && ny < H => self.cells[ny][nx],
+						_ => 0
+					});
+
+				let roll = if self.rules.is_stochastic() { Rng::keyed(key, &[x as u64, y as u64]).next_f64() } else { 0.0 };
+				*cell = self.rules.next_state_from(self.cells[y][x], neighbors, roll);
+			}
+		}
+
+		self.cells = next;
+		self.generation += 1;
+	}
+
+	/// Seeds the random number generator used by stochastic rules. Automata start out seeded with 0.
+	pub fn seed_rng(&mut self, seed: u64) {
+		self.rng = Rng::new(seed);
+	}
+
+	/// Get how many generations the automaton has been ticked through.
+	pub fn get_generation(&self) -> u64 {
+		self.generation
+	}
+
+	/// Get the state of a cell, or `None` if it's outside the grid.
+	pub fn get_cell(&self, v: &Vec2) -> Option<u8> {
+		self.cells.get(v.y).and_then(|row| row.get(v.x)).copied()
+	}
+
+	/// Sets the state of a cell. States too big for the rules are clamped to the live state, and cells outside the grid are ignored.
+	pub fn set_cell(&mut self, v: Vec2, state: u8) {
+		let max_state = self.rules.cell_states - 1;
+		if let Some(cell) = self.cells.get_mut(v.y).and_then(|row| row.get_mut(v.x)) { *cell = state.min(max_state); }
+	}
+
+	/// Get every cell in the grid, one row at a time.
+	pub fn get_cells(&self) -> &[[u8; W]; H] {
+		&self.cells
+	}
+
+	/// Get the automaton's rules.
+	pub fn get_rules(&self) -> &AutomataRules {
+		&self.rules
+	}
+}
+
+impl UnboundedAutomaton {
+	/// Creates a new unbounded 2D automaton with the given rules and starting cells.
+	/// Just like with `Automaton::new`, this fails if your rules ask for more neighbors than a cell could have.
+	pub fn new(rules: AutomataRules, start_cells: Vec<IVec2>) -> Result<UnboundedAutomaton, AutomataError> {
+		rules.validate(max_neighbors(&rules.neighbor_method)?, 2)?;
+
+		let live = rules.cell_states - 1;
+		let cells = start_cells.into_iter().map(|v| (v, live)).collect();
+
+		let offsets = neighbor_offsets(&rules.neighbor_method);
+		Ok(UnboundedAutomaton { rules, cells, species: HashMap::new(), offsets, limits: (None, None, GrowthPolicy::Error), width: CoordinateWidth::I64, rng: Rng::new(0) })
+	}
+
+	/// Advances the automaton by one time step (or tick).
+	/// This only fails if the growth limits say to, in which case it gives back an `OutgrewLimits` error and nothing changes.
+	/// Nothing can go past the largest or smallest coordinate there can be, so a pattern that flies that far dies off at the edge instead of wrapping around to the other side.
+	/// 
+	/// ```
+	/// # use cellular_snapp::{presets, flat::{UnboundedAutomaton, IVec2}};
+	/// // a glider heading off past the largest x coordinate
+	/// let edge = IVec2::new(i64::MAX - 2, 0);
+	/// let glider = [IVec2::new(1, 0), IVec2::new(2, 1), IVec2::new(0, 2), IVec2::new(1, 2), IVec2::new(2, 2)];
+	/// let mut life = UnboundedAutomaton::new(presets::life(), glider.iter().map(|v| v.saturating_add(&edge)).collect()).unwrap();
+	/// 
+	/// for _ in 0..40 { life.tick().unwrap(); }
+	/// assert!(life.get_cells().keys().all(|v| v.x() > 0));
+	/// ```
+	pub fn tick(&mut self) -> Result<(), AutomataError> {
+		let offsets = &self.offsets;
+		let neighbor = |v: &IVec2, offset: &IVec2| self.neighbor(v, offset);
+
+		// Dead cells can only change if they're next to something, so only those (and the cells themselves) need checking.
+		let candidates: HashSet<IVec2> = self.cells.keys()
+			.flat_map(|v| offsets.iter().filter_map(move |offset| neighbor(v, offset)).chain(std::iter::once(v.clone())))
+			.collect();
+
+		let mut rng = self.rng.clone();
+		let key = rng.next_u64();
+
+		let mut next = candidates.into_iter().filter_map(|v| {
+			let neighbors = offsets.iter().map(|offset| neighbor(&v, offset).and_then(|n| self.cells.get(&n).copied()).unwrap_or(0));
+			let roll = if self.rules.is_stochastic() { Rng::keyed(key, &[v.x as u64, v.y as u64]).next_f64() } else { 0.0 };
+			let state = self.rules.next_state_from(self.cells.get(&v).copied().unwrap_or(0), neighbors, roll);
+			if state > 0 { Some((v, state)) } else { None }
+		}).collect();
+
+		self.limit(&mut next)?;
+		self.rng = rng;
+		let before = std::mem::replace(&mut self.cells, next);
+
+		if self.rules.species > 1 {
+			let neighbor = |v: &IVec2, offset: &IVec2| self.neighbor(v, offset);
+			self.species = self.rules.inherit_species(&before, &self.cells, &self.species, |v| self.offsets.iter().filter_map(move |offset| neighbor(&v, offset)));
+		}
+
+		Ok(())
+	}
+
+	/// Limits how big the automaton can grow, so rules that explode can't eat up all the memory of a long-running program.
+	/// `max_cells` is the most live and dying cells there can be, and `area` is a box (from its first corner up to but not including its second) that every cell has to stay inside.
+	/// Whenever a tick would go past either of them, the policy says what happens. Automata start out without any limits.
+	/// 
+	/// Cells already past the limits get dealt with right away, just like at the end of a tick, except with `GrowthPolicy::Error`, which leaves them be until the next tick fails.
+	/// 
+	/// ```
+	/// # use cellular_snapp::{presets, AutomataError, GrowthPolicy, flat::{UnboundedAutomaton, IVec2}};
+	/// let glider = vec![IVec2::new(1, 0), IVec2::new(2, 1), IVec2::new(0, 2), IVec2::new(1, 2), IVec2::new(2, 2)];
+	/// let mut life = UnboundedAutomaton::new(presets::life(), glider).unwrap();
+	/// 
+	/// // in an 8x8 torus, the glider flies all the way around and ends up right back where it started
+	/// life.set_growth_limits(None, Some((IVec2::new(0, 0), IVec2::new(8, 8))), GrowthPolicy::Wrap);
+	/// let start = life.get_cells();
+	/// for _ in 0..32 { life.tick().unwrap(); }
+	/// assert_eq!(life.get_cells(), start);
+	/// 
+	/// // a box as big as every coordinate there can be works too
+	/// life.set_growth_limits(None, Some((IVec2::new(i64::MIN, i64::MIN), IVec2::new(i64::MAX, i64::MAX))), GrowthPolicy::Wrap);
+	/// life.tick().unwrap();
+	/// 
+	/// // but an R-pentomino can't get past 6 cells without an error
+	/// let mut r = UnboundedAutomaton::new(presets::life(), presets::r_pentomino().positions().into_iter().map(IVec2::from).collect()).unwrap();
+	/// r.set_growth_limits(Some(6), None, GrowthPolicy::Error);
+	/// assert_eq!(r.tick(), Ok(()));
+	/// assert_eq!(r.tick(), Err(AutomataError::OutgrewLimits));
+	/// assert_eq!(r.occupancy().cells(), 6);
+	/// ```
+	pub fn set_growth_limits(&mut self, max_cells: Option<usize>, area: Option<(IVec2, IVec2)>, policy: GrowthPolicy) {
+		self.limits = (max_cells, area, policy);
+		if policy == GrowthPolicy::Error { return; }
+
+		let mut cells = HashMap::new();
+		for (v, s) in std::mem::take(&mut self.cells) {
+			let state = cells.entry(self.wrapped(v)).or_insert(0);
+			*state = s.max(*state);
+		}
+
+		// every cell is already here, so nothing counts as a newborn
+		self.cells = cells.clone();
+		let _ = self.limit(&mut cells);
+		self.species = std::mem::take(&mut self.species).into_iter().map(|(v, s)| (self.wrapped(v), s)).filter(|(v, _)| cells.contains_key(v)).collect();
+		self.cells = cells;
+	}
+
+	/// Get how many cells there are, the box around them, and roughly how much memory they take up.
+	pub fn occupancy(&self) -> Occupancy<IVec2> {
+		let bounds = self.cells.keys().fold(None, |bounds: Option<(IVec2, IVec2)>, v| Some(match bounds {
+			Some((min, max)) => (IVec2::new(min.x.min(v.x), min.y.min(v.y)), IVec2::new(max.x.max(v.x), max.y.max(v.y))),
+			None => (v.clone(), v.clone())
+		}));
+
+		let memory = (self.cells.capacity() + self.species.capacity()) * (std::mem::size_of::<(IVec2, u8)>() + 1);
+		Occupancy::new(self.cells.len(), bounds, memory)
+	}
+
+	/// Sets how far coordinates can go. A pattern that flies past the edge of the width dies off there, just like at the edge of an `i64`,
+	/// and cells that are already past it die right away. Automata start out with `CoordinateWidth::I64`.
+	/// 
+	/// ```
+	/// # use cellular_snapp::{presets, CoordinateWidth, flat::{UnboundedAutomaton, IVec2}};
+	/// // a glider heading off past the largest i32 x coordinate
+	/// let edge = IVec2::new(i32::MAX as i64 - 2, 0);
+	/// let glider = [IVec2::new(1, 0), IVec2::new(2, 1), IVec2::new(0, 2), IVec2::new(1, 2), IVec2::new(2, 2)];
+	/// let mut life = UnboundedAutomaton::new(presets::life(), glider.iter().map(|v| v.clone() + edge.clone()).collect()).unwrap();
+	/// life.set_coordinate_width(CoordinateWidth::I32);
+	/// 
+	/// for _ in 0..40 { life.tick().unwrap(); }
+	/// assert!(life.get_cells().keys().all(|v| CoordinateWidth::I32.fits(v.x())));
+	/// ```
+	pub fn set_coordinate_width(&mut self, width: CoordinateWidth) {
+		self.width = width;
+		let fits = |v: &IVec2| width.fits(v.x) && width.fits(v.y);
+		self.cells.retain(|v, _| fits(v));
+		self.species.retain(|v, _| fits(v));
+	}
+
+	/// Get how far coordinates can go.
+	pub fn get_coordinate_width(&self) -> CoordinateWidth {
+		self.width
+	}
+
+	/// Finds the neighbor of a cell at an offset, wrapped into the growth limits' box, or `None` if that's past the edge of the coordinates.
+	fn neighbor(&self, v: &IVec2, offset: &IVec2) -> Option<IVec2> {
+		let v = self.wrapped(v.checked_add(offset)?);
+		if self.width.fits(v.x) && self.width.fits(v.y) { Some(v) } else { None }
+	}
+
+	/// Wraps a cell around into the growth limits' box, if they say to wrap and the box isn't empty.
+	fn wrapped(&self, v: IVec2) -> IVec2 {
+		match &self.limits {
+			(_, Some((min, max)), GrowthPolicy::Wrap) if min.x < max.x && min.y < max.y => {
+				IVec2::new(wrap_coordinate(v.x, min.x, max.x), wrap_coordinate(v.y, min.y, max.y))
+			},
+			_ => v
+		}
+	}
+
+	/// Makes the next generation fit inside the growth limits, or gives back an error if the policy says to.
+	/// Anything that isn't already alive counts as a newborn, and those are the first to go.
+	fn limit(&self, next: &mut HashMap<IVec2, u8>) -> Result<(), AutomataError> {
+		let (max_cells, area, policy) = &self.limits;
+		let outside = |v: &IVec2| area.as_ref().is_some_and(|(min, max)| v.x < min.x || v.y < min.y || v.x >= max.x || v.y >= max.y);
+
+		if *policy == GrowthPolicy::Error {
+			let over = max_cells.is_some_and(|max| next.len() > max) || next.keys().any(outside);
+			return if over { Err(AutomataError::OutgrewLimits) } else { Ok(()) };
+		}
+
+		next.retain(|v, _| !outside(v));
+		if let Some(excess) = max_cells.and_then(|max| next.len().checked_sub(max)).filter(|excess| *excess > 0) {
+			let mut doomed: Vec<IVec2> = next.keys().cloned().collect();
+			doomed.sort_by_key(|v| (self.cells.contains_key(v), std::cmp::Reverse((v.y, v.x))));
+			for v in doomed.into_iter().take(excess) { next.remove(&v); }
+		}
+
+		Ok(())
+	}
+
+	/// Seeds the random number generator used by stochastic rules. Automata start out seeded with 0.
+	pub fn seed_rng(&mut self, seed: u64) {
+		self.rng = Rng::new(seed);
+	}
+
+	/// Get a copy of the automaton's live and dying cells. Anything not in here is dead.
+	pub fn get_cells(&self) -> HashMap<IVec2, u8> {
+		self.cells.clone()
+	}
+
+	/// Get the species of every live or dying cell. Cells start out as species 0 unless you say otherwise.
+	pub fn get_species(&self) -> HashMap<IVec2, u8> {
+		self.cells.keys().map(|v| (v.clone(), self.species.get(v).copied().unwrap_or(0))).collect()
+	}
+
+	/// Sets the species of a single live or dying cell. Species too big for the rules are clamped to the last one, and dead cells are ignored.
+	pub fn set_species(&mut self, v: IVec2, species: u8) {
+		if self.cells.contains_key(&v) {
+			self.species.insert(v, species.min(self.rules.species - 1));
+		}
+	}
+
+	/// Get the rules the automaton is currently following.
+	pub fn get_rules(&self) -> &AutomataRules {
+		&self.rules
+	}
+}
+
+impl Pattern {
+	/// Creates a new pattern out of a name and a set of relative coordinates, all of which will be placed as live cells.
+	pub fn new(name: &str, cells: Vec<Vec2>) -> Pattern {
+		Pattern { name: name.to_string(), cells: cells.into_iter().map(|v| (v, None)).collect() }
+	}
+
+	/// Creates a new pattern where every cell carries its own state value.
+	/// When stamped, states are clamped to whatever the automaton's maximum state is, and cells with a state of 0 clear whatever they land on.
+	pub fn with_states(name: &str, cells: Vec<(Vec2, u8)>) -> Pattern {
+		Pattern { name: name.to_string(), cells: cells.into_iter().map(|(v, s)| (v, Some(s))).collect() }
+	}
+
+	/// Reads a pattern out of RLE, the format most Life patterns are shared in.
+	/// The name comes from the `#N` line if there is one, and otherwise is left empty.
+	/// 
+	/// Multi-state patterns use Golly's letters, where `A` is alive and later letters are dying cells.
+	/// Those can only be given the right states if the header includes a rule, so without one every non-dead cell is treated as alive.
+	pub fn from_rle(rle: &str) -> Result<Pattern, AutomataError> {
+		let invalid = |reason: &str| AutomataError::InvalidPattern(reason.to_string());
+		let mut name = String::new();
+		let mut rules: Option<AutomataRules> = None;
+		let mut body = String::new();
+
+		for line in rle.lines().map(str::trim) {
+			if let Some(n) = line.strip_prefix("#N") {
+				name = n.trim().to_string();
+			} else if line.starts_with('#') || line.is_empty() {
+				continue;
+			} else if line.starts_with('x') {
+				if let Some((_, rule)) = line.split_once("rule") {
+					let rule = rule.trim_start_matches([' ', '=']).trim();
+					rules = rule.parse::<AutomataRules>().ok();
+				}
+			} else {
+				body.push_str(line);
+			}
+		}
+
+		let mut cells = Vec::new();
+		let (mut x, mut y) = (0, 0);
+		let mut count = String::new();
+		let mut chars = body.chars();
+
+		while let Some(c) = chars.next() {
+			let run = if c.is_ascii_digit() { 0 } else { count.parse().unwrap_or(1) };
+
+			match c {
+				'0'..='9' => { count.push(c); continue; },
+				'b' | '.' => x += run,
+				'$' => { y += run; x = 0; },
+				'!' => break,
+				c if c.is_whitespace() => continue,
+				'o' | 'A'..='X' | 'p'..='y' => {
+					let golly = match c {
+						'o' => 1,
+						'A'..='X' => c as u32 - 'A' as u32 + 1,
+						_ => match chars.next() {
+							Some(l @ 'A'..='X') => (c as u32 - 'p' as u32 + 1) * 24 + (l as u32 - 'A' as u32) + 1,
+							_ => return Err(invalid("a state prefix must be followed by a state letter"))
+						}
+					};
+
+					let state = match &rules {
+						Some(r) if r.is_table() => Some(golly.min(r.cell_states as u32 - 1) as u8),
+						Some(r) if golly > 1 => Some((r.cell_states as u32).saturating_sub(golly) as u8),
+						_ => None
+					};
+
+					for _ in 0..run {
+						if state != Some(0) { cells.push((Vec2::new(x, y), state)); }
+						x += 1;
+					}
+				},
+				_ => return Err(invalid(&format!("unexpected character '{}'", c)))
+			}
+
+			count.clear();
+		}
+
+		Ok(Pattern { name, cells })
+	}
+
+	/// Reads a pattern out of the Life 1.06 format, which is just a list of `x y` coordinates of live cells, one per line.
+	/// Coordinates can be negative, so the whole pattern is moved so its top-left corner ends up at (0, 0). Life 1.06 files don't have names.
+	pub fn from_life106(text: &str) -> Result<Pattern, AutomataError> {
+		let mut coords = Vec::new();
+
+		for line in text.lines().map(str::trim).filter(|l| !l.is_empty() && !l.starts_with('#')) {
+			let mut parts = line.split_whitespace().map(str::parse::<i64>);
+
+			match (parts.next(), parts.next(), parts.next()) {
+				(Some(Ok(x)), Some(Ok(y)), None) => coords.push(IVec2::new(x, y)),
+				_ => return Err(AutomataError::InvalidPattern(format!("'{}' isn't a pair of coordinates", line)))
+			}
+		}
+
+		Ok(Pattern::new("", normalize(coords)))
+	}
+
+	/// Reads a pattern out of the Life 1.05 format, where `#P x y` lines start blocks of rows drawn with `*` for live cells and `.` for dead ones.
+	/// Just like with Life 1.06, the pattern is moved so its top-left corner ends up at (0, 0). The name is left empty.
+	pub fn from_life105(text: &str) -> Result<Pattern, AutomataError> {
+		let invalid = |reason: &str| AutomataError::InvalidPattern(reason.to_string());
+		let mut coords = Vec::new();
+		let (mut left, mut y) = (0, 0);
+
+		for line in text.lines().map(str::trim).filter(|l| !l.is_empty()) {
+			if let Some(block) = line.strip_prefix("#P") {
+				let mut parts = block.split_whitespace().map(str::parse::<i64>);
+
+				match (parts.next(), parts.next()) {
+					(Some(Ok(x)), Some(Ok(top))) => (left, y) = (x, top),
+					_ => return Err(invalid("#P must be followed by the block's x and y"))
+				}
+			} else if !line.starts_with('#') {
+				for (x, c) in line.chars().enumerate() {
+					match c {
+						'*' => coords.push(IVec2::new(left + x as i64, y)),
+						'.' => {},
+						_ => return Err(invalid(&format!("unexpected character '{}'", c)))
+					}
+				}
+
+				y += 1;
+			}
+		}
+
+		Ok(Pattern::new("", normalize(coords)))
+	}
+
+	/// Reads a pattern out of the plaintext (`.cells`) format, where each row is drawn with `O` for live cells and `.` for dead ones.
+	/// The name comes from the `!Name:` line if there is one, and otherwise is left empty.
+	/// 
+	/// ```
+	/// # use cellular_snapp::{presets, flat::{Automaton, Pattern, Vec2}};
+	/// let glider = Pattern::from_cells("!Name: Glider\n.O.\n..O\nOOO\n").unwrap();
+	/// assert_eq!(glider.name(), "Glider");
+	/// 
+	/// let life = Automaton::new(presets::life(), Vec2::new(3, 3), glider.positions()).unwrap();
+	/// assert_eq!(life.to_cells(), ".O.\n..O\nOOO\n");
+	/// assert_eq!(life.to_life106(), "#Life 1.06\n1 0\n2 1\n0 2\n1 2\n2 2\n");
+	/// assert!(Pattern::from_life106(&life.to_life106()).unwrap().positions().len() == 5);
+	/// assert!(Pattern::from_life105(&life.to_life105()).unwrap().positions().len() == 5);
+	/// ```
+	pub fn from_cells(text: &str) -> Result<Pattern, AutomataError> {
+		let mut name = String::new();
+		let mut cells = Vec::new();
+		let mut y = 0;
+
+		for line in text.lines().map(|l| l.trim_end()) {
+			if let Some(comment) = line.strip_prefix('!') {
+				if let Some(n) = comment.strip_prefix("Name:") { name = n.trim().to_string(); }
+				continue;
+			}
+
+			for (x, c) in line.chars().enumerate() {
+				match c {
+					'O' | '*' => cells.push(Vec2::new(x, y)),
+					'.' => {},
+					_ => return Err(AutomataError::InvalidPattern(format!("unexpected character '{}'", c)))
+				}
+			}
+
+			y += 1;
+		}
+
+		Ok(Pattern::new(&name, cells))
+	}
+
+	/// Get the name of the pattern.
+	pub fn name(&self) -> &str {
+		&self.name
+	}
+
+	/// Get the positions of every cell in the pattern that isn't dead, which you can hand straight to `Automaton::new` as a seed.
+	pub fn positions(&self) -> Vec<Vec2> {
+		self.cells.iter().filter(|(_, s)| *s != Some(0)).map(|(v, _)| *v).collect()
+	}
+
+	/// Get the smallest and largest corners of the box containing the pattern, or None if the pattern is empty.
+	fn corners(&self) -> Option<(Vec2, Vec2)> {
+		let mut cells = self.cells.iter();
+		let (first, _) = cells.next()?;
+		let (mut min, mut max) = (*first, *first);
+
+		for (v, _) in cells {
+			min.x = min.x.min(v.x);
+			min.y = min.y.min(v.y);
+			max.x = max.x.max(v.x);
+			max.y = max.y.max(v.y);
+		}
+
+		Some((min, max))
+	}
+
+	/// Applies a transformation to every cell, given the corners of the pattern's bounding box.
+	fn transform(&self, f: impl Fn(&Vec2, &Vec2, &Vec2) -> Vec2) -> Pattern {
+		match self.corners() {
+			Some((min, max)) => Pattern {
+				name: self.name.clone(),
+				cells: self.cells.iter().map(|(v, s)| (f(v, &min, &max), *s)).collect()
+			},
+			None => self.clone()
+		}
+	}
+
+	/// Rotates the pattern a quarter turn clockwise (with y pointing down, like most screens) inside its bounding box.
+	/// The top-left corner of the bounding box stays put.
+	pub fn rotate90(&self) -> Pattern {
+		self.transform(|v, min, max| Vec2::new(min.x + (max.y - v.y), min.y + (v.x - min.x)))
+	}
+
+	/// Mirrors the pattern along the x axis, flipping it left to right.
+	pub fn mirror_x(&self) -> Pattern {
+		self.transform(|v, min, max| Vec2::new(min.x + max.x - v.x, v.y))
+	}
+
+	/// Mirrors the pattern along the y axis, flipping it top to bottom.
+	pub fn mirror_y(&self) -> Pattern {
+		self.transform(|v, min, max| Vec2::new(v.x, min.y + max.y - v.y))
+	}
+
+	/// Moves every cell in the pattern by the given offset.
+	pub fn translate(&self, offset: Vec2) -> Pattern {
+		Pattern {
+			name: self.name.clone(),
+			cells: self.cells.iter().map(|(v, s)| (*v + offset, *s)).collect()
+		}
+	}
+
+	/// Places the pattern into an automaton, with the pattern's origin at the given offset.
+	/// Any cells that would land outside of the automaton's bounds are skipped.
+	pub fn stamp_into(&self, automaton: &mut Automaton, offset: Vec2) {
+		let max_state = automaton.rules.cell_states - 1;
+
+		for (v, s) in &self.cells {
+			let Some(pos) = v.checked_add_position(&offset) else { continue; };
+
+			if pos.x < automaton.bounds.x && pos.y < automaton.bounds.y {
+				automaton.cells.set(pos, s.map_or(max_state, |s| s.min(max_state)));
+			}
+		}
+	}
+}
+
+//--> Interop <--
+
+#[cfg(feature = "glam")]
+impl From<glam::UVec2> for Vec2 {
+	fn from(v: glam::UVec2) -> Vec2 { Vec2::new(v.x as usize, v.y as usize) }
+}
+
+/// Note that components bigger than `u32::MAX` get truncated.
+#[cfg(feature = "glam")]
+impl From<Vec2> for glam::UVec2 {
+	fn from(v: Vec2) -> glam::UVec2 { glam::UVec2::new(v.x as u32, v.y as u32) }
+}
+
+#[cfg(feature = "glam")]
+impl From<glam::I64Vec2> for IVec2 {
+	fn from(v: glam::I64Vec2) -> IVec2 { IVec2::new(v.x, v.y) }
+}
+
+#[cfg(feature = "glam")]
+impl From<IVec2> for glam::I64Vec2 {
+	fn from(v: IVec2) -> glam::I64Vec2 { glam::I64Vec2::new(v.x, v.y) }
+}
+
+#[cfg(feature = "nalgebra")]
+impl From<nalgebra::Vector2<usize>> for Vec2 {
+	fn from(v: nalgebra::Vector2<usize>) -> Vec2 { Vec2::new(v.x, v.y) }
+}
+
+#[cfg(feature = "nalgebra")]
+impl From<Vec2> for nalgebra::Vector2<usize> {
+	fn from(v: Vec2) -> nalgebra::Vector2<usize> { nalgebra::Vector2::new(v.x, v.y) }
+}
+
+#[cfg(feature = "nalgebra")]
+impl From<nalgebra::Vector2<i64>> for IVec2 {
+	fn from(v: nalgebra::Vector2<i64>) -> IVec2 { IVec2::new(v.x, v.y) }
+}
+
+#[cfg(feature = "nalgebra")]
+impl From<IVec2> for nalgebra::Vector2<i64> {
+	fn from(v: IVec2) -> nalgebra::Vector2<i64> { nalgebra::Vector2::new(v.x, v.y) }
+}
+
+#[cfg(feature = "ndarray")]
+impl Automaton {
+	/// Copies the current state into an ndarray.
+	/// The array is indexed `[[y, x]]`, so rows are y and columns are x, which is what plotting tools expect.
+	pub fn to_array2(&self) -> ndarray::Array2<u8> {
+		let mut array = ndarray::Array2::zeros((self.bounds.y, self.bounds.x));
+
+		for (v, s) in self.cells.live() {
+			array[[v.y, v.x]] = s;
+		}
+
+		array
+	}
+
+	/// Creates a new automaton from an ndarray of cell states, indexed the same way `to_array2` does it.
+	/// The bounds are taken from the shape of the array, and states are clamped to whatever the rules allow.
+	pub fn from_array2(rules: AutomataRules, array: &ndarray::Array2<u8>) -> Result<Automaton, AutomataError> {
+		let (y, x) = array.dim();
+		let mut a = Automaton::new(rules, Vec2::new(x, y), Vec::new())?;
+		let max_state = a.rules.cell_states - 1;
+
+		for ((y, x), s) in array.indexed_iter() {
+			a.cells.set(Vec2::new(x, y), (*s).min(max_state));
+		}
+
+		Ok(a)
+	}
+}
+
+#[cfg(feature = "npy")]
+impl Automaton {
+	/// Writes the current state out as a NumPy `.npy` file of bytes, so `numpy.load` gives back an array of cell states.
+	/// The array is indexed `[y, x]`, just like `to_array2`.
+	/// 
+	/// ```
+	/// # use cellular_snapp::{presets, flat::{Automaton, Vec2}};
+	/// let life = Automaton::new(presets::life(), Vec2::new(12, 8), presets::glider().positions()).unwrap();
+	/// 
+	/// let mut npy = Vec::new();
+	/// life.to_npy(&mut npy).unwrap();
+	/// let loaded = Automaton::from_npy(presets::life(), npy.as_slice()).unwrap();
+	/// assert_eq!(loaded.get_bounds(), &Vec2::new(12, 8));
+	/// assert_eq!(loaded.live_cells_sorted(), life.live_cells_sorted());
+	/// ```
+	pub fn to_npy(&self, mut writer: impl Write) -> io::Result<()> {
+		let mut states = vec![0; self.bounds.x * self.bounds.y];
+		for (v, s) in self.cells.live() { states[v.y * self.bounds.x + v.x] = s; }
+
+		npy::write(&mut writer, &[self.bounds.y, self.bounds.x], &states)
+	}
+
+	/// Creates a new automaton from a NumPy `.npy` file holding a 2D array of `uint8` (or `bool`) cell states, indexed the same way `to_npy` does it.
+	/// The bounds are taken from the shape of the array, and states are clamped to whatever the rules allow.
+	/// Files that can't be read, or that hold anything but a 2D array of bytes, give back an `InvalidPattern` error.
+	pub fn from_npy(rules: AutomataRules, mut reader: impl Read) -> Result<Automaton, AutomataError> {
+		let (shape, states) = npy::read(&mut reader).map_err(AutomataError::InvalidPattern)?;
+		let [y, x] = shape[..] else { return Err(AutomataError::InvalidPattern(format!("the array should have 2 dimensions, but it has {}", shape.len()))); };
+
+		let mut a = Automaton::new(rules, Vec2::new(x, y), Vec::new())?;
+		let max_state = a.rules.cell_states - 1;
+
+		for (i, s) in states.into_iter().enumerate().filter(|(_, s)| *s > 0) {
+			a.cells.set(Vec2::new(i % x, i / x), s.min(max_state));
+		}
+
+		Ok(a)
+	}
+}
+
+/// Reads a black-and-white or grayscale image into a seed you can hand to `Automaton::new`, with one cell per pixel.
+/// Pixels at least as bright as the threshold (from 0 to 255) are alive, which matches how the command line tool draws grids, so its images load straight back in.
+/// Transparent pixels count as dark, and colored pixels go by how bright they look.
+#[cfg(feature = "image")]
+pub fn seed_from_image(path: impl AsRef<std::path::Path>, threshold: u8) -> Result<Vec<Vec2>, AutomataError> {
+	let image = read_image(path)?;
+	Ok(image.enumerate_pixels().filter(|(_, _, p)| p.0[0] >= threshold).map(|(x, y, _)| Vec2::new(x as usize, y as usize)).collect())
+}
+
+/// Reads a grayscale image into a pattern where each pixel's brightness picks its cell's state, from dead for black up to alive for white.
+/// Every pixel's cell is included (dead ones too), so stamping the pattern in overwrites everything underneath it.
+#[cfg(feature = "image")]
+pub fn pattern_from_image(path: impl AsRef<std::path::Path>, cell_states: u8) -> Result<Pattern, AutomataError> {
+	let image = read_image(path)?;
+	let live = cell_states.saturating_sub(1) as u32;
+
+	let cells = image.enumerate_pixels().map(|(x, y, p)| (Vec2::new(x as usize, y as usize), ((p.0[0] as u32 * live + 127) / 255) as u8)).collect();
+	Ok(Pattern::with_states("", cells))
+}
+
+/// Opens an image and turns it into grayscale, with transparent pixels darkened to match.
+#[cfg(feature = "image")]
+fn read_image(path: impl AsRef<std::path::Path>) -> Result<image::GrayImage, AutomataError> {
+	let image = image::open(path).map_err(|e| AutomataError::InvalidPattern(format!("couldn't read the image: {}", e)))?.to_luma_alpha8();
+	Ok(image::GrayImage::from_fn(image.width(), image.height(), |x, y| {
+		let [luma, alpha] = image.get_pixel(x, y).0;
+		image::Luma([(luma as u32 * alpha as u32 / 255) as u8])
+	}))
+}
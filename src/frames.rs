@@ -0,0 +1,267 @@
+//! This module streams every generation of a run out to a file (or anywhere else), so it can be replayed later without simulating it again.
+//! 
+//! Frames are delta-encoded: each one only holds the cells that changed since the frame before, so a pattern that mostly sits still costs next to nothing per frame.
+//! That makes it a good fit for offline rendering, where every frame gets loaded into something like Blender one at a time.
+//! 
+//! ```
+//! # use cellular_snapp::{presets, frames::{FrameReader, FrameWriter}, flat::{Automaton, Vec2}};
+//! let mut life = Automaton::new(presets::life(), Vec2::new(16, 16), presets::glider().positions()).unwrap();
+//! let mut writer = FrameWriter::new(Vec::new());
+//! 
+//! for _ in 0..8 {
+//!     writer.write_frame(&life).unwrap();
+//!     life.tick();
+//! }
+//! 
+//! let bytes = writer.into_inner();
+//! let frames = FrameReader::new(bytes.as_slice()).collect::<Result<Vec<_>, _>>().unwrap();
+//! 
+//! assert_eq!(frames.len(), 8);
+//! assert!(frames.iter().all(|frame| frame.cells().len() == 5));
+//! // the glider moves every tick, but only a handful of cells change each time
+//! assert!(frames[1].changes().len() < 10);
+//! ```
+//! 
+//! A stream starts with the bytes `CFRM`, a version byte, and the bounds of the grid as three little-endian 64-bit numbers (flat grids are 1 cell deep).
+//! Each frame is then a count of changed cells, followed by each changed cell's index in the grid (counting row by row, and then layer by layer)
+//! as a gap from the one before it, and its new state. Counts and gaps are LEB128 varints.
+
+//--> Imports <--
+
+use crate::{flat, deep};
+use crate::checkpoint::{read_varint, write_varint};
+use std::collections::HashMap;
+use std::io::{self, Read, Write};
+
+//--> Structs <--
+
+/// Writes frames out to a stream, one generation at a time.
+pub struct FrameWriter<W: Write> {
+	writer: W,
+	bounds: Option<[usize; 3]>,
+	last: HashMap<[usize; 3], u8>,
+	seen: usize,
+	sample_every: usize
+}
+
+/// Reads frames back in from a stream made by a `FrameWriter`. Every item is the next frame, until the stream runs out.
+pub struct FrameReader<R: Read> {
+	reader: R,
+	bounds: Option<[usize; 3]>,
+	cells: HashMap<[usize; 3], u8>,
+	frame: usize
+}
+
+/// One generation read back from a stream.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Frame {
+	index: usize,
+	cells: HashMap<[usize; 3], u8>,
+	changes: Vec<([usize; 3], u8)>
+}
+
+//--> Functions <--
+
+const MAGIC: &[u8; 4] = b"CFRM";
+const VERSION: u8 = 1;
+
+impl<W: Write> FrameWriter<W> {
+	/// Creates a writer that writes into the given stream. Nothing gets written until the first frame.
+	pub fn new(writer: W) -> FrameWriter<W> {
+		FrameWriter { writer, bounds: None, last: HashMap::new(), seen: 0, sample_every: 1 }
+	}
+
+	/// Only writes every `n`th generation it's given, starting with the first one, so a fast rule can be written out as a time-lapse while it still ticks every step.
+	/// 
+	/// ```
+	/// # use cellular_snapp::{presets, frames::{FrameReader, FrameWriter}, flat::{Automaton, Vec2}};
+	/// let mut life = Automaton::new(presets::life(), Vec2::new(16, 16), presets::glider().positions()).unwrap();
+	/// let mut writer = FrameWriter::new(Vec::new()).sample_every(10);
+	/// 
+	/// for _ in 0..25 {
+	///     writer.write_frame(&life).unwrap();
+	///     life.tick();
+	/// }
+	/// 
+	/// let bytes = writer.into_inner();
+	/// assert_eq!(FrameReader::new(bytes.as_slice()).count(), 3);
+	/// ```
+	pub fn sample_every(mut self, n: usize) -> FrameWriter<W> {
+		self.sample_every = n.max(1);
+		self
+	}
+
+	/// Writes the current generation of a flat automaton as the next frame, unless it's one being skipped by `sample_every`.
+	pub fn write_frame(&mut self, automaton: &flat::Automaton) -> io::Result<()> {
+		let bounds = automaton.get_bounds();
+		let live = automaton.live_cells_in(&flat::Rect2::new(flat::Vec2::new(0, 0), *bounds)).into_iter().map(|(v, s)| ([v.x(), v.y(), 0], s)).collect();
+		self.push([bounds.x(), bounds.y(), 1], live)
+	}
+
+	/// Writes the current generation of a deep automaton as the next frame, unless it's one being skipped by `sample_every`.
+	pub fn write_frame_deep(&mut self, automaton: &deep::Automaton) -> io::Result<()> {
+		let bounds = automaton.get_bounds();
+		let live = automaton.live_cells_in(&deep::Box3::new(deep::Vec3::new(0, 0, 0), *bounds)).into_iter().map(|(v, s)| ([v.x(), v.y(), v.z()], s)).collect();
+		self.push([bounds.x(), bounds.y(), bounds.z()], live)
+	}
+
+	/// Writes a frame out from its live cells and the bounds of the grid. Every frame in a stream has to have the same bounds.
+	fn push(&mut self, bounds: [usize; 3], live: HashMap<[usize; 3], u8>) -> io::Result<()> {
+		let skipped = !self.seen.is_multiple_of(self.sample_every);
+		self.seen += 1;
+		if skipped { return Ok(()); }
+
+		match self.bounds {
+			Some(b) if b != bounds => return Err(io::Error::new(io::ErrorKind::InvalidInput, "every frame in a stream needs the same bounds")),
+			Some(_) => {},
+			None => {
+				self.writer.write_all(MAGIC)?;
+				self.writer.write_all(&[VERSION])?;
+				for b in bounds { self.writer.write_all(&(b as u64).to_le_bytes())?; }
+				self.bounds = Some(bounds);
+			}
+		}
+
+		// cells that died show up as changes to state 0
+		let died = self.last.keys().filter(|v| !live.contains_key(*v)).map(|v| (*v, 0));
+		let changed = live.iter().filter(|(v, s)| self.last.get(*v) != Some(s)).map(|(v, s)| (*v, *s));
+
+		let index = |[x, y, z]: [usize; 3]| ((z as u64 * bounds[1] as u64) + y as u64) * bounds[0] as u64 + x as u64;
+		let mut changes = died.chain(changed).map(|(v, s)| (index(v), s)).collect::<Vec<(u64, u8)>>();
+		changes.sort_unstable();
+
+		write_varint(&mut self.writer, changes.len() as u64)?;
+		let mut last = 0;
+
+		for (i, s) in changes {
+			write_varint(&mut self.writer, i - last)?;
+			self.writer.write_all(&[s])?;
+			last = i;
+		}
+
+		self.last = live;
+		Ok(())
+	}
+
+	/// Flushes the stream, making sure every frame so far has actually been written.
+	pub fn flush(&mut self) -> io::Result<()> {
+		self.writer.flush()
+	}
+
+	/// Gives back the stream the frames were written into.
+	pub fn into_inner(self) -> W {
+		self.writer
+	}
+}
+
+impl<R: Read> FrameReader<R> {
+	/// Creates a reader that reads frames out of the given stream.
+	pub fn new(reader: R) -> FrameReader<R> {
+		FrameReader { reader, bounds: None, cells: HashMap::new(), frame: 0 }
+	}
+
+	/// Get the bounds of the grid the frames are from, or `None` if no frames have been read yet. Flat grids are 1 cell deep.
+	pub fn bounds(&self) -> Option<[usize; 3]> {
+		self.bounds
+	}
+
+	/// Reads the next frame, or gives `None` if the stream has run out.
+	fn read_frame(&mut self) -> io::Result<Option<Frame>> {
+		let bounds = match self.bounds {
+			Some(bounds) => bounds,
+			None => {
+				let mut header = [0; 5];
+				if !read_or_end(&mut self.reader, &mut header)? { return Ok(None); }
+				if &header[..4] != MAGIC { return Err(invalid("this isn't a frame stream")); }
+				if header[4] != VERSION { return Err(invalid(&format!("frame stream version {} isn't supported", header[4]))); }
+
+				let mut bounds = [0; 3];
+
+				for b in bounds.iter_mut() {
+					let mut bytes = [0; 8];
+					self.reader.read_exact(&mut bytes)?;
+					*b = u64::from_le_bytes(bytes) as usize;
+				}
+
+				self.bounds = Some(bounds);
+				bounds
+			}
+		};
+
+		// the stream can only end cleanly between frames
+		let mut first = [0];
+		if !read_or_end(&mut self.reader, &mut first)? { return Ok(None); }
+
+		let count = match first[0] & 0x80 {
+			0 => first[0] as u64,
+			_ => (first[0] & 0x7F) as u64 | read_varint(&mut self.reader)? << 7
+		};
+
+		let (width, height) = (bounds[0] as u64, bounds[1] as u64);
+		let mut changes = Vec::new();
+		let mut index: u64 = 0;
+
+		for _ in 0..count {
+			index = index.checked_add(read_varint(&mut self.reader)?).ok_or_else(|| invalid("a cell is outside the grid"))?;
+
+			let mut state = [0];
+			self.reader.read_exact(&mut state)?;
+
+			let v = [(index % width.max(1)) as usize, (index / width.max(1) % height.max(1)) as usize, (index / (width * height).max(1)) as usize];
+			if v[2] >= bounds[2] { return Err(invalid("a cell is outside the grid")); }
+
+			if state[0] == 0 { self.cells.remove(&v); } else { self.cells.insert(v, state[0]); }
+			changes.push((v, state[0]));
+		}
+
+		self.frame += 1;
+		Ok(Some(Frame { index: self.frame - 1, cells: self.cells.clone(), changes }))
+	}
+}
+
+impl<R: Read> Iterator for FrameReader<R> {
+	type Item = io::Result<Frame>;
+
+	fn next(&mut self) -> Option<io::Result<Frame>> {
+		self.read_frame().transpose()
+	}
+}
+
+impl Frame {
+	/// Get which frame this is, counting from 0.
+	pub fn index(&self) -> usize {
+		self.index
+	}
+
+	/// Get every live or dying cell in this frame, keyed by `[x, y, z]`. Cells from flat grids all have a z of 0.
+	pub fn cells(&self) -> &HashMap<[usize; 3], u8> {
+		&self.cells
+	}
+
+	/// Get just the cells that changed since the frame before, along with their new states. Cells that died have a state of 0.
+	pub fn changes(&self) -> &[([usize; 3], u8)] {
+		&self.changes
+	}
+}
+
+/// Makes an error for a frame stream that doesn't make sense.
+fn invalid(reason: &str) -> io::Error {
+	io::Error::new(io::ErrorKind::InvalidData, format!("couldn't read the frames: {}", reason))
+}
+
+/// Fills the buffer from the stream, or gives back false if the stream was already at its end. Running out partway through is still an error.
+fn read_or_end(reader: &mut impl Read, buf: &mut [u8]) -> io::Result<bool> {
+	let mut read = 0;
+
+	while read < buf.len() {
+		match reader.read(&mut buf[read..]) {
+			Ok(0) if read == 0 => return Ok(false),
+			Ok(0) => return Err(io::ErrorKind::UnexpectedEof.into()),
+			Ok(n) => read += n,
+			Err(e) if e.kind() == io::ErrorKind::Interrupted => {},
+			Err(e) => return Err(e)
+		}
+	}
+
+	Ok(true)
+}
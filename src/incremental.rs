@@ -0,0 +1,98 @@
+//! A grid that keeps every cell's state in one flat array along with how many of its neighbors are counted by the rules, like a life list.
+//! 
+//! Counts only change when a neighbor is born or dies, so instead of counting every cell's neighbors again on every tick,
+//! setting a cell nudges the counts of its neighbors up or down. Every cell whose state or count changed gets marked,
+//! and a cell that wasn't marked has the same state and count it had last tick, so it would stay put again. Ticking only has to look at the marked cells,
+//! which makes ticks take time that scales with how much changed instead of with the size of the grid.
+//! 
+//! The grid doesn't know anything about how cells are laid out, so whoever sets a cell says which cells are its neighbors.
+
+//--> Imports <--
+
+use std::ops::RangeInclusive;
+
+//--> Structs <--
+
+/// A grid of cell states and neighbor counts, with a list of the cells that changed since it was last ticked.
+#[derive(Clone)]
+pub(crate) struct Incremental {
+	cells: Vec<u8>,
+	counts: Vec<u8>,
+	counted: RangeInclusive<u8>,
+	dirty: Vec<usize>,
+	marked: Vec<bool>
+}
+
+//--> Functions <--
+
+impl Incremental {
+	/// Creates a grid of `len` dead cells, where neighbors in the `counted` states get counted. Every cell starts out marked, since nothing has been ticked yet.
+	pub(crate) fn new(len: usize, counted: RangeInclusive<u8>) -> Incremental {
+		Incremental { cells: vec![0; len], counts: vec![0; len], counted, dirty: (0..len).collect(), marked: vec![true; len] }
+	}
+
+	/// Get the state of a cell.
+	pub(crate) fn get(&self, i: usize) -> u8 {
+		self.cells[i]
+	}
+
+	/// Get how many of a cell's neighbors are in a counted state.
+	pub(crate) fn count(&self, i: usize) -> u8 {
+		self.counts[i]
+	}
+
+	/// Sets the state of a cell, given the cells it's a neighbor of (which are the same as its own neighbors, since neighborhoods are symmetric).
+	/// If it starts or stops being counted, their counts change too, and every cell that changed gets marked.
+	pub(crate) fn set(&mut self, i: usize, state: u8, neighbors: impl Iterator<Item = usize>) {
+		let old = std::mem::replace(&mut self.cells[i], state);
+		if old == state { return; }
+		self.mark(i);
+
+		let (was, is) = (self.counted.contains(&old), self.counted.contains(&state));
+		if was == is { return; }
+
+		for n in neighbors {
+			if is { self.counts[n] += 1; } else { self.counts[n] -= 1; }
+			self.mark(n);
+		}
+	}
+
+	/// Takes the list of every marked cell, unmarking them all.
+	pub(crate) fn take_dirty(&mut self) -> Vec<usize> {
+		let dirty = std::mem::take(&mut self.dirty);
+		for i in &dirty { self.marked[*i] = false; }
+		dirty
+	}
+
+	/// Marks every cell, for when the grid got ticked some other way and there's no telling which cells would stay put anymore.
+	pub(crate) fn mark_all(&mut self) {
+		for i in 0..self.cells.len() { self.mark(i); }
+	}
+
+	/// Counts every cell's neighbors again from nothing, with a new set of counted states, and marks every cell.
+	pub(crate) fn recount<N: Iterator<Item = usize>>(&mut self, counted: RangeInclusive<u8>, neighbors: impl Fn(usize) -> N) {
+		self.counted = counted;
+		self.counts.fill(0);
+
+		for i in 0..self.cells.len() {
+			if self.counted.contains(&self.cells[i]) {
+				for n in neighbors(i) { self.counts[n] += 1; }
+			}
+		}
+
+		self.mark_all();
+	}
+
+	/// Get the position and state of every cell that isn't dead, as `(index, state)`.
+	pub(crate) fn live(&self) -> Vec<(usize, u8)> {
+		self.cells.iter().enumerate().filter(|(_, s)| **s > 0).map(|(i, s)| (i, *s)).collect()
+	}
+
+	/// Marks a cell, unless it's already marked.
+	fn mark(&mut self, i: usize) {
+		if !self.marked[i] {
+			self.marked[i] = true;
+			self.dirty.push(i);
+		}
+	}
+}
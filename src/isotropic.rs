@@ -0,0 +1,106 @@
+//! Isotropic non-totalistic rules, written in Hensel notation (like `B2-a/S12`).
+//! 
+//! Instead of just counting neighbors, these rules look at how the neighbors are arranged. Each letter after a neighbor count picks out one arrangement
+//! (along with all of its rotations and reflections), so `B2a` is only born when the two neighbors are next to each other.
+
+//--> Imports <--
+
+use std::fmt;
+
+//--> Structs <--
+
+/// Lookup tables saying which arrangements of live Moore neighbors a cell is born or survives with.
+/// Arrangements are indexed with one bit per neighbor, in the same order as `flat::neighbor_offsets`.
+#[derive(Clone, PartialEq)]
+pub(crate) struct Isotropic {
+	pub(crate) born: [bool; 256],
+	pub(crate) survive: [bool; 256],
+	notation: (String, String)
+}
+
+//--> Functions <--
+
+/// One arrangement for each letter of each neighbor count from 1 to 4. Bits go clockwise from north: N, NE, E, SE, S, SW, W, NW.
+/// Arrangements for 5 to 7 neighbors are the opposites of the ones for 3 to 1, and use the same letters.
+const ARRANGEMENTS: [(u8, char, u8); 31] = [
+	(1, 'c', 0b00000010), (1, 'e', 0b00000001),
+	(2, 'a', 0b00000011), (2, 'c', 0b00001010), (2, 'e', 0b00000101), (2, 'i', 0b00010001), (2, 'k', 0b00001001), (2, 'n', 0b00100010),
+	(3, 'a', 0b00000111), (3, 'c', 0b00101010), (3, 'e', 0b00010101), (3, 'i', 0b10000011), (3, 'j', 0b01000011),
+	(3, 'k', 0b00100101), (3, 'n', 0b00001011), (3, 'q', 0b00100011), (3, 'r', 0b00010011), (3, 'y', 0b00101001),
+	(4, 'a', 0b00001111), (4, 'c', 0b10101010), (4, 'e', 0b01010101), (4, 'i', 0b00011011), (4, 'j', 0b01010011),
+	(4, 'k', 0b01001011), (4, 'n', 0b10001011), (4, 'q', 0b00100111), (4, 'r', 0b00010111), (4, 't', 0b10010011),
+	(4, 'w', 0b01100011), (4, 'y', 0b00101011), (4, 'z', 0b00110011)
+];
+
+impl Isotropic {
+	/// Parses the birth and survival parts of a Hensel rule (without the `B` and `S`), like `2-a` and `12`.
+	pub(crate) fn parse(born: &str, survive: &str) -> Option<Isotropic> {
+		Some(Isotropic { born: parse_part(born)?, survive: parse_part(survive)?, notation: (born.to_string(), survive.to_string()) })
+	}
+
+	/// Get the birth and survival parts back out, the same way they were written.
+	pub(crate) fn notation(&self) -> (&str, &str) {
+		(&self.notation.0, &self.notation.1)
+	}
+}
+
+/// Only the notation gets shown, since the tables are just that notation spelled out.
+impl fmt::Debug for Isotropic {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "Isotropic(B{}/S{})", self.notation.0, self.notation.1)
+	}
+}
+
+/// Get every letter used for the given neighbor count, along with its arrangement.
+/// Having no neighbors (or all of them) only has the one arrangement, which doesn't get a letter.
+fn arrangements(count: u8) -> Vec<(char, u8)> {
+	match count {
+		0 => vec![(' ', 0)],
+		8 => vec![(' ', 0b11111111)],
+		_ => {
+			let (lookup, flip) = if count > 4 { (8 - count, true) } else { (count, false) };
+			ARRANGEMENTS.iter().filter(|(c, _, _)| *c == lookup).map(|(_, l, bits)| (*l, if flip { !bits } else { *bits })).collect()
+		}
+	}
+}
+
+/// Parses one part of a Hensel rule into a lookup table.
+fn parse_part(part: &str) -> Option<[bool; 256]> {
+	let mut table = [false; 256];
+	let mut chars = part.chars().peekable();
+
+	while let Some(c) = chars.next() {
+		let count = c.to_digit(10).filter(|d| *d <= 8)? as u8;
+		let negate = chars.next_if_eq(&'-').is_some();
+
+		let mut picked = Vec::new();
+		while let Some(l) = chars.next_if(|l| l.is_ascii_lowercase()) { picked.push(l); }
+		if negate && picked.is_empty() { return None; }
+
+		let all = arrangements(count);
+		if picked.iter().any(|l| !all.iter().any(|(letter, _)| letter == l)) { return None; }
+
+		for (letter, bits) in all {
+			if !picked.is_empty() && picked.contains(&letter) == negate { continue; }
+
+			for bits in symmetries(bits) {
+				table[to_offset_order(bits) as usize] = true;
+			}
+		}
+	}
+
+	Some(table)
+}
+
+/// Get every rotation and reflection of an arrangement.
+fn symmetries(bits: u8) -> Vec<u8> {
+	// reflecting across the north-south line swaps the neighbors on either side of north
+	let reflected = (0..8).fold(0u8, |r, i| if bits & 1 << i != 0 { r | 1 << ((8 - i) % 8) } else { r });
+	(0..4).flat_map(|turn| [bits.rotate_left(turn * 2), reflected.rotate_left(turn * 2)]).collect()
+}
+
+/// Converts clockwise-from-north bits into the order `flat::neighbor_offsets` gives neighbors in: NW, W, SW, N, S, NE, E, SE.
+fn to_offset_order(bits: u8) -> u8 {
+	const POSITIONS: [u8; 8] = [3, 5, 6, 7, 4, 2, 1, 0];
+	(0..8).fold(0u8, |r, i| if bits & 1 << i != 0 { r | 1 << POSITIONS[i] } else { r })
+}
@@ -6,7 +6,34 @@
 
 //--> Imports <--
 
-use std::ops::Range;
+use std::ops::{Range, RangeInclusive};
+use std::fmt;
+use std::error::Error;
+use std::str::FromStr;
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::ops::ControlFlow;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+#[cfg(feature = "profiling")]
+use std::time::{Duration, Instant};
+use isotropic::Isotropic;
+use ruletable::{Lookup, RuleTable};
+use random::Rng;
+
+mod isotropic;
+mod ruletable;
+mod tree;
+mod bits;
+mod dense;
+mod incremental;
+#[cfg(all(feature = "mmap", unix))]
+mod mapped;
+mod checkpoint;
+#[cfg(feature = "noise")]
+mod noise;
+#[cfg(feature = "npy")]
+mod npy;
 
 /// Create flat (2D) cellular automata.
 pub mod flat;
@@ -14,6 +41,84 @@ pub mod flat;
 /// Create deep (3D) cellular automata.
 pub mod deep;
 
+/// Well-known rules and patterns.
+pub mod presets;
+
+/// Seedable random numbers.
+pub mod random;
+
+/// Ready-made forest-fire and epidemic models.
+pub mod models;
+
+/// Create continuous-state automata, like SmoothLife and Lenia.
+pub mod continuous;
+
+/// Create block (Margolus) automata, like Critters and the billiard ball model.
+pub mod block;
+
+/// Turmites (like Langton's Ant) that walk around on flat automata.
+pub mod agents;
+
+/// Working out what patterns do, like finding oscillators and spaceships.
+pub mod analysis;
+
+/// Searching through random soups to see what objects a rule makes.
+pub mod search;
+
+/// Recording per-generation statistics, like population, births, and deaths.
+pub mod stats;
+
+/// Streaming delta-encoded frames of a run to disk, and replaying them without simulating it again.
+pub mod frames;
+
+/// Replay logs, for running stochastic automata again exactly the same way, and checking runs against hashes from a known good one.
+pub mod replay;
+
+/// Running an automaton on a background thread, with play, pause and step commands and a channel of snapshots.
+pub mod runner;
+
+/// Tools for working with rules themselves, like searching through them for interesting ones.
+pub mod rules;
+
+/// Colors for every state a cell can be in, with ready-made gradients for drawing dying cells fading out.
+pub mod palette;
+
+/// Stacks of flat automata where each layer's cells can depend on the other layers, for things like predator and prey models.
+pub mod multilayer;
+
+/// Compact deltas with sequence numbers, for keeping copies of an automaton on different machines in sync.
+pub mod sync;
+
+/// Tiles of huge 3D grids with ghost cells around them, for splitting a grid up between processes and swapping the cells along their faces.
+pub mod tiling;
+
+/// Cells that carry their own data along with their state, like a species, an energy level, or an age.
+pub mod cellstate;
+
+/// Running lots of small flat automata with the same rules at once, for searches that spend all their time on tiny grids.
+pub mod batch;
+
+/// Audit trails of ticks, rule changes, and edits, which can be drained as a list or written out as JSON lines.
+pub mod events;
+
+/// Following patterns around as they move, so a renderer can keep a spaceship centered.
+pub mod motion;
+
+/// Names, descriptions, and discoverers of well-known rules, looked up by rule string or by name.
+pub mod registry;
+
+/// Python bindings.
+#[cfg(feature = "python")]
+pub mod python;
+
+/// JavaScript bindings for running in the browser.
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+/// A C interface for embedding in other languages and engines.
+#[cfg(feature = "ffi")]
+pub mod ffi;
+
 //--> Structs <--
 
 /// Any cellular automata has four different rules that govern how it works.
@@ -26,56 +131,2446 @@ pub mod deep;
 /// 
 /// You can think of these states as the amount of time steps (or ticks) it takes for a cell to die.
 /// Given a cell which is alive but no longer has enough neighbors to survive, its state value will be decremented to 0 (dead) each tick.
-#[derive(Clone)]
+/// Whether those dying cells still count as neighbors (or can be saved) along the way is up to the decay mode.
+#[derive(Clone, Debug)]
 pub struct AutomataRules {
     to_survive: Rule,
     to_be_born: Rule,
     cell_states: u8,
-    neighbor_method: Method
+    neighbor_method: Method,
+    decay_mode: DecayMode,
+    birth_chance: f64,
+    survival_chance: f64,
+    table: Option<TransitionTable>,
+    species: u8,
+    inheritance: Inheritance,
+    isotropic: Option<Isotropic>,
+    directions: Option<Directions>,
+    vote: Option<TieBreak>,
+    count_self: bool,
+    weights: Option<Vec<u8>>
+}
+
+/// Which neighbors count toward a cell being born, and which count toward it surviving, with a flag for every neighbor in the same order as the neighbor offsets.
+#[derive(Clone, PartialEq, Debug)]
+struct Directions {
+    birth: Vec<bool>,
+    survival: Vec<bool>
+}
+
+/// One entry in a transition table, saying what a cell in one state turns into.
+/// 
+/// Transition tables are for automata like Wireworld, where what happens to a cell depends on which named state it's in rather than just how long it's been dying.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Transition {
+    from: u8,
+    condition: Option<(u8, Rule)>,
+    to: u8
+}
+
+/// A named list of transitions, used in place of birth and survival rules.
+/// Tables loaded from Golly come with a lookup table instead.
+#[derive(Clone, PartialEq, Debug)]
+struct TransitionTable {
+    name: String,
+    transitions: Vec<Transition>,
+    lookup: Option<Lookup>
+}
+
+/// How far along a long-running operation (like `tick_n_with` or a soup search) is, handed to its progress callback every step of the way.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Progress {
+    done: usize,
+    total: usize
+}
+
+/// A flag for stopping long-running operations early, which can be flipped from anywhere (like a cancel button on another thread).
+/// Clones all share the same flag, so hand a clone to whatever needs to cancel and check the original in the progress callback.
+/// 
+/// ```
+/// # use cellular_snapp::{presets, CancelToken, flat::{Automaton, Vec2}};
+/// let token = CancelToken::new();
+/// let mut life = Automaton::new(presets::life(), Vec2::new(32, 32), presets::glider().positions()).unwrap();
+/// 
+/// let canceller = token.clone();
+/// let done = life.tick_n_with(100, |progress| {
+///     if progress.done() == 10 { canceller.cancel(); }
+///     token.check()
+/// });
+/// 
+/// assert_eq!(done, 10);
+/// assert_eq!(life.get_generation(), 10);
+/// ```
+#[derive(Clone, Default, Debug)]
+pub struct CancelToken {
+    cancelled: Arc<AtomicBool>
 }
 
+/// How long each part of an automaton's last tick took, and how many cells it looked at, for tuning big simulations. This needs the `profiling` feature.
+/// 
+/// Neighbors get counted and the rules get applied to them in the same pass over the grid, so they're timed together as stepping.
+#[cfg(feature = "profiling")]
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub struct TickProfile {
+    total: Duration,
+    stepping: Duration,
+    swapping: Duration,
+    bookkeeping: Duration,
+    cells: usize
+}
+
+/// How much room an unbounded automaton is taking up, for keeping an eye on long-running simulations.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Occupancy<V> {
+    cells: usize,
+    bounds: Option<(V, V)>,
+    memory: usize
+}
+
+/// Keeps track of the `TickProfile` of the last tick. Without the `profiling` feature, there's nothing in it and it never looks at the clock.
+#[derive(Clone, Default)]
+pub(crate) struct Profiler {
+    #[cfg(feature = "profiling")]
+    last: TickProfile,
+    #[cfg(feature = "profiling")]
+    marks: Option<(Instant, Instant)>
+}
+
+/// Memory that gets held onto between ticks so it doesn't have to be allocated all over again, like the grid from the generation before last.
+/// What's in it is never worth copying, so cloning one just gives back an empty one.
+pub(crate) struct Spare<T>(pub(crate) Option<T>);
+
 //--> Enums <--
 
 /// Any cellular automata has two rules that care about neighbors.
 /// One rule says how many neighbors a cell needs to be born, and another says how many neighbors a cell needs to continue living.
 /// This enum is used to tell the automata how many neighbor counts are valid for a given rule.
-#[derive(Clone)]
+#[derive(Clone, Debug)]
 pub enum Rule {
     /// This rule only matches a single count of neighbors.
     Single(u8),
     /// This rule matches a consecutive set of neighbor counts.
     /// Note that this range is inclusive on the start and exclusive on the end, so a range of 3..5 will include the values 3 and 4.
     Range(Range<u8>),
+    /// This rule matches a consecutive set of neighbor counts, including both ends, so a range of 3..=5 will include the values 3, 4, and 5.
+    /// This is usually what you want when copying a rule out of B/S notation.
+    RangeInclusive(RangeInclusive<u8>),
     /// This rule matches a non-consecutive set of neighbor counts.
     Many(Vec<u8>)
 }
 
-/// Any cellular automata has one of two ways to determine whether any given cell is the neighbor of any other cell.
+/// Any cellular automata has a way to determine whether any given cell is the neighbor of any other cell.
 /// This enum allows choosing which method is used by an automaton to determine neighbors.
-#[derive(Clone)]
+#[derive(Clone, PartialEq, Debug)]
 pub enum Method {
     /// The Moore method counts any cell as a neighbor of a given cell if that cell is next to it, even if they don't share a face.
     /// More mathmatically, if any two cells have coordinates that are only off by one from each-other for any given component, they are neighbors.
     Moore,
     /// The Von Neumann method counts any cell as a neighbor of a given cell if the cells share a face, or are touching.
-    VonNeumann
+    VonNeumann,
+    /// This method counts any cell as a neighbor of a given cell if the cells share a face or an edge, which gives each cell 18 neighbors in 3D.
+    /// More mathmatically, the cells are next to each other and their coordinates are off by one in no more than two components.
+    /// Every neighbor in a flat grid shares at least an edge, so in 2D this would just be Moore. Flat automata (and the models in `models`) won't take it, and give back an `UnsupportedFacesAndEdges` error instead.
+    /// 
+    /// ```
+    /// # use cellular_snapp::{AutomataError, AutomataRules, Method, deep::{Automaton, Vec3}};
+    /// let too_many = AutomataRules::new(19, 19, 2, Method::FacesAndEdges);
+    /// assert_eq!(Automaton::new(too_many, Vec3::new(8, 8, 8), Vec::new()).err(), Some(AutomataError::TooManyNeighbors(18)));
+    /// 
+    /// // a 3x3x3 cube with its corners cut off is every neighbor of the middle cell, plus the middle cell itself
+    /// let cube = (0..27).map(|i| Vec3::new(i % 3 + 1, i / 3 % 3 + 1, i / 9 + 1)).filter(|v| [v.x(), v.y(), v.z()].iter().filter(|c| **c == 2).count() > 0).collect();
+    /// let mut a = Automaton::new(AutomataRules::new(18, 18, 2, Method::FacesAndEdges), Vec3::new(4, 4, 4), cube).unwrap();
+    /// a.tick();
+    /// assert_eq!(a.get_cell(&Vec3::new(2, 2, 2)), Some(1));
+    /// assert_eq!(a.get_cell(&Vec3::new(1, 1, 2)), Some(0));
+    /// 
+    /// # use cellular_snapp::flat;
+    /// let flat_rules = AutomataRules::new(2..=3, 3, 2, Method::FacesAndEdges);
+    /// assert_eq!(flat::Automaton::new(flat_rules, flat::Vec2::new(8, 8), Vec::new()).err(), Some(AutomataError::UnsupportedFacesAndEdges));
+    /// ```
+    FacesAndEdges,
+    /// This method counts any cell as a neighbor of a given cell if it's within the given distance (the radius) as the crow flies,
+    /// which makes for a circle of neighbors in 2D and a ball of them in 3D, like the ones Larger than Life's Bugs use.
+    /// Neighbor counts have to fit in a `u8`, so a radius of about 9 is as big as it gets in 2D, and about 3.9 in 3D.
+    /// 
+    /// ```
+    /// # use cellular_snapp::{AutomataError, AutomataRules, Method, flat::{Automaton, Vec2}};
+    /// // rules like Larger than Life's Bugs, on a disc of radius 5
+    /// let bugs = AutomataRules::new(34..=58, 34..=45, 2, Method::Euclidean(5.0));
+    /// assert!(Automaton::new(bugs, Vec2::new(32, 32), Vec::new()).is_ok());
+    /// 
+    /// let too_big = AutomataRules::new(3, 3, 2, Method::Euclidean(10.0));
+    /// assert_eq!(Automaton::new(too_big, Vec2::new(32, 32), Vec::new()).err(), Some(AutomataError::NeighborhoodTooBig(316)));
+    /// ```
+    Euclidean(f64)
+}
+
+/// Automata with more than two cell states have dying cells, and there's more than one way to treat them.
+/// This enum says whether dying cells count as live neighbors, and whether they can be brought back to life.
+/// You can also pick one by those two choices with `AutomataRules::with_dying_counts_as_neighbor` and `AutomataRules::with_dying_can_recover`.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum DecayMode {
+    /// Dying cells still count as live neighbors, and always finish dying. This is the default.
+    Counted,
+    /// Dying cells don't count as neighbors at all, and always finish dying.
+    /// This matches Golly's Generations rules, like Brian's Brain and Star Wars.
+    Generations,
+    /// Dying cells still count as live neighbors, and a dying cell that has the right amount of neighbors to survive comes back to life.
+    Revivable,
+    /// Dying cells don't count as neighbors at all, but a dying cell that has the right amount of live neighbors to survive comes back to life.
+    RevivableGenerations
+}
+
+/// When cells belong to different species (or colors), this says which species a newborn cell ends up as, based on its live neighbors (its parents).
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Inheritance {
+    /// Newborns take whichever species most of their parents are. Ties go to the lowest species. This is how Immigration works.
+    Majority,
+    /// Like `Majority`, except that if no two parents share a species, the newborn takes the lowest species none of its parents are.
+    /// This is how QuadLife works, where three parents of three different colors give birth to the fourth color.
+    MajorityOrMissing
+}
+
+/// What a cell's state means under a set of rules, so you don't have to compare states against `states() - 1` yourself.
+/// 
+/// ```
+/// # use cellular_snapp::{presets, CellView};
+/// let brain = presets::brians_brain();
+/// assert_eq!(brain.view(0), CellView::Dead);
+/// assert_eq!(brain.view(2), CellView::Alive);
+/// assert_eq!(brain.view(1), CellView::Dying { remaining: 1 });
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CellView {
+    /// The cell is dead (state 0).
+    Dead,
+    /// The cell is alive, in the highest state.
+    Alive,
+    /// The cell is dying, and will be dead after `remaining` more ticks unless something saves it. This is the same as its state.
+    Dying { remaining: u8 }
+}
+
+/// When a vote rule has more than one state with the most votes, this says which one wins.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum TieBreak {
+    /// The cell stays in whatever state it's in, as long as that's one of the states that tied. Otherwise it goes to the lowest of them.
+    Keep,
+    /// One of the states that tied gets picked at random, which makes the rules stochastic.
+    Random,
+    /// The highest of the states that tied wins, so with just two states, ties always come out alive.
+    PreferAlive
+}
+
+/// When resizing an automaton, this says which part of the grid stays put while the rest grows or shrinks around it.
+/// The same anchor is used on every axis.
+#[derive(Clone, Copy)]
+pub enum Anchor {
+    /// Keep the start (the zero side) of each axis where it is, so cells keep their coordinates.
+    Start,
+    /// Keep the middle of the grid in the middle, growing or shrinking evenly on both sides.
+    Center,
+    /// Keep the end (the far side) of each axis where it is.
+    End
+}
+
+/// When pasting a pattern into an automaton, this says how the pattern's cells get combined with the cells already there.
+#[derive(Clone, Copy)]
+pub enum BlendMode {
+    /// The pattern's cells replace whatever was there, dead cells included, so a copied region pastes back exactly as it was.
+    Overwrite,
+    /// Each cell ends up with whichever of the two states is bigger, so nothing already there gets cleared.
+    /// With two cell states, a cell is alive if it's alive in either.
+    Or,
+    /// A cell ends up dead if it's live (or dying) in both, and otherwise takes whichever state isn't dead.
+    /// Pasting the same pattern twice puts everything back the way it was, and pasting a pattern over its own mirror image makes symmetric soups.
+    Xor
+}
+
+/// When merging one automaton into another, this says which cell wins wherever both of them have a live or dying cell.
+#[derive(Clone, Copy)]
+pub enum ConflictPolicy {
+    /// Whichever cell has the bigger state wins, so live cells beat dying ones.
+    KeepMax,
+    /// The cell that was already there wins.
+    PreferSelf,
+    /// The cell being merged in wins.
+    PreferOther
+}
+
+/// When masking an automaton with a pattern, this says how the pattern's live cells get combined with the automaton's live and dying cells.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum MaskOp {
+    /// Only cells inside the mask stay, and everything else dies, which is handy for cutting the grid down to one area.
+    And,
+    /// Dead cells inside the mask come alive, and cells that were already live or dying stay as they are.
+    Or,
+    /// Cells inside the mask flip, so dead ones come alive and live or dying ones die. Masking twice puts everything back the way it was (apart from dying cells).
+    Xor
+}
+
+/// How a wall cell looks to the cells around it. Walls never get changed by the rules, whatever their neighbors are doing.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Wall {
+    /// The wall is a fully alive cell, and counts as a live neighbor.
+    Alive,
+    /// The wall is a dead cell, so it never counts as a neighbor, and nothing can be born there.
+    Dead,
+    /// The wall keeps whatever state it had when it was put up, but the cells around it act like it isn't there at all.
+    Excluded
+}
+
+/// What order the cells get updated in during a tick. Asynchronous automata can behave completely differently from synchronous ones with the same rules,
+/// since every cell sees the cells that were updated before it this tick, instead of the generation before.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum UpdateScheme {
+    /// Every cell works out its next state from the generation before, all at once. This is the default, and how almost every automaton is defined.
+    Synchronous,
+    /// Cells get updated one at a time, in a random order that gets shuffled again every tick. The order only depends on the given seed and the generation.
+    RandomAsync(u64),
+    /// Cells get updated one at a time, row by row (and in 3D, layer by layer), starting from the smallest corner.
+    Sequential
+}
+
+/// What an unbounded automaton does when a tick would take it past its growth limits.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum GrowthPolicy {
+    /// The tick fails with an `OutgrewLimits` error, and the automaton is left just like it was before it.
+    Error,
+    /// Cells outside the box are killed, and if there are still too many cells, newborns are killed (the ones furthest along, row by row, first) until there aren't.
+    ClampAndKill,
+    /// The box wraps around like a torus, so cells leaving one side come back in on the other, and cells near an edge see the ones across from them.
+    /// If there are too many cells, newborns are killed just like with `ClampAndKill`.
+    Wrap
+}
+
+/// How far an unbounded automaton's coordinates can go. They're always kept as `i64`s, but a narrower width makes a pattern die off at the edge of that range instead,
+/// so positions can be handed to something that only has room for `i32`s (like a GPU buffer or a file format) without ever being cut off.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum CoordinateWidth {
+    /// Every coordinate fits in an `i32`.
+    I32,
+    /// Every coordinate fits in an `i64`, which is as far as they can go. This is the default.
+    #[default]
+    I64
+}
+
+/// Where an automaton keeps its cells. Every backend ticks the same way, they just trade memory and speed off differently.
+/// Some backends are only there with some features turned on, so matches on this need a catch-all arm.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[non_exhaustive]
+pub enum Backend {
+    /// Live and dying cells get an entry in a hash map, and every cell without one is dead, so memory scales with how much is alive instead of with the size of the grid.
+    /// Ticks only look at the cells next to something live. This is the default.
+    HashMap,
+    /// Only live and dying cells are kept, in a quadtree (or an octree in 3D), so memory and tick time scale with the size of the pattern instead of the size of the grid.
+    /// This is the one to pick for huge grids that are mostly empty, and it's quick at finding the cells in a region.
+    Tree,
+    /// Every cell gets a single bit, which takes 8 times less memory than a byte and lets whole words of cells be ticked at once.
+    /// This only works for rules with two cell states.
+    BitPacked,
+    /// Every cell gets a byte in one big array, row after row. Whole rows have their neighbors counted at once with SIMD adds,
+    /// which makes this the fastest backend for grids that are full of activity.
+    Dense,
+    /// Every cell gets a byte in one big array, along with a count of its live neighbors that gets changed whenever a neighbor is born or dies,
+    /// instead of every neighbor being counted again on every tick. Ticks only look at the cells whose state or count just changed,
+    /// so they take time that scales with how much is going on instead of with the size of the grid, which is great for patterns that have mostly settled down.
+    /// Rules that need more than a neighbor count, rule regions, and fields fall back to ticking every cell near a live one.
+    Incremental,
+    /// Every cell gets a byte just like with `Dense`, except the array is kept in a memory-mapped file in the temporary directory instead of in memory,
+    /// for grids that don't fit in RAM, like 8192 by 8192 multi-state grids or big 3D volumes. Ticks go through the grid in tiles a few thousand cells wide, row by row,
+    /// so the operating system only has to keep the rows of the tile being worked on in memory. This trades a lot of speed for how big a grid can be.
+    /// 
+    /// This needs the `mmap` feature and only works on Unix. Creating an automaton gives back a `MappingFailed` error if the file can't be made, like when the disk is full
+    /// (or on anything but Unix). If another file can't be made later on, ticks change the grid in place a cell at a time instead, and copies of the grid are kept in memory.
+    #[cfg(feature = "mmap")]
+    Mapped
+}
+
+/// Things that can go wrong when creating or changing an automaton.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AutomataError {
+    /// One of the rules asks for more neighbors than a cell could possibly have, given the neighbor method.
+    /// This contains the maximum amount of neighbors a cell can have.
+    TooManyNeighbors(u8),
+    /// A rule string couldn't be understood. This contains the string in question.
+    InvalidRuleString(String),
+    /// A pattern file (like RLE) couldn't be understood. This contains what went wrong.
+    InvalidPattern(String),
+    /// A transition table uses a state that's too big for the rules. This contains the state in question.
+    InvalidState(u8),
+    /// A Golly rule table couldn't be understood. This contains what went wrong.
+    InvalidRuleTable(String),
+    /// The rules can't be stored in the chosen backend, like rules with more than two cell states in a bit-packed grid. This contains the backend in question.
+    UnsupportedBackend(Backend),
+    /// Rules for part of a grid don't fit with the rest of the grid's rules, since they need the same amount of cell states and the same neighbor method.
+    /// This contains the rules that don't fit.
+    MismatchedRules(String),
+    /// The neighbor method gives cells more neighbors than can be counted, since neighbor counts have to fit in a `u8`. This contains how many neighbors it gives.
+    NeighborhoodTooBig(usize),
+    /// A replay log couldn't be understood, or doesn't fit the automaton it's being replayed into. This contains what went wrong.
+    InvalidReplay(String),
+    /// Directional rules were used in a 3D automaton, where their directions don't make sense.
+    UnsupportedDirections,
+    /// A layer of a multilayer automaton doesn't exist. This contains the index that was asked for.
+    NoSuchLayer(usize),
+    /// A delta for syncing an automaton couldn't be understood, or doesn't fit the automaton it's being applied to. This contains what went wrong.
+    InvalidDelta(String),
+    /// A delta turned up out of order, so at least one before it was missed. This contains the sequence number that was expected, and the one that turned up.
+    MissedDelta(u64, u64),
+    /// A slice of cells for the face of a tile is the wrong length. This contains how long it should be.
+    WrongSliceLength(usize),
+    /// Rules have fewer than two cell states, so there's no telling live cells from dead ones. This contains how many states they have.
+    TooFewStates(u8),
+    /// The rules can't be written out in the chosen format, like rules with chances in a Golly rule table. This contains why not.
+    UnsupportedRule(String),
+    /// The automaton can't be run backwards, since more than one generation could have come before this one (or the one before isn't remembered).
+    NotReversible,
+    /// A tick would have taken an unbounded automaton past its growth limits, so it didn't happen.
+    OutgrewLimits,
+    /// The file a memory-mapped grid is kept in couldn't be made. This contains what went wrong.
+    MappingFailed(String),
+    /// The faces and edges neighbor method was used in a flat automaton or model, where every neighbor shares an edge and it would just be Moore.
+    UnsupportedFacesAndEdges
+}
+
+//--> Traits <--
+
+/// Everything flat and deep automata have in common, so tools that don't care how many dimensions there are only need writing once.
+/// 
+/// ```
+/// # use cellular_snapp::{presets, CellularAutomaton, flat, deep};
+/// fn population_after(mut automaton: impl CellularAutomaton, ticks: usize) -> usize {
+///     for _ in 0..ticks { automaton.tick(); }
+///     automaton.live_cells().len()
+/// }
+/// 
+/// let blinker = vec![flat::Vec2::new(1, 2), flat::Vec2::new(2, 2), flat::Vec2::new(3, 2)];
+/// assert_eq!(population_after(flat::Automaton::new(presets::life(), flat::Vec2::new(5, 5), blinker).unwrap(), 3), 3);
+/// assert_eq!(population_after(deep::Automaton::new(presets::life(), deep::Vec3::new(5, 5, 5), Vec::new()).unwrap(), 3), 0);
+/// ```
+pub trait CellularAutomaton {
+    /// Where a cell is, like a `flat::Vec2` or a `deep::Vec3`.
+    type Coord: Copy + Eq + Hash + fmt::Debug;
+
+    /// Advances by one tick.
+    fn tick(&mut self);
+
+    /// Get every live or dying cell. Anything not in here is dead.
+    fn live_cells(&self) -> HashMap<Self::Coord, u8>;
+
+    /// Get the state of a single cell, or `None` if it's outside the grid.
+    fn get_cell(&self, v: &Self::Coord) -> Option<u8>;
+
+    /// Sets the state of a single cell. Cells outside the grid are ignored.
+    fn set_cell(&mut self, v: Self::Coord, state: u8);
+
+    /// Get the size of the grid.
+    fn bounds(&self) -> Self::Coord;
+
+    /// Get how many generations it's been ticked through.
+    fn generation(&self) -> u64;
+
+    /// Get the rules it follows.
+    fn rules(&self) -> &AutomataRules;
 }
 
 //--> Functions <--
 
 impl AutomataRules {
     /// Creates a new set of cellular automaton rules.
-    pub fn new(to_survive: Rule, to_be_born: Rule, cell_states: u8, neighbor_method: Method) -> AutomataRules {
+    /// The survival and birth rules can be anything that turns into a `Rule`, like a single count, a range, or a list of counts.
+    /// 
+    /// Cells are dead in state 0 and alive in the highest state (one less than `cell_states`). With 2 states, that's all there is, and live cells without
+    /// the right amount of neighbors die straight away. With more, they count down through the states in between (the dying states) before they're dead.
+    /// Rules need at least 2 states, so automata won't take rules with fewer, and give back a `TooFewStates` error instead. Use `try_new` to find out straight away.
+    /// 
+    /// ```
+    /// # use cellular_snapp::{AutomataRules, Method};
+    /// // Conway's Game of Life
+    /// let life = AutomataRules::new(2..=3, 3, 2, Method::Moore);
+    /// assert_eq!(life.to_string(), "B3/S23");
+    /// 
+    /// // HighLife
+    /// let highlife = AutomataRules::new(2..=3, [3, 6].as_slice(), 2, Method::Moore);
+    /// assert_eq!(highlife.to_string(), "B36/S23");
+    /// ```
+    pub fn new(to_survive: impl Into<Rule>, to_be_born: impl Into<Rule>, cell_states: u8, neighbor_method: Method) -> AutomataRules {
+        AutomataRules::new_const(to_survive.into(), to_be_born.into(), cell_states, neighbor_method)
+    }
+
+    /// Creates a new set of rules just like `new`, but gives back a `TooFewStates` error right away if `cell_states` is 0 or 1,
+    /// instead of waiting until the rules are given to an automaton.
+    /// 
+    /// ```
+    /// # use cellular_snapp::{AutomataError, AutomataRules, Method};
+    /// assert!(AutomataRules::try_new(2..=3, 3, 2, Method::Moore).is_ok());
+    /// assert_eq!(AutomataRules::try_new(2..=3, 3, 1, Method::Moore).err(), Some(AutomataError::TooFewStates(1)));
+    /// ```
+    pub fn try_new(to_survive: impl Into<Rule>, to_be_born: impl Into<Rule>, cell_states: u8, neighbor_method: Method) -> Result<AutomataRules, AutomataError> {
+        if cell_states < 2 { return Err(AutomataError::TooFewStates(cell_states)) }
+        Ok(AutomataRules::new(to_survive, to_be_born, cell_states, neighbor_method))
+    }
+
+    /// Creates a new set of rules just like `new`, except the survival and birth rules have to be `Rule`s already, so it works in `const` items.
+    /// `Rule::Many` needs a `Vec`, which can only be empty in a constant, so rules with counts that aren't all in a row have to go through `new` instead.
+    /// 
+    /// ```
+    /// # use cellular_snapp::{AutomataRules, Method, Rule};
+    /// const LIFE: AutomataRules = AutomataRules::new_const(Rule::RangeInclusive(2..=3), Rule::Single(3), 2, Method::Moore);
+    /// assert_eq!(LIFE.to_string(), "B3/S23");
+    /// ```
+    pub const fn new_const(to_survive: Rule, to_be_born: Rule, cell_states: u8, neighbor_method: Method) -> AutomataRules {
         AutomataRules {
             to_survive,
             to_be_born,
             cell_states,
-            neighbor_method
+            neighbor_method,
+            decay_mode: DecayMode::Counted,
+            birth_chance: 1.0,
+            survival_chance: 1.0,
+            table: None,
+            species: 1,
+            inheritance: Inheritance::Majority,
+            isotropic: None,
+            directions: None,
+            vote: None,
+            count_self: false,
+            weights: None
+        }
+    }
+
+    /// Makes sure the rules have at least 2 states, and that the survival and birth rules don't ask for more than `max_neighbors` neighbors
+    /// (or one more, for rules that count themselves), giving the rules right back if they're fine.
+    /// Automata check all of this (and more) when they're made anyway, but in a `const` item this happens while compiling,
+    /// so a typo in a built-in rule stops the build instead of turning into an error at runtime. Anything wrong panics with what it was.
+    /// 
+    /// ```
+    /// # use cellular_snapp::{AutomataRules, Method, Rule};
+    /// const SEEDS: AutomataRules = AutomataRules::new_const(Rule::Many(Vec::new()), Rule::Single(2), 2, Method::Moore).checked(8);
+    /// assert_eq!(SEEDS.to_string(), "B2/S");
+    /// ```
+    /// 
+    /// ```compile_fail
+    /// # use cellular_snapp::{AutomataRules, Method, Rule};
+    /// // nothing in a flat Moore neighborhood has 9 neighbors
+    /// const BROKEN: AutomataRules = AutomataRules::new_const(Rule::Single(9), Rule::Single(3), 2, Method::Moore).checked(8);
+    /// # let _ = BROKEN;
+    /// ```
+    pub const fn checked(self, max_neighbors: u8) -> AutomataRules {
+        if self.cell_states < 2 { panic!("rules need at least 2 states"); }
+
+        let most = max_neighbors.saturating_add(self.count_self as u8);
+        if self.to_survive.most() > most { panic!("the survival rule asks for more neighbors than a cell can have"); }
+        if self.to_be_born.most() > most { panic!("the birth rule asks for more neighbors than a cell can have"); }
+
+        self
+    }
+
+    /// Creates rules out of a transition table instead of birth and survival rules.
+    /// 
+    /// Every tick, each cell goes through the transitions in order and takes the first one that matches its state (and neighbors).
+    /// If none of them match, the cell stays in whatever state it was in. Cells spawned by a seed are put in the highest state, one less than `cell_states`.
+    /// The name is just used when writing the rules out, since there's no rule string for transition tables. Just like with `new`, automata won't take these with fewer than 2 states.
+    pub fn from_transitions(name: &str, cell_states: u8, neighbor_method: Method, transitions: Vec<Transition>) -> AutomataRules {
+        AutomataRules {
+            table: Some(TransitionTable { name: name.to_string(), transitions, lookup: None }),
+            ..AutomataRules::new(Rule::Many(Vec::new()), Rule::Many(Vec::new()), cell_states, neighbor_method)
+        }
+    }
+
+    /// Creates vote rules, where every cell takes whichever state is the most common out of itself and its neighbors, and ties are broken with `tie_break`.
+    /// States don't mean alive or dying here, they're just colors (like with transition tables), and every one of them gets a vote.
+    /// Just like with `new`, automata won't take these with fewer than 2 states.
+    /// 
+    /// ```
+    /// # use cellular_snapp::{AutomataRules, Method, TieBreak, flat::{Automaton, Vec2}};
+    /// let rules = AutomataRules::vote(2, Method::Moore, TieBreak::Keep);
+    /// assert_eq!(rules.to_string(), "Vote/2/M/keep");
+    /// 
+    /// // a lone live cell gets outvoted, but a dead cell surrounded by five live ones gets voted in
+    /// let mut automaton = Automaton::new(rules, Vec2::new(5, 5), vec![Vec2::new(0, 0), Vec2::new(1, 3), Vec2::new(2, 3), Vec2::new(3, 3), Vec2::new(1, 4), Vec2::new(3, 4)]).unwrap();
+    /// automaton.tick();
+    /// assert_eq!(automaton.get_cell(&Vec2::new(0, 0)), Some(0));
+    /// assert_eq!(automaton.get_cell(&Vec2::new(2, 4)), Some(1));
+    /// ```
+    pub fn vote(cell_states: u8, neighbor_method: Method, tie_break: TieBreak) -> AutomataRules {
+        AutomataRules {
+            vote: Some(tie_break),
+            ..AutomataRules::new(Rule::Many(Vec::new()), Rule::Many(Vec::new()), cell_states, neighbor_method)
+        }
+    }
+
+    /// Get how ties are broken, or `None` if these aren't vote rules.
+    pub fn tie_break(&self) -> Option<TieBreak> {
+        self.vote
+    }
+
+    /// Loads rules out of a Golly rule table (the `@TABLE` part of a `.rule` file), so you can run multi-state rules written for Golly.
+    /// Just like with `from_transitions`, cells the table doesn't cover stay in whatever state they're in, and seeded cells go in the highest state.
+    /// 
+    /// Only the Moore and von Neumann neighborhoods are supported, and rule tables only work with flat automata, since their neighbors are listed in 2D.
+    /// 
+    /// ```
+    /// # use cellular_snapp::{AutomataRules, deep, flat::{Automaton, Vec2}};
+    /// let table = "@RULE Spread\n@TABLE\nn_states:2\nneighborhood:vonNeumann\nsymmetries:rotate4\n0,1,0,0,0,1";
+    /// let rules = AutomataRules::from_rule_table(table).unwrap();
+    /// assert_eq!(rules.to_string(), "Spread");
+    /// 
+    /// // dead cells with a live neighbor to the north (or any other side, thanks to the symmetry) come alive
+    /// let mut a = Automaton::new(rules.clone(), Vec2::new(3, 3), vec![Vec2::new(1, 1)]).unwrap();
+    /// a.tick();
+    /// assert_eq!(a.get_cell(&Vec2::new(1, 0)), Some(1));
+    /// assert_eq!(a.get_cell(&Vec2::new(0, 1)), Some(1));
+    /// assert_eq!(a.get_cell(&Vec2::new(0, 0)), Some(0));
+    /// 
+    /// // a 3D von Neumann neighborhood has 6 neighbors, which a table written for 4 can't look up
+    /// assert!(deep::Automaton::new(rules, deep::Vec3::new(3, 3, 3), Vec::new()).is_err());
+    /// ```
+    pub fn from_rule_table(text: &str) -> Result<AutomataRules, AutomataError> {
+        let t = RuleTable::parse(text).map_err(AutomataError::InvalidRuleTable)?;
+        if t.cell_states < 2 { return Err(AutomataError::TooFewStates(t.cell_states)) }
+
+        Ok(AutomataRules {
+            table: Some(TransitionTable { name: t.name, transitions: Vec::new(), lookup: Some(t.lookup) }),
+            ..AutomataRules::new(Rule::Many(Vec::new()), Rule::Many(Vec::new()), t.cell_states, t.neighbor_method)
+        })
+    }
+
+    /// Writes the rules out as a Golly `.rule` file, so rules found or tuned here can be opened in Golly (or anything else that reads rule tables) and shared.
+    /// Every neighborhood that changes a cell gets its own transition, so rules with lots of states make big files.
+    /// 
+    /// States are numbered the way Golly numbers them, just like `to_rle` does: 1 is alive, counting up as cells die.
+    /// Rules loaded from rule tables and transition tables are written as-is, since their states already mean whatever the table says.
+    /// 
+    /// Rule tables only work with Moore and von Neumann neighborhoods, and can't have chances, species, or neighbors that count differently depending on where they are,
+    /// so rules like that give back an `UnsupportedRule` error.
+    /// 
+    /// ```
+    /// # use cellular_snapp::{presets, AutomataRules, flat::{Automaton, Vec2}};
+    /// let table = presets::life().to_rule_table().unwrap();
+    /// assert!(table.starts_with("@RULE B3_S23\n"));
+    /// 
+    /// // the table runs exactly the same way as the rules it came from
+    /// let glider = presets::glider().positions();
+    /// let mut life = Automaton::new(presets::life(), Vec2::new(16, 16), glider.clone()).unwrap();
+    /// let mut loaded = Automaton::new(AutomataRules::from_rule_table(&table).unwrap(), Vec2::new(16, 16), glider).unwrap();
+    /// for _ in 0..20 { life.tick(); loaded.tick(); }
+    /// assert!(life.get_cells() == loaded.get_cells());
+    /// ```
+    pub fn to_rule_table(&self) -> Result<String, AutomataError> {
+        let unsupported = |why: &str| Err(AutomataError::UnsupportedRule(why.to_string()));
+        if self.cell_states < 2 { return Err(AutomataError::TooFewStates(self.cell_states)) }
+
+        let neighbors = match self.neighbor_method {
+            Method::Moore => 8,
+            Method::VonNeumann => 4,
+            Method::FacesAndEdges | Method::Euclidean(_) => return unsupported("rule tables only work with Moore and von Neumann neighborhoods")
+        };
+
+        if self.is_stochastic() { return unsupported("rule tables can't have chances"); }
+        if self.species > 1 { return unsupported("rule tables can't have species"); }
+        if self.isotropic.is_some() || self.directions.is_some() { return unsupported("rule tables can't count neighbors differently depending on where they are"); }
+        if self.weights.is_some() { return unsupported("rule tables can't weight neighbors"); }
+
+        let method = if neighbors == 8 { Method::Moore } else { Method::VonNeumann };
+
+        if let Some(TransitionTable { name, lookup: Some(lookup), .. }) = &self.table {
+            let (transitions, symmetries) = lookup.transitions(&method);
+            return Ok(ruletable::write(name, self.cell_states, &method, symmetries, &transitions));
+        }
+
+        // every neighborhood the rules could see, which has to stay small enough to write out
+        let neighborhoods = (0..neighbors as u128).fold(1u128, |n, i| n * (self.cell_states as u128 + i) / (i + 1));
+        if neighborhoods * self.cell_states as u128 > 1_000_000 { return unsupported("there are too many states to write out every neighborhood"); }
+
+        let golly = |state: u8| if self.is_table() || state == 0 { state } else { self.cell_states - state };
+
+        let mut transitions = Vec::new();
+        for multiset in ruletable::neighbor_multisets(neighbors, self.cell_states) {
+            for state in 0..self.cell_states {
+                let next = self.next_state_from(state, multiset.iter().copied(), 0.0);
+                if next != state { transitions.push(std::iter::once(golly(state)).chain(multiset.iter().map(|n| golly(*n))).chain(std::iter::once(golly(next))).collect()); }
+            }
+        }
+
+        // Golly's rule names can't have anything but letters, numbers, dashes, and underscores
+        let name: String = self.to_string().chars().map(|c| if c.is_ascii_alphanumeric() || c == '-' { c } else { '_' }).collect();
+
+        Ok(ruletable::write(&name, self.cell_states, &method, "permute", &transitions))
+    }
+
+    /// Makes the rules stochastic. A dead cell with the right amount of neighbors is only born with a `birth` chance,
+    /// and a live cell with the right amount of neighbors only survives with a `survival` chance (otherwise it starts dying).
+    /// Both should be between 0 and 1, and rules start out with both at 1.
+    /// 
+    /// The random numbers come from a generator stored on each automaton, so seeding it the same way gives the same results.
+    pub const fn with_chances(mut self, birth: f64, survival: f64) -> AutomataRules {
+        self.birth_chance = birth;
+        self.survival_chance = survival;
+        self
+    }
+
+    /// Makes the rules directional, so only the neighbors at the `birth` offsets count toward a dead cell being born,
+    /// and only the ones at the `survival` offsets count toward a live cell surviving. Offsets are `(x, y)`, and y goes down, so `(0, -1)` is the neighbor above.
+    /// Offsets that aren't in the neighborhood are left out, and the neighbor counts in the rules are counts of just the neighbors that are left.
+    /// 
+    /// This lets rules tell directions apart, which totalistic rules can't, for things like grains that fall and flows that only go one way.
+    /// Directions only work with birth and survival rules in flat automata, and transition tables and isotropic rules ignore them.
+    /// Rule strings can't hold directions either, so they get left out when the rules are written out (checkpoints and replay logs still keep them).
+    /// 
+    /// ```
+    /// # use cellular_snapp::{AutomataRules, Method, flat::{Automaton, Vec2}};
+    /// // a grain is born under a grain, and only stays put if there's one under it
+    /// let sand = AutomataRules::new(1, 1, 2, Method::Moore).with_directions(&[(0, -1)], &[(0, 1)]);
+    /// let mut grains = Automaton::new(sand, Vec2::new(5, 5), vec![Vec2::new(2, 0), Vec2::new(4, 3), Vec2::new(4, 4)]).unwrap();
+    /// grains.tick();
+    /// 
+    /// // the lone grain fell, and the grain resting on the other one stayed put (but the one on the bottom fell off the grid)
+    /// assert_eq!(grains.get_cell(&Vec2::new(2, 1)), Some(1));
+    /// assert_eq!(grains.get_cell(&Vec2::new(2, 0)), Some(0));
+    /// assert_eq!(grains.get_cell(&Vec2::new(4, 3)), Some(1));
+    /// ```
+    pub fn with_directions(mut self, birth: &[(i64, i64)], survival: &[(i64, i64)]) -> AutomataRules {
+        let offsets = flat::neighbor_offsets(&self.neighbor_method);
+        let mask = |directions: &[(i64, i64)]| offsets.iter().map(|offset| directions.contains(&(offset.x(), offset.y()))).collect();
+        self.directions = Some(Directions { birth: mask(birth), survival: mask(survival) });
+        self
+    }
+
+    /// Checks whether the rules tell apart neighbors in different directions.
+    pub fn is_directional(&self) -> bool {
+        self.directions.is_some()
+    }
+
+    /// Get the flags saying which neighbors count toward being born and which count toward surviving, in the same order as the neighbor offsets.
+    pub(crate) fn direction_masks(&self) -> Option<(&[bool], &[bool])> {
+        self.directions.as_ref().map(|d| (&d.birth[..], &d.survival[..]))
+    }
+
+    /// Puts back direction flags gotten from `direction_masks`, like when loading a saved run.
+    pub(crate) fn with_direction_masks(mut self, birth: Vec<bool>, survival: Vec<bool>) -> AutomataRules {
+        self.directions = Some(Directions { birth, survival });
+        self
+    }
+
+    /// Get the chance of a dead cell with the right amount of neighbors being born.
+    pub fn birth_chance(&self) -> f64 {
+        self.birth_chance
+    }
+
+    /// Get the chance of a live cell with the right amount of neighbors surviving.
+    pub fn survival_chance(&self) -> f64 {
+        self.survival_chance
+    }
+
+    /// Checks whether the given backend can hold cells for these rules.
+    pub(crate) fn check_backend(&self, backend: Backend) -> Result<(), AutomataError> {
+        match backend {
+            Backend::BitPacked if self.cell_states != 2 => Err(AutomataError::UnsupportedBackend(backend)),
+            _ => Ok(())
+        }
+    }
+
+    /// Get the next state for every cell state and every neighbor count from 0 up to `max`, indexed by `state * (max + 1) + count`.
+    /// Rules that need more than a neighbor count to work out a cell's next state, like tables, isotropic rules, and birth or survival chances, give `None`.
+    pub(crate) fn count_table(&self, max: u8) -> Option<Vec<u8>> {
+        if self.table.is_some() || self.isotropic.is_some() || self.directions.is_some() || self.weights.is_some() || self.is_stochastic() { return None; }
+
+        // votes only come down to a count when there's just one state to count
+        if let Some(tie_break) = self.vote {
+            if self.cell_states > 2 { return None; }
+
+            return Some((0..self.cell_states).flat_map(|state| {
+                (0..=max).map(move |n| vote_state(state, std::iter::repeat_n(1, n as usize).chain(std::iter::repeat_n(0, (max - n) as usize)), 0.0, tie_break))
+            }).collect())
+        }
+
+        Some((0..self.cell_states).flat_map(|state| {
+            let own = (self.count_self && self.counts_as_neighbor(state)) as u8;
+            (0..=max).map(move |n| self.next_state(state, self.to_be_born.matches(n + own), self.to_survive.matches(n + own), 0.0, (self.birth_chance, self.survival_chance)))
+        }).collect())
+    }
+
+    /// Get the states that count as live neighbors, which always run from some state up to the live state.
+    pub(crate) fn counted_states(&self) -> RangeInclusive<u8> {
+        if self.dying_counts_as_neighbor() { 1..=self.cell_states - 1 } else { self.cell_states - 1..=self.cell_states - 1 }
+    }
+
+    /// Checks whether these rules need random numbers at all.
+    pub(crate) fn is_stochastic(&self) -> bool {
+        self.table.is_none() && (self.birth_chance < 1.0 || self.survival_chance < 1.0 || self.vote == Some(TieBreak::Random))
+    }
+
+    /// Takes each cell's state from the generation before away from what the rules say its next state is, for second-order automata.
+    pub(crate) fn second_order<V: Eq + Hash + Clone>(&self, mut next: HashMap<V, u8>, previous: &HashMap<V, u8>) -> HashMap<V, u8> {
+        // sparse grids leave dead cells out, so anything that was only alive the generation before needs to be filled in
+        for v in previous.keys() { next.entry(v.clone()).or_insert(0); }
+
+        next.into_iter().map(|(v, s)| {
+            let before = previous.get(&v).copied().unwrap_or(0);
+            let state = (s as u16 + self.cell_states as u16 - before as u16) % self.cell_states as u16;
+            (v, state as u8)
+        }).collect()
+    }
+
+    /// Gives live cells a species (or color), with the given amount of species to go around.
+    /// Species don't change which cells live or die, but newborn cells inherit a species from their parents, and cells keep their species until they die.
+    /// Rules start out with just the one species, which turns all of this off.
+    pub const fn with_species(mut self, species: u8, inheritance: Inheritance) -> AutomataRules {
+        self.species = if species == 0 { 1 } else { species };
+        self.inheritance = inheritance;
+        self
+    }
+
+    /// Get the amount of species live cells can be.
+    pub fn species(&self) -> u8 {
+        self.species
+    }
+
+    /// Get how newborn cells pick their species.
+    pub fn inheritance(&self) -> Inheritance {
+        self.inheritance
+    }
+
+    /// Works out the species of every cell that's alive after a tick. Cells that were already around keep their species,
+    /// and newborns inherit one from the neighbors (given by `parents_of`) that counted towards their birth.
+    pub(crate) fn inherit_species<V: Eq + Hash + Clone, P: Iterator<Item = V>>(&self, before: &HashMap<V, u8>, after: &HashMap<V, u8>, species: &HashMap<V, u8>, parents_of: impl Fn(V) -> P) -> HashMap<V, u8> {
+        after.iter().filter(|(_, s)| **s > 0).map(|(v, _)| {
+            let species = match (before.get(v), species.get(v)) {
+                (Some(old), Some(species)) if *old > 0 => *species,
+                _ => {
+                    let parents = parents_of(v.clone())
+                        .filter(|p| before.get(p).is_some_and(|s| self.weight(*s) > 0))
+                        .map(|p| species.get(&p).copied().unwrap_or(0));
+
+                    self.inherit(parents)
+                }
+            };
+
+            (v.clone(), species)
+        }).collect()
+    }
+
+    /// Picks a newborn's species out of its parents' species.
+    fn inherit(&self, parents: impl Iterator<Item = u8>) -> u8 {
+        // there can't be more than 255 species, so the counts fit on the stack
+        let mut counts = [0u16; 256];
+        let counts = &mut counts[..self.species as usize];
+        for p in parents { if let Some(c) = counts.get_mut(p as usize) { *c += 1; } }
+
+        // max_by_key picks the last of any ties, so go backwards to get the lowest species
+        let (majority, most) = counts.iter().enumerate().rev().max_by_key(|(_, c)| **c).map_or((0, 0), |(s, c)| (s as u8, *c));
+
+        match self.inheritance {
+            Inheritance::MajorityOrMissing if most < 2 => counts.iter().position(|c| *c == 0).map_or(majority, |s| s as u8),
+            _ => majority
+        }
+    }
+
+    /// Checks whether these rules treat states as plain colors instead of alive and dying, like transition tables and vote rules do.
+    pub(crate) fn is_table(&self) -> bool {
+        self.table.is_some() || self.vote.is_some()
+    }
+
+    /// Changes how dying cells are treated. Rules start out with `DecayMode::Counted`.
+    pub const fn with_decay_mode(mut self, decay_mode: DecayMode) -> AutomataRules {
+        self.decay_mode = decay_mode;
+        self
+    }
+
+    /// Get how dying cells are treated.
+    pub fn decay_mode(&self) -> DecayMode {
+        self.decay_mode
+    }
+
+    /// Changes whether dying cells count as live neighbors, keeping whether they can recover the way it was.
+    /// This is just another way of picking a `DecayMode`, for when you know the exact semantics your reference rule expects but not which mode that is.
+    /// Rules start out counting dying cells, like `DecayMode::Counted`.
+    /// 
+    /// ```
+    /// # use cellular_snapp::{AutomataRules, DecayMode, Method};
+    /// let rules = AutomataRules::new(2..=3, 3, 4, Method::Moore).with_dying_counts_as_neighbor(false);
+    /// assert_eq!(rules.decay_mode(), DecayMode::Generations);
+    /// 
+    /// let rules = rules.with_dying_can_recover(true);
+    /// assert_eq!(rules.decay_mode(), DecayMode::RevivableGenerations);
+    /// assert!(!rules.dying_counts_as_neighbor() && rules.dying_can_recover());
+    /// ```
+    pub const fn with_dying_counts_as_neighbor(self, counted: bool) -> AutomataRules {
+        let recover = self.dying_can_recover();
+        self.with_decay_mode(DecayMode::from_semantics(counted, recover))
+    }
+
+    /// Changes whether a dying cell with the right amount of neighbors to survive comes back to life, keeping whether dying cells count as neighbors the way it was.
+    /// Rules start out letting dying cells always finish dying, like `DecayMode::Counted`.
+    pub const fn with_dying_can_recover(self, recover: bool) -> AutomataRules {
+        let counted = self.dying_counts_as_neighbor();
+        self.with_decay_mode(DecayMode::from_semantics(counted, recover))
+    }
+
+    /// Checks whether dying cells count as live neighbors.
+    pub const fn dying_counts_as_neighbor(&self) -> bool {
+        matches!(self.decay_mode, DecayMode::Counted | DecayMode::Revivable)
+    }
+
+    /// Checks whether a dying cell with the right amount of neighbors to survive comes back to life, instead of always finishing dying.
+    pub const fn dying_can_recover(&self) -> bool {
+        matches!(self.decay_mode, DecayMode::Revivable | DecayMode::RevivableGenerations)
+    }
+
+    /// Makes each cell count itself as one of its own neighbors (as long as it's in a state that gets counted), like some Larger than Life rules do.
+    /// This works the same in both flat and deep automata, and the rules can ask for one more neighbor than the neighborhood has, since the cell itself is in there too.
+    /// Isotropic rules and transition tables don't go by neighbor counts, so they ignore this, and vote rules always count themselves anyway.
+    /// Rules start out not counting themselves.
+    /// 
+    /// ```
+    /// # use cellular_snapp::{presets, AutomataRules, flat::{Automaton, Vec2}};
+    /// // counting itself, a live cell survives with 3 or 4, which is just Life
+    /// let rules = "B3/S34".parse::<AutomataRules>().unwrap().with_count_self(true);
+    /// assert_eq!(rules.to_string(), "B3/S34/M+");
+    /// 
+    /// let glider = vec![Vec2::new(1, 0), Vec2::new(2, 1), Vec2::new(0, 2), Vec2::new(1, 2), Vec2::new(2, 2)];
+    /// let mut a = Automaton::new(rules, Vec2::new(10, 10), glider.clone()).unwrap();
+    /// let mut life = Automaton::new(presets::life(), Vec2::new(10, 10), glider).unwrap();
+    /// for _ in 0..8 { a.tick(); life.tick(); }
+    /// assert_eq!(a.live_cells_sorted(), life.live_cells_sorted());
+    /// ```
+    pub const fn with_count_self(mut self, count_self: bool) -> AutomataRules {
+        self.count_self = count_self;
+        self
+    }
+
+    /// Checks whether each cell counts itself as one of its neighbors.
+    pub fn counts_self(&self) -> bool {
+        self.count_self
+    }
+
+    /// Checks whether a cell in the given state counts as a live neighbor.
+    pub(crate) fn counts_as_neighbor(&self, state: u8) -> bool {
+        if self.dying_counts_as_neighbor() { state > 0 } else { state == self.cell_states - 1 }
+    }
+
+    /// Weights each neighbor by its state, so a neighbor counts as however much `weights` gives back for its state instead of just 1 or 0.
+    /// The birth and survival rules then go by the total, which is how Generations-style rules where dying (refractory) cells only half count get written,
+    /// by giving live cells 2 and dying cells 1 and doubling the counts in the rules. Cells that count themselves get weighted too.
+    /// 
+    /// This takes over from the decay mode for working out which neighbors count, though dying cells still decay (or get rescued) the way it says.
+    /// Isotropic rules, transition tables, and vote rules don't go by neighbor counts, so they ignore this.
+    /// The weights get worked out for every state up front, so rules with the same weights are equal even if they came from different functions.
+    /// Weighted rules can't be written out as a rule string, so they get left out along with directions (checkpoints and replay logs still keep them), and counting them can't be sped up with lookup tables.
+    /// 
+    /// ```
+    /// # use cellular_snapp::{AutomataRules, Method, flat::{Automaton, Vec2}};
+    /// // live cells count 2 and dying cells count 1, and a dead cell is born with a total of 4
+    /// let rules = AutomataRules::new(4..=6, 4, 3, Method::Moore).with_neighbor_weights(|state| state);
+    /// 
+    /// // one live and two dying neighbors add up to 4, even though that's only 3 neighbors
+    /// let mut a = Automaton::new(rules, Vec2::new(5, 5), vec![Vec2::new(1, 1)]).unwrap();
+    /// a.set_cell(Vec2::new(3, 1), 1);
+    /// a.set_cell(Vec2::new(2, 3), 1);
+    /// a.tick();
+    /// assert_eq!(a.get_cell(&Vec2::new(2, 2)), Some(2));
+    /// ```
+    pub fn with_neighbor_weights(mut self, weights: fn(u8) -> u8) -> AutomataRules {
+        self.weights = Some((0..=255).map(weights).collect());
+        self
+    }
+
+    /// Checks whether the rules weight neighbors by their state.
+    pub fn is_weighted(&self) -> bool {
+        self.weights.is_some()
+    }
+
+    /// Get the weight of every state, or `None` if the rules don't weight neighbors.
+    pub(crate) fn weight_table(&self) -> Option<&[u8]> {
+        self.weights.as_deref()
+    }
+
+    /// Puts back a weight for every state gotten from `weight_table`, like when loading a saved run.
+    pub(crate) fn with_weight_table(mut self, weights: Vec<u8>) -> AutomataRules {
+        self.weights = Some(weights);
+        self
+    }
+
+    /// Get how much a neighbor in the given state counts for, which is just 1 or 0 (whether it counts as a live neighbor) unless the rules weight neighbors.
+    pub fn weight(&self, state: u8) -> u8 {
+        match &self.weights {
+            Some(weights) => weights[state as usize],
+            None => self.counts_as_neighbor(state) as u8
+        }
+    }
+
+    /// Get the amount of states a cell can be in. Live cells have a state of one less than this.
+    pub fn states(&self) -> u8 {
+        self.cell_states
+    }
+
+    /// Works out what a state means under these rules. States too big for the rules count as alive.
+    /// Transition tables and vote rules don't have dying cells, so every state but 0 is alive for them.
+    pub fn view(&self, state: u8) -> CellView {
+        match state {
+            0 => CellView::Dead,
+            _ if self.is_table() || state >= self.cell_states.saturating_sub(1) => CellView::Alive,
+            _ => CellView::Dying { remaining: state }
+        }
+    }
+
+    /// Get the rule saying how many neighbors a live cell needs to survive.
+    /// Transition tables and isotropic rules don't go by neighbor counts alone, so theirs is empty.
+    pub fn survival(&self) -> &Rule {
+        &self.to_survive
+    }
+
+    /// Get the rule saying how many neighbors a dead cell needs to be born.
+    /// Transition tables and isotropic rules don't go by neighbor counts alone, so theirs is empty.
+    pub fn birth(&self) -> &Rule {
+        &self.to_be_born
+    }
+
+    /// Get how the rules decide which cells are neighbors.
+    pub fn method(&self) -> &Method {
+        &self.neighbor_method
+    }
+
+    /// Checks whether a live cell with the given amount of live neighbors survives, ignoring the survival chance.
+    /// With isotropic rules, this is true if the cell survives with at least one arrangement of that many neighbors.
+    /// Transition tables don't have survival rules, so this is always false for them.
+    /// 
+    /// ```
+    /// # use cellular_snapp::presets;
+    /// let life = presets::life();
+    /// assert!(life.matches_survival(2) && life.matches_survival(3));
+    /// assert!(!life.matches_survival(4));
+    /// assert!(life.matches_birth(3) && !life.matches_birth(2));
+    /// ```
+    pub fn matches_survival(&self, count: u8) -> bool {
+        match (&self.table, &self.isotropic) {
+            (Some(_), _) => false,
+            (None, Some(iso)) => (0..256).any(|a: u32| a.count_ones() == count as u32 && iso.survive[a as usize]),
+            (None, None) => self.to_survive.matches(count)
+        }
+    }
+
+    /// Checks whether a dead cell with the given amount of live neighbors is born, ignoring the birth chance.
+    /// With isotropic rules, this is true if the cell is born with at least one arrangement of that many neighbors.
+    /// Transition tables don't have birth rules, so this is always false for them.
+    pub fn matches_birth(&self, count: u8) -> bool {
+        match (&self.table, &self.isotropic) {
+            (Some(_), _) => false,
+            (None, Some(iso)) => (0..256).any(|a: u32| a.count_ones() == count as u32 && iso.born[a as usize]),
+            (None, None) => self.to_be_born.matches(count)
+        }
+    }
+
+    /// Makes sure none of the rules ask for more neighbors than a cell can have, and that transition tables only use states the rules have,
+    /// for an automaton with the given amount of dimensions.
+    pub(crate) fn validate(&self, max_neighbors: u8, dimensions: u8) -> Result<(), AutomataError> {
+        // with fewer than 2 states, live cells would be dead (or not exist at all)
+        if self.cell_states < 2 { return Err(AutomataError::TooFewStates(self.cell_states)) }
+
+        let transitions = self.table.iter().flat_map(|t| t.transitions.iter());
+        let conditions = transitions.clone().filter_map(|t| t.condition.as_ref().map(|(_, rule)| rule));
+
+        // a cell that counts itself has one more neighbor than its neighborhood does, and weighted neighbors can count for more than one each
+        let heaviest = (0..self.cell_states).map(|state| self.weight(state)).max().unwrap_or(1);
+        let most = max_neighbors.saturating_add(self.count_self as u8).saturating_mul(heaviest.max(1));
+
+        for rule in [&self.to_survive, &self.to_be_born].into_iter().chain(conditions) {
+            if rule.most() > most { return Err(AutomataError::TooManyNeighbors(most)) }
+        }
+
+        // rule tables are written for 2D neighborhoods, with the neighbors in the order of the one they were loaded with
+        if let Some(TransitionTable { lookup: Some(lookup), .. }) = &self.table {
+            if dimensions != 2 { return Err(AutomataError::InvalidRuleTable(String::from("rule tables only work with flat automata"))) }
+            if flat::neighbor_offsets(lookup.method()) != flat::neighbor_offsets(&self.neighbor_method) {
+                return Err(AutomataError::InvalidRuleTable(format!("the rule table is for the {:?} neighborhood, not {:?}", lookup.method(), self.neighbor_method)))
+            }
+        }
+
+        // arrangements of neighbors only make sense for the 8 neighbors of a 2D Moore neighborhood
+        if self.isotropic.is_some() && max_neighbors != 8 { return Err(AutomataError::InvalidRuleString(self.to_string())) }
+
+        // directions are worked out for flat neighborhoods, and every 3D neighborhood has more neighbors than the flat one
+        if self.directions.as_ref().is_some_and(|d| d.birth.len() != max_neighbors as usize) { return Err(AutomataError::UnsupportedDirections) }
+
+        for t in transitions {
+            let counted = t.condition.as_ref().map(|(c, _)| *c);
+
+            for state in [Some(t.from), Some(t.to), counted].into_iter().flatten() {
+                if state >= self.cell_states { return Err(AutomataError::InvalidState(state)) }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Works out what a cell's next state should be, given its current state and the states of all of its neighbors.
+    /// This handles both transition tables and birth and survival rules.
+    pub(crate) fn next_state_from(&self, state: u8, neighbors: impl Iterator<Item = u8> + Clone, roll: f64) -> u8 {
+        self.next_state_scaled(state, neighbors, roll, (1.0, 1.0))
+    }
+
+    /// Works out a cell's next state like `next_state_from`, except the birth and survival chances are multiplied by the given amounts first (and kept between 0 and 1).
+    pub(crate) fn next_state_scaled(&self, state: u8, neighbors: impl Iterator<Item = u8> + Clone, roll: f64, scale: (f64, f64)) -> u8 {
+        if let Some(tie_break) = self.vote { return vote_state(state, neighbors, roll, tie_break) }
+
+        match &self.table {
+            Some(TransitionTable { lookup: Some(lookup), .. }) => lookup.get(state, neighbors).unwrap_or(state),
+            Some(table) => table.transitions.iter()
+                .find(|t| t.from == state && t.condition.as_ref().is_none_or(|(counted, rule)| {
+                    rule.matches(neighbors.clone().filter(|n| n == counted).count() as u8)
+                }))
+                .map_or(state, |t| t.to),
+            None => {
+                let (born, survives) = match &self.isotropic {
+                    Some(iso) => {
+                        let arrangement = neighbors.enumerate().filter(|(_, n)| self.counts_as_neighbor(*n)).fold(0, |a, (i, _)| a | 1 << i);
+                        (iso.born[arrangement], iso.survive[arrangement])
+                    },
+                    None => {
+                        let own = if self.count_self { self.weight(state) } else { 0 };
+
+                        match &self.directions {
+                            Some(directions) => {
+                                let weighted = neighbors.map(|n| self.weight(n)).enumerate();
+                                let (born, survive) = weighted.fold((own, own), |(b, s), (i, w)| (b.saturating_add(w * directions.birth[i] as u8), s.saturating_add(w * directions.survival[i] as u8)));
+                                (self.to_be_born.matches(born), self.to_survive.matches(survive))
+                            },
+                            None => {
+                                let count = neighbors.fold(own, |count, n| count.saturating_add(self.weight(n)));
+                                (self.to_be_born.matches(count), self.to_survive.matches(count))
+                            }
+                        }
+                    }
+                };
+
+                let chances = ((self.birth_chance * scale.0).clamp(0.0, 1.0), (self.survival_chance * scale.1).clamp(0.0, 1.0));
+                self.next_state(state, born, survives, roll, chances)
+            }
+        }
+    }
+
+    /// Works out what a cell's next state should be, given its current state and how many live neighbors it has.
+    /// The roll is a random number between 0 and 1 that gets compared against the birth and survival chances, which deterministic rules can just leave at 0.
+    fn next_state(&self, state: u8, born: bool, survives: bool, roll: f64, (birth_chance, survival_chance): (f64, f64)) -> u8 {
+        let survives = || survives && roll < survival_chance;
+
+        if state == 0 {
+            // cell is dead
+            if born && roll < birth_chance { self.cell_states - 1 } else { 0 }
+        } else if state == self.cell_states - 1 {
+            // cell is alive, and will start dying if it doesn't have the right amount of neighbors
+            if survives() { state } else { state - 1 }
+        } else if self.dying_can_recover() && survives() {
+            // cell is dying, but has been rescued
+            self.cell_states - 1
+        } else {
+            // cell is dying
+            state - 1
+        }
+    }
+
+    /// Works out what a cell's state should be after rules have changed from `old` to these, so live cells stay alive and dying cells keep dying.
+    /// Transition tables and vote rules don't have live or dying cells, so their states are just clamped to fit.
+    pub(crate) fn carry_state(&self, old: &AutomataRules, state: u8) -> u8 {
+        if self.is_table() || old.is_table() {
+            state.min(self.cell_states - 1)
+        } else if state == 0 {
+            0
+        } else if state == old.cell_states - 1 {
+            self.cell_states - 1
+        } else {
+            state.min(self.cell_states.saturating_sub(2))
         }
     }
 }
 
-//--> Tests <--
+/// Rules can be written out as strings in one of two ways.
+/// 
+/// The first is the B/S notation used for most 2D rules, like `B3/S23` for Conway's Game of Life.
+/// Each digit after the B or S is a neighbor count, and you can tack on an amount of cell states with either `/C3` or just `/3`, like `B2/S/3` for Brian's Brain.
+/// 
+/// B/S rules can also use Hensel notation for isotropic non-totalistic rules, where letters after a digit say which arrangements of that many neighbors count,
+/// and a dash before the letters means every arrangement except those. For example, `B2-a/S12` is born with two neighbors unless they're right next to each other.
+/// These only work with 2D Moore neighborhoods.
+/// 
+/// ```
+/// # use cellular_snapp::{AutomataRules, flat::{Automaton, Vec2}};
+/// let rules = "B2-a/S12".parse::<AutomataRules>().unwrap();
+/// assert_eq!(rules.to_string(), "B2-a/S12");
+/// 
+/// // two neighbors across from each other give birth, but two next to each other don't
+/// let mut a = Automaton::new(rules.clone(), Vec2::new(5, 5), vec![Vec2::new(1, 2), Vec2::new(3, 2)]).unwrap();
+/// a.tick();
+/// assert_eq!(a.get_cell(&Vec2::new(2, 2)), Some(1));
+/// 
+/// let mut b = Automaton::new(rules, Vec2::new(5, 5), vec![Vec2::new(1, 1), Vec2::new(2, 1)]).unwrap();
+/// b.tick();
+/// assert_eq!(b.get_cell(&Vec2::new(1, 2)), Some(0));
+/// ```
+/// 
+/// The second is the survival/birth/states/method notation used for most 3D rules, like `4/4/5/M` or `13-26/13-14,17-19/2/M`.
+/// Neighbor counts are separated by commas, and a dash includes everything between two counts.
+/// 
+/// Either way, the neighbor method is `M` for Moore, `N` (or `V`) for Von Neumann, `E` for faces and edges, or `R` and a radius for Euclidean (like `R2.5`).
+/// B/S rules default to Moore. A `+` after the neighbor method (like `B3/S34/M+`) means every cell counts itself as one of its neighbors.
+/// Since B/S notation comes from Golly, B/S rules use `DecayMode::Generations`, while the other notation uses `DecayMode::Counted`.
+/// 
+/// Vote rules are written as `Vote/states/method/tie-break`, where the tie-break is `keep`, `random`, or `alive`, like `Vote/2/M/keep`.
+impl FromStr for AutomataRules {
+    type Err = AutomataError;
 
-#[cfg(test)]
-mod tests {}
\ No newline at end of file
+    fn from_str(s: &str) -> Result<AutomataRules, AutomataError> {
+        let invalid = || AutomataError::InvalidRuleString(s.to_string());
+
+        // transition tables don't have a rule string, so the only one we know is by name
+        if s.trim().eq_ignore_ascii_case("wireworld") { return Ok(presets::wireworld()) }
+
+        let parts = s.trim().split('/').map(str::trim).collect::<Vec<&str>>();
+
+        if parts[0].eq_ignore_ascii_case("vote") {
+            let [_, states, method, tie_break] = parts[..] else { return Err(invalid()) };
+            let states = states.parse().map_err(|_| invalid())?;
+            if states < 2 { return Err(AutomataError::TooFewStates(states)) }
+
+            let tie_break = match tie_break.to_ascii_lowercase().as_str() {
+                "keep" => TieBreak::Keep,
+                "random" => TieBreak::Random,
+                "alive" => TieBreak::PreferAlive,
+                _ => return Err(invalid())
+            };
+
+            return Ok(AutomataRules::vote(states, parse_method(method).ok_or_else(invalid)?, tie_break))
+        }
+
+        if parts.iter().any(|p| p.starts_with(['B', 'b', 'S', 's'])) {
+            let (mut survive, mut born, mut states, mut method, mut count_self) = (None, None, 2, Method::Moore, false);
+
+            for part in parts {
+                let mut chars = part.chars();
+
+                match chars.next().map(|c| c.to_ascii_uppercase()) {
+                    Some('B') => born = Some(chars.as_str()),
+                    Some('S') => survive = Some(chars.as_str()),
+                    Some('C') | Some('G') => states = chars.as_str().parse().map_err(|_| invalid())?,
+                    Some(c) if c.is_ascii_digit() => states = part.parse().map_err(|_| invalid())?,
+                    Some(_) => (method, count_self) = parse_neighborhood(part).ok_or_else(invalid)?,
+                    None => return Err(invalid())
+                }
+            }
+
+            let (survive, born) = (survive.ok_or_else(invalid)?, born.ok_or_else(invalid)?);
+            if states < 2 { return Err(AutomataError::TooFewStates(states)) }
+
+            // letters after the digits mean the rule is written in Hensel notation
+            let rules = if survive.chars().chain(born.chars()).all(|c| c.is_ascii_digit()) {
+                AutomataRules::new(parse_digits(survive).ok_or_else(invalid)?, parse_digits(born).ok_or_else(invalid)?, states, method)
+            } else {
+                if method != Method::Moore { return Err(invalid()) }
+
+                AutomataRules {
+                    isotropic: Some(Isotropic::parse(born, survive).ok_or_else(invalid)?),
+                    ..AutomataRules::new(Rule::Many(Vec::new()), Rule::Many(Vec::new()), states, method)
+                }
+            };
+
+            Ok(rules.with_decay_mode(DecayMode::Generations).with_count_self(count_self))
+        } else {
+            let [survive, born, states, method] = parts[..] else { return Err(invalid()) };
+            let states = states.parse().map_err(|_| invalid())?;
+            if states < 2 { return Err(AutomataError::TooFewStates(states)) }
+            let (method, count_self) = parse_neighborhood(method).ok_or_else(invalid)?;
+
+            Ok(AutomataRules::new(
+                parse_counts(survive).ok_or_else(invalid)?,
+                parse_counts(born).ok_or_else(invalid)?,
+                states,
+                method
+            ).with_count_self(count_self))
+        }
+    }
+}
+
+/// Rules are written out in B/S notation (like `B3/S23`) if every neighbor count fits in a single digit and dying cells follow Golly's Generations semantics (or there aren't any),
+/// and in survival/birth/states/method notation (like `4/4/5/M`) otherwise.
+/// Either way, the string can be parsed right back into the same rules, except for `DecayMode::Revivable` and `DecayMode::RevivableGenerations`, which neither notation can express.
+/// Transition tables are just written out as their name, and vote rules as `Vote/states/method/tie-break`.
+impl fmt::Display for AutomataRules {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(table) = &self.table { return write!(f, "{}", table.name) }
+
+        if let Some(tie_break) = self.vote {
+            let tie_break = match tie_break {
+                TieBreak::Keep => "keep",
+                TieBreak::Random => "random",
+                TieBreak::PreferAlive => "alive"
+            };
+
+            return write!(f, "Vote/{}/{}/{}", self.cell_states, write_method(&self.neighbor_method), tie_break)
+        }
+
+        if let Some(iso) = &self.isotropic {
+            let (born, survive) = iso.notation();
+            write!(f, "B{}/S{}", born, survive)?;
+            if self.cell_states != 2 { write!(f, "/C{}", self.cell_states)?; }
+            return Ok(())
+        }
+
+        let (survive, born) = (self.to_survive.counts(), self.to_be_born.counts());
+
+        let generations = self.cell_states == 2 || matches!(self.decay_mode, DecayMode::Generations);
+
+        if generations && survive.iter().chain(born.iter()).all(|c| *c < 10) {
+            let digits = |counts: &[u8]| counts.iter().map(|c| c.to_string()).collect::<String>();
+            write!(f, "B{}/S{}", digits(&born), digits(&survive))?;
+
+            if self.cell_states != 2 { write!(f, "/C{}", self.cell_states)?; }
+            match self.neighbor_method {
+                Method::Moore if !self.count_self => {},
+                Method::Moore => write!(f, "/M")?,
+                Method::VonNeumann => write!(f, "/V")?,
+                Method::FacesAndEdges => write!(f, "/E")?,
+                Method::Euclidean(radius) => write!(f, "/R{}", radius)?
+            }
+
+            if self.count_self { write!(f, "+")?; }
+            Ok(())
+        } else {
+            write!(f, "{}/{}/{}/{}", write_counts(&survive), write_counts(&born), self.cell_states, write_method(&self.neighbor_method))?;
+            if self.count_self { write!(f, "+")?; }
+            Ok(())
+        }
+    }
+}
+
+impl Transition {
+    /// A cell in state `from` always turns into `to`.
+    pub fn always(from: u8, to: u8) -> Transition {
+        Transition { from, condition: None, to }
+    }
+
+    /// A cell in state `from` turns into `to` if the amount of its neighbors in state `counted` matches the given rule.
+    pub fn when(from: u8, counted: u8, neighbors: impl Into<Rule>, to: u8) -> Transition {
+        Transition { from, condition: Some((counted, neighbors.into())), to }
+    }
+}
+
+impl Rule {
+    /// Get every neighbor count this rule matches, from smallest to largest.
+    pub(crate) fn counts(&self) -> Vec<u8> {
+        let mut counts = match self {
+            Rule::Single(goal) => vec![*goal],
+            Rule::Range(goal_range) => goal_range.clone().collect(),
+            Rule::RangeInclusive(goal_range) => goal_range.clone().collect(),
+            Rule::Many(goals) => goals.clone()
+        };
+
+        counts.sort_unstable();
+        counts.dedup();
+        counts
+    }
+
+    /// Turns a list of neighbor counts into the simplest rule that matches them.
+    fn from_counts(mut counts: Vec<u8>) -> Rule {
+        counts.sort_unstable();
+        counts.dedup();
+
+        if counts.len() == 1 { Rule::Single(counts[0]) } else { Rule::Many(counts) }
+    }
+
+    /// Get the biggest neighbor count written in this rule, counting the end of a range even if the range leaves it out, or 0 if there aren't any.
+    pub(crate) const fn most(&self) -> u8 {
+        const fn larger(a: u8, b: u8) -> u8 { if a > b { a } else { b } }
+
+        match self {
+            Rule::Single(goal) => *goal,
+            Rule::Range(goal_range) => larger(goal_range.start, goal_range.end),
+            Rule::RangeInclusive(goal_range) => larger(*goal_range.start(), *goal_range.end()),
+            Rule::Many(goals) => {
+                let (goals, mut i, mut most) = (goals.as_slice(), 0, 0);
+                while i < goals.len() {
+                    most = larger(most, goals[i]);
+                    i += 1;
+                }
+                most
+            }
+        }
+    }
+
+    /// Checks whether a given amount of neighbors satisfies this rule.
+    pub(crate) fn matches(&self, neighbors: u8) -> bool {
+        match self {
+            Rule::Single(goal) => *goal == neighbors,
+            Rule::Range(goal_range) => goal_range.contains(&neighbors),
+            Rule::RangeInclusive(goal_range) => goal_range.contains(&neighbors),
+            Rule::Many(goals) => goals.contains(&neighbors)
+        }
+    }
+}
+
+impl From<u8> for Rule {
+    fn from(count: u8) -> Rule { Rule::Single(count) }
+}
+
+impl From<Range<u8>> for Rule {
+    fn from(counts: Range<u8>) -> Rule { Rule::Range(counts) }
+}
+
+impl From<RangeInclusive<u8>> for Rule {
+    fn from(counts: RangeInclusive<u8>) -> Rule { Rule::RangeInclusive(counts) }
+}
+
+impl From<&[u8]> for Rule {
+    fn from(counts: &[u8]) -> Rule { Rule::Many(counts.to_vec()) }
+}
+
+impl From<Vec<u8>> for Rule {
+    fn from(counts: Vec<u8>) -> Rule { Rule::Many(counts) }
+}
+
+/// Two rules are equal when they match the same neighbor counts, however they're written, so `2..=3`, `2..4`, and `Many(vec![3, 2])` are all the same rule.
+impl PartialEq for Rule {
+    fn eq(&self, other: &Rule) -> bool {
+        self.counts() == other.counts()
+    }
+}
+
+/// Two sets of rules are equal when they make every cell do the same thing, so it doesn't matter how the neighbor counts are written,
+/// and the decay mode (or how species are inherited) doesn't matter when there aren't any dying cells (or species) for it to apply to.
+/// 
+/// ```
+/// # use cellular_snapp::{presets, AutomataRules, DecayMode, Method, Rule};
+/// assert_eq!("B3/S23".parse::<AutomataRules>().unwrap(), presets::life());
+/// assert_eq!(AutomataRules::new(Rule::Many(vec![3, 2]), 3, 2, Method::Moore), presets::life());
+/// assert_eq!(presets::life().with_decay_mode(DecayMode::Revivable), presets::life());
+/// 
+/// // with dying cells around, the decay mode does matter
+/// assert_ne!(presets::brians_brain().with_decay_mode(DecayMode::Counted), presets::brians_brain());
+/// ```
+impl PartialEq for AutomataRules {
+    fn eq(&self, other: &AutomataRules) -> bool {
+        self.to_survive == other.to_survive && self.to_be_born == other.to_be_born && self.cell_states == other.cell_states
+            && self.neighbor_method == other.neighbor_method
+            && (self.cell_states <= 2 || self.decay_mode == other.decay_mode)
+            && self.birth_chance == other.birth_chance && self.survival_chance == other.survival_chance
+            && self.table == other.table && self.species == other.species
+            && (self.species <= 1 || self.inheritance == other.inheritance)
+            && self.isotropic == other.isotropic && self.directions == other.directions && self.vote == other.vote
+            && self.count_self == other.count_self && self.weights == other.weights
+    }
+}
+
+impl Method {
+    /// Get how far away along any one axis a neighbor can be.
+    pub(crate) fn reach(&self) -> i64 {
+        match self {
+            // anything past this gives far too many neighbors to count however many dimensions there are, so there's no need to look any further
+            Method::Euclidean(radius) => (radius.floor() as i64).clamp(0, 16),
+            _ => 1
+        }
+    }
+}
+
+impl DecayMode {
+    /// Picks the mode where dying cells count as neighbors or not, and can recover or not.
+    pub(crate) const fn from_semantics(counted: bool, recover: bool) -> DecayMode {
+        match (counted, recover) {
+            (true, false) => DecayMode::Counted,
+            (false, false) => DecayMode::Generations,
+            (true, true) => DecayMode::Revivable,
+            (false, true) => DecayMode::RevivableGenerations
+        }
+    }
+}
+
+impl Anchor {
+    /// Works out how far cells need to move along one axis when it changes from `old` to `new` in size.
+    pub(crate) fn shift(&self, old: usize, new: usize) -> i64 {
+        match self {
+            Anchor::Start => 0,
+            Anchor::Center => (new as i64 - old as i64) / 2,
+            Anchor::End => new as i64 - old as i64
+        }
+    }
+}
+
+impl BlendMode {
+    /// Works out what a cell in the given state turns into when a cell from a pattern is pasted over it.
+    pub(crate) fn blend(&self, existing: u8, incoming: u8) -> u8 {
+        match self {
+            BlendMode::Overwrite => incoming,
+            BlendMode::Or => existing.max(incoming),
+            BlendMode::Xor if existing > 0 && incoming > 0 => 0,
+            BlendMode::Xor => existing.max(incoming)
+        }
+    }
+}
+
+impl ConflictPolicy {
+    /// Checks whether a live or dying cell being merged in replaces the cell already there.
+    pub(crate) fn takes_other(&self, mine: u8, theirs: u8) -> bool {
+        match self {
+            _ if mine == 0 => true,
+            ConflictPolicy::KeepMax => theirs > mine,
+            ConflictPolicy::PreferSelf => false,
+            ConflictPolicy::PreferOther => true
+        }
+    }
+}
+
+impl CoordinateWidth {
+    /// Checks whether a coordinate fits in this width.
+    pub fn fits(&self, c: i64) -> bool {
+        match self {
+            CoordinateWidth::I32 => i32::try_from(c).is_ok(),
+            CoordinateWidth::I64 => true
+        }
+    }
+}
+
+impl Progress {
+    pub(crate) fn new(done: usize, total: usize) -> Progress {
+        Progress { done, total }
+    }
+
+    /// Get how many steps are done so far.
+    pub fn done(&self) -> usize {
+        self.done
+    }
+
+    /// Get how many steps there could be in total. Operations that can finish early, like `run_until_stable_with`, give the most there could be.
+    pub fn total(&self) -> usize {
+        self.total
+    }
+
+    /// Get how far along the operation is, from 0 to 1.
+    pub fn fraction(&self) -> f64 {
+        if self.total == 0 { 1.0 } else { self.done as f64 / self.total as f64 }
+    }
+}
+
+impl<V> Occupancy<V> {
+    pub(crate) fn new(cells: usize, bounds: Option<(V, V)>, memory: usize) -> Occupancy<V> {
+        Occupancy { cells, bounds, memory }
+    }
+
+    /// Get how many live and dying cells there are.
+    pub fn cells(&self) -> usize {
+        self.cells
+    }
+
+    /// Get the smallest and biggest corners of the box around every live or dying cell (both inside it), or `None` if there aren't any.
+    pub fn bounds(&self) -> Option<&(V, V)> {
+        self.bounds.as_ref()
+    }
+
+    /// Get roughly how many bytes the cells take up, going by how big their hash maps have grown. Hash maps never shrink on their own, so this doesn't go back down when cells die.
+    pub fn memory(&self) -> usize {
+        self.memory
+    }
+}
+
+#[cfg(feature = "profiling")]
+impl TickProfile {
+    /// Get how long the whole tick took.
+    pub fn total(&self) -> Duration {
+        self.total
+    }
+
+    /// Get how long it took to count every cell's neighbors and work out its next state.
+    pub fn stepping(&self) -> Duration {
+        self.stepping
+    }
+
+    /// Get how long it took to put the next generation in place, whether that's swapping grids around or writing every change into the grid.
+    pub fn swapping(&self) -> Duration {
+        self.swapping
+    }
+
+    /// Get how long everything else took, like walls, species, ages, trails, and the heatmap.
+    pub fn bookkeeping(&self) -> Duration {
+        self.bookkeeping
+    }
+
+    /// Get how many cells had their next state worked out. Sparse grids only look at the cells near live ones, so this is usually a lot less than the whole grid.
+    pub fn cells(&self) -> usize {
+        self.cells
+    }
+}
+
+impl Profiler {
+    /// Starts timing a new tick.
+    #[inline]
+    pub(crate) fn begin(&mut self) {
+        #[cfg(feature = "profiling")]
+        {
+            let now = Instant::now();
+            self.last = TickProfile::default();
+            self.marks = Some((now, now));
+        }
+    }
+
+    /// Starts timing the next phase of the tick from now.
+    #[inline]
+    pub(crate) fn mark(&mut self) {
+        #[cfg(feature = "profiling")]
+        if let Some((_, mark)) = &mut self.marks { *mark = Instant::now(); }
+    }
+
+    /// Counts the time since the last mark as stepping, after looking at the given amount of cells.
+    #[inline]
+    pub(crate) fn stepped(&mut self, _cells: usize) {
+        #[cfg(feature = "profiling")]
+        if let Some((_, mark)) = &mut self.marks {
+            self.last.stepping += mark.elapsed();
+            self.last.cells += _cells;
+            *mark = Instant::now();
+        }
+    }
+
+    /// Counts the time since the last mark as swapping.
+    #[inline]
+    pub(crate) fn swapped(&mut self) {
+        #[cfg(feature = "profiling")]
+        if let Some((_, mark)) = &mut self.marks {
+            self.last.swapping += mark.elapsed();
+            *mark = Instant::now();
+        }
+    }
+
+    /// Finishes timing the tick, counting whatever wasn't stepping or swapping as bookkeeping.
+    #[inline]
+    pub(crate) fn finish(&mut self) {
+        #[cfg(feature = "profiling")]
+        if let Some((start, _)) = self.marks.take() {
+            self.last.total = start.elapsed();
+            self.last.bookkeeping = self.last.total.saturating_sub(self.last.stepping + self.last.swapping);
+        }
+    }
+
+    /// Get the profile of the last tick.
+    #[cfg(feature = "profiling")]
+    pub(crate) fn last(&self) -> &TickProfile {
+        &self.last
+    }
+}
+
+impl CancelToken {
+    /// Creates a token that hasn't been cancelled yet.
+    pub fn new() -> CancelToken {
+        CancelToken::default()
+    }
+
+    /// Cancels the token, and every clone of it along with it.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    /// Checks whether the token has been cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    /// Checks the token in a way a progress callback can give straight back, breaking once it's been cancelled.
+    pub fn check(&self) -> ControlFlow<()> {
+        if self.is_cancelled() { ControlFlow::Break(()) } else { ControlFlow::Continue(()) }
+    }
+}
+
+impl<T> Clone for Spare<T> {
+    fn clone(&self) -> Spare<T> {
+        Spare(None)
+    }
+}
+
+impl fmt::Display for AutomataError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AutomataError::TooManyNeighbors(max) => write!(f, "rules ask for more neighbors than a cell can have (at most {})", max),
+            AutomataError::InvalidRuleString(rule) => write!(f, "couldn't understand the rule string '{}'", rule),
+            AutomataError::InvalidPattern(reason) => write!(f, "couldn't understand the pattern: {}", reason),
+            AutomataError::InvalidState(state) => write!(f, "state {} is too big for the rules", state),
+            AutomataError::InvalidRuleTable(reason) => write!(f, "couldn't understand the rule table: {}", reason),
+            AutomataError::UnsupportedBackend(backend) => write!(f, "the {:?} backend can't hold cells for these rules", backend),
+            AutomataError::MismatchedRules(rule) => write!(f, "the rules '{}' need the same cell states and neighbor method as the rest of the grid", rule),
+            AutomataError::NeighborhoodTooBig(neighbors) => write!(f, "the neighbor method gives {} neighbors, but no more than 255 can be counted", neighbors),
+            AutomataError::InvalidReplay(reason) => write!(f, "couldn't replay the run: {}", reason),
+            AutomataError::UnsupportedDirections => write!(f, "directional rules only work in flat automata"),
+            AutomataError::NoSuchLayer(layer) => write!(f, "there's no layer {}", layer),
+            AutomataError::InvalidDelta(reason) => write!(f, "couldn't apply the delta: {}", reason),
+            AutomataError::MissedDelta(expected, got) => write!(f, "expected delta {}, but got delta {}", expected, got),
+            AutomataError::WrongSliceLength(length) => write!(f, "the slice should have {} cells", length),
+            AutomataError::TooFewStates(states) => write!(f, "rules need at least 2 cell states, but these have {}", states),
+            AutomataError::UnsupportedRule(reason) => write!(f, "couldn't write the rules out: {}", reason),
+            AutomataError::NotReversible => write!(f, "the automaton can't be run backwards"),
+            AutomataError::OutgrewLimits => write!(f, "the automaton would have grown past its limits"),
+            AutomataError::MappingFailed(reason) => write!(f, "couldn't map a file for the grid: {}", reason),
+            AutomataError::UnsupportedFacesAndEdges => write!(f, "the faces and edges neighbor method only works in deep automata")
+        }
+    }
+}
+
+impl Error for AutomataError {}
+
+/// Parses the single-digit neighbor counts used in B/S notation, like the `23` in `S23`.
+fn parse_digits(digits: &str) -> Option<Rule> {
+    digits.chars().map(|c| c.to_digit(10).map(|d| d as u8)).collect::<Option<Vec<u8>>>().map(Rule::from_counts)
+}
+
+/// Parses comma-separated neighbor counts and ranges, like `13-14,17-19`.
+fn parse_counts(counts: &str) -> Option<Rule> {
+    let mut all = Vec::new();
+
+    for item in counts.split(',').map(str::trim).filter(|i| !i.is_empty()) {
+        match item.split_once('-') {
+            Some((start, end)) => all.extend(start.trim().parse::<u8>().ok()?..=end.trim().parse::<u8>().ok()?),
+            None => all.push(item.parse().ok()?)
+        }
+    }
+
+    Some(Rule::from_counts(all))
+}
+
+/// Writes out a neighbor method the way `parse_method` reads it.
+fn write_method(method: &Method) -> String {
+    match method {
+        Method::Moore => String::from("M"),
+        Method::VonNeumann => String::from("N"),
+        Method::FacesAndEdges => String::from("E"),
+        Method::Euclidean(radius) => format!("R{}", radius)
+    }
+}
+
+/// Works out a cell's next state under vote rules, which is whichever state the most of it and its neighbors are in.
+fn vote_state(state: u8, neighbors: impl Iterator<Item = u8>, roll: f64, tie_break: TieBreak) -> u8 {
+    let mut votes = [0u16; 256];
+    for s in std::iter::once(state).chain(neighbors) { votes[s as usize] += 1; }
+
+    let most = votes.iter().max().copied().unwrap_or(0);
+    let tied: Vec<u8> = (0..=255).filter(|s| votes[*s as usize] == most).collect();
+
+    match tie_break {
+        _ if tied.len() == 1 => tied[0],
+        TieBreak::Keep => if tied.contains(&state) { state } else { tied[0] },
+        TieBreak::Random => tied[((roll * tied.len() as f64) as usize).min(tied.len() - 1)],
+        TieBreak::PreferAlive => tied[tied.len() - 1]
+    }
+}
+
+/// Writes out a sorted list of neighbor counts with runs collapsed into ranges, like `13-14,17-19`.
+fn write_counts(counts: &[u8]) -> String {
+    let mut items = Vec::new();
+    let mut i = 0;
+
+    while i < counts.len() {
+        let start = counts[i];
+        while i + 1 < counts.len() && counts[i + 1] == counts[i] + 1 { i += 1; }
+
+        items.push(if counts[i] == start { start.to_string() } else { format!("{}-{}", start, counts[i]) });
+        i += 1;
+    }
+
+    items.join(",")
+}
+
+/// Parses a neighbor method letter.
+/// Reads a neighbor method that might have a `+` after it, which means cells count themselves.
+fn parse_neighborhood(part: &str) -> Option<(Method, bool)> {
+    match part.strip_suffix('+') {
+        Some(method) => parse_method(method).map(|m| (m, true)),
+        None => parse_method(part).map(|m| (m, false))
+    }
+}
+
+fn parse_method(method: &str) -> Option<Method> {
+    match method {
+        "M" | "m" => Some(Method::Moore),
+        "N" | "n" | "V" | "v" => Some(Method::VonNeumann),
+        "E" | "e" => Some(Method::FacesAndEdges),
+        _ if method.starts_with(['R', 'r']) => method[1..].parse().ok().filter(|r: &f64| r.is_finite() && *r >= 0.0).map(Method::Euclidean),
+        _ => None
+    }
+}
+
+/// Splits `0..len` into even slabs, runs `f` on each one on its own scoped thread, and strings the results back together in order.
+/// With one thread (or nothing worth splitting), `f` just runs on everything right here.
+pub(crate) fn in_slabs<T: Send>(len: usize, threads: usize, f: impl Fn(Range<usize>) -> Vec<T> + Sync) -> Vec<T> {
+    if threads <= 1 || len <= 1 { return f(0..len); }
+
+    let size = len.div_ceil(threads);
+    let f = &f;
+
+    std::thread::scope(|scope| {
+        let slabs = (0..len).step_by(size).map(|start| scope.spawn(move || f(start..(start + size).min(len)))).collect::<Vec<_>>();
+        slabs.into_iter().flat_map(|slab| slab.join().unwrap_or_else(|e| std::panic::resume_unwind(e))).collect()
+    })
+}
+
+/// Like `in_slabs`, except the results get written straight into `out` instead of a new `Vec`.
+/// `out` is split into slabs of whole rows, `row_len` items each, and `f` gets the rows a slab covers along with the part of `out` they go in.
+pub(crate) fn in_slabs_mut<T: Send>(out: &mut [T], row_len: usize, threads: usize, f: impl Fn(Range<usize>, &mut [T]) + Sync) {
+    let len = out.len().checked_div(row_len).unwrap_or(0);
+    if threads <= 1 || len <= 1 { return f(0..len, out); }
+
+    let size = len.div_ceil(threads);
+    let f = &f;
+
+    std::thread::scope(|scope| {
+        for (i, slab) in out.chunks_mut(size * row_len).enumerate() {
+            scope.spawn(move || f(i * size..i * size + slab.len() / row_len, slab));
+        }
+    });
+}
+
+/// Hashes a set of cells the same way no matter what order they come in, on every platform and every version of Rust.
+/// Each cell gets hashed on its own and the hashes are added up, so shuffling the cells around doesn't change anything.
+pub(crate) fn hash_cells<const D: usize>(cells: impl Iterator<Item = ([u64; D], u8)>) -> u64 {
+    cells.fold(0, |hash, (coords, state)| {
+        let mut key = coords.to_vec();
+        key.push(state as u64);
+        hash.wrapping_add(Rng::keyed(0x5EED, &key).next_u64())
+    })
+}
+
+/// Wraps a coordinate around into `min..max`, which can't be empty. The math is done with `i128`s so even a box as big as every `i64` can't overflow.
+pub(crate) fn wrap_coordinate(c: i64, min: i64, max: i64) -> i64 {
+    (min as i128 + (c as i128 - min as i128).rem_euclid(max as i128 - min as i128)) as i64
+}
+
+/// Goes through every position along a straight line from `a` to `b`, both ends included, that's inside of `bounds`.
+/// It takes one step along whichever axis changes the most each time, rounding the other axes to the nearest position, so the line never has gaps.
+pub(crate) fn line<const D: usize>(a: [usize; D], b: [usize; D], bounds: [usize; D]) -> impl Iterator<Item = [usize; D]> {
+    let delta: [i128; D] = std::array::from_fn(|i| b[i] as i128 - a[i] as i128);
+    let steps = delta.iter().map(|d| d.abs()).max().unwrap_or(0);
+
+    let at = move |i: usize, step: i128| {
+        if steps == 0 { return a[i] as i128; }
+        a[i] as i128 + (2 * delta[i] * step + steps).div_euclid(2 * steps)
+    };
+
+    // every axis only goes one way along the line, so the steps inside the bounds are all in a row,
+    // and the ends of that row can be found without going through every step of a line that's mostly outside
+    let (mut first, mut last) = (0, steps);
+
+    for i in 0..D {
+        let inside = |step: i128| at(i, step) < bounds[i] as i128;
+        let (mut lo, mut hi) = (0, steps + 1);
+
+        // finds the first step where the line goes from inside to outside (going forwards) or from outside to inside (going backwards)
+        while lo < hi {
+            let mid = lo + (hi - lo) / 2;
+            if inside(mid) == (delta[i] >= 0) { lo = mid + 1; } else { hi = mid; }
+        }
+
+        if delta[i] >= 0 { last = last.min(lo - 1); } else { first = first.max(lo); }
+    }
+
+    (first..=last).map(move |step| std::array::from_fn(|i| at(i, step) as usize))
+}
+
+//--> Tests <--
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{flat, deep};
+
+    /// Runs a stochastic rule on a random soup with the given backend and amount of threads.
+    fn noisy_run(backend: Backend, threads: usize) -> flat::Automaton {
+        let rules = AutomataRules::new(2..=3, [3, 6].as_slice(), 2, Method::Moore).with_chances(0.98, 0.98);
+        let bounds = flat::Vec2::new(48, 40);
+        let mut automaton = flat::Automaton::with_backend(rules, bounds, flat::random_seed(bounds, 0.4, &mut Rng::new(11)), backend).unwrap().threads(threads);
+        automaton.seed_rng(99);
+        automaton.tick_n(60);
+        automaton
+    }
+
+    #[test]
+    fn runs_are_the_same_on_every_backend_and_thread_count() {
+        let expected = noisy_run(Backend::HashMap, 1);
+        assert!(!expected.live_cells_sorted().is_empty());
+
+        for (backend, threads) in [(Backend::HashMap, 4), (Backend::Tree, 3), (Backend::BitPacked, 1), (Backend::BitPacked, 5), (Backend::Dense, 1), (Backend::Dense, 4), (Backend::Incremental, 1), (Backend::Incremental, 3)] {
+            let run = noisy_run(backend, threads);
+            assert_eq!(run.live_cells_sorted(), expected.live_cells_sorted(), "{:?} with {} threads", backend, threads);
+            assert_eq!(run.state_hash(), expected.state_hash());
+        }
+    }
+
+    #[test]
+    fn runs_are_the_same_every_time() {
+        // if this changes, every run anyone has saved a seed for ticks differently now
+        assert_eq!(noisy_run(Backend::HashMap, 1).state_hash(), 3062963359678341732);
+    }
+
+    /// Runs a rule without any chances on a random soup with the given backend and amount of threads, so the backends that tick whole rows or words at once get used.
+    fn steady_run(rules: &AutomataRules, backend: Backend, threads: usize) -> flat::Automaton {
+        let bounds = flat::Vec2::new(70, 40);
+        let mut automaton = flat::Automaton::with_backend(rules.clone(), bounds, flat::random_seed(bounds, 0.2, &mut Rng::new(21)), backend).unwrap().threads(threads);
+        automaton.tick_n(40);
+        automaton
+    }
+
+    #[test]
+    fn steady_runs_are_the_same_on_every_backend_and_thread_count() {
+        let backends = [Backend::HashMap, Backend::Tree, Backend::BitPacked, Backend::Dense, Backend::Incremental];
+
+        // bit-packed grids only take two states, and only Life's rows of states are already rows of neighbors to count
+        for (rules, backends) in [(presets::life(), &backends[..]), (presets::brians_brain(), &[Backend::HashMap, Backend::Tree, Backend::Dense][..])] {
+            assert!(rules.count_table(8).is_some());
+            let expected = steady_run(&rules, Backend::HashMap, 1);
+            assert!(!expected.live_cells_sorted().is_empty());
+
+            for &backend in backends {
+                for threads in [1, 3, 4] {
+                    let run = steady_run(&rules, backend, threads);
+                    assert_eq!(run.live_cells_sorted(), expected.live_cells_sorted(), "{} on {:?} with {} threads", rules, backend, threads);
+                    assert_eq!(run.state_hash(), expected.state_hash());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn deep_steady_runs_are_the_same_on_every_backend_and_thread_count() {
+        let run = |rules: &AutomataRules, backend, threads| {
+            let bounds = deep::Vec3::new(14, 12, 10);
+            let mut automaton = deep::Automaton::with_backend(rules.clone(), bounds, deep::random_seed(bounds, 0.3, &mut Rng::new(8)), backend).unwrap().threads(threads);
+            automaton.tick_n(12);
+            automaton.live_cells_sorted()
+        };
+
+        let all = [Backend::HashMap, Backend::Tree, Backend::BitPacked, Backend::Dense, Backend::Incremental];
+        for (rules, backends) in [(AutomataRules::new(4..=5, 5, 2, Method::Moore), &all[..]), (presets::rule_445(), &[Backend::HashMap, Backend::Tree, Backend::Dense][..])] {
+            let expected = run(&rules, Backend::HashMap, 1);
+            assert!(!expected.is_empty());
+
+            for &backend in backends {
+                for threads in [1, 3] {
+                    assert_eq!(run(&rules, backend, threads), expected, "{} on {:?} with {} threads", rules, backend, threads);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn deep_runs_are_the_same_on_every_thread_count() {
+        let run = |threads| {
+            let rules = AutomataRules::new(4..=5, 5, 4, Method::Moore).with_chances(0.8, 0.9);
+            let bounds = deep::Vec3::new(12, 12, 12);
+            let mut automaton = deep::Automaton::with_backend(rules, bounds, deep::random_seed(bounds, 0.3, &mut Rng::new(3)), Backend::Dense).unwrap().threads(threads);
+            automaton.seed_rng(5);
+            automaton.tick_n(20);
+            automaton.live_cells_sorted()
+        };
+
+        assert_eq!(run(1), run(3));
+    }
+
+    #[test]
+    fn sorted_cells_come_out_row_by_row() {
+        let cells = vec![flat::Vec2::new(3, 1), flat::Vec2::new(0, 2), flat::Vec2::new(1, 1), flat::Vec2::new(5, 0)];
+        let automaton = flat::Automaton::new(AutomataRules::new(2..=3, 3, 2, Method::Moore), flat::Vec2::new(8, 4), cells).unwrap();
+
+        let sorted: Vec<(usize, usize)> = automaton.live_cells_sorted().into_iter().map(|(v, _)| (v.x(), v.y())).collect();
+        assert_eq!(sorted, vec![(5, 0), (1, 1), (3, 1), (0, 2)]);
+
+        let live: Vec<(usize, usize)> = automaton.iter_cells().filter(|(_, s)| *s > 0).map(|(v, _)| (v.x(), v.y())).collect();
+        assert_eq!(live, sorted);
+        assert_eq!(automaton.iter_cells().count(), 32);
+    }
+
+    #[test]
+    fn rules_without_enough_states_are_refused() {
+        for states in [0, 1] {
+            assert!(matches!(AutomataRules::try_new(2..=3, 3, states, Method::Moore), Err(AutomataError::TooFewStates(s)) if s == states));
+
+            for rules in [AutomataRules::new(2..=3, 3, states, Method::Moore), AutomataRules::vote(states, Method::Moore, TieBreak::Keep), AutomataRules::from_transitions("none", states, Method::Moore, Vec::new())] {
+                assert!(matches!(flat::Automaton::new(rules.clone(), flat::Vec2::new(4, 4), vec![flat::Vec2::new(1, 1)]), Err(AutomataError::TooFewStates(s)) if s == states));
+                assert!(matches!(deep::Automaton::new(rules.clone(), deep::Vec3::new(4, 4, 4), Vec::new()), Err(AutomataError::TooFewStates(s)) if s == states));
+                assert!(matches!(rules.to_rule_table(), Err(AutomataError::TooFewStates(s)) if s == states));
+                assert_eq!(rules.view(1), CellView::Alive);
+            }
+        }
+
+        assert_eq!("B3/S23/C1".parse::<AutomataRules>().err(), Some(AutomataError::TooFewStates(1)));
+        assert_eq!("4/4/0/M".parse::<AutomataRules>().err(), Some(AutomataError::TooFewStates(0)));
+        assert_eq!("Vote/1/M/keep".parse::<AutomataRules>().err(), Some(AutomataError::TooFewStates(1)));
+        assert_eq!(AutomataRules::from_rule_table("@TABLE\nn_states:1\nneighborhood:Moore\nsymmetries:none\n").err(), Some(AutomataError::TooFewStates(1)));
+    }
+
+    #[test]
+    fn two_state_cells_die_straight_away() {
+        let rules = AutomataRules::new(2..=3, 3, 2, Method::Moore);
+
+        assert_eq!(rules.next_state(1, false, false, 0.0, (1.0, 1.0)), 0);
+        assert_eq!(rules.next_state(1, false, true, 0.0, (1.0, 1.0)), 1);
+        assert_eq!(rules.next_state(0, true, false, 0.0, (1.0, 1.0)), 1);
+        assert!(rules.counts_as_neighbor(1) && !rules.counts_as_neighbor(0));
+    }
+
+    #[test]
+    fn dying_cells_count_down_to_dead() {
+        let rules = AutomataRules::new(2..=3, 3, 4, Method::Moore);
+
+        // live cells without enough neighbors start dying, and keep going whatever their neighbors do
+        assert_eq!(rules.next_state(3, false, false, 0.0, (1.0, 1.0)), 2);
+        assert_eq!(rules.next_state(2, true, true, 0.0, (1.0, 1.0)), 1);
+        assert_eq!(rules.next_state(1, false, true, 0.0, (1.0, 1.0)), 0);
+
+        // a lone cell fades out one state a tick
+        let mut automaton = flat::Automaton::new(rules, flat::Vec2::new(3, 3), vec![flat::Vec2::new(1, 1)]).unwrap();
+        let fading: Vec<u8> = (0..4).map(|_| { automaton.tick(); automaton[(1, 1)] }).collect();
+        assert_eq!(fading, vec![2, 1, 0, 0]);
+    }
+
+    #[test]
+    fn decay_modes_treat_dying_cells_differently() {
+        let counted = AutomataRules::new(2..=3, 3, 4, Method::Moore);
+        let generations = counted.clone().with_decay_mode(DecayMode::Generations);
+        let revivable = counted.clone().with_decay_mode(DecayMode::Revivable);
+
+        assert!(counted.counts_as_neighbor(1) && revivable.counts_as_neighbor(1));
+        assert!(!generations.counts_as_neighbor(1) && generations.counts_as_neighbor(3));
+
+        // only revivable rules bring dying cells with the right amount of neighbors back to life
+        assert_eq!(counted.next_state(2, false, true, 0.0, (1.0, 1.0)), 1);
+        assert_eq!(generations.next_state(2, false, true, 0.0, (1.0, 1.0)), 1);
+        assert_eq!(revivable.next_state(2, false, true, 0.0, (1.0, 1.0)), 3);
+    }
+
+    #[test]
+    fn dying_semantics_options_cover_every_decay_mode() {
+        let rules = AutomataRules::new(2..=3, 3, 4, Method::Moore);
+        assert!(rules.dying_counts_as_neighbor() && !rules.dying_can_recover());
+
+        for (counted, recover, mode) in [(true, false, DecayMode::Counted), (false, false, DecayMode::Generations), (true, true, DecayMode::Revivable), (false, true, DecayMode::RevivableGenerations)] {
+            let rules = rules.clone().with_dying_counts_as_neighbor(counted).with_dying_can_recover(recover);
+            assert_eq!(rules.decay_mode(), mode);
+            assert_eq!((rules.dying_counts_as_neighbor(), rules.dying_can_recover()), (counted, recover));
+
+            // setting one option leaves the other alone
+            assert_eq!(rules.clone().with_dying_counts_as_neighbor(!counted).dying_can_recover(), recover);
+            assert_eq!(rules.clone().with_dying_can_recover(!recover).dying_counts_as_neighbor(), counted);
+
+            assert_eq!(rules.counts_as_neighbor(2), counted);
+            assert_eq!(rules.counted_states().contains(&1), counted);
+            assert_eq!(rules.next_state(2, false, true, 0.0, (1.0, 1.0)), if recover { 3 } else { 1 });
+            // a dying cell without the right amount of neighbors never recovers
+            assert_eq!(rules.next_state(2, true, false, 0.0, (1.0, 1.0)), 1);
+        }
+    }
+
+    #[test]
+    fn recovering_dying_cells_tick_the_same_on_every_backend() {
+        // a blinker next to a dying cell, which only survives if the blinker is there to rescue it
+        let start = [((1, 2), 3), ((2, 2), 3), ((3, 2), 3), ((2, 3), 2)];
+
+        for rules in [AutomataRules::new(2..=3, 3, 4, Method::Moore).with_dying_can_recover(true), AutomataRules::new(2..=3, 3, 4, Method::Moore).with_dying_can_recover(true).with_dying_counts_as_neighbor(false)] {
+            let mut runs: Vec<Vec<u8>> = [Backend::Dense, Backend::HashMap, Backend::Tree, Backend::Incremental].into_iter().map(|backend| {
+                let mut a = flat::Automaton::with_backend(rules.clone(), flat::Vec2::new(6, 6), Vec::new(), backend).unwrap();
+                for ((x, y), state) in start { a.set_cell(flat::Vec2::new(x, y), state); }
+                for _ in 0..3 { a.tick(); }
+                (0..36).map(|i| a[(i % 6, i / 6)]).collect()
+            }).collect();
+
+            let first = runs.remove(0);
+            assert!(runs.iter().all(|run| *run == first));
+        }
+    }
+
+    #[test]
+    fn checkpoints_for_grids_too_big_to_make_are_refused() {
+        let life = flat::Automaton::with_backend(AutomataRules::new(2..=3, 3, 2, Method::Moore), flat::Vec2::new(8, 8), vec![flat::Vec2::new(1, 1)], Backend::Dense).unwrap();
+        let mut checkpoint = Vec::new();
+        life.save_checkpoint(&mut checkpoint).unwrap();
+
+        // the bounds come straight after the magic bytes, the version, and the amount of dimensions
+        let with_bounds = |checkpoint: &[u8], x: u64, y: u64| {
+            let mut changed = checkpoint.to_vec();
+            changed[6..14].copy_from_slice(&x.to_le_bytes());
+            changed[14..22].copy_from_slice(&y.to_le_bytes());
+            changed
+        };
+
+        for (x, y) in [(1 << 40, 1 << 40), (u64::MAX, 2), (1 << 20, 1 << 20)] {
+            let err = flat::Automaton::load_checkpoint(with_bounds(&checkpoint, x, y).as_slice()).err().unwrap();
+            assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+        }
+
+        // grids that only keep live cells can be as big as they like, as long as the cell count fits (the live cell ends up somewhere else on a wider grid)
+        let sparse = flat::Automaton::with_backend(AutomataRules::new(2..=3, 3, 2, Method::Moore), flat::Vec2::new(8, 8), vec![flat::Vec2::new(1, 1)], Backend::HashMap).unwrap();
+        let mut checkpoint = Vec::new();
+        sparse.save_checkpoint(&mut checkpoint).unwrap();
+        assert_eq!(flat::Automaton::load_checkpoint(with_bounds(&checkpoint, 1 << 30, 1 << 30).as_slice()).unwrap().get_cell(&flat::Vec2::new(9, 0)), Some(1));
+        assert!(flat::Automaton::load_checkpoint(with_bounds(&checkpoint, 1 << 40, 1 << 40).as_slice()).is_err());
+
+        // a cell past the end of the grid is caught before the grid gets made
+        assert!(flat::Automaton::load_checkpoint(with_bounds(&checkpoint, 1, 1).as_slice()).is_err());
+
+        let cube = deep::Automaton::with_backend(AutomataRules::new(4..=5, 5, 2, Method::Moore), deep::Vec3::new(4, 4, 4), Vec::new(), Backend::Dense).unwrap();
+        let mut checkpoint = Vec::new();
+        cube.save_checkpoint(&mut checkpoint).unwrap();
+        // an empty grid with two huge sides still can't be made, since the backend multiplies those two together
+        checkpoint[6..14].copy_from_slice(&0u64.to_le_bytes());
+        checkpoint[14..22].copy_from_slice(&(1u64 << 40).to_le_bytes());
+        checkpoint[22..30].copy_from_slice(&(1u64 << 40).to_le_bytes());
+        assert_eq!(deep::Automaton::load_checkpoint(checkpoint.as_slice()).err().unwrap().kind(), std::io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn checkpoints_wont_leave_out_anything_that_changes_the_run() {
+        let start = vec![flat::Vec2::new(1, 2), flat::Vec2::new(2, 2), flat::Vec2::new(3, 2)];
+        let life = || flat::Automaton::new(AutomataRules::new(2..=3, 3, 2, Method::Moore), flat::Vec2::new(8, 8), start.clone()).unwrap();
+        let refused = |a: &flat::Automaton| a.save_checkpoint(Vec::new()).err().map(|e| e.kind()) == Some(std::io::ErrorKind::InvalidInput);
+
+        let mut reversible = life();
+        reversible.set_second_order(true);
+        reversible.tick();
+        assert!(refused(&reversible));
+
+        let mut scheduled = life();
+        scheduled.set_rule_schedule(vec![AutomataRules::new(2..=3, 3, 2, Method::Moore), AutomataRules::new(Rule::Many(Vec::new()), 2, 2, Method::Moore)], 1).unwrap();
+        assert!(refused(&scheduled));
+
+        let mut regions = life();
+        regions.add_rule_region(flat::Rect2::new(flat::Vec2::new(0, 0), flat::Vec2::new(4, 4)), AutomataRules::new(Rule::Many(Vec::new()), 2, 2, Method::Moore)).unwrap();
+        assert!(refused(&regions));
+
+        let mut field = life();
+        field.set_birth_field(Some(flat::Field::from_fn(|v| v.x() as f64 / 8.0)));
+        assert!(refused(&field));
+
+        assert!(!refused(&life()));
+
+        let mut scheduled = deep::Automaton::new(AutomataRules::new(4..=5, 5, 2, Method::Moore), deep::Vec3::new(4, 4, 4), Vec::new()).unwrap();
+        scheduled.set_rule_schedule(vec![AutomataRules::new(4..=5, 5, 2, Method::Moore), AutomataRules::new(4, 4, 2, Method::Moore)], 2).unwrap();
+        assert!(scheduled.save_checkpoint(Vec::new()).is_err());
+    }
+
+    #[test]
+    fn rule_regions_bigger_than_the_grid_turn_with_it() {
+        let (life, seeds) = (AutomataRules::new(2..=3, 3, 2, Method::Moore), AutomataRules::new(Rule::Many(Vec::new()), 2, 2, Method::Moore));
+        let mut a = flat::Automaton::new(life.clone(), flat::Vec2::new(10, 10), Vec::new()).unwrap();
+        a.add_rule_region(flat::Rect2::new(flat::Vec2::new(0, 0), flat::Vec2::new(100, 100)), seeds.clone()).unwrap();
+
+        a.rotate90();
+        a.mirror_x();
+        a.mirror_y();
+        assert!((0..100).all(|i| *a.get_rules_at(&flat::Vec2::new(i % 10, i / 10)) == seeds));
+
+        // a region hanging off the right side is cut down to the grid before it turns
+        let mut a = flat::Automaton::new(life.clone(), flat::Vec2::new(10, 6), Vec::new()).unwrap();
+        a.add_rule_region(flat::Rect2::new(flat::Vec2::new(8, 0), flat::Vec2::new(100, 3)), seeds.clone()).unwrap();
+        a.rotate90();
+
+        let inside: Vec<(usize, usize)> = (0..60).map(|i| (i % 6, i / 6)).filter(|(x, y)| *a.get_rules_at(&flat::Vec2::new(*x, *y)) == seeds).collect();
+        assert_eq!(inside, vec![(3, 8), (4, 8), (5, 8), (3, 9), (4, 9), (5, 9)]);
+
+        a.mirror_x();
+        assert_eq!(*a.get_rules_at(&flat::Vec2::new(0, 8)), seeds);
+        assert_eq!(*a.get_rules_at(&flat::Vec2::new(3, 8)), life);
+    }
+
+    #[test]
+    fn patterns_hanging_off_the_edge_are_cut_off() {
+        let square = flat::Pattern::new("square", (0..9).map(|i| flat::Vec2::new(i % 3, i / 3)).collect());
+        let mut a = flat::Automaton::new(AutomataRules::new(2..=3, 3, 2, Method::Moore), flat::Vec2::new(8, 8), Vec::new()).unwrap();
+
+        // only the corner of the square that's inside the grid gets pasted
+        a.paste(&square, flat::Vec2::new(6, 6), BlendMode::Overwrite);
+        assert_eq!(a.live_cells_sorted().into_iter().map(|(v, _)| (v.x(), v.y())).collect::<Vec<_>>(), vec![(6, 6), (7, 6), (6, 7), (7, 7)]);
+
+        // offsets so big that adding them would go past the largest usize are skipped instead of wrapping around onto the grid
+        for offset in [flat::Vec2::new(usize::MAX - 1, 0), flat::Vec2::new(0, usize::MAX), flat::Vec2::new(usize::MAX, usize::MAX)] {
+            a.paste(&square, offset, BlendMode::Xor);
+            a.apply_mask(&square, offset, MaskOp::Or);
+            square.stamp_into(&mut a, offset);
+
+            let other = flat::Automaton::new(AutomataRules::new(2..=3, 3, 2, Method::Moore), flat::Vec2::new(8, 8), square.positions()).unwrap();
+            a.merge(&other, offset, ConflictPolicy::KeepMax);
+        }
+        assert_eq!(a.live_cells_sorted().len(), 4);
+
+        let cube = deep::Pattern::new("cube", (0..8).map(|i| deep::Vec3::new(i % 2, i / 2 % 2, i / 4)).collect());
+        let mut b = deep::Automaton::new(AutomataRules::new(4..=5, 5, 2, Method::Moore), deep::Vec3::new(4, 4, 4), Vec::new()).unwrap();
+        b.paste(&cube, deep::Vec3::new(3, 3, 3), BlendMode::Overwrite);
+        b.paste(&cube, deep::Vec3::new(usize::MAX, 0, 0), BlendMode::Overwrite);
+        b.apply_mask(&cube, deep::Vec3::new(0, usize::MAX, 0), MaskOp::Or);
+        cube.stamp_into(&mut b, deep::Vec3::new(0, 0, usize::MAX));
+        let other = deep::Automaton::new(AutomataRules::new(4..=5, 5, 2, Method::Moore), deep::Vec3::new(4, 4, 4), cube.positions()).unwrap();
+        b.merge(&other, deep::Vec3::new(usize::MAX, usize::MAX, 0), ConflictPolicy::KeepMax);
+        assert_eq!(b.live_cells_sorted().into_iter().map(|(v, _)| (v.x(), v.y(), v.z())).collect::<Vec<_>>(), vec![(3, 3, 3)]);
+    }
+
+    #[test]
+    fn patterns_turn_and_flip_inside_their_bounding_box() {
+        // an L lying on its side, three wide and two tall, that doesn't start at the origin
+        //   XXX
+        //   X..
+        let l = flat::Pattern::with_states("L", vec![(flat::Vec2::new(1, 2), 1), (flat::Vec2::new(2, 2), 1), (flat::Vec2::new(3, 2), 1), (flat::Vec2::new(1, 3), 2)]);
+        let cells = |p: &flat::Pattern| {
+            let mut cells: Vec<(usize, usize)> = p.positions().into_iter().map(|v| (v.x(), v.y())).collect();
+            cells.sort_unstable();
+            cells
+        };
+
+        // turning it clockwise stands it up, two wide and three tall, with the top-left corner staying put
+        //   XX
+        //   .X
+        //   .X
+        assert_eq!(cells(&l.rotate90()), vec![(1, 2), (2, 2), (2, 3), (2, 4)]);
+        assert_eq!(cells(&l.rotate90().rotate90().rotate90().rotate90()), cells(&l));
+        assert_eq!(cells(&l.rotate90().rotate90()), cells(&l.mirror_x().mirror_y()));
+
+        assert_eq!(cells(&l.mirror_x()), vec![(1, 2), (2, 2), (3, 2), (3, 3)]);
+        assert_eq!(cells(&l.mirror_y()), vec![(1, 2), (1, 3), (2, 3), (3, 3)]);
+        assert_eq!(cells(&l.mirror_x().mirror_x()), cells(&l));
+        assert_eq!(cells(&flat::Pattern::new("empty", Vec::new()).rotate90()), Vec::new());
+
+        // states go along with the cells they're on
+        let mut a = flat::Automaton::new(AutomataRules::new(2..=3, 3, 3, Method::Moore), flat::Vec2::new(8, 8), Vec::new()).unwrap();
+        l.rotate90().stamp_into(&mut a, flat::Vec2::new(0, 0));
+        assert_eq!(a.get_cell(&flat::Vec2::new(1, 2)), Some(2));
+        assert_eq!(a.get_cell(&flat::Vec2::new(2, 4)), Some(1));
+
+        let cube = deep::Pattern::new("", vec![deep::Vec3::new(0, 0, 0), deep::Vec3::new(1, 0, 0), deep::Vec3::new(2, 0, 0), deep::Vec3::new(0, 1, 1)]);
+        let cells = |p: &deep::Pattern| {
+            let mut cells: Vec<(usize, usize, usize)> = p.positions().into_iter().map(|v| (v.x(), v.y(), v.z())).collect();
+            cells.sort_unstable();
+            cells
+        };
+
+        assert_eq!(cells(&cube.rotate90(deep::Axis::Z)), vec![(0, 0, 1), (1, 0, 0), (1, 1, 0), (1, 2, 0)]);
+        assert_eq!(cells(&cube.rotate90(deep::Axis::X)), vec![(0, 0, 1), (0, 1, 0), (1, 1, 0), (2, 1, 0)]);
+        assert_eq!(cells(&cube.mirror_z()), vec![(0, 0, 1), (0, 1, 0), (1, 0, 1), (2, 0, 1)]);
+        for axis in [deep::Axis::X, deep::Axis::Y, deep::Axis::Z] {
+            assert_eq!(cells(&cube.rotate90(axis).rotate90(axis).rotate90(axis).rotate90(axis)), cells(&cube));
+        }
+    }
+
+    #[test]
+    fn stamps_hanging_off_the_edge_are_cut_off() {
+        let l = flat::Pattern::with_states("L", vec![(flat::Vec2::new(0, 0), 1), (flat::Vec2::new(1, 0), 7), (flat::Vec2::new(2, 0), 1), (flat::Vec2::new(0, 1), 1)]);
+        let mut a = flat::Automaton::new(AutomataRules::new(2..=3, 3, 3, Method::Moore), flat::Vec2::new(4, 3), Vec::new()).unwrap();
+
+        // only the two cells of the top row that fit go in, and the state too big for the rules is clamped to alive
+        l.stamp_into(&mut a, flat::Vec2::new(2, 2));
+        assert_eq!(a.live_cells_sorted().into_iter().map(|(v, s)| (v.x(), v.y(), s)).collect::<Vec<_>>(), vec![(2, 2, 1), (3, 2, 2)]);
+
+        // cells without their own state go in alive
+        let mut a = flat::Automaton::new(AutomataRules::new(2..=3, 3, 3, Method::Moore), flat::Vec2::new(4, 3), Vec::new()).unwrap();
+        flat::Pattern::new("line", (0..6).map(|x| flat::Vec2::new(x, 0)).collect()).rotate90().stamp_into(&mut a, flat::Vec2::new(1, 0));
+        assert_eq!(a.live_cells_sorted().into_iter().map(|(v, s)| (v.x(), v.y(), s)).collect::<Vec<_>>(), vec![(1, 0, 2), (1, 1, 2), (1, 2, 2)]);
+
+        let mut b = deep::Automaton::new(AutomataRules::new(4..=5, 5, 2, Method::Moore), deep::Vec3::new(2, 2, 2), Vec::new()).unwrap();
+        deep::Pattern::new("", (0..27).map(|i| deep::Vec3::new(i % 3, i / 3 % 3, i / 9)).collect()).stamp_into(&mut b, deep::Vec3::new(1, 1, 1));
+        assert_eq!(b.live_cells_sorted().into_iter().map(|(v, _)| (v.x(), v.y(), v.z())).collect::<Vec<_>>(), vec![(1, 1, 1)]);
+    }
+
+    #[test]
+    fn changing_how_many_states_there_are_keeps_live_cells_alive() {
+        let rules = |states| AutomataRules::new(2..=3, 3, states, Method::Moore);
+        let states = |a: &flat::Automaton| a.live_cells_sorted().into_iter().map(|(v, s)| (v.x(), s)).collect::<Vec<_>>();
+
+        for backend in [Backend::HashMap, Backend::Dense] {
+            // one live cell and three dying ones, in a row far enough apart that none of them are neighbors
+            let mut a = flat::Automaton::with_backend(rules(5), flat::Vec2::new(8, 1), Vec::new(), backend).unwrap();
+            for (x, state) in [(0, 4), (2, 3), (4, 2), (6, 1)] { a.set_cell(flat::Vec2::new(x, 0), state); }
+            a.set_edge_state(4);
+
+            // with fewer states, live cells are still alive and every dying cell gets clamped to the last dying state
+            a.set_rules(rules(3)).unwrap();
+            assert_eq!(states(&a), vec![(0, 2), (2, 1), (4, 1), (6, 1)]);
+            assert_eq!(a.get_edge_state(), 2);
+
+            // with two, there aren't any dying states left, so the dying cells are gone
+            a.set_rules(rules(2)).unwrap();
+            assert_eq!(states(&a), vec![(0, 1)]);
+
+            // and growing again just moves live cells up to the new live state
+            a.set_rules(rules(6)).unwrap();
+            assert_eq!(states(&a), vec![(0, 5)]);
+        }
+
+        // species get clamped to fit too
+        let mut a = flat::Automaton::new(rules(2).with_species(4, Inheritance::Majority), flat::Vec2::new(4, 4), vec![flat::Vec2::new(1, 1), flat::Vec2::new(2, 2)]).unwrap();
+        a.set_species(flat::Vec2::new(1, 1), 3);
+        a.set_species(flat::Vec2::new(2, 2), 1);
+        a.set_rules(rules(2).with_species(2, Inheritance::Majority)).unwrap();
+        assert_eq!(a.get_species()[&flat::Vec2::new(1, 1)], 1);
+        assert_eq!(a.get_species()[&flat::Vec2::new(2, 2)], 1);
+
+        // transition tables don't have dying states, so their states just get clamped
+        let mut a = flat::Automaton::new(AutomataRules::from_transitions("colors", 6, Method::Moore, Vec::new()), flat::Vec2::new(8, 1), Vec::new()).unwrap();
+        for (x, state) in [(0, 5), (2, 4), (4, 1)] { a.set_cell(flat::Vec2::new(x, 0), state); }
+        a.set_rules(AutomataRules::from_transitions("colors", 3, Method::Moore, Vec::new())).unwrap();
+        assert_eq!(states(&a), vec![(0, 2), (2, 2), (4, 1)]);
+
+        let mut b = deep::Automaton::new(AutomataRules::new(4..=5, 5, 5, Method::Moore), deep::Vec3::new(8, 1, 1), Vec::new()).unwrap();
+        for (x, state) in [(0, 4), (2, 3), (4, 1)] { b.set_cell(deep::Vec3::new(x, 0, 0), state); }
+        b.set_rules(AutomataRules::new(4..=5, 5, 3, Method::Moore)).unwrap();
+        assert_eq!(b.live_cells_sorted().into_iter().map(|(v, s)| (v.x(), s)).collect::<Vec<_>>(), vec![(0, 2), (2, 1), (4, 1)]);
+    }
+
+    #[test]
+    fn resizing_keeps_the_anchor_in_place() {
+        // a diagonal where every cell has its own state, so it's easy to tell which ones made it
+        let diagonal = || {
+            let mut a = flat::Automaton::new(AutomataRules::new(2..=3, 3, 5, Method::Moore), flat::Vec2::new(4, 4), Vec::new()).unwrap();
+            for i in 0..4 { a.set_cell(flat::Vec2::new(i, i), 4 - i as u8); }
+            a
+        };
+        let cells = |a: &flat::Automaton| a.live_cells_sorted().into_iter().map(|(v, s)| (v.x(), v.y(), s)).collect::<Vec<_>>();
+
+        for (anchor, grown, shrunk) in [
+            (Anchor::Start, vec![(0, 0, 4), (1, 1, 3), (2, 2, 2), (3, 3, 1)], vec![(0, 0, 4), (1, 1, 3)]),
+            (Anchor::Center, vec![(1, 2, 4), (2, 3, 3), (3, 4, 2), (4, 5, 1)], vec![(0, 0, 3), (1, 1, 2)]),
+            (Anchor::End, vec![(2, 4, 4), (3, 5, 3), (4, 6, 2), (5, 7, 1)], vec![(0, 0, 2), (1, 1, 1)])
+        ] {
+            let mut a = diagonal();
+            a.resize(flat::Vec2::new(6, 8), anchor);
+            assert_eq!(a.get_bounds(), &flat::Vec2::new(6, 8));
+            assert_eq!(cells(&a), grown);
+
+            let mut a = diagonal();
+            a.resize(flat::Vec2::new(2, 2), anchor);
+            assert_eq!(cells(&a), shrunk);
+        }
+
+        let mut b = deep::Automaton::new(AutomataRules::new(4..=5, 5, 2, Method::Moore), deep::Vec3::new(4, 4, 4), vec![deep::Vec3::new(0, 0, 0), deep::Vec3::new(3, 3, 3)]).unwrap();
+        b.resize(deep::Vec3::new(6, 8, 4), Anchor::End);
+        assert_eq!(b.live_cells_sorted().into_iter().map(|(v, _)| (v.x(), v.y(), v.z())).collect::<Vec<_>>(), vec![(2, 4, 0), (5, 7, 3)]);
+        b.resize(deep::Vec3::new(2, 2, 2), Anchor::Center);
+        assert!(b.live_cells_sorted().is_empty());
+    }
+
+    #[test]
+    fn cropping_leaves_just_the_live_cells_and_padding() {
+        let life = AutomataRules::new(2..=3, 3, 2, Method::Moore);
+        let mut a = flat::Automaton::new(life.clone(), flat::Vec2::new(16, 16), vec![flat::Vec2::new(5, 5), flat::Vec2::new(7, 6)]).unwrap();
+        a.crop_to_live_cells(1);
+        assert_eq!(a.get_bounds(), &flat::Vec2::new(5, 4));
+        assert_eq!(a.live_cells_sorted().into_iter().map(|(v, _)| (v.x(), v.y())).collect::<Vec<_>>(), vec![(1, 1), (3, 2)]);
+
+        // an empty grid ends up as nothing but padding, or nothing at all without any
+        for (padding, bounds) in [(3, 6), (0, 0)] {
+            let mut empty = flat::Automaton::new(life.clone(), flat::Vec2::new(16, 16), Vec::new()).unwrap();
+            empty.crop_to_live_cells(padding);
+            assert_eq!(empty.get_bounds(), &flat::Vec2::new(bounds, bounds));
+            assert!(empty.live_cells_sorted().is_empty());
+            empty.tick();
+        }
+
+        let mut b = deep::Automaton::new(AutomataRules::new(4..=5, 5, 2, Method::Moore), deep::Vec3::new(8, 8, 8), Vec::new()).unwrap();
+        b.crop_to_live_cells(2);
+        assert_eq!(b.get_bounds(), &deep::Vec3::new(4, 4, 4));
+        b.set_cell(deep::Vec3::new(1, 2, 3), 1);
+        b.crop_to_live_cells(0);
+        assert_eq!(b.get_bounds(), &deep::Vec3::new(1, 1, 1));
+        assert_eq!(b.get_cell(&deep::Vec3::new(0, 0, 0)), Some(1));
+    }
+
+    /// Ticks a random soup with the given backend, walls and edge state, giving back every generation along the way.
+    fn backend_run(rules: &AutomataRules, bounds: flat::Vec2, backend: Backend, walls: &[(flat::Vec2, Wall)], edge: u8) -> Vec<Vec<(flat::Vec2, u8)>> {
+        let mut automaton = flat::Automaton::with_backend(rules.clone(), bounds, flat::random_seed(bounds, 0.4, &mut Rng::new(bounds.x() as u64)), backend).unwrap();
+        for (v, wall) in walls { automaton.set_wall(*v, *wall); }
+        automaton.set_edge_state(edge);
+        (0..24).map(|_| { automaton.tick(); automaton.live_cells_sorted() }).collect()
+    }
+
+    #[test]
+    fn bit_packed_grids_tick_like_hash_maps() {
+        // rows that end right before, on and right after a word boundary, where the last cell of one row sits next to the first cell of the next in memory,
+        // so anything that leaked across would show up along the edges
+        let life = presets::life();
+        for width in [1, 5, 63, 64, 65, 130] {
+            let bounds = flat::Vec2::new(width, 9);
+            let walls: Vec<_> = [(0, 0, Wall::Alive), (width - 1, 4, Wall::Alive), (width / 2, 8, Wall::Dead), (width - 1, 0, Wall::Excluded), (0, 5, Wall::Excluded)].into_iter().map(|(x, y, wall)| (flat::Vec2::new(x, y), wall)).collect();
+            for (walls, edge) in [(&[][..], 0), (&walls[..], 0), (&[][..], 1), (&walls[..], 1)] {
+                let expected = backend_run(&life, bounds, Backend::HashMap, walls, edge);
+                assert!(width == 1 || !expected[0].is_empty());
+                assert_eq!(backend_run(&life, bounds, Backend::BitPacked, walls, edge), expected, "{} wide with {} walls and edge {}", width, walls.len(), edge);
+            }
+        }
+
+        // and there's only one bit for each cell, so nothing with more than two states fits
+        let brain = flat::Automaton::with_backend(presets::brians_brain(), flat::Vec2::new(8, 8), Vec::new(), Backend::BitPacked);
+        assert!(matches!(brain, Err(AutomataError::UnsupportedBackend(Backend::BitPacked))));
+
+        let bounds = deep::Vec3::new(65, 4, 3);
+        let run = |backend| {
+            let mut automaton = deep::Automaton::with_backend(AutomataRules::new(4..=5, 5, 2, Method::Moore), bounds, deep::random_seed(bounds, 0.3, &mut Rng::new(4)), backend).unwrap();
+            automaton.set_wall(deep::Vec3::new(64, 3, 2), Wall::Alive);
+            automaton.set_wall(deep::Vec3::new(0, 0, 1), Wall::Excluded);
+            (0..12).map(|_| { automaton.tick(); automaton.live_cells_sorted() }).collect::<Vec<_>>()
+        };
+        assert_eq!(run(Backend::BitPacked), run(Backend::HashMap));
+    }
+
+    #[test]
+    fn incremental_grids_tick_like_hash_maps() {
+        // cells are kept in one long array, so the last cell of a row and the first of the next are right next to each other in it, but shouldn't be neighbors
+        for rules in [presets::life(), presets::brians_brain(), presets::greenberg_hastings(2, 3, Method::VonNeumann), AutomataRules::new(2..=3, 3, 4, Method::Moore)] {
+            for width in [1, 5, 17] {
+                let bounds = flat::Vec2::new(width, 11);
+                let walls: Vec<_> = [(0, 0, Wall::Alive), (width - 1, 4, Wall::Alive), (width / 2, 10, Wall::Dead), (width - 1, 0, Wall::Excluded), (0, 5, Wall::Excluded)].into_iter().map(|(x, y, wall)| (flat::Vec2::new(x, y), wall)).collect();
+                for (walls, edge) in [(&[][..], 0), (&walls[..], 0), (&[][..], 1), (&walls[..], 1)] {
+                    let expected = backend_run(&rules, bounds, Backend::HashMap, walls, edge);
+                    assert!(width == 1 || !expected[0].is_empty());
+                    assert_eq!(backend_run(&rules, bounds, Backend::Incremental, walls, edge), expected, "{} {} wide with {} walls and edge {}", rules, width, walls.len(), edge);
+                }
+            }
+        }
+
+        let bounds = deep::Vec3::new(7, 5, 4);
+        let run = |backend| {
+            let mut automaton = deep::Automaton::with_backend(presets::rule_445(), bounds, deep::random_seed(bounds, 0.3, &mut Rng::new(6)), backend).unwrap();
+            automaton.set_wall(deep::Vec3::new(6, 4, 3), Wall::Alive);
+            automaton.set_wall(deep::Vec3::new(0, 0, 1), Wall::Excluded);
+            (0..12).map(|_| { automaton.tick(); automaton.live_cells_sorted() }).collect::<Vec<_>>()
+        };
+        assert_eq!(run(Backend::Incremental), run(Backend::HashMap));
+    }
+
+    #[test]
+    #[cfg(all(feature = "mmap", unix))]
+    fn mapped_grids_tick_like_hash_maps() {
+        for rules in [presets::life(), presets::brians_brain()] {
+            for width in [1, 15, 16, 17] {
+                let bounds = flat::Vec2::new(width, 9);
+                let walls: Vec<_> = [(0, 0, Wall::Alive), (width - 1, 4, Wall::Alive), (width / 2, 8, Wall::Dead), (width - 1, 0, Wall::Excluded)].into_iter().map(|(x, y, wall)| (flat::Vec2::new(x, y), wall)).collect();
+                for (walls, edge) in [(&[][..], 0), (&walls[..], 0), (&walls[..], 1)] {
+                    assert_eq!(backend_run(&rules, bounds, Backend::Mapped, walls, edge), backend_run(&rules, bounds, Backend::HashMap, walls, edge), "{} {} wide with {} walls and edge {}", rules, width, walls.len(), edge);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn deltas_with_the_last_sequence_number_are_refused() {
+        // a snapshot of an empty 4x4 grid, with the sequence number written out as a varint by hand
+        let snapshot = |sequence: u64| {
+            let mut bytes = vec![1, 1];
+            let mut rest = sequence;
+            while rest >= 0x80 { bytes.push(rest as u8 | 0x80); rest >>= 7; }
+            bytes.push(rest as u8);
+            bytes.extend([0, 4, 4, 1, 0]);
+            bytes
+        };
+
+        let mut decoder = sync::DeltaDecoder::new();
+        let mut a = flat::Automaton::new(presets::life(), flat::Vec2::new(4, 4), vec![flat::Vec2::new(1, 1)]).unwrap();
+        assert!(matches!(decoder.apply_delta(&mut a, &snapshot(u64::MAX)), Err(AutomataError::InvalidDelta(_))));
+        assert_eq!(a.get_cell(&flat::Vec2::new(1, 1)), Some(1));
+        assert_eq!(decoder.sequence(), 0);
+
+        // with one less, the snapshot goes through and the next delta is the last one there can be
+        decoder.apply_delta(&mut a, &snapshot(u64::MAX - 1)).unwrap();
+        assert_eq!(decoder.sequence(), u64::MAX);
+        assert!(a.live_cells_sorted().is_empty());
+
+        let mut b = deep::Automaton::new(AutomataRules::new(4..=5, 5, 2, Method::Moore), deep::Vec3::new(4, 4, 1), Vec::new()).unwrap();
+        assert!(matches!(sync::DeltaDecoder::new().apply_delta_deep(&mut b, &snapshot(u64::MAX)), Err(AutomataError::InvalidDelta(_))));
+    }
+}
\ No newline at end of file
@@ -0,0 +1,98 @@
+//! Buffers of bytes that live in a file mapped into memory instead of in memory itself, for grids too big to fit in RAM.
+//! 
+//! Each buffer gets a file of its own in the temporary directory (wherever `TMPDIR` points), which gets deleted as soon as it's mapped,
+//! so nothing is left lying around even if the program crashes. The operating system pages parts of the file in and out of memory as they get used,
+//! which is a lot slower than memory when it has to go to the disk, but the grid can be as big as the disk has room for.
+//! 
+//! This only works on Unix, since it calls `mmap` straight out of the C library, so the module is left out everywhere else.
+
+//--> Imports <--
+
+use std::fs::{self, OpenOptions};
+use std::io;
+use std::ops::{Deref, DerefMut};
+use std::os::raw::{c_int, c_void};
+use std::os::unix::io::AsRawFd;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+//--> Structs <--
+
+/// A buffer of bytes kept in a memory-mapped file, which starts out all zeroes.
+pub(crate) struct Mapped {
+	ptr: *mut u8,
+	len: usize
+}
+
+// the mapping belongs to the buffer alone, so it's just as safe to hand over or share as a Vec<u8>
+unsafe impl Send for Mapped {}
+unsafe impl Sync for Mapped {}
+
+//--> Functions <--
+
+/// The C library's `off_t`, which `mmap` takes its offset as. It's 32 bits on 32-bit Linux and Android (where the 64-bit one is `mmap64`), and 64 bits everywhere else.
+#[cfg(all(any(target_os = "linux", target_os = "android"), target_pointer_width = "32"))]
+#[allow(non_camel_case_types)]
+type off_t = i32;
+#[cfg(not(all(any(target_os = "linux", target_os = "android"), target_pointer_width = "32")))]
+#[allow(non_camel_case_types)]
+type off_t = i64;
+
+extern "C" {
+	fn mmap(addr: *mut c_void, len: usize, prot: c_int, flags: c_int, fd: c_int, offset: off_t) -> *mut c_void;
+	fn munmap(addr: *mut c_void, len: usize) -> c_int;
+}
+
+const PROT_READ: c_int = 1;
+const PROT_WRITE: c_int = 2;
+const MAP_SHARED: c_int = 1;
+
+/// Counts up for every file made, so two buffers never go after the same file.
+static NEXT_FILE: AtomicUsize = AtomicUsize::new(0);
+
+impl Mapped {
+	/// Creates a buffer of `len` zeroes in a new memory-mapped file.
+	pub(crate) fn new(len: usize) -> io::Result<Mapped> {
+		// mapping nothing at all isn't allowed, and there's nothing to keep anyway
+		if len == 0 { return Ok(Mapped { ptr: std::ptr::NonNull::dangling().as_ptr(), len }) }
+
+		let path = std::env::temp_dir().join(format!("cellular-{}-{}.cells", std::process::id(), NEXT_FILE.fetch_add(1, Ordering::Relaxed)));
+		let file = OpenOptions::new().read(true).write(true).create_new(true).open(&path)?;
+
+		// growing the file fills it with zeroes, which most file systems don't even write out until something else gets written there
+		let mapped = file.set_len(len as u64).and_then(|()| {
+			let ptr = unsafe { mmap(std::ptr::null_mut(), len, PROT_READ | PROT_WRITE, MAP_SHARED, file.as_raw_fd(), 0) };
+			if ptr as isize == -1 { Err(io::Error::last_os_error()) } else { Ok(Mapped { ptr: ptr as *mut u8, len }) }
+		});
+
+		// the mapping keeps the file around until it's unmapped, so it can go right away
+		let _ = fs::remove_file(&path);
+		mapped
+	}
+
+	/// Copies the buffer into a new file of its own.
+	pub(crate) fn try_clone(&self) -> io::Result<Mapped> {
+		let mut copy = Mapped::new(self.len)?;
+		copy.copy_from_slice(self);
+		Ok(copy)
+	}
+}
+
+impl Deref for Mapped {
+	type Target = [u8];
+
+	fn deref(&self) -> &[u8] {
+		unsafe { std::slice::from_raw_parts(self.ptr, self.len) }
+	}
+}
+
+impl DerefMut for Mapped {
+	fn deref_mut(&mut self) -> &mut [u8] {
+		unsafe { std::slice::from_raw_parts_mut(self.ptr, self.len) }
+	}
+}
+
+impl Drop for Mapped {
+	fn drop(&mut self) {
+		if self.len > 0 { unsafe { munmap(self.ptr as *mut c_void, self.len); } }
+	}
+}
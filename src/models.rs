@@ -0,0 +1,359 @@
+//! This module has ready-made versions of a couple of classic cellular automaton models that don't fit neatly into birth and survival rules.
+//! 
+//! The Drossel–Schwabl forest-fire model grows trees at random, strikes them with lightning at random, and lets fire spread from tree to tree.
+//! 
+//! ```
+//! # use cellular_snapp::{Method, flat::Vec2, models::{ForestFire, Forest}};
+//! let mut forest = ForestFire::new(Vec2::new(32, 32), Method::VonNeumann, 0.05, 0.001, 0.5, 1).unwrap();
+//! for _ in 0..50 { forest.tick(); }
+//! assert_eq!(forest.count(Forest::Empty) + forest.count(Forest::Tree) + forest.count(Forest::Burning), 32 * 32);
+//! ```
+//! 
+//! The SIR epidemic model spreads an infection from infected cells to their susceptible neighbors, who eventually recover.
+//! 
+//! ```
+//! # use cellular_snapp::{Method, flat::Vec2, models::{Epidemic, Health}};
+//! let mut epidemic = Epidemic::new(Vec2::new(32, 32), Method::Moore, 0.3, 0.1, vec![Vec2::new(16, 16)], 1).unwrap();
+//! for _ in 0..50 { epidemic.tick(); }
+//! assert_eq!(epidemic.count(Health::Susceptible) + epidemic.count(Health::Infected) + epidemic.count(Health::Recovered), 32 * 32);
+//! ```
+//! 
+//! The Abelian sandpile piles grains of sand up on cells, and any cell with too many of them topples, handing a grain to each of its neighbors.
+//! 
+//! ```
+//! # use cellular_snapp::{Method, flat::Vec2, models::Sandpile};
+//! let mut pile = Sandpile::new(Vec2::new(5, 5), Method::VonNeumann).unwrap();
+//! pile.add_grains(Vec2::new(2, 2), 4);
+//! 
+//! assert_eq!(pile.relax(), 1);
+//! assert_eq!(pile.get_grains(&Vec2::new(2, 2)), Some(0));
+//! assert_eq!(pile.get_grains(&Vec2::new(2, 1)), Some(1));
+//! assert!(pile.is_stable());
+//! ```
+
+//--> Imports <--
+
+use crate::{AutomataError, Method};
+use crate::flat::{self, IVec2, Vec2};
+use crate::random::Rng;
+use std::collections::HashMap;
+use std::collections::HashSet;
+
+//--> Structs <--
+
+/// The Drossel–Schwabl forest-fire model.
+/// 
+/// Every tick, burning trees burn out, trees next to a burning tree catch fire, trees get struck by lightning with a `lightning` chance, and empty ground grows a tree with a `growth` chance.
+pub struct ForestFire {
+	bounds: Vec2,
+	method: Method,
+	growth: f64,
+	lightning: f64,
+	cells: HashMap<Vec2, Forest>,
+	rng: Rng
+}
+
+/// The SIR (susceptible, infected, recovered) epidemic model.
+/// 
+/// Every tick, each infected neighbor of a susceptible cell has an `infection` chance of passing the infection on, and infected cells recover with a `recovery` chance.
+/// Recovered cells are immune forever, unless you give them a chance of losing that immunity (which makes it an SIRS model).
+pub struct Epidemic {
+	bounds: Vec2,
+	method: Method,
+	infection: f64,
+	recovery: f64,
+	immunity_loss: f64,
+	cells: HashMap<Vec2, Health>,
+	rng: Rng
+}
+
+/// The Abelian sandpile model.
+/// 
+/// Every cell holds some amount of grains, and a cell with at least `threshold` of them is unstable and topples,
+/// losing `threshold` grains and passing one to each of its neighbors. Grains that would go past the edge of the grid are lost.
+/// It doesn't matter what order cells topple in, the pile always settles down the same way, which is what makes it Abelian.
+#[derive(Clone)]
+pub struct Sandpile {
+	bounds: Vec2,
+	method: Method,
+	threshold: u32,
+	cells: HashMap<Vec2, u32>
+}
+
+//--> Enums <--
+
+/// What's in a spot of the forest.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Forest {
+	/// Nothing here yet.
+	Empty,
+	/// A healthy tree.
+	Tree,
+	/// A tree on fire, which will be gone next tick.
+	Burning
+}
+
+/// How healthy a cell is.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Health {
+	/// Hasn't caught the infection, but could.
+	Susceptible,
+	/// Has the infection, and can pass it on.
+	Infected,
+	/// Has gotten over the infection, and is immune.
+	Recovered
+}
+
+//--> Functions <--
+
+impl ForestFire {
+	/// Creates a new forest, where every spot starts with a `tree_density` chance of having a tree in it.
+	/// The seed is used for the random number generator, so the same seed always burns the same way.
+	/// Just like flat automata, this gives back an `UnsupportedFacesAndEdges` error for the faces and edges neighbor method.
+	pub fn new(bounds: Vec2, method: Method, growth: f64, lightning: f64, tree_density: f64, seed: u64) -> Result<ForestFire, AutomataError> {
+		check_method(&method)?;
+		let mut rng = Rng::new(seed);
+		let mut cells = HashMap::new();
+
+		for x in 0..bounds.x() {
+			for y in 0..bounds.y() {
+				cells.insert(Vec2::new(x, y), if rng.chance(tree_density) { Forest::Tree } else { Forest::Empty });
+			}
+		}
+
+		Ok(ForestFire { bounds, method, growth, lightning, cells, rng })
+	}
+
+	/// Advances the forest by one time step (or tick).
+	pub fn tick(&mut self) {
+		let offsets = flat::neighbor_offsets(&self.method);
+		let key = self.rng.next_u64();
+
+		self.cells = self.cells.iter().map(|(v, cell)| {
+			let mut rng = Rng::keyed(key, &[v.x() as u64, v.y() as u64]);
+
+			let next = match cell {
+				Forest::Burning => Forest::Empty,
+				Forest::Tree => {
+					let fire_nearby = offsets.iter()
+						.filter_map(|offset| v.checked_add_offset(offset))
+						.any(|n| self.cells.get(&n) == Some(&Forest::Burning));
+
+					if fire_nearby || rng.chance(self.lightning) { Forest::Burning } else { Forest::Tree }
+				},
+				Forest::Empty => if rng.chance(self.growth) { Forest::Tree } else { Forest::Empty }
+			};
+
+			(*v, next)
+		}).collect();
+	}
+
+	/// Get a copy of the forest.
+	pub fn get_cells(&self) -> HashMap<Vec2, Forest> {
+		self.cells.clone()
+	}
+
+	/// Get the bounds (the size of the grid) of the forest.
+	pub fn get_bounds(&self) -> &Vec2 {
+		&self.bounds
+	}
+
+	/// Counts how many spots of the forest are in the given state.
+	pub fn count(&self, state: Forest) -> usize {
+		self.cells.values().filter(|c| **c == state).count()
+	}
+}
+
+impl Epidemic {
+	/// Creates a new population where everyone is susceptible, apart from the given infected cells.
+	/// The seed is used for the random number generator, so the same seed always spreads the same way.
+	/// Just like flat automata, this gives back an `UnsupportedFacesAndEdges` error for the faces and edges neighbor method.
+	pub fn new(bounds: Vec2, method: Method, infection: f64, recovery: f64, infected: Vec<Vec2>, seed: u64) -> Result<Epidemic, AutomataError> {
+		check_method(&method)?;
+		let mut cells = HashMap::new();
+
+		for x in 0..bounds.x() {
+			for y in 0..bounds.y() {
+				let v = Vec2::new(x, y);
+				let health = if infected.contains(&v) { Health::Infected } else { Health::Susceptible };
+				cells.insert(v, health);
+			}
+		}
+
+		Ok(Epidemic { bounds, method, infection, recovery, immunity_loss: 0.0, cells, rng: Rng::new(seed) })
+	}
+
+	/// Gives recovered cells a chance of becoming susceptible again each tick, turning this into an SIRS model.
+	pub fn with_immunity_loss(mut self, immunity_loss: f64) -> Epidemic {
+		self.immunity_loss = immunity_loss;
+		self
+	}
+
+	/// Advances the epidemic by one time step (or tick).
+	pub fn tick(&mut self) {
+		let offsets = flat::neighbor_offsets(&self.method);
+		let key = self.rng.next_u64();
+
+		self.cells = self.cells.iter().map(|(v, cell)| {
+			let mut rng = Rng::keyed(key, &[v.x() as u64, v.y() as u64]);
+
+			let next = match cell {
+				Health::Susceptible => {
+					let infected_neighbors = offsets.iter()
+						.filter_map(|offset| v.checked_add_offset(offset))
+						.filter(|n| self.cells.get(n) == Some(&Health::Infected))
+						.count();
+
+					// every infected neighbor gets its own chance to pass the infection on
+					let escape = (1.0 - self.infection).powi(infected_neighbors as i32);
+					if rng.chance(1.0 - escape) { Health::Infected } else { Health::Susceptible }
+				},
+				Health::Infected => if rng.chance(self.recovery) { Health::Recovered } else { Health::Infected },
+				Health::Recovered => if rng.chance(self.immunity_loss) { Health::Susceptible } else { Health::Recovered }
+			};
+
+			(*v, next)
+		}).collect();
+	}
+
+	/// Get a copy of the population.
+	pub fn get_cells(&self) -> HashMap<Vec2, Health> {
+		self.cells.clone()
+	}
+
+	/// Get the bounds (the size of the grid) of the population.
+	pub fn get_bounds(&self) -> &Vec2 {
+		&self.bounds
+	}
+
+	/// Counts how many cells are in the given state.
+	pub fn count(&self, state: Health) -> usize {
+		self.cells.values().filter(|c| **c == state).count()
+	}
+}
+
+impl Sandpile {
+	/// Creates an empty sandpile, where cells topple once they have as many grains as they have neighbors.
+	/// A neighborhood without any neighbors in it still needs at least 1 grain to topple, and that grain is always lost.
+	/// Just like flat automata, this gives back an `UnsupportedFacesAndEdges` error for the faces and edges neighbor method.
+	/// 
+	/// ```
+	/// # use cellular_snapp::{AutomataError, Method, flat::Vec2, models::Sandpile};
+	/// let mut pile = Sandpile::new(Vec2::new(3, 3), Method::Euclidean(0.5)).unwrap();
+	/// pile.add_grains(Vec2::new(1, 1), 5);
+	/// 
+	/// assert_eq!(pile.get_threshold(), 1);
+	/// assert_eq!(pile.relax(), 5);
+	/// assert_eq!(pile.total_grains(), 0);
+	/// 
+	/// assert!(matches!(Sandpile::new(Vec2::new(3, 3), Method::FacesAndEdges), Err(AutomataError::UnsupportedFacesAndEdges)));
+	/// ```
+	pub fn new(bounds: Vec2, method: Method) -> Result<Sandpile, AutomataError> {
+		check_method(&method)?;
+		let threshold = (flat::neighbor_offsets(&method).len() as u32).max(1);
+		let cells = (0..bounds.x()).flat_map(|x| (0..bounds.y()).map(move |y| (Vec2::new(x, y), 0))).collect();
+		Ok(Sandpile { bounds, method, threshold, cells })
+	}
+
+	/// Changes how many grains it takes for a cell to topple. It can't be less than the amount of neighbors a cell has (or 1, whichever is more),
+	/// and anything past that is lost every time a cell topples, which makes for a dissipative sandpile.
+	pub fn with_threshold(mut self, threshold: u32) -> Sandpile {
+		self.threshold = threshold.max(flat::neighbor_offsets(&self.method).len() as u32).max(1);
+		self
+	}
+
+	/// Drops grains onto a cell. Cells outside the grid are ignored, and cells can't hold more than `u32::MAX` grains.
+	pub fn add_grains(&mut self, v: Vec2, grains: u32) {
+		if let Some(cell) = self.cells.get_mut(&v) { *cell = cell.saturating_add(grains); }
+	}
+
+	/// Topples every unstable cell once, all at the same time, and gives back how many cells toppled.
+	pub fn tick(&mut self) -> usize {
+		let offsets = flat::neighbor_offsets(&self.method);
+		let unstable: Vec<Vec2> = self.cells.iter().filter(|(_, grains)| **grains >= self.threshold).map(|(v, _)| *v).collect();
+
+		for v in &unstable { self.topple(v, 1, &offsets); }
+
+		unstable.len()
+	}
+
+	/// Keeps toppling cells until every cell is stable, and gives back how many topplings that took.
+	/// 
+	/// Only cells that just got grains can become unstable, so this only ever looks at those.
+	/// Cells topple as many times as they can at once, which gives the same pile as toppling them one at a time.
+	pub fn relax(&mut self) -> u64 {
+		let offsets = flat::neighbor_offsets(&self.method);
+		let mut unstable: Vec<Vec2> = self.cells.iter().filter(|(_, grains)| **grains >= self.threshold).map(|(v, _)| *v).collect();
+		let mut queued: HashSet<Vec2> = unstable.iter().copied().collect();
+		let mut topplings = 0;
+
+		while let Some(v) = unstable.pop() {
+			queued.remove(&v);
+
+			let times = self.cells[&v] / self.threshold;
+			if times == 0 { continue; }
+
+			topplings += times as u64;
+
+			for n in self.topple(&v, times, &offsets) {
+				if self.cells[&n] >= self.threshold && queued.insert(n) { unstable.push(n); }
+			}
+		}
+
+		topplings
+	}
+
+	/// Topples a cell some amount of times, and gives back the neighbors that got its grains.
+	fn topple(&mut self, v: &Vec2, times: u32, offsets: &[IVec2]) -> Vec<Vec2> {
+		if let Some(cell) = self.cells.get_mut(v) { *cell -= times * self.threshold; }
+
+		let neighbors: Vec<Vec2> = offsets.iter()
+			.filter_map(|offset| v.checked_add_offset(offset))
+			.filter(|n| n.x() < self.bounds.x() && n.y() < self.bounds.y())
+			.collect();
+
+		for n in &neighbors {
+			if let Some(cell) = self.cells.get_mut(n) { *cell = cell.saturating_add(times); }
+		}
+
+		neighbors
+	}
+
+	/// Checks whether every cell is stable, so nothing would topple.
+	pub fn is_stable(&self) -> bool {
+		self.cells.values().all(|grains| *grains < self.threshold)
+	}
+
+	/// Get how many grains a cell has, or `None` if it's outside the grid.
+	pub fn get_grains(&self, v: &Vec2) -> Option<u32> {
+		self.cells.get(v).copied()
+	}
+
+	/// Get a copy of how many grains every cell has.
+	pub fn get_cells(&self) -> HashMap<Vec2, u32> {
+		self.cells.clone()
+	}
+
+	/// Get the bounds (the size of the grid) of the sandpile.
+	pub fn get_bounds(&self) -> &Vec2 {
+		&self.bounds
+	}
+
+	/// Get how many grains it takes for a cell to topple.
+	pub fn get_threshold(&self) -> u32 {
+		self.threshold
+	}
+
+	/// Counts up every grain on the pile.
+	pub fn total_grains(&self) -> u64 {
+		self.cells.values().map(|grains| *grains as u64).sum()
+	}
+}
+
+/// Makes sure a neighbor method works in 2D, which every one but the faces and edges method does.
+fn check_method(method: &Method) -> Result<(), AutomataError> {
+	match method {
+		Method::FacesAndEdges => Err(AutomataError::UnsupportedFacesAndEdges),
+		_ => Ok(())
+	}
+}
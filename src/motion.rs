@@ -0,0 +1,179 @@
+//! This module follows a pattern around as it moves, so a renderer can keep a spaceship centered on screen instead of watching it fly off the edge.
+//! 
+//! Show a `MotionTracker` the automaton after every tick, and it works out the pattern's bounding box and center of mass,
+//! and how far each of them moved since the last time it was shown anything. Positions are `[x, y, z]`, where z is always 0 for flat automata.
+//! 
+//! ```
+//! # use cellular_snapp::{presets, motion::MotionTracker, flat::{UnboundedAutomaton, IVec2}};
+//! let glider = presets::glider().positions().into_iter().map(IVec2::from).collect();
+//! let mut life = UnboundedAutomaton::new(presets::life(), glider).unwrap();
+//! let mut tracker = MotionTracker::new();
+//! 
+//! let start = tracker.track_unbounded(&life).unwrap();
+//! assert_eq!(start.center_shift(), None);
+//! 
+//! for _ in 0..4 { life.tick().unwrap(); }
+//! let motion = tracker.track_unbounded(&life).unwrap();
+//! 
+//! // after a whole period, the glider is one cell further down and right
+//! assert_eq!(motion.box_shift(), Some([1, 1, 0]));
+//! assert_eq!(motion.center_shift(), Some([1.0, 1.0, 0.0]));
+//! ```
+//! 
+//! Spaceships change shape as they go, so their center of mass wobbles from tick to tick. `average_velocity` smooths that out,
+//! which makes for a steadier camera.
+
+//--> Imports <--
+
+use crate::{flat, deep};
+
+//--> Structs <--
+
+/// Where a pattern was when it was tracked, and how far it moved since the time before.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Motion {
+	min: [i64; 3],
+	max: [i64; 3],
+	center: [f64; 3],
+	sum: [i128; 3],
+	count: usize,
+	box_shift: Option<[i64; 3]>,
+	center_shift: Option<[f64; 3]>
+}
+
+/// Keeps track of where a pattern has been, one generation at a time.
+#[derive(Clone, Default)]
+pub struct MotionTracker {
+	first: Option<([i128; 3], usize)>,
+	last: Option<Motion>,
+	tracked: usize
+}
+
+//--> Functions <--
+
+impl Motion {
+	/// Get the corner of the bounding box closest to the origin.
+	pub fn min(&self) -> [i64; 3] {
+		self.min
+	}
+
+	/// Get the corner of the bounding box furthest from the origin, which is the last live or dying cell along each axis (so it's inside the box).
+	pub fn max(&self) -> [i64; 3] {
+		self.max
+	}
+
+	/// Get the average position of every live and dying cell.
+	pub fn center(&self) -> [f64; 3] {
+		self.center
+	}
+
+	/// Get how far the bounding box's first corner moved since the last time the tracker was shown a pattern, or `None` if this is the first time.
+	pub fn box_shift(&self) -> Option<[i64; 3]> {
+		self.box_shift
+	}
+
+	/// Get how far the center of mass moved since the last time the tracker was shown a pattern, or `None` if this is the first time.
+	pub fn center_shift(&self) -> Option<[f64; 3]> {
+		self.center_shift
+	}
+}
+
+impl MotionTracker {
+	/// Creates a tracker that hasn't seen anything yet.
+	pub fn new() -> MotionTracker {
+		MotionTracker::default()
+	}
+
+	/// Tracks the live and dying cells of a flat automaton. This gives back `None` (and doesn't count as a time it was shown something) if every cell is dead.
+	pub fn track(&mut self, automaton: &flat::Automaton) -> Option<Motion> {
+		self.push(automaton.live_cells_sorted().into_iter().map(|(v, _)| [v.x() as i64, v.y() as i64, 0]))
+	}
+
+	/// Tracks the live and dying cells of a deep automaton. This gives back `None` if every cell is dead.
+	pub fn track_deep(&mut self, automaton: &deep::Automaton) -> Option<Motion> {
+		self.push(automaton.live_cells_sorted().into_iter().map(|(v, _)| [v.x() as i64, v.y() as i64, v.z() as i64]))
+	}
+
+	/// Tracks the live and dying cells of an unbounded flat automaton, which is where spaceships can fly for as long as they like. This gives back `None` if every cell is dead.
+	pub fn track_unbounded(&mut self, automaton: &flat::UnboundedAutomaton) -> Option<Motion> {
+		self.push(automaton.get_cells().into_keys().map(|v| [v.x(), v.y(), 0]))
+	}
+
+	/// Tracks the live and dying cells of an unbounded deep automaton. This gives back `None` if every cell is dead.
+	pub fn track_unbounded_deep(&mut self, automaton: &deep::UnboundedAutomaton) -> Option<Motion> {
+		self.push(automaton.get_cells().into_keys().map(|v| [v.x(), v.y(), v.z()]))
+	}
+
+	/// Get the last motion worked out, or `None` if the tracker hasn't been shown a pattern yet.
+	pub fn last(&self) -> Option<&Motion> {
+		self.last.as_ref()
+	}
+
+	/// Get how far the center of mass has moved per showing, on average, since the first time the tracker was shown a pattern.
+	/// Show it the automaton after every tick, and this is the pattern's velocity in cells per tick. This is `None` until it's been shown a pattern twice.
+	/// 
+	/// ```
+	/// # use cellular_snapp::{presets, motion::MotionTracker, flat::{Automaton, Vec2}};
+	/// let mut life = Automaton::new(presets::life(), Vec2::new(32, 32), presets::glider().positions()).unwrap();
+	/// let mut tracker = MotionTracker::new();
+	/// 
+	/// for _ in 0..=8 {
+	///     tracker.track(&life);
+	///     life.tick();
+	/// }
+	/// 
+	/// // gliders go at c/4, diagonally
+	/// assert_eq!(tracker.average_velocity(), Some([0.25, 0.25, 0.0]));
+	/// ```
+	pub fn average_velocity(&self) -> Option<[f64; 3]> {
+		let ((sum, count), last) = (self.first?, self.last.as_ref()?);
+		if self.tracked < 2 { return None; }
+
+		let steps = (self.tracked - 1) as f64;
+		Some(shift(sum, count, last.sum, last.count).map(|d| d / steps))
+	}
+
+	/// Forgets everything it's seen, like when the camera jumps somewhere else.
+	pub fn reset(&mut self) {
+		*self = MotionTracker::default();
+	}
+
+	/// Works out the motion of a pattern from the positions of its live and dying cells.
+	fn push(&mut self, cells: impl Iterator<Item = [i64; 3]>) -> Option<Motion> {
+		let (mut min, mut max, mut sum, mut count) = ([i64::MAX; 3], [i64::MIN; 3], [0i128; 3], 0usize);
+
+		for v in cells {
+			for i in 0..3 {
+				min[i] = min[i].min(v[i]);
+				max[i] = max[i].max(v[i]);
+				sum[i] += v[i] as i128;
+			}
+			count += 1;
+		}
+
+		if count == 0 { return None; }
+		let center = sum.map(|s| s as f64 / count as f64);
+
+		let motion = Motion {
+			min,
+			max,
+			center,
+			sum,
+			count,
+			box_shift: self.last.as_ref().map(|last| std::array::from_fn(|i| min[i].saturating_sub(last.min[i]))),
+			center_shift: self.last.as_ref().map(|last| shift(last.sum, last.count, sum, count))
+		};
+
+		self.first.get_or_insert((sum, count));
+		self.last = Some(motion.clone());
+		self.tracked += 1;
+		Some(motion)
+	}
+}
+
+/// Works out how far the center of mass moved, from the sums of the positions before and after and how many cells there were.
+/// Doing it all with whole numbers before dividing keeps a pattern that moved by a whole cell from coming out as 0.9999999.
+fn shift(before: [i128; 3], before_count: usize, after: [i128; 3], after_count: usize) -> [f64; 3] {
+	let (b, a) = (before_count as i128, after_count as i128);
+	std::array::from_fn(|i| (after[i] * b - before[i] * a) as f64 / (a * b) as f64)
+}
@@ -0,0 +1,194 @@
+//! This module runs several flat automata on top of each other, where what happens on one layer can depend on what's going on in the others.
+//! 
+//! Every layer is an ordinary `Automaton` with its own rules, and they all share the same bounds.
+//! On top of its own rules, a layer can have conditions that cells need to meet to be born or to survive, like the cell in the same spot on another layer being alive,
+//! or having the right amount of live neighbors over there. That's enough for predator and prey models, or for one layer to act as walls for another.
+//! 
+//! ```
+//! # use cellular_snapp::{AutomataRules, Method, Rule, multilayer::{Condition, Multilayer}, flat::Vec2};
+//! let mut world = Multilayer::new(Vec2::new(5, 5));
+//! 
+//! // the prey are a blinker, and the predators never move, but starve wherever there's no prey
+//! let prey = world.add_layer("B3/S23".parse().unwrap(), vec![Vec2::new(1, 2), Vec2::new(2, 2), Vec2::new(3, 2)]).unwrap();
+//! let predators = world.add_layer(AutomataRules::new(0..=8, Rule::Many(Vec::new()), 2, Method::Moore), vec![Vec2::new(2, 2), Vec2::new(3, 2)]).unwrap();
+//! world.survival_needs(predators, Condition::Alive(prey)).unwrap();
+//! 
+//! world.tick();
+//! world.tick();
+//! 
+//! // the middle of the blinker never goes away, but the end of it does
+//! assert_eq!(world.layer(predators).get_cell(&Vec2::new(2, 2)), Some(1));
+//! assert_eq!(world.layer(predators).get_cell(&Vec2::new(3, 2)), Some(0));
+//! ```
+//! 
+//! Conditions always look at the generation before, so every layer ticks at the same time and the order they were added in doesn't matter.
+
+//--> Imports <--
+
+use crate::{AutomataError, AutomataRules, Rule};
+use crate::flat::{self, Automaton, Field, IVec2, Rect2, Vec2};
+use std::sync::Arc;
+
+//--> Structs <--
+
+/// A stack of flat automata with the same bounds, ticking together.
+#[derive(Clone)]
+pub struct Multilayer {
+	bounds: Vec2,
+	layers: Vec<Layer>,
+	generation: u64
+}
+
+/// A single layer, along with the conditions its cells need to meet on the other layers.
+#[derive(Clone)]
+struct Layer {
+	automaton: Automaton,
+	birth: Vec<Condition>,
+	survival: Vec<Condition>
+}
+
+//--> Enums <--
+
+/// Something a cell needs from another layer (given by its index) to be born or to survive.
+/// Cells on other layers count as alive if they're live or dying.
+#[derive(Clone, PartialEq, Debug)]
+pub enum Condition {
+	/// The cell in the same spot on the other layer has to be alive.
+	Alive(usize),
+	/// The cell in the same spot on the other layer has to be dead.
+	Dead(usize),
+	/// The amount of live cells on the other layer that neighbor this spot has to match the rule.
+	/// Neighbors are worked out with the neighbor method of the layer that has the condition.
+	Neighbors(usize, Rule)
+}
+
+//--> Functions <--
+
+impl Multilayer {
+	/// Creates a stack with no layers yet, where every layer will have the given bounds.
+	pub fn new(bounds: Vec2) -> Multilayer {
+		Multilayer { bounds, layers: Vec::new(), generation: 0 }
+	}
+
+	/// Adds a layer on top of the stack with its own rules and starting cells, and gives back its index.
+	pub fn add_layer(&mut self, rules: AutomataRules, start_cells: Vec<Vec2>) -> Result<usize, AutomataError> {
+		let automaton = Automaton::new(rules, self.bounds, start_cells)?;
+		self.layers.push(Layer { automaton, birth: Vec::new(), survival: Vec::new() });
+		Ok(self.layers.len() - 1)
+	}
+
+	/// Makes cells on a layer need to meet a condition to be born, on top of what its rules say.
+	/// Gives back an error if either the layer or the one the condition looks at doesn't exist.
+	pub fn birth_needs(&mut self, layer: usize, condition: Condition) -> Result<(), AutomataError> {
+		self.check(layer, &condition)?;
+		self.layers[layer].birth.push(condition);
+		Ok(())
+	}
+
+	/// Makes cells on a layer need to meet a condition to survive, on top of what its rules say. Cells that don't meet it start dying.
+	/// Gives back an error if either the layer or the one the condition looks at doesn't exist.
+	pub fn survival_needs(&mut self, layer: usize, condition: Condition) -> Result<(), AutomataError> {
+		self.check(layer, &condition)?;
+		self.layers[layer].survival.push(condition);
+		Ok(())
+	}
+
+	/// Takes away every condition on a layer, so it just follows its own rules again.
+	pub fn clear_conditions(&mut self, layer: usize) {
+		if let Some(layer) = self.layers.get_mut(layer) {
+			layer.birth.clear();
+			layer.survival.clear();
+			layer.automaton.set_birth_field(None);
+			layer.automaton.set_survival_field(None);
+		}
+	}
+
+	/// Checks that a layer and the layer its condition looks at both exist.
+	fn check(&self, layer: usize, condition: &Condition) -> Result<(), AutomataError> {
+		let other = match condition {
+			Condition::Alive(other) | Condition::Dead(other) | Condition::Neighbors(other, _) => *other
+		};
+
+		match [layer, other].into_iter().find(|i| *i >= self.layers.len()) {
+			Some(missing) => Err(AutomataError::NoSuchLayer(missing)),
+			None => Ok(())
+		}
+	}
+
+	/// Advances every layer by one tick, all at the same time.
+	pub fn tick(&mut self) {
+		// every layer's conditions look at the same snapshot of the generation before, so it doesn't matter which layer ticks first
+		let (width, height) = (self.bounds.x(), self.bounds.y());
+
+		let snapshot: Arc<Vec<Vec<bool>>> = Arc::new(self.layers.iter().map(|layer| {
+			let mut alive = vec![false; width * height];
+			for v in layer.automaton.live_cells_in(&Rect2::new(Vec2::new(0, 0), self.bounds)).keys() { alive[v.y() * width + v.x()] = true; }
+			alive
+		}).collect());
+
+		for layer in &mut self.layers {
+			let offsets: Arc<Vec<IVec2>> = Arc::new(flat::neighbor_offsets(layer.automaton.get_rules().method()));
+
+			let field = |conditions: &[Condition]| (!conditions.is_empty()).then(|| {
+				let (conditions, snapshot, offsets) = (conditions.to_vec(), snapshot.clone(), offsets.clone());
+				Field::from_fn(move |v| if conditions.iter().all(|condition| condition.holds(&snapshot, &offsets, v, width, height)) { 1.0 } else { 0.0 })
+			});
+
+			layer.automaton.set_birth_field(field(&layer.birth));
+			layer.automaton.set_survival_field(field(&layer.survival));
+			layer.automaton.tick();
+		}
+
+		self.generation += 1;
+	}
+
+	/// Get a layer by its index. This panics if the layer doesn't exist.
+	pub fn layer(&self, layer: usize) -> &Automaton {
+		&self.layers[layer].automaton
+	}
+
+	/// Get a layer by its index so it can be changed, like to set cells or seed its random number generator.
+	/// Any birth or survival fields set on it get replaced every tick with the ones its conditions need (or taken away, if it doesn't have any).
+	/// This panics if the layer doesn't exist.
+	pub fn layer_mut(&mut self, layer: usize) -> &mut Automaton {
+		&mut self.layers[layer].automaton
+	}
+
+	/// Get how many layers there are.
+	pub fn len(&self) -> usize {
+		self.layers.len()
+	}
+
+	/// Checks whether there aren't any layers yet.
+	pub fn is_empty(&self) -> bool {
+		self.layers.is_empty()
+	}
+
+	/// Get the bounds every layer shares.
+	pub fn bounds(&self) -> &Vec2 {
+		&self.bounds
+	}
+
+	/// Get how many ticks the stack has been advanced by.
+	pub fn generation(&self) -> u64 {
+		self.generation
+	}
+}
+
+impl Condition {
+	/// Checks whether the condition holds at a cell, given whether every cell on every layer was alive.
+	fn holds(&self, snapshot: &[Vec<bool>], offsets: &[IVec2], v: &Vec2, width: usize, height: usize) -> bool {
+		match self {
+			Condition::Alive(layer) => snapshot[*layer][v.y() * width + v.x()],
+			Condition::Dead(layer) => !snapshot[*layer][v.y() * width + v.x()],
+			Condition::Neighbors(layer, rule) => {
+				let neighbors = offsets.iter()
+					.filter_map(|offset| v.checked_add_offset(offset))
+					.filter(|n| n.x() < width && n.y() < height && snapshot[*layer][n.y() * width + n.x()])
+					.count();
+
+				rule.matches(neighbors as u8)
+			}
+		}
+	}
+}
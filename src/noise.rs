@@ -0,0 +1,69 @@
+//! Ken Perlin's improved gradient noise, in 3D.
+//!
+//! Noise gives smooth, blobby randomness instead of the salt-and-pepper kind, which makes for much more natural-looking seeds.
+//! The permutation table is shuffled with the crate's own random number generator, so the same seed always gives the same noise on every platform.
+
+//--> Imports <--
+
+use crate::random::Rng;
+
+//--> Structs <--
+
+/// A seeded source of 3D Perlin noise.
+pub(crate) struct Perlin {
+	perm: [u8; 512]
+}
+
+//--> Functions <--
+
+impl Perlin {
+	/// Creates a noise source from a seed.
+	pub(crate) fn new(seed: u64) -> Perlin {
+		let mut rng = Rng::new(seed);
+		let mut table: [u8; 256] = std::array::from_fn(|i| i as u8);
+
+		for i in (1..256).rev() {
+			table.swap(i, rng.below(i as u64 + 1) as usize);
+		}
+
+		Perlin { perm: std::array::from_fn(|i| table[i % 256]) }
+	}
+
+	/// Get the noise at a point, which is somewhere between -1 and 1 and changes smoothly from point to point.
+	/// The noise is always 0 at whole-number coordinates, so sample it between them.
+	pub(crate) fn get(&self, x: f64, y: f64, z: f64) -> f64 {
+		let (xi, yi, zi) = (x.floor() as i64 as usize & 255, y.floor() as i64 as usize & 255, z.floor() as i64 as usize & 255);
+		let (x, y, z) = (x - x.floor(), y - y.floor(), z - z.floor());
+		let (u, v, w) = (fade(x), fade(y), fade(z));
+		let p = &self.perm;
+
+		let (a, b) = (p[xi] as usize + yi, p[xi + 1] as usize + yi);
+		let (aa, ab, ba, bb) = (p[a] as usize + zi, p[a + 1] as usize + zi, p[b] as usize + zi, p[b + 1] as usize + zi);
+
+		lerp(w,
+			lerp(v,
+				lerp(u, grad(p[aa], x, y, z), grad(p[ba], x - 1.0, y, z)),
+				lerp(u, grad(p[ab], x, y - 1.0, z), grad(p[bb], x - 1.0, y - 1.0, z))),
+			lerp(v,
+				lerp(u, grad(p[aa + 1], x, y, z - 1.0), grad(p[ba + 1], x - 1.0, y, z - 1.0)),
+				lerp(u, grad(p[ab + 1], x, y - 1.0, z - 1.0), grad(p[bb + 1], x - 1.0, y - 1.0, z - 1.0))))
+	}
+}
+
+/// Eases a position between grid points, so the noise doesn't have creases along the grid lines.
+fn fade(t: f64) -> f64 {
+	t * t * t * (t * (t * 6.0 - 15.0) + 10.0)
+}
+
+/// Blends between two values.
+fn lerp(t: f64, a: f64, b: f64) -> f64 {
+	a + t * (b - a)
+}
+
+/// Picks one of 12 gradient directions from the hash and works out how far along it the point is.
+fn grad(hash: u8, x: f64, y: f64, z: f64) -> f64 {
+	let h = hash & 15;
+	let u = if h < 8 { x } else { y };
+	let v = if h < 4 { y } else if h == 12 || h == 14 { x } else { z };
+	(if h & 1 == 0 { u } else { -u }) + (if h & 2 == 0 { v } else { -v })
+}
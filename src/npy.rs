@@ -0,0 +1,120 @@
+//! A reader and writer for NumPy's `.npy` files, for arrays of cell states made with (or headed for) `numpy.save` and `numpy.load`.
+//! 
+//! A `.npy` file starts with the bytes `\x93NUMPY` and a version, then the length of the header and the header itself,
+//! which is a Python dictionary like `{'descr': '|u1', 'fortran_order': False, 'shape': (16, 32), }` padded out with spaces.
+//! Then come the values, one byte each, with the last axis changing fastest (or the first one, for Fortran order).
+//! 
+//! Only arrays of bytes (`uint8`) and booleans get read, since those are the only ones that fit cell states without guessing.
+
+//--> Imports <--
+
+use std::io::{self, Read, Write};
+
+//--> Functions <--
+
+const MAGIC: &[u8; 6] = b"\x93NUMPY";
+
+/// Writes an array of bytes with the given shape (slowest axis first) into a version 1 `.npy` file.
+pub(crate) fn write(w: &mut impl Write, shape: &[usize], data: &[u8]) -> io::Result<()> {
+	let dims = match shape {
+		[n] => format!("({},)", n),
+		_ => format!("({})", shape.iter().map(usize::to_string).collect::<Vec<String>>().join(", "))
+	};
+
+	let mut header = format!("{{'descr': '|u1', 'fortran_order': False, 'shape': {}, }}", dims);
+
+	// the whole preamble gets padded out to a multiple of 64 bytes, ending in a newline
+	let preamble = MAGIC.len() + 4;
+	header.extend(std::iter::repeat_n(' ', (64 - (preamble + header.len() + 1) % 64) % 64));
+	header.push('\n');
+
+	let length = u16::try_from(header.len()).map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "the array has too many dimensions for a .npy header"))?;
+
+	w.write_all(MAGIC)?;
+	w.write_all(&[1, 0])?;
+	w.write_all(&length.to_le_bytes())?;
+	w.write_all(header.as_bytes())?;
+	w.write_all(data)
+}
+
+/// Reads an array of bytes out of a `.npy` file, giving back its shape (slowest axis first) and its values with the last axis changing fastest,
+/// or what went wrong if it can't be understood.
+pub(crate) fn read(r: &mut impl Read) -> Result<(Vec<usize>, Vec<u8>), String> {
+	let mut magic = [0; 8];
+	r.read_exact(&mut magic).map_err(|e| format!("couldn't read the .npy file: {}", e))?;
+	if &magic[..6] != MAGIC { return Err(String::from("this isn't a .npy file")); }
+
+	// version 1 has a 2-byte header length, and versions 2 and 3 have 4 bytes
+	let length = match magic[6] {
+		1 => { let mut b = [0; 2]; r.read_exact(&mut b).map_err(|e| e.to_string())?; u16::from_le_bytes(b) as usize },
+		2 | 3 => { let mut b = [0; 4]; r.read_exact(&mut b).map_err(|e| e.to_string())?; u32::from_le_bytes(b) as usize },
+		version => return Err(format!(".npy version {} isn't supported", version))
+	};
+
+	let mut header = vec![0; length];
+	r.read_exact(&mut header).map_err(|e| format!("couldn't read the .npy header: {}", e))?;
+	let header = String::from_utf8_lossy(&header);
+
+	let descr = value_of(&header, "descr")?.trim_matches(|c| c == '\'' || c == '"');
+	if !matches!(descr, "|u1" | "<u1" | ">u1" | "u1" | "|b1") {
+		return Err(format!("arrays of '{}' can't be read, only arrays of uint8 or bool", descr));
+	}
+
+	let fortran = match value_of(&header, "fortran_order")? {
+		"True" => true,
+		"False" => false,
+		other => return Err(format!("fortran_order '{}' isn't True or False", other))
+	};
+
+	let shape = value_of(&header, "shape")?.trim_matches(|c| c == '(' || c == ')');
+	let shape = shape.split(',').map(str::trim).filter(|d| !d.is_empty())
+		.map(|d| d.trim_end_matches('L').parse::<usize>().map_err(|_| format!("shape '{}' isn't a list of sizes", shape)))
+		.collect::<Result<Vec<usize>, String>>()?;
+
+	let count = shape.iter().try_fold(1usize, |n, d| n.checked_mul(*d)).ok_or("the array is too big")?;
+	let mut data = Vec::new();
+	r.take(count as u64).read_to_end(&mut data).map_err(|e| format!("couldn't read the array: {}", e))?;
+	if data.len() != count { return Err(format!("the array should have {} values, but there are only {}", count, data.len())); }
+
+	if fortran && shape.len() > 1 { data = from_fortran(&shape, &data); }
+	Ok((shape, data))
+}
+
+/// Finds the value of a key in a `.npy` header, which is everything up to the next comma that isn't inside brackets.
+fn value_of<'a>(header: &'a str, key: &str) -> Result<&'a str, String> {
+	let start = [format!("'{}'", key), format!("\"{}\"", key)].iter().find_map(|k| header.find(k.as_str()).map(|i| i + k.len()))
+		.ok_or_else(|| format!("the .npy header is missing '{}'", key))?;
+
+	let rest = header[start..].trim_start().strip_prefix(':').ok_or_else(|| format!("'{}' in the .npy header doesn't have a value", key))?.trim_start();
+
+	let mut depth = 0;
+	let end = rest.char_indices().find(|(_, c)| {
+		match c {
+			'(' | '[' => depth += 1,
+			')' | ']' => depth -= 1,
+			',' | '}' if depth == 0 => return true,
+			_ => ()
+		}
+		false
+	}).map_or(rest.len(), |(i, _)| i);
+
+	Ok(rest[..end].trim())
+}
+
+/// Rearranges values from Fortran order (first axis changing fastest) into C order (last axis changing fastest).
+fn from_fortran(shape: &[usize], data: &[u8]) -> Vec<u8> {
+	let mut index = vec![0; shape.len()];
+
+	(0..data.len()).map(|_| {
+		let fortran = index.iter().zip(shape).rev().fold(0, |at, (i, d)| at * d + i);
+
+		// step to the next index in C order, carrying over from the last axis
+		for (i, d) in index.iter_mut().zip(shape).rev() {
+			*i += 1;
+			if *i < *d { break; }
+			*i = 0;
+		}
+
+		data[fortran]
+	}).collect()
+}
@@ -0,0 +1,119 @@
+//! This module turns cell states into colors, for drawing automata with more than two states without working out how dying cells should fade every time.
+//! 
+//! A palette has one RGBA color for every state a cell can be in. They're usually made from a gradient that runs from dead cells (state 0)
+//! up to live ones (the biggest state), so dying cells fade out a little more every tick.
+//! 
+//! ```
+//! # use cellular_snapp::{AutomataRules, Method, palette::{Gradient, Palette}};
+//! let rules = AutomataRules::new(2..=3, 3, 5, Method::Moore);
+//! let palette = Palette::for_rules(&rules, Gradient::Lerp([0, 0, 0, 255], [200, 100, 0, 255]));
+//! 
+//! assert_eq!(palette.color(0), [0, 0, 0, 255]);
+//! assert_eq!(palette.color(2), [100, 50, 0, 255]);
+//! assert_eq!(palette.color(4), [200, 100, 0, 255]);
+//! ```
+
+//--> Imports <--
+
+use crate::AutomataRules;
+
+//--> Structs <--
+
+/// A color for every state a cell can be in, from dead (state 0) on up.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Palette {
+	colors: Vec<[u8; 4]>
+}
+
+//--> Enums <--
+
+/// A run of colors from dead cells up to live ones, for making palettes out of.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Gradient {
+	/// Black for dead cells, up to white for live ones.
+	Grayscale,
+	/// Dark purple, through blue and green, up to yellow, like matplotlib's viridis. It's easy to tell apart for colorblind people and in grayscale.
+	Viridis,
+	/// Black, through red and orange, up to a white-hot yellow.
+	Fire,
+	/// A straight blend from the first color (for dead cells) to the second (for live ones).
+	Lerp([u8; 4], [u8; 4])
+}
+
+//--> Functions <--
+
+/// The colors viridis passes through, evenly spaced.
+const VIRIDIS: [[u8; 4]; 5] = [[68, 1, 84, 255], [59, 82, 139, 255], [33, 145, 140, 255], [94, 201, 98, 255], [253, 231, 37, 255]];
+
+/// The colors fire passes through, evenly spaced.
+const FIRE: [[u8; 4]; 5] = [[0, 0, 0, 255], [160, 0, 0, 255], [255, 96, 0, 255], [255, 200, 0, 255], [255, 255, 224, 255]];
+
+impl Palette {
+	/// Creates a palette out of a color for every state, starting with dead cells. States past the end of the list get the last color.
+	pub fn new(colors: Vec<[u8; 4]>) -> Palette {
+		Palette { colors }
+	}
+
+	/// Creates a palette for cells with the given amount of states, spread evenly along a gradient so dead cells get its start and live cells get its end.
+	pub fn from_gradient(gradient: Gradient, states: u8) -> Palette {
+		let live = (states.max(1) - 1).max(1) as f64;
+		Palette { colors: (0..states.max(1)).map(|state| gradient.at(state as f64 / live)).collect() }
+	}
+
+	/// Creates a palette with a color for every state the rules have.
+	pub fn for_rules(rules: &AutomataRules, gradient: Gradient) -> Palette {
+		Palette::from_gradient(gradient, rules.states())
+	}
+
+	/// Changes the color of dead cells, like to a transparent one so only the live and dying cells get drawn.
+	pub fn with_dead(mut self, color: [u8; 4]) -> Palette {
+		match self.colors.first_mut() {
+			Some(dead) => *dead = color,
+			None => self.colors.push(color)
+		}
+
+		self
+	}
+
+	/// Get the color of a state. States the palette doesn't have a color for get the last one, and an empty palette makes everything transparent.
+	pub fn color(&self, state: u8) -> [u8; 4] {
+		self.colors.get(state as usize).or(self.colors.last()).copied().unwrap_or([0; 4])
+	}
+
+	/// Get the color of every state, starting with dead cells.
+	pub fn colors(&self) -> &[[u8; 4]] {
+		&self.colors
+	}
+
+	/// Colors in a whole grid of states at once, like the one `as_slice` gives on the dense backend, and gives back 4 bytes of RGBA for every cell in the same order.
+	pub fn render(&self, states: &[u8]) -> Vec<u8> {
+		let lookup: Vec<[u8; 4]> = (0..=u8::MAX).map(|state| self.color(state)).collect();
+		states.iter().flat_map(|state| lookup[*state as usize]).collect()
+	}
+}
+
+impl Gradient {
+	/// Get the color at `t` along the gradient, which runs from 0 (dead) to 1 (alive). Anything outside of that gets clamped.
+	pub fn at(&self, t: f64) -> [u8; 4] {
+		let t = if t.is_nan() { 0.0 } else { t.clamp(0.0, 1.0) };
+
+		match self {
+			Gradient::Grayscale => lerp([0, 0, 0, 255], [255, 255, 255, 255], t),
+			Gradient::Viridis => along(&VIRIDIS, t),
+			Gradient::Fire => along(&FIRE, t),
+			Gradient::Lerp(from, to) => lerp(*from, *to, t)
+		}
+	}
+}
+
+/// Blends straight from one color to another, so `t` of 0 gives `from` and 1 gives `to`.
+fn lerp(from: [u8; 4], to: [u8; 4], t: f64) -> [u8; 4] {
+	std::array::from_fn(|i| (from[i] as f64 + (to[i] as f64 - from[i] as f64) * t).round() as u8)
+}
+
+/// Blends along a list of evenly spaced colors.
+fn along(stops: &[[u8; 4]], t: f64) -> [u8; 4] {
+	let scaled = t * (stops.len() - 1) as f64;
+	let i = (scaled.floor() as usize).min(stops.len() - 2);
+	lerp(stops[i], stops[i + 1], scaled - i as f64)
+}
@@ -0,0 +1,195 @@
+//! This module has a bunch of well-known rules and patterns, so you can get something interesting going in one line.
+//! 
+//! ```
+//! # use cellular_snapp::{presets, flat::{Automaton, Vec2}};
+//! let mut life = Automaton::new(presets::life(), Vec2::new(20, 20), presets::glider().translate(Vec2::new(5, 5)).positions()).unwrap();
+//! 
+//! // a glider moves one cell down and to the right every four ticks
+//! for _ in 0..4 { life.tick(); }
+//! 
+//! let live = life.get_cells().into_iter().filter(|(_, s)| *s > 0).map(|(v, _)| v).collect::<Vec<_>>();
+//! let moved = presets::glider().translate(Vec2::new(6, 6)).positions();
+//! assert_eq!(live.len(), moved.len());
+//! assert!(moved.iter().all(|v| live.contains(v)));
+//! ```
+
+//--> Imports <--
+
+use crate::{AutomataRules, DecayMode, Inheritance, Method, Rule, Transition};
+use crate::flat::{self, Pattern, Vec2};
+
+//--> Functions <--
+
+/// The same rules as `life`, as a constant. Every constant here gets checked while compiling, and works as a starting point for `const` rules of your own.
+/// 
+/// ```
+/// # use cellular_snapp::{presets, AutomataRules, Inheritance};
+/// const TWO_COLOR_LIFE: AutomataRules = presets::LIFE.with_species(2, Inheritance::Majority);
+/// assert_eq!(TWO_COLOR_LIFE, presets::immigration());
+/// ```
+pub const LIFE: AutomataRules = AutomataRules::new_const(Rule::Range(2..4), Rule::Single(3), 2, Method::Moore).checked(8);
+
+/// The same rules as `seeds`, as a constant.
+pub const SEEDS: AutomataRules = AutomataRules::new_const(Rule::Many(Vec::new()), Rule::Single(2), 2, Method::Moore).checked(8);
+
+/// The same rules as `brians_brain`, as a constant.
+pub const BRIANS_BRAIN: AutomataRules = AutomataRules::new_const(Rule::Many(Vec::new()), Rule::Single(2), 3, Method::Moore).with_decay_mode(DecayMode::Generations).checked(8);
+
+/// The same rules as `rule_445`, as a constant.
+pub const RULE_445: AutomataRules = AutomataRules::new_const(Rule::Single(4), Rule::Single(4), 5, Method::Moore).checked(26);
+
+/// The same rules as `pyroclastic`, as a constant.
+pub const PYROCLASTIC: AutomataRules = AutomataRules::new_const(Rule::Range(4..8), Rule::Range(6..9), 10, Method::Moore).checked(26);
+
+/// Conway's Game of Life (B3/S23), the one that started it all.
+pub fn life() -> AutomataRules {
+	LIFE
+}
+
+/// HighLife (B36/S23), which is a lot like Life but has a small replicator.
+pub fn high_life() -> AutomataRules {
+	AutomataRules::new(Rule::Range(2..4), Rule::Many(vec![3, 6]), 2, Method::Moore)
+}
+
+/// Seeds (B2/S), where every live cell dies immediately but things still explode outwards.
+pub fn seeds() -> AutomataRules {
+	SEEDS
+}
+
+/// Day & Night (B3678/S34678), where live and dead cells behave symmetrically.
+pub fn day_and_night() -> AutomataRules {
+	AutomataRules::new(Rule::Many(vec![3, 4, 6, 7, 8]), Rule::Many(vec![3, 6, 7, 8]), 2, Method::Moore)
+}
+
+/// Immigration, which is Life with two species. Newborns take whichever species most of their three parents are.
+/// 
+/// ```
+/// # use cellular_snapp::{presets, flat::{Automaton, Vec2}};
+/// let mut a = Automaton::new(presets::immigration(), Vec2::new(10, 10), vec![Vec2::new(4, 5), Vec2::new(5, 5), Vec2::new(6, 5)]).unwrap();
+/// a.set_species(Vec2::new(5, 5), 1);
+/// a.set_species(Vec2::new(6, 5), 1);
+/// 
+/// // the blinker flips over, and its new cells are born into the majority species
+/// a.tick();
+/// assert_eq!(a.get_species()[&Vec2::new(5, 4)], 1);
+/// assert_eq!(a.get_species()[&Vec2::new(5, 6)], 1);
+/// ```
+pub fn immigration() -> AutomataRules {
+	life().with_species(2, Inheritance::Majority)
+}
+
+/// QuadLife, which is Life with four species. Newborns take their parents' majority species, or the one species none of them are if all three differ.
+pub fn quad_life() -> AutomataRules {
+	life().with_species(4, Inheritance::MajorityOrMissing)
+}
+
+/// Brian's Brain (B2/S/3), where every live cell spends one tick dying before it turns off.
+/// Dying cells don't count as neighbors here, just like in Golly.
+pub fn brians_brain() -> AutomataRules {
+	BRIANS_BRAIN
+}
+
+/// The 3D "445" rule (4/4/5/M), which grows crystal-like structures.
+pub fn rule_445() -> AutomataRules {
+	RULE_445
+}
+
+/// The 3D "Clouds" rule (13-26/13-14,17-19/2/M), which clumps a random soup into blobs.
+pub fn clouds() -> AutomataRules {
+	AutomataRules::new(Rule::Many((13..=26).collect()), Rule::Many(vec![13, 14, 17, 18, 19]), 2, Method::Moore)
+}
+
+/// The 3D "Pyroclastic" rule (4-7/6-8/10/M), which bubbles away like, well, lava.
+pub fn pyroclastic() -> AutomataRules {
+	PYROCLASTIC
+}
+
+/// Wireworld, a 2D rule for simulating electronics. The states are numbered the same way Golly does it:
+/// 0 is empty, 1 is an electron head, 2 is an electron tail, and 3 is a conductor (wire).
+/// Heads always turn into tails, tails always turn back into wire, and wire becomes a head if one or two of its neighbors are heads.
+/// 
+/// Seeded cells start out as wire, so you'll need to stamp in some heads (with `Pattern::with_states`) to get things moving.
+/// 
+/// ```
+/// # use cellular_snapp::{presets, flat::{Automaton, Pattern, Vec2}};
+/// let wire = (0..6).map(|x| Vec2::new(x, 1)).collect::<Vec<_>>();
+/// let mut circuit = Automaton::new(presets::wireworld(), Vec2::new(6, 3), wire).unwrap();
+/// Pattern::with_states("electron", vec![(Vec2::new(1, 0), 1), (Vec2::new(0, 0), 2)]).stamp_into(&mut circuit, Vec2::new(0, 1));
+/// 
+/// // the electron moves one cell along the wire every tick
+/// circuit.tick();
+/// assert_eq!(circuit.get_cells()[&Vec2::new(2, 1)], 1);
+/// assert_eq!(circuit.get_cells()[&Vec2::new(1, 1)], 2);
+/// assert_eq!(circuit.get_cells()[&Vec2::new(0, 1)], 3);
+/// ```
+pub fn wireworld() -> AutomataRules {
+	AutomataRules::from_transitions("WireWorld", 4, Method::Moore, vec![
+		Transition::always(1, 2),
+		Transition::always(2, 3),
+		Transition::when(3, 1, Rule::Many(vec![1, 2]), 1)
+	])
+}
+
+/// The Greenberg–Hastings model of an excitable medium, like heart tissue or a forest that burns and grows back, which makes spiral waves out of the right soup.
+/// A resting cell gets excited once at least `threshold` of its neighbors are excited, and an excited cell spends `refractory` ticks recovering before it can rest again.
+/// 
+/// The states count down like Generations rules: the highest state is excited, the ones below it are refractory, and 0 is resting,
+/// so seeded cells start out excited. Neighbor counts go up to what `method` gives in a flat grid.
+/// 
+/// ```
+/// # use cellular_snapp::{presets, Method, flat::{Automaton, Vec2}};
+/// let mut medium = Automaton::new(presets::greenberg_hastings(1, 2, Method::VonNeumann), Vec2::new(5, 5), vec![Vec2::new(2, 2)]).unwrap();
+/// 
+/// // the excitement spreads out in a ring, and the middle needs two ticks to recover
+/// medium.tick();
+/// assert_eq!(medium.get_cell(&Vec2::new(2, 1)), Some(3));
+/// assert_eq!(medium.get_cell(&Vec2::new(2, 2)), Some(2));
+/// medium.tick();
+/// assert_eq!(medium.get_cell(&Vec2::new(2, 2)), Some(1));
+/// medium.tick();
+/// assert_eq!(medium.get_cell(&Vec2::new(2, 2)), Some(0));
+/// ```
+pub fn greenberg_hastings(threshold: u8, refractory: u8, method: Method) -> AutomataRules {
+	let excited = refractory.min(u8::MAX - 1) + 1;
+	let max = flat::neighbor_offsets(&method).len().min(u8::MAX as usize) as u8;
+
+	let mut transitions = vec![Transition::when(0, excited, Rule::RangeInclusive(threshold..=max), excited)];
+	transitions.extend((1..=excited).map(|state| Transition::always(state, state - 1)));
+
+	AutomataRules::from_transitions("Greenberg-Hastings", excited + 1, method, transitions)
+}
+
+/// The glider, the smallest spaceship in Life. This one travels down and to the right.
+pub fn glider() -> Pattern {
+	Pattern::new("glider", to_vecs(&[(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)]))
+}
+
+/// The lightweight spaceship (LWSS), which travels to the right.
+pub fn lwss() -> Pattern {
+	Pattern::new("lwss", to_vecs(&[(1, 0), (4, 0), (0, 1), (0, 2), (4, 2), (0, 3), (1, 3), (2, 3), (3, 3)]))
+}
+
+/// Bill Gosper's glider gun, which fires off a new glider every 30 ticks.
+pub fn gosper_glider_gun() -> Pattern {
+	Pattern::new("gosper glider gun", to_vecs(&[
+		(24, 0),
+		(22, 1), (24, 1),
+		(12, 2), (13, 2), (20, 2), (21, 2), (34, 2), (35, 2),
+		(11, 3), (15, 3), (20, 3), (21, 3), (34, 3), (35, 3),
+		(0, 4), (1, 4), (10, 4), (16, 4), (20, 4), (21, 4),
+		(0, 5), (1, 5), (10, 5), (14, 5), (16, 5), (17, 5), (22, 5), (24, 5),
+		(10, 6), (16, 6), (24, 6),
+		(11, 7), (15, 7),
+		(12, 8), (13, 8)
+	]))
+}
+
+/// The R-pentomino, a tiny methuselah that takes over a thousand ticks to settle down.
+pub fn r_pentomino() -> Pattern {
+	Pattern::new("r-pentomino", to_vecs(&[(1, 0), (2, 0), (0, 1), (1, 1), (1, 2)]))
+}
+
+/// Turns a list of coordinate pairs into positions.
+fn to_vecs(coords: &[(usize, usize)]) -> Vec<Vec2> {
+	coords.iter().map(|&(x, y)| Vec2::new(x, y)).collect()
+}
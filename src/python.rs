@@ -0,0 +1,114 @@
+//! This module exposes the automata to Python through pyo3, so you can poke at them from a notebook.
+//! 
+//! Build it with maturin and the `python` feature, and then over in Python:
+//! 
+//! ```python
+//! from cellular_snapp import Rules, FlatAutomaton
+//! 
+//! life = FlatAutomaton(Rules.from_string("B3/S23"), 50, 50, [(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)])
+//! life.tick()
+//! grid = life.to_numpy()
+//! ```
+
+//--> Imports <--
+
+use crate::{AutomataRules, Method, Rule, flat, deep};
+use numpy::{IntoPyArray, PyArray2, PyArray3};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+//--> Structs <--
+
+/// A set of automaton rules, as seen from Python.
+#[pyclass(name = "Rules")]
+#[derive(Clone)]
+pub struct PyRules(AutomataRules);
+
+/// A flat (2D) automaton, as seen from Python.
+#[pyclass(name = "FlatAutomaton")]
+pub struct PyFlatAutomaton(flat::Automaton);
+
+/// A deep (3D) automaton, as seen from Python.
+#[pyclass(name = "DeepAutomaton")]
+pub struct PyDeepAutomaton(deep::Automaton);
+
+//--> Functions <--
+
+#[pymethods]
+impl PyRules {
+	/// Creates rules out of lists of neighbor counts for survival and birth, an amount of cell states, and a neighbor method ("moore", "von_neumann", or "faces_and_edges").
+	#[new]
+	#[pyo3(signature = (survive, born, states = 2, method = "moore"))]
+	fn new(survive: Vec<u8>, born: Vec<u8>, states: u8, method: &str) -> PyResult<PyRules> {
+		let method = match method {
+			"moore" => Method::Moore,
+			"von_neumann" => Method::VonNeumann,
+			"faces_and_edges" => Method::FacesAndEdges,
+			_ => return Err(PyValueError::new_err(format!("unknown neighbor method '{}'", method)))
+		};
+
+		if states < 2 { return Err(PyValueError::new_err(crate::AutomataError::TooFewStates(states).to_string())) }
+		Ok(PyRules(AutomataRules::new(Rule::Many(survive), Rule::Many(born), states, method)))
+	}
+
+	/// Creates rules out of a rule string, like "B3/S23" or "4/4/5/M".
+	#[staticmethod]
+	fn from_string(rule: &str) -> PyResult<PyRules> {
+		rule.parse().map(PyRules).map_err(|e: crate::AutomataError| PyValueError::new_err(e.to_string()))
+	}
+}
+
+#[pymethods]
+impl PyFlatAutomaton {
+	/// Creates a new 2D automaton with the given rules, size, and list of (x, y) live cells.
+	#[new]
+	fn new(rules: PyRules, width: usize, height: usize, seed: Vec<(usize, usize)>) -> PyResult<PyFlatAutomaton> {
+		let seed = seed.into_iter().map(|(x, y)| flat::Vec2::new(x, y)).collect();
+
+		flat::Automaton::new(rules.0, flat::Vec2::new(width, height), seed)
+			.map(PyFlatAutomaton)
+			.map_err(|e| PyValueError::new_err(e.to_string()))
+	}
+
+	/// Advances the automaton by one tick.
+	fn tick(&mut self) {
+		self.0.tick();
+	}
+
+	/// Copies the current state into a numpy array, indexed [y, x].
+	fn to_numpy<'py>(&self, py: Python<'py>) -> Bound<'py, PyArray2<u8>> {
+		self.0.to_array2().into_pyarray(py)
+	}
+}
+
+#[pymethods]
+impl PyDeepAutomaton {
+	/// Creates a new 3D automaton with the given rules, size, and list of (x, y, z) live cells.
+	#[new]
+	fn new(rules: PyRules, width: usize, height: usize, depth: usize, seed: Vec<(usize, usize, usize)>) -> PyResult<PyDeepAutomaton> {
+		let seed = seed.into_iter().map(|(x, y, z)| deep::Vec3::new(x, y, z)).collect();
+
+		deep::Automaton::new(rules.0, deep::Vec3::new(width, height, depth), seed)
+			.map(PyDeepAutomaton)
+			.map_err(|e| PyValueError::new_err(e.to_string()))
+	}
+
+	/// Advances the automaton by one tick.
+	fn tick(&mut self) {
+		self.0.tick();
+	}
+
+	/// Copies the current state into a numpy array, indexed [z, y, x].
+	fn to_numpy<'py>(&self, py: Python<'py>) -> Bound<'py, PyArray3<u8>> {
+		self.0.to_array3().into_pyarray(py)
+	}
+}
+
+/// The Python module itself.
+#[pymodule]
+fn cellular_snapp(m: &Bound<'_, PyModule>) -> PyResult<()> {
+	m.add_class::<PyRules>()?;
+	m.add_class::<PyFlatAutomaton>()?;
+	m.add_class::<PyDeepAutomaton>()?;
+	Ok(())
+}
@@ -0,0 +1,178 @@
+//! This module knows the names of well-known rules, so a front-end can show "Conway's Game of Life" instead of `B3/S23`.
+//! 
+//! A `RuleRegistry` starts out with a bunch of famous rules in it, and you can register your own on top of those.
+//! Rule strings get looked up by what they mean rather than how they're written, so `B3/S23`, `S23/B3`, and `2,3/3/2/M` all find Life.
+//! 
+//! ```
+//! # use cellular_snapp::{presets, registry::RuleRegistry};
+//! let registry = RuleRegistry::new();
+//! 
+//! assert_eq!(registry.by_rule("B3/S23").unwrap().name(), "Conway's Game of Life");
+//! assert_eq!(registry.by_rule("S23/B36").unwrap().name(), "HighLife");
+//! assert_eq!(registry.by_rules(&presets::day_and_night()).unwrap().discoverer(), Some("Nathan Thompson"));
+//! 
+//! // and the other way around
+//! let brain = registry.by_name("brian's brain").unwrap();
+//! assert_eq!(brain.rule(), "B2/S/C3");
+//! assert_eq!(brain.rules().unwrap(), presets::brians_brain());
+//! ```
+
+//--> Imports <--
+
+use crate::{AutomataError, AutomataRules};
+
+//--> Structs <--
+
+/// What's known about a rule: what it's called, how it's written, what it does, who found it, and how many dimensions it's meant for.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct RuleInfo {
+	name: String,
+	rule: String,
+	description: String,
+	discoverer: Option<String>,
+	dimensions: u8
+}
+
+/// A list of known rules that can be looked up by rule string or by name.
+#[derive(Clone, Debug)]
+pub struct RuleRegistry {
+	rules: Vec<RuleInfo>
+}
+
+//--> Functions <--
+
+/// Every rule a new registry knows about, as (name, rule string, description, discoverer, dimensions).
+const KNOWN: &[(&str, &str, &str, Option<&str>, u8)] = &[
+	("Conway's Game of Life", "B3/S23", "The one that started it all, full of gliders, oscillators, and guns.", Some("John Conway"), 2),
+	("HighLife", "B36/S23", "A lot like Life, but with a small replicator.", Some("Nathan Thompson"), 2),
+	("Seeds", "B2/S", "Every live cell dies right away, but patterns still explode outwards.", Some("Brian Silverman"), 2),
+	("Day & Night", "B3678/S34678", "Live and dead cells behave the same way, so patterns of dead cells in a live sea act just like live ones in a dead sea.", Some("Nathan Thompson"), 2),
+	("Replicator", "B1357/S1357", "Every pattern gets copied over and over.", Some("Edward Fredkin"), 2),
+	("Diamoeba", "B35678/S5678", "Makes big diamond-shaped blobs with wobbly edges.", Some("Dean Hickerson"), 2),
+	("2x2", "B36/S125", "Patterns made out of 2x2 blocks stay that way.", None, 2),
+	("Morley", "B368/S245", "Also called Move, with lots of small spaceships.", Some("Stephen Morley"), 2),
+	("Maze", "B3/S12345", "Grows into maze-like corridors.", None, 2),
+	("Life without Death", "B3/S012345678", "Life where nothing ever dies, so patterns just keep growing.", None, 2),
+	("Brian's Brain", "B2/S/C3", "Every live cell spends one tick dying before it turns off, which makes for lots of spaceships.", Some("Brian Silverman"), 2),
+	("WireWorld", "WireWorld", "Electrons moving along wires, for building logic circuits.", Some("Brian Silverman"), 2),
+	("445", "4/4/5/M", "Grows crystal-like structures.", None, 3),
+	("Clouds", "13-26/13-14,17-19/2/M", "Clumps a random soup into blobs.", None, 3),
+	("Pyroclastic", "4-7/6-8/10/M", "Bubbles away like lava.", None, 3)
+];
+
+impl RuleInfo {
+	/// Creates the info for a rule, written however you like. Registering it writes the rule string the standard way.
+	pub fn new(name: &str, rule: &str, description: &str, dimensions: u8) -> RuleInfo {
+		RuleInfo { name: name.to_string(), rule: rule.to_string(), description: description.to_string(), discoverer: None, dimensions }
+	}
+
+	/// Says who found (or first wrote about) the rule.
+	pub fn with_discoverer(mut self, discoverer: &str) -> RuleInfo {
+		self.discoverer = Some(discoverer.to_string());
+		self
+	}
+
+	/// Get what the rule is called.
+	pub fn name(&self) -> &str {
+		&self.name
+	}
+
+	/// Get the rule string, which is written the standard way (the way `AutomataRules` writes itself out) once the rule's been registered.
+	pub fn rule(&self) -> &str {
+		&self.rule
+	}
+
+	/// Get a short description of what the rule does.
+	pub fn description(&self) -> &str {
+		&self.description
+	}
+
+	/// Get who found the rule, if anyone knows.
+	pub fn discoverer(&self) -> Option<&str> {
+		self.discoverer.as_deref()
+	}
+
+	/// Get how many dimensions the rule is meant for, which is 2 for flat automata and 3 for deep ones.
+	pub fn dimensions(&self) -> u8 {
+		self.dimensions
+	}
+
+	/// Parses the rule string into rules, ready to use.
+	pub fn rules(&self) -> Result<AutomataRules, AutomataError> {
+		self.rule.parse()
+	}
+}
+
+impl RuleRegistry {
+	/// Creates a registry with every well-known rule in it.
+	pub fn new() -> RuleRegistry {
+		let rules = KNOWN.iter().map(|(name, rule, description, discoverer, dimensions)| {
+			let info = RuleInfo::new(name, rule, description, *dimensions);
+			match discoverer { Some(d) => info.with_discoverer(d), None => info }
+		}).collect();
+
+		RuleRegistry { rules }
+	}
+
+	/// Creates a registry without any rules in it at all.
+	pub fn empty() -> RuleRegistry {
+		RuleRegistry { rules: Vec::new() }
+	}
+
+	/// Adds a rule to the registry, taking the place of one with the same rule and dimensions if it's already there (so known rules can be renamed).
+	/// This fails with an `InvalidRuleString` error if the rule string can't be parsed.
+	/// 
+	/// ```
+	/// # use cellular_snapp::registry::{RuleInfo, RuleRegistry};
+	/// let mut registry = RuleRegistry::new();
+	/// registry.register(RuleInfo::new("Bugs", "B34/S345", "Slow, wiggly growth.", 2)).unwrap();
+	/// assert_eq!(registry.by_rule("S345/B34").unwrap().name(), "Bugs");
+	/// 
+	/// registry.register(RuleInfo::new("Life", "2,3/3/2/M", "Just Life.", 2)).unwrap();
+	/// assert_eq!(registry.by_rule("B3/S23").unwrap().name(), "Life");
+	/// assert!(registry.by_name("Conway's Game of Life").is_none());
+	/// 
+	/// assert!(registry.register(RuleInfo::new("Nonsense", "B3/S23/Q", "", 2)).is_err());
+	/// ```
+	pub fn register(&mut self, mut info: RuleInfo) -> Result<(), AutomataError> {
+		info.rule = info.rules()?.to_string();
+		self.rules.retain(|known| known.rule != info.rule || known.dimensions != info.dimensions);
+		self.rules.push(info);
+		Ok(())
+	}
+
+	/// Finds a rule by its rule string, however it's written, or gives back `None` if it isn't known (or can't be parsed).
+	/// If the same rule is known in both 2D and 3D, this finds whichever was registered first, so use `by_rule_in` when it matters.
+	pub fn by_rule(&self, rule: &str) -> Option<&RuleInfo> {
+		let rule = rule.parse::<AutomataRules>().ok()?.to_string();
+		self.rules.iter().find(|known| known.rule == rule)
+	}
+
+	/// Finds a rule by its rule string, only looking at rules meant for the given amount of dimensions.
+	pub fn by_rule_in(&self, rule: &str, dimensions: u8) -> Option<&RuleInfo> {
+		let rule = rule.parse::<AutomataRules>().ok()?.to_string();
+		self.rules.iter().find(|known| known.rule == rule && known.dimensions == dimensions)
+	}
+
+	/// Finds the rule that some rules are written as. Only what shows up in the rule string counts, so Immigration turns up as Life.
+	pub fn by_rules(&self, rules: &AutomataRules) -> Option<&RuleInfo> {
+		let rule = rules.to_string();
+		self.rules.iter().find(|known| known.rule == rule)
+	}
+
+	/// Finds a rule by its name, ignoring case.
+	pub fn by_name(&self, name: &str) -> Option<&RuleInfo> {
+		self.rules.iter().find(|known| known.name.eq_ignore_ascii_case(name))
+	}
+
+	/// Goes through every rule in the registry, in the order they were added.
+	pub fn iter(&self) -> impl Iterator<Item = &RuleInfo> {
+		self.rules.iter()
+	}
+}
+
+impl Default for RuleRegistry {
+	fn default() -> RuleRegistry {
+		RuleRegistry::new()
+	}
+}
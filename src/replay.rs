@@ -0,0 +1,312 @@
+//! This module has replay logs, which hold everything needed to run a stochastic automaton again and get exactly the same thing every time:
+//! the rules, the bounds, the cells it started with, and the seed for its random number generator.
+//! 
+//! Grab a log with `replay_log` before running something, and if it turns out to be interesting, the log can be written out as text and shared.
+//! Anyone with the log can then rebuild the automaton with `from_replay` and watch the very same run.
+//! 
+//! ```
+//! # use cellular_snapp::{AutomataRules, Method, replay::ReplayLog, flat::{self, Automaton, Vec2}};
+//! # use cellular_snapp::random::Rng;
+//! let rules = AutomataRules::new(2..=3, 3, 2, Method::Moore).with_chances(0.8, 0.95);
+//! let mut original = Automaton::new(rules, Vec2::new(32, 32), flat::random_seed(Vec2::new(32, 32), 0.4, &mut Rng::new(7))).unwrap();
+//! original.seed_rng(1234);
+//! 
+//! let log: ReplayLog = original.replay_log().unwrap().to_string().parse().unwrap();
+//! let mut replayed = Automaton::from_replay(&log).unwrap();
+//! 
+//! for _ in 0..20 {
+//!     original.tick();
+//!     replayed.tick();
+//! }
+//! 
+//! assert!(replayed.get_cells() == original.get_cells());
+//! ```
+//! 
+//! As text, a log is a list of lines that each start with what they're for: `rule` (a rule string), `decay`, `chances`, `species`, `seed`, and `bounds` once each,
+//! `weights` (a weight for each of the 256 states) and `directions` (a 0 or 1 for every neighbor, first for being born and then for surviving) if the rules have them,
+//! `edge` and `scheme` if they aren't dead and synchronous, a `wall` line for every wall, giving its x, y, and z, whether it's `alive`, `dead`, or `excluded`, and its state,
+//! and then a `cell` line for every live or dying cell, giving its x, y, and z, its state, and its species.
+//! Flat grids are 1 cell deep, and their cells all have a z of 0.
+//! 
+//! Logs also make for regression tests. `reference_hashes` runs a log and writes down the `state_hash` of every generation,
+//! and `verify_against_reference` runs it again (with whichever backend) and finds the first generation that doesn't match,
+//! so anything that changes how ticks work on the inside can be checked against a run that's known to be right.
+//! 
+//! ```
+//! # use cellular_snapp::{presets, Backend, replay, flat::{self, Automaton, Vec2}};
+//! # use cellular_snapp::random::Rng;
+//! let soup = flat::random_seed(Vec2::new(48, 48), 0.35, &mut Rng::new(3));
+//! let log = Automaton::new(presets::life(), Vec2::new(48, 48), soup).unwrap().replay_log().unwrap();
+//! let expected = replay::reference_hashes(&log, Backend::HashMap, 60).unwrap();
+//! 
+//! for backend in [Backend::Tree, Backend::BitPacked, Backend::Dense, Backend::Incremental] {
+//!     assert_eq!(replay::verify_against_reference(&log, backend, &expected).unwrap(), None);
+//! }
+//! 
+//! // a run that went somewhere else gets caught at the first generation that's different
+//! let mut wrong = expected.clone();
+//! wrong[40] ^= 1;
+//! assert_eq!(replay::verify_against_reference(&log, Backend::Dense, &wrong).unwrap(), Some(40));
+//! ```
+
+//--> Imports <--
+
+use crate::{AutomataError, AutomataRules, Backend, DecayMode, Inheritance, UpdateScheme, Wall};
+use crate::{deep, flat};
+use std::fmt;
+use std::str::FromStr;
+
+//--> Structs <--
+
+/// Everything needed to run an automaton again exactly the same way, captured at the point the run starts.
+/// Walls, the edge state, and the update scheme are part of it too, but rule regions, birth and survival fields, resources, and rule schedules aren't,
+/// so runs that use them can't be replayed.
+#[derive(Clone, PartialEq, Debug)]
+pub struct ReplayLog {
+	rules: AutomataRules,
+	bounds: [usize; 3],
+	cells: Vec<([usize; 3], u8, u8)>,
+	walls: Vec<([usize; 3], Wall, u8)>,
+	edge: u8,
+	scheme: UpdateScheme,
+	seed: u64
+}
+
+//--> Functions <--
+
+impl ReplayLog {
+	/// Creates a log from the rules, the bounds (flat grids are 1 cell deep), every live or dying cell along with its state and species, and the random number generator's seed.
+	pub(crate) fn new(rules: AutomataRules, bounds: [usize; 3], mut cells: Vec<([usize; 3], u8, u8)>, seed: u64) -> ReplayLog {
+		cells.sort_unstable();
+		ReplayLog { rules, bounds, cells, walls: Vec::new(), edge: 0, scheme: UpdateScheme::Synchronous, seed }
+	}
+
+	/// Adds the walls (each with what kind it is and the state it holds) and the state past the edge of the grid to the log.
+	pub(crate) fn with_walls(mut self, mut walls: Vec<([usize; 3], Wall, u8)>, edge: u8) -> ReplayLog {
+		walls.sort_unstable_by_key(|(v, _, _)| *v);
+		self.walls = walls;
+		self.edge = edge;
+		self
+	}
+
+	/// Adds the order cells get updated in to the log.
+	pub(crate) fn with_update_scheme(mut self, scheme: UpdateScheme) -> ReplayLog {
+		self.scheme = scheme;
+		self
+	}
+
+	/// Get the rules the run uses.
+	pub fn rules(&self) -> &AutomataRules {
+		&self.rules
+	}
+
+	/// Get the bounds of the grid, as `[x, y, z]`. Flat grids are 1 cell deep.
+	pub fn bounds(&self) -> [usize; 3] {
+		self.bounds
+	}
+
+	/// Get every live or dying cell the run starts out with, keyed by `[x, y, z]`, along with its state and species (which is 0 if it doesn't have one).
+	pub fn cells(&self) -> &[([usize; 3], u8, u8)] {
+		&self.cells
+	}
+
+	/// Get every wall, keyed by `[x, y, z]`, along with what kind of wall it is and the state it holds.
+	pub fn walls(&self) -> &[([usize; 3], Wall, u8)] {
+		&self.walls
+	}
+
+	/// Get the state every cell past the edge of the grid is in.
+	pub fn edge_state(&self) -> u8 {
+		self.edge
+	}
+
+	/// Get the order cells get updated in during each tick.
+	pub fn update_scheme(&self) -> UpdateScheme {
+		self.scheme
+	}
+
+	/// Get the seed the random number generator starts out with.
+	pub fn seed(&self) -> u64 {
+		self.seed
+	}
+}
+
+/// Runs a log for `ticks` ticks with the given backend, and gives back the `state_hash` of every generation along the way, starting with the one the log was made at.
+/// That makes `ticks + 1` hashes in all. Logs of grids 1 cell deep run as flat automata, and anything deeper runs as a deep one.
+pub fn reference_hashes(log: &ReplayLog, backend: Backend, ticks: usize) -> Result<Vec<u64>, AutomataError> {
+	let mut next = runner(log, backend)?;
+	Ok((0..=ticks).map(|generation| next(generation > 0)).collect())
+}
+
+/// Runs a log again with the given backend, checking the `state_hash` of every generation against hashes from `reference_hashes`, for as many generations as there are hashes.
+/// This gives back the first generation that doesn't match, or `None` if every one of them does.
+pub fn verify_against_reference(log: &ReplayLog, backend: Backend, expected: &[u64]) -> Result<Option<usize>, AutomataError> {
+	let mut next = runner(log, backend)?;
+	Ok(expected.iter().enumerate().position(|(generation, hash)| next(generation > 0) != *hash))
+}
+
+/// Rebuilds the automaton a log is for, and gives back something that (optionally) ticks it and then hashes it.
+fn runner(log: &ReplayLog, backend: Backend) -> Result<Box<dyn FnMut(bool) -> u64>, AutomataError> {
+	if log.bounds[2] == 1 {
+		let mut automaton = flat::Automaton::from_replay_with_backend(log, backend)?;
+		Ok(Box::new(move |tick| { if tick { automaton.tick(); } automaton.state_hash() }))
+	} else {
+		let mut automaton = deep::Automaton::from_replay_with_backend(log, backend)?;
+		Ok(Box::new(move |tick| { if tick { automaton.tick(); } automaton.state_hash() }))
+	}
+}
+
+/// Writes the log out as text. Rules without a rule string, like most transition tables, get written out as their name, which can't be read back in.
+/// Neighbor weights and directions get lines of their own, since rule strings leave them out.
+/// 
+/// ```
+/// # use cellular_snapp::{AutomataRules, Method, replay::ReplayLog, flat::{Automaton, Vec2}};
+/// let sand = AutomataRules::new(1, 1, 2, Method::Moore).with_directions(&[(0, -1)], &[(0, 1)]);
+/// let log = Automaton::new(sand, Vec2::new(8, 8), vec![Vec2::new(3, 0)]).unwrap().replay_log().unwrap();
+/// let text = log.to_string();
+/// assert!(text.contains("directions 00010000 00001000"));
+/// 
+/// let read_back: ReplayLog = text.parse().unwrap();
+/// assert!(read_back.rules() == log.rules());
+/// ```
+impl fmt::Display for ReplayLog {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		let decay_mode = match self.rules.decay_mode() {
+			DecayMode::Counted => "counted",
+			DecayMode::Generations => "generations",
+			DecayMode::Revivable => "revivable",
+			DecayMode::RevivableGenerations => "revivable-generations"
+		};
+
+		let inheritance = match self.rules.inheritance() {
+			Inheritance::Majority => "majority",
+			Inheritance::MajorityOrMissing => "majority-or-missing"
+		};
+
+		writeln!(f, "rule {}", self.rules)?;
+		writeln!(f, "decay {}", decay_mode)?;
+		writeln!(f, "chances {} {}", self.rules.birth_chance(), self.rules.survival_chance())?;
+		writeln!(f, "species {} {}", self.rules.species(), inheritance)?;
+		if let Some(weights) = self.rules.weight_table() {
+			writeln!(f, "weights {}", weights.iter().map(u8::to_string).collect::<Vec<String>>().join(" "))?;
+		}
+
+		if let Some((birth, survival)) = self.rules.direction_masks() {
+			let flags = |mask: &[bool]| mask.iter().map(|&d| if d { '1' } else { '0' }).collect::<String>();
+			writeln!(f, "directions {} {}", flags(birth), flags(survival))?;
+		}
+
+		if self.edge != 0 { writeln!(f, "edge {}", self.edge)?; }
+
+		match self.scheme {
+			UpdateScheme::Synchronous => {}
+			UpdateScheme::RandomAsync(seed) => writeln!(f, "scheme random-async {}", seed)?,
+			UpdateScheme::Sequential => writeln!(f, "scheme sequential")?
+		}
+
+		writeln!(f, "seed {}", self.seed)?;
+		writeln!(f, "bounds {} {} {}", self.bounds[0], self.bounds[1], self.bounds[2])?;
+
+		for ([x, y, z], wall, state) in &self.walls {
+			let wall = match wall { Wall::Alive => "alive", Wall::Dead => "dead", Wall::Excluded => "excluded" };
+			writeln!(f, "wall {} {} {} {} {}", x, y, z, wall, state)?;
+		}
+
+		for ([x, y, z], state, species) in &self.cells {
+			writeln!(f, "cell {} {} {} {} {}", x, y, z, state, species)?;
+		}
+
+		Ok(())
+	}
+}
+
+impl FromStr for ReplayLog {
+	type Err = AutomataError;
+
+	fn from_str(s: &str) -> Result<ReplayLog, AutomataError> {
+		let invalid = |reason: &str| AutomataError::InvalidReplay(reason.to_string());
+		let (mut rules, mut bounds, mut seed) = (None, None, None);
+		let (mut decay_mode, mut chances, mut species) = (None, None, None);
+		let (mut weights, mut directions) = (None, None);
+		let (mut edge, mut scheme) = (0, UpdateScheme::Synchronous);
+		let (mut cells, mut walls) = (Vec::new(), Vec::new());
+
+		for line in s.lines().map(str::trim).filter(|line| !line.is_empty()) {
+			let (key, value) = line.split_once(' ').unwrap_or((line, ""));
+			let numbers = || value.split_whitespace().map(str::parse).collect::<Result<Vec<usize>, _>>().map_err(|_| invalid(line));
+
+			match key {
+				"rule" => rules = Some(value.parse::<AutomataRules>()?),
+				"decay" => decay_mode = Some(match value {
+					"counted" => DecayMode::Counted,
+					"generations" => DecayMode::Generations,
+					"revivable" => DecayMode::Revivable,
+					"revivable-generations" => DecayMode::RevivableGenerations,
+					_ => return Err(invalid(line))
+				}),
+				"chances" => match value.split_whitespace().map(str::parse).collect::<Result<Vec<f64>, _>>().as_deref() {
+					Ok([birth, survival]) => chances = Some((*birth, *survival)),
+					_ => return Err(invalid(line))
+				},
+				"species" => species = Some(match value.split_once(' ') {
+					Some((count, "majority")) => (count.parse().map_err(|_| invalid(line))?, Inheritance::Majority),
+					Some((count, "majority-or-missing")) => (count.parse().map_err(|_| invalid(line))?, Inheritance::MajorityOrMissing),
+					_ => return Err(invalid(line))
+				}),
+				"weights" => match numbers()? {
+					w if w.len() == 256 && w.iter().all(|&w| w <= u8::MAX as usize) => weights = Some(w.into_iter().map(|w| w as u8).collect()),
+					_ => return Err(invalid(line))
+				},
+				"directions" => {
+					let flags = |mask: &str| mask.chars().map(|d| match d { '0' => Ok(false), '1' => Ok(true), _ => Err(invalid(line)) }).collect::<Result<Vec<bool>, _>>();
+					match value.split_once(' ') {
+						Some((birth, survival)) if birth.len() == survival.len() => directions = Some((flags(birth)?, flags(survival)?)),
+						_ => return Err(invalid(line))
+					}
+				},
+				"edge" => edge = value.parse::<u8>().map_err(|_| invalid(line))?,
+				"scheme" => scheme = match value.split_once(' ').unwrap_or((value, "")) {
+					("synchronous", "") => UpdateScheme::Synchronous,
+					("random-async", seed) => UpdateScheme::RandomAsync(seed.parse().map_err(|_| invalid(line))?),
+					("sequential", "") => UpdateScheme::Sequential,
+					_ => return Err(invalid(line))
+				},
+				"wall" => match value.split_whitespace().collect::<Vec<&str>>()[..] {
+					[x, y, z, wall, state] => {
+						let number = |n: &str| n.parse::<usize>().map_err(|_| invalid(line));
+						let wall = match wall {
+							"alive" => Wall::Alive,
+							"dead" => Wall::Dead,
+							"excluded" => Wall::Excluded,
+							_ => return Err(invalid(line))
+						};
+						walls.push(([number(x)?, number(y)?, number(z)?], wall, state.parse::<u8>().map_err(|_| invalid(line))?));
+					}
+					_ => return Err(invalid(line))
+				},
+				"seed" => seed = Some(value.parse::<u64>().map_err(|_| invalid(line))?),
+				"bounds" => match numbers()?[..] {
+					[x, y, z] => bounds = Some([x, y, z]),
+					_ => return Err(invalid(line))
+				},
+				"cell" => match numbers()?[..] {
+					[x, y, z, state, species] if state <= u8::MAX as usize && species <= u8::MAX as usize => cells.push(([x, y, z], state as u8, species as u8)),
+					_ => return Err(invalid(line))
+				},
+				_ => return Err(invalid(line))
+			}
+		}
+
+		let mut rules = rules.ok_or_else(|| invalid("there's no rule"))?;
+		if let Some(decay_mode) = decay_mode { rules = rules.with_decay_mode(decay_mode); }
+		if let Some((birth, survival)) = chances { rules = rules.with_chances(birth, survival); }
+		if let Some((count, inheritance)) = species { rules = rules.with_species(count, inheritance); }
+		if let Some(weights) = weights { rules = rules.with_weight_table(weights); }
+		if let Some((birth, survival)) = directions { rules = rules.with_direction_masks(birth, survival); }
+
+		let bounds = bounds.ok_or_else(|| invalid("there are no bounds"))?;
+		let seed = seed.ok_or_else(|| invalid("there's no seed"))?;
+
+		Ok(ReplayLog::new(rules, bounds, cells, seed).with_walls(walls, edge).with_update_scheme(scheme))
+	}
+}
@@ -0,0 +1,4 @@
+//! This module has tools for working with rules themselves, rather than the automata that follow them.
+
+/// Random rules, mutations, and crossovers, for searching through rules for interesting ones.
+pub mod explore;
@@ -0,0 +1,261 @@
+//! This module has tools for exploring the space of rules: making random ones, mutating them, breeding two of them together,
+//! and scoring how interesting they are, which is everything an evolutionary search for interesting rules needs.
+//! 
+//! ```
+//! # use cellular_snapp::{AutomataRules, Method, deep::{Automaton, Vec3}};
+//! # use cellular_snapp::random::Rng;
+//! let mut rng = Rng::new(42);
+//! 
+//! let parent = AutomataRules::random(Method::Moore, &mut rng);
+//! let child = parent.mutate(0.1, &mut rng);
+//! let mixed = AutomataRules::crossover(&parent, &child, &mut rng);
+//! 
+//! assert!(Automaton::new(mixed, Vec3::new(8, 8, 8), Vec::new()).is_ok());
+//! ```
+//! 
+//! Those go by how many neighbors a cell has in 3D (like 26 for Moore), since that's where most of the unexplored rules are. `random_flat` and `mutate_flat` go by 2D instead.
+//! 
+//! Random rules never have cells being born with no neighbors, since rules like that just flash the whole grid on and off.
+//! 
+//! `score_rule` (and `score_rule_deep` for 3D rules) runs a rule on a random soup and measures what it does, so thousands of candidates can be screened without looking at any of them.
+//! 
+//! ```
+//! # use cellular_snapp::{presets, rules::explore::{score_rule, Outcome}, flat::Vec2};
+//! let life = score_rule(&presets::life(), Vec2::new(32, 32), 7, 200).unwrap();
+//! assert!(life.activity() > 0.0);
+//! 
+//! // without survival, every cell dies straight away
+//! let doomed = score_rule(&"B/S".parse().unwrap(), Vec2::new(32, 32), 7, 200).unwrap();
+//! assert_eq!(doomed.outcome(), Outcome::DiedOut { tick: 1 });
+//! assert_eq!(doomed.score(), 0.0);
+//! ```
+
+//--> Imports <--
+
+use crate::{AutomataError, AutomataRules, Method, Rule};
+use crate::{flat, deep};
+use crate::analysis::shannon_entropy;
+use crate::random::Rng;
+use std::collections::HashMap;
+
+//--> Structs <--
+
+/// What a rule did over a run, for comparing it against other rules.
+#[derive(Clone, PartialEq, Debug)]
+pub struct RuleScore {
+	activity: f64,
+	growth_rate: f64,
+	entropy: f64,
+	outcome: Outcome
+}
+
+//--> Enums <--
+
+/// How a run ended up.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Outcome {
+	/// Every cell was dead by the given tick.
+	DiedOut { tick: usize },
+	/// The grid went back to a generation it had already been through at the given tick, and kept cycling every `period` ticks after that.
+	/// A period of 1 means it froze solid.
+	Settled { tick: usize, period: usize },
+	/// More than half the grid was live or dying at the end, and it hadn't settled down.
+	Exploded,
+	/// It was still going at the end, without filling up the grid.
+	Active
+}
+
+//--> Functions <--
+
+/// The most cell states random rules can have.
+const MAX_STATES: u8 = 10;
+
+/// How much of the grid the soups that rules get scored on start out filling.
+const SOUP_DENSITY: f64 = 0.3;
+
+impl AutomataRules {
+	/// Creates random birth and survival rules for 3D cells with the given neighborhood, with somewhere from 2 to 10 cell states.
+	/// Every neighbor count has an even chance of being in each rule.
+	pub fn random(method: Method, rng: &mut Rng) -> AutomataRules {
+		let max_neighbors = deep::max_neighbors(&method).unwrap_or(u8::MAX);
+		AutomataRules::random_up_to(method, max_neighbors, rng)
+	}
+
+	/// Creates random birth and survival rules just like `random`, except for 2D cells.
+	/// 
+	/// ```
+	/// # use cellular_snapp::{AutomataRules, Method, random::Rng, flat::{Automaton, Vec2}};
+	/// let rules = AutomataRules::random_flat(Method::Moore, &mut Rng::new(3)).mutate_flat(0.2, &mut Rng::new(4));
+	/// assert!(Automaton::new(rules, Vec2::new(8, 8), Vec::new()).is_ok());
+	/// ```
+	pub fn random_flat(method: Method, rng: &mut Rng) -> AutomataRules {
+		let max_neighbors = flat::max_neighbors(&method).unwrap_or(u8::MAX);
+		AutomataRules::random_up_to(method, max_neighbors, rng)
+	}
+
+	/// Gives back a copy of the rules with a few random changes, for 3D cells.
+	/// Every neighbor count gets added to or taken out of each rule with a `strength` chance, and the amount of states goes up or down by one with a `strength` chance.
+	/// Everything else, like the chances, the decay mode, and species, stays the same.
+	/// Transition tables, isotropic rules, and vote rules don't go by birth and survival counts, so they just get copied.
+	pub fn mutate(&self, strength: f64, rng: &mut Rng) -> AutomataRules {
+		self.mutate_up_to(strength, deep::max_neighbors(&self.neighbor_method).unwrap_or(u8::MAX), rng)
+	}
+
+	/// Gives back a copy of the rules with a few random changes just like `mutate`, except for 2D cells.
+	pub fn mutate_flat(&self, strength: f64, rng: &mut Rng) -> AutomataRules {
+		self.mutate_up_to(strength, flat::max_neighbors(&self.neighbor_method).unwrap_or(u8::MAX), rng)
+	}
+
+	/// Creates random rules for cells with up to `max_neighbors` neighbors.
+	fn random_up_to(method: Method, max_neighbors: u8, rng: &mut Rng) -> AutomataRules {
+		let survive = (0..=max_neighbors).filter(|_| rng.chance(0.5)).collect();
+		let born = (1..=max_neighbors).filter(|_| rng.chance(0.5)).collect();
+		let states = 2 + rng.below((MAX_STATES - 1) as u64) as u8;
+
+		AutomataRules::new(Rule::from_counts(survive), Rule::from_counts(born), states, method)
+	}
+
+	/// Mutates the rules for cells with up to `max_neighbors` neighbors.
+	fn mutate_up_to(&self, strength: f64, max_neighbors: u8, rng: &mut Rng) -> AutomataRules {
+		if self.table.is_some() || self.isotropic.is_some() || self.vote.is_some() { return self.clone(); }
+
+		let mut flip = |rule: &Rule, from: u8| {
+			let counts = rule.counts();
+			Rule::from_counts((from..=max_neighbors).filter(|n| counts.contains(n) != rng.chance(strength)).collect())
+		};
+
+		let (to_survive, to_be_born) = (flip(&self.to_survive, 0), flip(&self.to_be_born, 1));
+
+		let cell_states = match rng.chance(strength) {
+			true if rng.chance(0.5) => self.cell_states.saturating_add(1),
+			true => self.cell_states.saturating_sub(1).max(2),
+			false => self.cell_states
+		};
+
+		AutomataRules { to_survive, to_be_born, cell_states, ..self.clone() }
+	}
+
+	/// Breeds two sets of rules together. Each neighbor count in the birth and survival rules comes from one parent or the other at random,
+	/// and so does the amount of cell states. Everything else comes from `a`.
+	pub fn crossover(a: &AutomataRules, b: &AutomataRules, rng: &mut Rng) -> AutomataRules {
+		let mut mix = |from_a: &Rule, from_b: &Rule| {
+			let (from_a, from_b) = (from_a.counts(), from_b.counts());
+			let max = from_a.iter().chain(&from_b).copied().max().unwrap_or(0);
+			Rule::from_counts((0..=max).filter(|n| if rng.chance(0.5) { from_a.contains(n) } else { from_b.contains(n) }).collect())
+		};
+
+		let (to_survive, to_be_born) = (mix(&a.to_survive, &b.to_survive), mix(&a.to_be_born, &b.to_be_born));
+		let cell_states = if rng.chance(0.5) { a.cell_states } else { b.cell_states };
+
+		AutomataRules { to_survive, to_be_born, cell_states, ..a.clone() }
+	}
+}
+
+impl RuleScore {
+	/// Get the sustained activity: the share of the grid that was born or died each tick, averaged over the second half of the run, from 0 to 1.
+	/// Rules that settle down early score low here even if they start out busy.
+	pub fn activity(&self) -> f64 {
+		self.activity
+	}
+
+	/// Get how fast the live and dying cells grew (or shrank, if it's less than 0), as the share of the grid gained each tick on average.
+	pub fn growth_rate(&self) -> f64 {
+		self.growth_rate
+	}
+
+	/// Get the Shannon entropy of the cell states at the end, in bits, scaled so 1 is the most the rule's states allow.
+	/// Empty and full grids score 0, and grids with an even spread of every state score 1.
+	pub fn entropy(&self) -> f64 {
+		self.entropy
+	}
+
+	/// Get how the run ended up.
+	pub fn outcome(&self) -> Outcome {
+		self.outcome
+	}
+
+	/// Get a single number for ranking rules by how interesting they are, which is the activity times the entropy.
+	/// Rules that died out score 0, and rules that exploded only get half.
+	pub fn score(&self) -> f64 {
+		match self.outcome {
+			Outcome::DiedOut { .. } => 0.0,
+			Outcome::Exploded => self.activity * self.entropy / 2.0,
+			Outcome::Settled { .. } | Outcome::Active => self.activity * self.entropy
+		}
+	}
+}
+
+/// Runs flat rules for up to `ticks` ticks on a random soup filling 30% of the grid (made from `seed`) and scores what they do.
+/// The run stops early if the grid dies out or settles into a cycle. Rules that don't fit in a flat automaton give back an error.
+pub fn score_rule(rules: &AutomataRules, bounds: flat::Vec2, seed: u64, ticks: usize) -> Result<RuleScore, AutomataError> {
+	let mut rng = Rng::new(seed);
+	let mut automaton = flat::Automaton::new(rules.clone(), bounds, flat::random_seed(bounds, SOUP_DENSITY, &mut rng))?;
+	automaton.seed_rng(rng.next_u64());
+
+	let region = flat::Rect2::new(flat::Vec2::new(0, 0), bounds);
+
+	Ok(score_run(rules.states(), bounds.x() * bounds.y(), ticks, |tick| {
+		if tick { automaton.tick(); }
+		let live = automaton.live_cells_in(&region).into_iter().map(|(v, s)| ([v.x(), v.y(), 0], s)).collect();
+		(automaton.state_hash(), live)
+	}))
+}
+
+/// Runs 3D rules for up to `ticks` ticks on a random soup filling 30% of the grid (made from `seed`) and scores what they do, just like `score_rule`.
+pub fn score_rule_deep(rules: &AutomataRules, bounds: deep::Vec3, seed: u64, ticks: usize) -> Result<RuleScore, AutomataError> {
+	let mut rng = Rng::new(seed);
+	let mut automaton = deep::Automaton::new(rules.clone(), bounds, deep::random_seed(bounds, SOUP_DENSITY, &mut rng))?;
+	automaton.seed_rng(rng.next_u64());
+
+	let region = deep::Box3::new(deep::Vec3::new(0, 0, 0), bounds);
+
+	Ok(score_run(rules.states(), bounds.x() * bounds.y() * bounds.z(), ticks, |tick| {
+		if tick { automaton.tick(); }
+		let live = automaton.live_cells_in(&region).into_iter().map(|(v, s)| ([v.x(), v.y(), v.z()], s)).collect();
+		(automaton.state_hash(), live)
+	}))
+}
+
+/// Scores a run on a grid of `cells` cells. `next(tick)` ticks the automaton first if `tick` is true,
+/// and gives back the state hash of the grid along with every live and dying cell.
+fn score_run(states: u8, cells: usize, ticks: usize, mut next: impl FnMut(bool) -> (u64, HashMap<[usize; 3], u8>)) -> RuleScore {
+	let (first_hash, mut live) = next(false);
+	let start = live.len();
+
+	let mut seen = HashMap::from([(first_hash, 0)]);
+	let mut changes = Vec::new();
+	let mut outcome = None;
+
+	for tick in 1..=ticks {
+		let (hash, now) = next(true);
+		changes.push(now.iter().filter(|(v, s)| live.get(*v) != Some(*s)).count() + live.keys().filter(|v| !now.contains_key(*v)).count());
+		live = now;
+
+		if live.is_empty() {
+			outcome = Some(Outcome::DiedOut { tick });
+			break;
+		}
+
+		if let Some(first) = seen.insert(hash, tick) {
+			outcome = Some(Outcome::Settled { tick: first, period: tick - first });
+			break;
+		}
+	}
+
+	let cells = cells.max(1) as f64;
+	let ran = changes.len();
+	let sustained = &changes[ran / 2..];
+	let activity = if sustained.is_empty() { 0.0 } else { sustained.iter().sum::<usize>() as f64 / sustained.len() as f64 / cells };
+	let growth_rate = if ran == 0 { 0.0 } else { (live.len() as f64 - start as f64) / cells / ran as f64 };
+
+	let outcome = outcome.unwrap_or(if live.len() as f64 > cells / 2.0 { Outcome::Exploded } else { Outcome::Active });
+
+	// every cell that isn't in the live list is dead, so that's state 0's share
+	let mut counts = vec![0; states.max(1) as usize];
+	counts[0] = cells as usize - live.len();
+	for state in live.values() { if let Some(count) = counts.get_mut(*state as usize) { *count += 1; } }
+
+	let entropy = if states < 2 { 0.0 } else { shannon_entropy(&counts) / (states as f64).log2() };
+
+	RuleScore { activity, growth_rate, entropy, outcome }
+}
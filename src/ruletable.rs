@@ -0,0 +1,260 @@
+//! A loader (and writer) for Golly's rule table (`@TABLE`) format.
+//! 
+//! Rule tables are lists of transitions like `0,1,1,1,0,0,0,0,0,1`, giving the state of a cell and each of its neighbors, followed by the cell's new state.
+//! Variables (like `var a={0,1}`) stand in for several states at once, and symmetries save having to write out every rotation and reflection.
+//! Everything gets expanded out into a lookup table up front, so ticking doesn't have to search through the transitions.
+//! 
+//! Writing goes the other way, listing out every transition that changes a cell, with no variables at all.
+
+//--> Imports <--
+
+use crate::Method;
+use std::collections::HashMap;
+use std::fmt;
+
+//--> Structs <--
+
+/// A compiled rule table: the new state for every cell and neighborhood the table covers.
+/// Neighborhoods are keyed by the cell's state and then its neighbors' states, in the same order as `flat::neighbor_offsets`.
+#[derive(Clone, PartialEq)]
+pub(crate) struct Lookup {
+	table: HashMap<[u8; 9], u8>,
+	method: Method,
+	permute: bool
+}
+
+/// Everything read out of a rule table file.
+pub(crate) struct RuleTable {
+	pub(crate) name: String,
+	pub(crate) cell_states: u8,
+	pub(crate) neighbor_method: Method,
+	pub(crate) lookup: Lookup
+}
+
+/// Where each of Golly's neighbors (which go clockwise from north) sits in `flat::neighbor_offsets`,
+/// along with every rearrangement of the neighbors the symmetries allow, and whether neighbors can be in any order at all.
+struct Neighborhood {
+	order: Vec<usize>,
+	permutations: Vec<Vec<usize>>,
+	permute: bool
+}
+
+//--> Functions <--
+
+impl Lookup {
+	/// Get the new state of a cell, or `None` if the table doesn't cover it (in which case the cell stays as it is).
+	pub(crate) fn get(&self, state: u8, neighbors: impl Iterator<Item = u8>) -> Option<u8> {
+		let mut key = [0; 9];
+		key[0] = state;
+
+		for (k, n) in key[1..].iter_mut().zip(neighbors) { *k = n; }
+		if self.permute { key[1..].sort_unstable(); }
+
+		self.table.get(&key).copied()
+	}
+
+	/// Get the neighbor method the table was written for, which is what its neighbors are in the order of.
+	pub(crate) fn method(&self) -> &Method {
+		&self.method
+	}
+
+	/// Get every transition in the table in Golly's order (the cell, then its neighbors going clockwise from north, then the new state),
+	/// along with the symmetries they need, skipping the ones that leave the cell as it was.
+	pub(crate) fn transitions(&self, method: &Method) -> (Vec<Vec<u8>>, &'static str) {
+		let order = neighborhood(method, "none").map(|n| n.order).unwrap_or_default();
+
+		let mut transitions: Vec<Vec<u8>> = self.table.iter().filter(|(key, to)| key[0] != **to).map(|(key, to)| {
+			std::iter::once(key[0]).chain(order.iter().map(|offset| key[1 + offset])).chain(std::iter::once(*to)).collect()
+		}).collect();
+
+		transitions.sort_unstable();
+		(transitions, if self.permute { "permute" } else { "none" })
+	}
+}
+
+/// Only the size of the table gets shown, since printing every neighborhood would go on for pages.
+impl fmt::Debug for Lookup {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "Lookup({} entries)", self.table.len())
+	}
+}
+
+impl RuleTable {
+	/// Parses a rule table, returning what went wrong if it can't be understood.
+	pub(crate) fn parse(text: &str) -> Result<RuleTable, String> {
+		let (mut name, mut cell_states, mut neighbor_method, mut symmetries) = (String::from("table"), None, None, String::from("none"));
+		let mut variables: HashMap<String, Vec<u8>> = HashMap::new();
+		let mut transitions = Vec::new();
+		let mut in_table = false;
+
+		for line in text.lines() {
+			let line = line.split('#').next().unwrap_or("").trim();
+			if line.is_empty() { continue; }
+
+			if let Some(section) = line.strip_prefix('@') {
+				let mut words = section.split_whitespace();
+				in_table = words.next() == Some("TABLE");
+
+				if section.starts_with("RULE") {
+					name = words.next().ok_or("@RULE is missing a name")?.to_string();
+				}
+
+				continue;
+			}
+
+			if !in_table { continue; }
+
+			if let Some((key, value)) = line.split_once(':') {
+				let value = value.trim();
+
+				match key.trim() {
+					"n_states" => cell_states = Some(value.parse::<u8>().map_err(|_| format!("n_states '{}' isn't a number between 1 and 255", value))?),
+					"neighborhood" => neighbor_method = Some(match value {
+						"Moore" => Method::Moore,
+						"vonNeumann" => Method::VonNeumann,
+						_ => return Err(format!("the {} neighborhood isn't supported", value))
+					}),
+					"symmetries" => symmetries = value.to_string(),
+					other => return Err(format!("unknown table setting '{}'", other))
+				}
+			} else if let Some(var) = line.strip_prefix("var ") {
+				let (var, values) = var.split_once('=').ok_or_else(|| format!("variable '{}' is missing its values", var))?;
+				let values = values.trim().trim_start_matches('{').trim_end_matches('}');
+
+				let mut states = Vec::new();
+				for v in values.split(',').map(str::trim) {
+					match v.parse::<u8>() {
+						Ok(s) => states.push(s),
+						Err(_) => states.extend(variables.get(v).ok_or_else(|| format!("unknown state or variable '{}'", v))?)
+					}
+				}
+
+				variables.insert(var.trim().to_string(), states);
+			} else {
+				// transitions can leave out the commas if every state is a single digit
+				let tokens = if line.contains(',') {
+					line.split(',').map(|t| t.trim().to_string()).collect::<Vec<String>>()
+				} else {
+					line.chars().filter(|c| !c.is_whitespace()).map(String::from).collect()
+				};
+
+				transitions.push(tokens);
+			}
+		}
+
+		let cell_states = cell_states.ok_or("the table doesn't say how many states it has")?;
+		let neighbor_method = neighbor_method.ok_or("the table doesn't say which neighborhood it uses")?;
+		let Neighborhood { order, permutations, permute } = neighborhood(&neighbor_method, &symmetries)?;
+
+		let mut table = HashMap::new();
+
+		for tokens in &transitions {
+			if tokens.len() != order.len() + 2 {
+				return Err(format!("transition '{}' should have {} states", tokens.join(","), order.len() + 2));
+			}
+
+			// each variable stands for the same state everywhere it shows up in a transition, so go through every combination of them
+			let mut names = tokens.iter().filter(|t| variables.contains_key(*t)).cloned().collect::<Vec<String>>();
+			names.sort();
+			names.dedup();
+
+			let mut combination = vec![0; names.len()];
+
+			loop {
+				let state_of = |token: &String| -> Result<u8, String> {
+					match names.iter().position(|n| n == token) {
+						Some(i) => Ok(variables[token][combination[i]]),
+						None => token.parse::<u8>().map_err(|_| format!("unknown state or variable '{}'", token))
+					}
+				};
+
+				let states = tokens.iter().map(state_of).collect::<Result<Vec<u8>, String>>()?;
+
+				if let Some(s) = states.iter().find(|s| **s >= cell_states) {
+					return Err(format!("state {} is too big for a table with {} states", s, cell_states));
+				}
+
+				for p in &permutations {
+					let mut key = [0; 9];
+					key[0] = states[0];
+
+					for (golly, offset) in order.iter().enumerate() {
+						key[1 + offset] = states[1 + p[golly]];
+					}
+
+					if permute { key[1..].sort_unstable(); }
+
+					// the first transition to match a neighborhood wins
+					table.entry(key).or_insert(states[order.len() + 1]);
+				}
+
+				// move on to the next combination of variables, stopping once they've all been tried
+				let next = names.iter().enumerate().position(|(i, n)| {
+					combination[i] += 1;
+					if combination[i] < variables[n].len() { return true; }
+					combination[i] = 0;
+					false
+				});
+
+				if next.is_none() { break; }
+			}
+		}
+
+		Ok(RuleTable { name, cell_states, neighbor_method: neighbor_method.clone(), lookup: Lookup { table, method: neighbor_method, permute } })
+	}
+}
+
+/// Writes out a whole `.rule` file with the given transitions, which are in the same order `Lookup::transitions` gives.
+pub(crate) fn write(name: &str, cell_states: u8, method: &Method, symmetries: &str, transitions: &[Vec<u8>]) -> String {
+	let neighborhood = if *method == Method::VonNeumann { "vonNeumann" } else { "Moore" };
+	let mut text = format!("@RULE {}\n\n@TABLE\nn_states:{}\nneighborhood:{}\nsymmetries:{}\n", name, cell_states, neighborhood, symmetries);
+
+	for transition in transitions {
+		text.push_str(&transition.iter().map(u8::to_string).collect::<Vec<String>>().join(","));
+		text.push('\n');
+	}
+
+	text
+}
+
+/// Get every way `n` neighbors can be in the given amount of states, not caring about which neighbor is which, with each one's states from smallest to biggest.
+pub(crate) fn neighbor_multisets(n: usize, states: u8) -> Vec<Vec<u8>> {
+	let mut multisets = Vec::new();
+	let mut current = vec![0; n];
+
+	loop {
+		multisets.push(current.clone());
+
+		// count up like an odometer, except every digit after the one that went up starts from it, so the states never go back down
+		let Some(i) = current.iter().rposition(|s| *s + 1 < states) else { break; };
+		let next = current[i] + 1;
+		for s in &mut current[i..] { *s = next; }
+	}
+
+	multisets
+}
+
+/// Works out the neighborhood for the given neighbor method and symmetries.
+fn neighborhood(method: &Method, symmetries: &str) -> Result<Neighborhood, String> {
+	let (order, step) = match method {
+		Method::Moore | Method::FacesAndEdges => (vec![3, 5, 6, 7, 4, 2, 1, 0], 2),
+		Method::VonNeumann => (vec![1, 3, 2, 0], 1),
+		Method::Euclidean(_) => return Err(String::from("rule tables only work with Moore and von Neumann neighborhoods"))
+	};
+
+	let n = order.len();
+	let turns = |by: usize| (0..n / by).map(move |t| (0..n).map(move |i| (i + t * by) % n).collect::<Vec<usize>>());
+	let reflect = |p: Vec<usize>| p.iter().map(|i| (n - i) % n).collect::<Vec<usize>>();
+
+	let permutations: Vec<Vec<usize>> = match symmetries {
+		"none" | "permute" => vec![(0..n).collect()],
+		"rotate4" => turns(step).collect(),
+		"rotate8" if n == 8 => turns(1).collect(),
+		"reflect" => vec![(0..n).collect(), reflect((0..n).collect())],
+		"rotate4reflect" => turns(step).flat_map(|p| [p.clone(), reflect(p)]).collect(),
+		"rotate8reflect" if n == 8 => turns(1).flat_map(|p| [p.clone(), reflect(p)]).collect(),
+		_ => return Err(format!("the {} symmetry isn't supported for this neighborhood", symmetries))
+	};
+
+	Ok(Neighborhood { order, permutations, permute: symmetries == "permute" })
+}
@@ -0,0 +1,203 @@
+//! This module runs an automaton on a background thread, so a GUI can keep drawing while the automaton ticks away on its own.
+//! 
+//! The runner owns the automaton and takes commands (play, pause, step, and how fast to go), and sends a snapshot of the automaton back after every tick.
+//! Snapshots go into a small buffer, and if nobody's picking them up quickly enough the extras get dropped instead of piling up.
+//! The newest snapshot is always kept aside too, so `latest` never misses it even when the buffer's full.
+//! 
+//! ```
+//! # use cellular_snapp::{presets, runner::Runner, flat::{Automaton, Vec2}};
+//! let life = Automaton::new(presets::life(), Vec2::new(32, 32), presets::glider().positions()).unwrap();
+//! let runner = Runner::spawn(life);
+//! 
+//! runner.step();
+//! runner.step();
+//! let second = runner.frames().iter().nth(1).unwrap();
+//! assert_eq!(second.get_generation(), 2);
+//! 
+//! // stopping gives the automaton back, as far along as it got
+//! assert_eq!(runner.stop().get_generation(), 2);
+//! ```
+
+//--> Imports <--
+
+use crate::CellularAutomaton;
+use std::sync::{Arc, Mutex};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender, SyncSender};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+//--> Structs <--
+
+/// An automaton ticking away on its own thread. Dropping the runner stops the thread, and `stop` does too while giving the automaton back.
+pub struct Runner<A: Tick> {
+	commands: Sender<Command>,
+	frames: Receiver<A>,
+	newest: Arc<Mutex<Option<A>>>,
+	thread: Option<JoinHandle<A>>
+}
+
+/// Where the background thread sends its snapshots.
+struct Outbox<A> {
+	frames: SyncSender<A>,
+	newest: Arc<Mutex<Option<A>>>
+}
+
+//--> Enums <--
+
+/// What the background thread gets told to do.
+enum Command {
+	Play,
+	Pause,
+	Step,
+	Speed(f64),
+	SampleEvery(usize),
+	Stop
+}
+
+//--> Traits <--
+
+/// Anything a runner can tick. This is implemented for every `CellularAutomaton` that can be sent to another thread, like flat and deep automata.
+pub trait Tick: Clone + Send + 'static {
+	/// Advances by one tick.
+	fn tick(&mut self);
+}
+
+//--> Functions <--
+
+/// How many snapshots can be waiting to be picked up before new ones start getting dropped.
+const BUFFERED_FRAMES: usize = 16;
+
+impl<A: Tick> Runner<A> {
+	/// Moves the automaton onto a new background thread. It starts out paused, and plays at 60 ticks a second once it's told to.
+	pub fn spawn(automaton: A) -> Runner<A> {
+		let (commands, inbox) = mpsc::channel();
+		let (outbox, frames) = mpsc::sync_channel(BUFFERED_FRAMES);
+		let newest = Arc::new(Mutex::new(None));
+		let slot = newest.clone();
+		let thread = thread::spawn(move || run(automaton, inbox, Outbox { frames: outbox, newest: slot }));
+
+		Runner { commands, frames, newest, thread: Some(thread) }
+	}
+
+	/// Starts ticking on its own.
+	pub fn play(&self) {
+		let _ = self.commands.send(Command::Play);
+	}
+
+	/// Stops ticking on its own, until `play` is called again.
+	pub fn pause(&self) {
+		let _ = self.commands.send(Command::Pause);
+	}
+
+	/// Ticks once. This works whether it's playing or paused.
+	pub fn step(&self) {
+		let _ = self.commands.send(Command::Step);
+	}
+
+	/// Sets how many ticks a second it plays at. Anything that isn't a positive, finite number means as fast as it can go.
+	pub fn set_speed(&self, ticks_per_second: f64) {
+		let _ = self.commands.send(Command::Speed(ticks_per_second));
+	}
+
+	/// Only sends a snapshot after every `n`th tick, so fast rules can be watched (or recorded) without copying every generation. It still ticks every step in between.
+	/// Ticks are counted from when the runner was spawned, so with `n` of 10, snapshots come out at ticks 10, 20, 30, and so on. Runners start out sending every tick.
+	/// 
+	/// ```
+	/// # use cellular_snapp::{presets, runner::Runner, flat::{Automaton, Vec2}};
+	/// let runner = Runner::spawn(Automaton::new(presets::life(), Vec2::new(32, 32), presets::glider().positions()).unwrap());
+	/// runner.sample_every(3);
+	/// for _ in 0..7 { runner.step(); }
+	/// 
+	/// let sampled: Vec<u64> = runner.frames().iter().take(2).map(|frame| frame.get_generation()).collect();
+	/// assert_eq!(sampled, vec![3, 6]);
+	/// assert_eq!(runner.stop().get_generation(), 7);
+	/// ```
+	pub fn sample_every(&self, n: usize) {
+		let _ = self.commands.send(Command::SampleEvery(n));
+	}
+
+	/// Get the channel snapshots come out of, one after every tick (or every few, with `sample_every`).
+	/// Only the first few that nobody's picked up yet get kept, so use `latest` to keep up with the newest one instead.
+	pub fn frames(&self) -> &Receiver<A> {
+		&self.frames
+	}
+
+	/// Get the newest snapshot, throwing away any older ones still waiting in `frames`, or `None` if there haven't been any ticks since the last one was picked up.
+	/// This is the newest one even if the buffer filled up and later snapshots stopped going into it.
+	/// 
+	/// ```
+	/// # use cellular_snapp::{presets, runner::Runner, flat::{Automaton, Vec2}};
+	/// let runner = Runner::spawn(Automaton::new(presets::life(), Vec2::new(32, 32), presets::glider().positions()).unwrap());
+	/// for _ in 0..40 { runner.step(); }
+	/// 
+	/// // way more ticks than fit in the buffer, but the last one still turns up
+	/// let last = std::iter::repeat_with(|| runner.latest()).flatten().find(|frame| frame.get_generation() == 40);
+	/// assert!(last.is_some());
+	/// assert!(runner.latest().is_none());
+	/// ```
+	pub fn latest(&self) -> Option<A> {
+		self.frames.try_iter().for_each(drop);
+		self.newest.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).take()
+	}
+
+	/// Stops the background thread and gives back the automaton.
+	pub fn stop(mut self) -> A {
+		let _ = self.commands.send(Command::Stop);
+		self.thread.take().unwrap().join().expect("the runner's thread panicked")
+	}
+}
+
+impl<A: Tick> Drop for Runner<A> {
+	fn drop(&mut self) {
+		let _ = self.commands.send(Command::Stop);
+		if let Some(thread) = self.thread.take() { let _ = thread.join(); }
+	}
+}
+
+impl<A: CellularAutomaton + Clone + Send + 'static> Tick for A {
+	fn tick(&mut self) { CellularAutomaton::tick(self) }
+}
+
+/// Runs on the background thread, ticking and sending snapshots until it's told to stop (or the runner goes away).
+fn run<A: Tick>(mut automaton: A, inbox: Receiver<Command>, outbox: Outbox<A>) -> A {
+	let (mut playing, mut interval) = (false, Some(Duration::from_secs_f64(1.0 / 60.0)));
+	let (mut ticks, mut sample_every) = (0, 1);
+	let mut next_tick = Instant::now();
+
+	loop {
+		let command = match (playing, interval) {
+			(false, _) => inbox.recv().map_err(|_| RecvTimeoutError::Disconnected),
+			(true, Some(_)) => inbox.recv_timeout(next_tick.saturating_duration_since(Instant::now())),
+			(true, None) => inbox.try_recv().map_err(|e| match e {
+				mpsc::TryRecvError::Empty => RecvTimeoutError::Timeout,
+				mpsc::TryRecvError::Disconnected => RecvTimeoutError::Disconnected
+			})
+		};
+
+		match command {
+			Ok(Command::Play) => { playing = true; next_tick = Instant::now(); },
+			Ok(Command::Pause) => playing = false,
+			Ok(Command::Step) => tick(&mut automaton, &outbox, &mut ticks, sample_every),
+			Ok(Command::Speed(tps)) => interval = (tps.is_finite() && tps > 0.0).then(|| Duration::from_secs_f64(1.0 / tps)),
+			Ok(Command::SampleEvery(n)) => sample_every = n.max(1),
+			Ok(Command::Stop) | Err(RecvTimeoutError::Disconnected) => return automaton,
+			Err(RecvTimeoutError::Timeout) => {
+				tick(&mut automaton, &outbox, &mut ticks, sample_every);
+				// falling behind doesn't make it rush to catch up
+				next_tick = (next_tick + interval.unwrap_or_default()).max(Instant::now());
+			}
+		}
+	}
+}
+
+/// Ticks once and sends out a snapshot if it's one of the ticks being sampled.
+/// It always becomes the newest snapshot, but only goes into the buffer if there's room.
+fn tick<A: Tick>(automaton: &mut A, outbox: &Outbox<A>, ticks: &mut usize, sample_every: usize) {
+	automaton.tick();
+	*ticks += 1;
+	if !ticks.is_multiple_of(sample_every) { return; }
+
+	let frame = automaton.clone();
+	*outbox.newest.lock().unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(frame.clone());
+	let _ = outbox.frames.try_send(frame);
+}
@@ -0,0 +1,307 @@
+//! This module has a soup search harness, in the spirit of apgsearch.
+//! 
+//! A soup search makes random soups over and over, runs each one until it settles down, and then takes a census of the objects left behind.
+//! Run enough soups and you get a good picture of what a rule's "ash" looks like, along with the odd rare object.
+//! 
+//! ```
+//! # use cellular_snapp::{presets, flat::Vec2, search::SoupSearch};
+//! let census = SoupSearch::new(presets::life(), Vec2::new(6, 6), 0.5).with_max_ticks(300).threads(2).run(4, 1);
+//! assert_eq!(census.soups(), 4);
+//! println!("{:?}", census.most_common());
+//! ```
+//! 
+//! Objects are named after the kind of object they are and how many cells they have, like `xs4` for a still life with 4 cells, `xp2` for a period 2 oscillator,
+//! or `xq4` for a period 4 spaceship, followed by a hash of their shape that's the same in every rotation, reflection, and phase.
+//! In Life, the most common objects just get their usual names instead, like `block` or `glider`.
+
+//--> Imports <--
+
+use crate::{AutomataRules, Backend, Progress, hash_cells, presets};
+use crate::analysis::{detect_period, Behavior};
+use crate::flat::{self, Automaton, Pattern, Rect2, Vec2};
+use crate::random::Rng;
+use std::collections::{HashMap, HashSet};
+use std::ops::ControlFlow;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::thread;
+
+//--> Structs <--
+
+/// The settings for a soup search.
+#[derive(Clone)]
+pub struct SoupSearch {
+	rules: AutomataRules,
+	soup_size: Vec2,
+	density: f64,
+	max_ticks: usize,
+	threads: usize
+}
+
+/// What a soup search found: how many soups were run, how many never settled down, and how many of each object turned up.
+#[derive(Clone, Default, Debug)]
+pub struct Census {
+	soups: usize,
+	unstable: usize,
+	objects: HashMap<String, usize>
+}
+
+//--> Functions <--
+
+/// The longest population cycle a soup can settle into and still be counted as stable.
+const MAX_PERIOD: usize = 12;
+
+/// How many generations in a row have to follow the population cycle before a soup counts as stable.
+const WINDOW: usize = 36;
+
+impl SoupSearch {
+	/// Creates a soup search for the given rules, where every soup is `soup_size` cells across and each of those cells has a `density` chance of starting out alive.
+	/// Soups get 2000 ticks to settle down and run one at a time unless you say otherwise.
+	/// 
+	/// Soups are run on a grid big enough that nothing can crash into the edges in time, using the tree backend, so rules that bring empty space to life will be very slow.
+	pub fn new(rules: AutomataRules, soup_size: Vec2, density: f64) -> SoupSearch {
+		SoupSearch { rules, soup_size, density, max_ticks: 2000, threads: 1 }
+	}
+
+	/// Sets how many ticks each soup gets to settle down before it's given up on and counted as unstable.
+	pub fn with_max_ticks(mut self, max_ticks: usize) -> SoupSearch {
+		self.max_ticks = max_ticks;
+		self
+	}
+
+	/// Sets how many soups get run at once, each on its own scoped thread. The census comes out the same however many threads there are.
+	pub fn threads(mut self, threads: usize) -> SoupSearch {
+		self.threads = threads.max(1);
+		self
+	}
+
+	/// Runs the given amount of soups and takes a census of everything they left behind.
+	/// The same seed always gives the same soups, and each soup only depends on the seed and its own number, so searches can be split up between machines.
+	pub fn run(&self, soups: usize, seed: u64) -> Census {
+		self.run_with(soups, seed, |_| ControlFlow::Continue(()))
+	}
+
+	/// Runs soups like `run`, calling `progress` every time a soup finishes. Once the callback breaks, the search stops as soon as it can,
+	/// and the census only has the soups that finished before then in it.
+	/// 
+	/// ```
+	/// # use cellular_snapp::{presets, flat::Vec2, search::SoupSearch};
+	/// # use std::ops::ControlFlow;
+	/// let search = SoupSearch::new(presets::life(), Vec2::new(6, 6), 0.5).with_max_ticks(300);
+	/// let census = search.run_with(100, 1, |progress| if progress.done() < 3 { ControlFlow::Continue(()) } else { ControlFlow::Break(()) });
+	/// assert_eq!(census.soups(), 3);
+	/// ```
+	pub fn run_with(&self, soups: usize, seed: u64, mut progress: impl FnMut(Progress) -> ControlFlow<()>) -> Census {
+		let known = if self.rules == presets::life() { common_objects() } else { Vec::new() };
+		let (next, stop) = (AtomicUsize::new(0), AtomicBool::new(false));
+		let (sender, receiver) = mpsc::channel();
+		let mut census = Census::default();
+
+		// soups get handed out one at a time, and the progress callback runs on this thread while the others work through them
+		thread::scope(|scope| {
+			for _ in 0..self.threads.min(soups) {
+				let (sender, next, stop, known) = (sender.clone(), &next, &stop, &known);
+
+				scope.spawn(move || loop {
+					let soup = next.fetch_add(1, Ordering::Relaxed);
+					if soup >= soups || stop.load(Ordering::Relaxed) { break; }
+					if sender.send(self.run_soup(Rng::keyed(seed, &[soup as u64]), known, stop)).is_err() { break; }
+				});
+			}
+
+			drop(sender);
+
+			for objects in receiver {
+				census.soups += 1;
+
+				match objects {
+					Some(objects) => for name in objects { *census.objects.entry(name).or_insert(0) += 1; },
+					None => census.unstable += 1
+				}
+
+				if progress(Progress::new(census.soups, soups)).is_break() {
+					stop.store(true, Ordering::Relaxed);
+					break;
+				}
+			}
+		});
+
+		census
+	}
+
+	/// Runs a single soup until it settles down, giving back the name of every object left over, or `None` if it never settled down.
+	/// It gives up early if the search gets stopped, since nobody's waiting on the answer anymore.
+	fn run_soup(&self, mut rng: Rng, known: &[(&str, u64)], stop: &AtomicBool) -> Option<Vec<String>> {
+		let margin = self.max_ticks + 2;
+		let bounds = Vec2::new(self.soup_size.x() + margin * 2, self.soup_size.y() + margin * 2);
+		let seed = flat::random_seed(self.soup_size, self.density, &mut rng).into_iter().map(|v| v + Vec2::new(margin, margin)).collect();
+
+		let mut soup = Automaton::with_backend(self.rules.clone(), bounds, seed, Backend::Tree).ok()?;
+		let whole = Rect2::new(Vec2::new(0, 0), bounds);
+		let mut populations = vec![soup.live_cells_in(&whole).len()];
+
+		while !settled(&populations) {
+			if populations.len() > self.max_ticks || stop.load(Ordering::Relaxed) { return None; }
+			soup.tick();
+			populations.push(soup.live_cells_in(&whole).len());
+		}
+
+		Some(clusters(soup.live_cells_in(&whole)).into_iter().map(|cells| self.name(cells, known)).collect())
+	}
+
+	/// Works out the name of a single object, using the name it's known by if it's in `known`.
+	fn name(&self, cells: Vec<(Vec2, u8)>, known: &[(&str, u64)]) -> String {
+		let min = Vec2::new(cells.iter().map(|(v, _)| v.x()).min().unwrap_or(0), cells.iter().map(|(v, _)| v.y()).min().unwrap_or(0));
+		let room = MAX_PERIOD * 2 + 2;
+
+		// give the object room to move around in, so spaceships don't crash into anything while their period is worked out
+		let placed = cells.iter().map(|(v, s)| (*v - min + Vec2::new(room, room), *s)).collect::<Vec<(Vec2, u8)>>();
+		let size = placed.iter().fold(Vec2::new(0, 0), |size, (v, _)| Vec2::new(size.x().max(v.x() + room + 1), size.y().max(v.y() + room + 1)));
+
+		let mut object = Automaton::with_backend(self.rules.clone(), size, Vec::new(), Backend::Tree).expect("the rules were already checked when the soup was made");
+		Pattern::with_states("", placed).stamp_into(&mut object, Vec2::new(0, 0));
+
+		let population = cells.len();
+		let (prefix, period) = match detect_period(&mut object, MAX_PERIOD * 2) {
+			Some(Behavior::StillLife) => ("xs", 1),
+			Some(Behavior::Oscillator { period }) => ("xp", period),
+			Some(Behavior::Spaceship { period, .. }) => ("xq", period),
+			None => return format!("zz{}", population)
+		};
+
+		// the same object can turn up in any phase, rotation, or reflection, so go with whichever of those hashes the lowest
+		// (detecting the period leaves the object in its cycle, so any sparks it started out with are gone by now)
+		let mut code = u64::MAX;
+		for _ in 0..period {
+			code = code.min(canonical_hash(&object.live_cells_in(&Rect2::new(Vec2::new(0, 0), size)).into_iter().collect::<Vec<(Vec2, u8)>>()));
+			object.tick();
+		}
+
+		if let Some((name, _)) = known.iter().find(|(_, c)| *c == code) { return name.to_string(); }
+
+		match prefix {
+			"xs" => format!("xs{}_{:016x}", population, code),
+			_ => format!("{}{}_{:016x}", prefix, period, code)
+		}
+	}
+}
+
+impl Census {
+	/// Get how many soups were run.
+	pub fn soups(&self) -> usize {
+		self.soups
+	}
+
+	/// Get how many soups never settled down in time, which weren't included in the census.
+	pub fn unstable(&self) -> usize {
+		self.unstable
+	}
+
+	/// Get how many times the named object turned up.
+	pub fn count(&self, name: &str) -> usize {
+		self.objects.get(name).copied().unwrap_or(0)
+	}
+
+	/// Get how many times every object turned up.
+	pub fn objects(&self) -> &HashMap<String, usize> {
+		&self.objects
+	}
+
+	/// Get every object along with how many times it turned up, most common first. Ties are sorted by name.
+	pub fn most_common(&self) -> Vec<(String, usize)> {
+		let mut objects = self.objects.iter().map(|(name, count)| (name.clone(), *count)).collect::<Vec<(String, usize)>>();
+		objects.sort_by(|(a_name, a), (b_name, b)| b.cmp(a).then(a_name.cmp(b_name)));
+		objects
+	}
+
+	/// Adds another census into this one, so searches split up between threads or machines can be put back together.
+	pub fn merge(&mut self, other: &Census) {
+		self.soups += other.soups;
+		self.unstable += other.unstable;
+		for (name, count) in &other.objects { *self.objects.entry(name.clone()).or_insert(0) += count; }
+	}
+}
+
+/// Checks whether a soup's population has been going around the same short cycle for a while.
+/// Gliders flying off don't change the population, so this still works for soups that shoot out spaceships.
+fn settled(populations: &[usize]) -> bool {
+	let t = populations.len();
+	(1..=MAX_PERIOD).any(|p| t >= WINDOW + p && (1..=WINDOW).all(|i| populations[t - i] == populations[t - i - p]))
+}
+
+/// Splits live cells up into separate objects, where cells within two spaces of each other count as part of the same object.
+fn clusters(live: HashMap<Vec2, u8>) -> Vec<Vec<(Vec2, u8)>> {
+	let mut left: HashSet<Vec2> = live.keys().copied().collect();
+	let mut clusters = Vec::new();
+
+	while let Some(start) = left.iter().min().copied() {
+		left.remove(&start);
+		let (mut cluster, mut stack) = (Vec::new(), vec![start]);
+
+		while let Some(v) = stack.pop() {
+			cluster.push((v, live[&v]));
+
+			for dy in -2..=2i64 {
+				for dx in -2..=2i64 {
+					let Some(n) = v.checked_add_offset(&flat::IVec2::new(dx, dy)) else { continue };
+					if left.remove(&n) { stack.push(n); }
+				}
+			}
+		}
+
+		clusters.push(cluster);
+	}
+
+	clusters
+}
+
+/// Moves a point to where it ends up after some rotation or reflection.
+type Turn = fn(i64, i64) -> (i64, i64);
+
+/// Hashes a set of cells the same way in all 8 of its rotations and reflections, and wherever it is.
+fn canonical_hash(cells: &[(Vec2, u8)]) -> u64 {
+	let points = cells.iter().map(|(v, s)| (v.x() as i64, v.y() as i64, *s)).collect::<Vec<(i64, i64, u8)>>();
+	let orientations: [Turn; 8] = [
+		|x, y| (x, y), |x, y| (-y, x), |x, y| (-x, -y), |x, y| (y, -x),
+		|x, y| (-x, y), |x, y| (y, x), |x, y| (x, -y), |x, y| (-y, -x)
+	];
+
+	orientations.iter().map(|turn| {
+		let turned = points.iter().map(|(x, y, s)| { let (x, y) = turn(*x, *y); (x, y, *s) }).collect::<Vec<(i64, i64, u8)>>();
+		let (min_x, min_y) = (turned.iter().map(|p| p.0).min().unwrap_or(0), turned.iter().map(|p| p.1).min().unwrap_or(0));
+		hash_cells(turned.into_iter().map(|(x, y, s)| ([(x - min_x) as u64, (y - min_y) as u64], s)))
+	}).min().unwrap_or(0)
+}
+
+/// The most common objects in Life's ash, along with their canonical hashes.
+fn common_objects() -> Vec<(&'static str, u64)> {
+	let shapes: [(&str, &[(usize, usize)]); 8] = [
+		("block", &[(0, 0), (1, 0), (0, 1), (1, 1)]),
+		("blinker", &[(0, 0), (1, 0), (2, 0)]),
+		("beehive", &[(1, 0), (2, 0), (0, 1), (3, 1), (1, 2), (2, 2)]),
+		("loaf", &[(1, 0), (2, 0), (0, 1), (3, 1), (1, 2), (3, 2), (2, 3)]),
+		("boat", &[(0, 0), (1, 0), (0, 1), (2, 1), (1, 2)]),
+		("ship", &[(0, 0), (1, 0), (0, 1), (2, 1), (1, 2), (2, 2)]),
+		("tub", &[(1, 0), (0, 1), (2, 1), (1, 2)]),
+		("pond", &[(1, 0), (2, 0), (0, 1), (3, 1), (0, 2), (3, 2), (1, 3), (2, 3)])
+	];
+
+	let moving = [("glider", presets::glider().positions()), ("lwss", presets::lwss().positions())];
+
+	// blinkers and spaceships change shape as they go, so their codes come from every phase just like the objects they're compared against
+	shapes.iter().map(|(name, cells)| (*name, cells.iter().map(|&(x, y)| Vec2::new(x, y)).collect::<Vec<Vec2>>()))
+		.chain(moving)
+		.map(|(name, cells)| {
+			let room = MAX_PERIOD * 2 + 2;
+			let size = Vec2::new(room * 2 + 5, room * 2 + 5);
+			let mut object = Automaton::with_backend(presets::life(), size, cells.iter().map(|v| *v + Vec2::new(room, room)).collect(), Backend::Tree).expect("life fits any grid");
+			let code = (0..4).map(|_| {
+				let hash = canonical_hash(&object.live_cells_in(&Rect2::new(Vec2::new(0, 0), size)).into_iter().collect::<Vec<(Vec2, u8)>>());
+				object.tick();
+				hash
+			}).min().unwrap_or(0);
+
+			(name, code)
+		})
+		.collect()
+}
@@ -0,0 +1,173 @@
+//! This module keeps track of how an automaton changes over time, one generation at a time, so you can plot it somewhere else.
+//! 
+//! Record a generation after every tick, then dump everything out as CSV or JSON.
+//! 
+//! ```
+//! # use cellular_snapp::{presets, stats::StatsRecorder, flat::{Automaton, Vec2}};
+//! let mut life = Automaton::new(presets::life(), Vec2::new(10, 10), vec![Vec2::new(4, 5), Vec2::new(5, 5), Vec2::new(6, 5)]).unwrap();
+//! let mut stats = StatsRecorder::new();
+//! 
+//! stats.record(&life);
+//! life.tick();
+//! stats.record(&life);
+//! 
+//! // the blinker turned on its side, so two cells were born and two died
+//! assert_eq!(stats.to_csv(), "generation,population,births,deaths,density\n0,3,0,0,0.03\n1,3,2,2,0.03\n");
+//! ```
+
+//--> Imports <--
+
+use crate::{flat, deep};
+use std::collections::HashSet;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+//--> Structs <--
+
+/// The numbers for a single generation.
+#[derive(Clone, PartialEq, Debug)]
+pub struct GenerationStats {
+	generation: usize,
+	population: usize,
+	births: usize,
+	deaths: usize,
+	density: f64
+}
+
+/// Collects the numbers for every generation it's shown.
+#[derive(Clone, Default)]
+pub struct StatsRecorder {
+	generations: Vec<GenerationStats>,
+	last: Option<HashSet<[usize; 3]>>,
+	seen: usize,
+	sample_every: usize
+}
+
+//--> Functions <--
+
+impl GenerationStats {
+	/// Get which generation this is, counting from 0 for the first one the recorder was shown.
+	pub fn generation(&self) -> usize {
+		self.generation
+	}
+
+	/// Get how many cells were live or dying.
+	pub fn population(&self) -> usize {
+		self.population
+	}
+
+	/// Get how many cells came to life since the generation before. The first generation recorded never has any.
+	pub fn births(&self) -> usize {
+		self.births
+	}
+
+	/// Get how many cells died since the generation before. Dying cells don't count until they're fully dead.
+	pub fn deaths(&self) -> usize {
+		self.deaths
+	}
+
+	/// Get how much of the grid was live or dying, from 0 to 1.
+	pub fn density(&self) -> f64 {
+		self.density
+	}
+}
+
+impl StatsRecorder {
+	/// Creates a recorder that hasn't seen any generations yet.
+	pub fn new() -> StatsRecorder {
+		StatsRecorder::default()
+	}
+
+	/// Only keeps every `n`th generation it's shown, starting with the first one, so it can be shown every generation without keeping them all.
+	/// Generations are still numbered by how many were shown, and births and deaths are still counted since the generation just before.
+	/// 
+	/// ```
+	/// # use cellular_snapp::{presets, stats::StatsRecorder, flat::{Automaton, Vec2}};
+	/// let mut life = Automaton::new(presets::life(), Vec2::new(16, 16), presets::glider().positions()).unwrap();
+	/// let mut stats = StatsRecorder::new().sample_every(10);
+	/// 
+	/// for _ in 0..25 {
+	///     stats.record(&life);
+	///     life.tick();
+	/// }
+	/// 
+	/// let kept: Vec<usize> = stats.generations().iter().map(|g| g.generation()).collect();
+	/// assert_eq!(kept, vec![0, 10, 20]);
+	/// ```
+	pub fn sample_every(mut self, n: usize) -> StatsRecorder {
+		self.sample_every = n.max(1);
+		self
+	}
+
+	/// Records the current generation of a flat automaton.
+	pub fn record(&mut self, automaton: &flat::Automaton) {
+		let bounds = automaton.get_bounds();
+		let live = automaton.live_cells_in(&flat::Rect2::new(flat::Vec2::new(0, 0), *bounds)).into_keys().map(|v| [v.x(), v.y(), 0]).collect();
+		self.push(live, bounds.x() * bounds.y());
+	}
+
+	/// Records the current generation of a deep automaton.
+	pub fn record_deep(&mut self, automaton: &deep::Automaton) {
+		let bounds = automaton.get_bounds();
+		let live = automaton.live_cells_in(&deep::Box3::new(deep::Vec3::new(0, 0, 0), *bounds)).into_keys().map(|v| [v.x(), v.y(), v.z()]).collect();
+		self.push(live, bounds.x() * bounds.y() * bounds.z());
+	}
+
+	/// Works out the numbers for a generation from its live cells and how many cells the grid has.
+	fn push(&mut self, live: HashSet<[usize; 3]>, cells: usize) {
+		let (births, deaths) = match &self.last {
+			Some(last) => (live.difference(last).count(), last.difference(&live).count()),
+			None => (0, 0)
+		};
+
+		let generation = self.seen;
+		self.seen += 1;
+
+		if !generation.is_multiple_of(self.sample_every.max(1)) {
+			self.last = Some(live);
+			return;
+		}
+
+		self.generations.push(GenerationStats {
+			generation,
+			population: live.len(),
+			births,
+			deaths,
+			density: if cells == 0 { 0.0 } else { live.len() as f64 / cells as f64 }
+		});
+
+		self.last = Some(live);
+	}
+
+	/// Get the numbers for every generation recorded so far.
+	pub fn generations(&self) -> &[GenerationStats] {
+		&self.generations
+	}
+
+	/// Writes every generation out as CSV, with a header row and one row per generation.
+	pub fn to_csv(&self) -> String {
+		let mut csv = String::from("generation,population,births,deaths,density\n");
+
+		for g in &self.generations {
+			csv.push_str(&format!("{},{},{},{},{}\n", g.generation, g.population, g.births, g.deaths, g.density));
+		}
+
+		csv
+	}
+
+	/// Writes every generation out as CSV into a file, replacing it if it's already there.
+	pub fn write_csv(&self, path: impl AsRef<Path>) -> io::Result<()> {
+		fs::write(path, self.to_csv())
+	}
+
+	/// Writes every generation out as a JSON array, with one object per generation.
+	pub fn to_json(&self) -> String {
+		let rows = self.generations.iter().map(|g| format!(
+			"{{\"generation\":{},\"population\":{},\"births\":{},\"deaths\":{},\"density\":{}}}",
+			g.generation, g.population, g.births, g.deaths, g.density
+		)).collect::<Vec<String>>();
+
+		format!("[{}]", rows.join(","))
+	}
+}
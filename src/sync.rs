@@ -0,0 +1,264 @@
+//! This module keeps copies of an automaton on different machines in sync, with one side (like a server) ticking the real one and sending out what changed.
+//! 
+//! A `DeltaEncoder` turns each generation into a delta holding only the cells that changed since the delta before it, and a `DeltaDecoder` applies them to a copy.
+//! Every delta has a sequence number, so a copy that missed one finds out straight away instead of quietly drifting off. A snapshot holds every live and dying cell,
+//! for bringing new copies (or ones that missed something) up to date.
+//! 
+//! ```
+//! # use cellular_snapp::{presets, AutomataError, sync::{DeltaDecoder, DeltaEncoder}, flat::{Automaton, Vec2}};
+//! let mut server = Automaton::new(presets::life(), Vec2::new(32, 32), presets::r_pentomino().translate(Vec2::new(14, 14)).positions()).unwrap();
+//! let mut client = Automaton::new(presets::life(), Vec2::new(32, 32), Vec::new()).unwrap();
+//! let (mut encoder, mut decoder) = (DeltaEncoder::new(), DeltaDecoder::new());
+//! 
+//! for _ in 0..10 {
+//!     decoder.apply_delta(&mut client, &encoder.encode_delta(&server)).unwrap();
+//!     server.tick();
+//! }
+//! 
+//! decoder.apply_delta(&mut client, &encoder.encode_delta(&server)).unwrap();
+//! assert_eq!(client, server);
+//! assert_eq!(client.get_generation(), 10);
+//! 
+//! // a delta that gets lost along the way is caught, and a snapshot catches the client back up
+//! server.tick();
+//! encoder.encode_delta(&server);
+//! server.tick();
+//! assert_eq!(decoder.apply_delta(&mut client, &encoder.encode_delta(&server)), Err(AutomataError::MissedDelta(11, 12)));
+//! 
+//! decoder.apply_delta(&mut client, &encoder.encode_snapshot(&server)).unwrap();
+//! assert_eq!(client, server);
+//! ```
+//! 
+//! Every message starts with a version byte and a byte saying whether it's a delta (0) or a snapshot (1), followed by the sequence number and the generation.
+//! Snapshots then have the bounds of the grid (flat grids are 1 cell deep). After that comes a count of cells, followed by each cell's index in the grid
+//! (counting row by row, and then layer by layer) as a gap from the one before it, and its state. Everything but the first two bytes and the states is a LEB128 varint.
+
+//--> Imports <--
+
+use crate::{AutomataError, flat, deep};
+use crate::checkpoint::write_varint;
+use std::collections::HashMap;
+
+//--> Structs <--
+
+/// Makes deltas and snapshots out of the automaton being synced, one generation at a time.
+#[derive(Clone, Debug, Default)]
+pub struct DeltaEncoder {
+	bounds: Option<[usize; 3]>,
+	last: HashMap<[usize; 3], u8>,
+	sequence: u64
+}
+
+/// Applies deltas and snapshots from a `DeltaEncoder` to a copy of the automaton, keeping track of which delta should come next.
+#[derive(Clone, Debug, Default)]
+pub struct DeltaDecoder {
+	sequence: u64
+}
+
+/// A delta or snapshot that's been read in, but not applied yet.
+struct Message {
+	snapshot: bool,
+	/// The sequence number of the delta that should come after this one.
+	next: u64,
+	generation: u64,
+	cells: Vec<([usize; 3], u8)>
+}
+
+//--> Functions <--
+
+const VERSION: u8 = 1;
+const DELTA: u8 = 0;
+const SNAPSHOT: u8 = 1;
+
+impl DeltaEncoder {
+	/// Creates an encoder that hasn't sent anything yet, so its first delta holds every live and dying cell.
+	pub fn new() -> DeltaEncoder {
+		DeltaEncoder::default()
+	}
+
+	/// Makes a delta with every cell of a flat automaton that changed since the last delta.
+	/// If the bounds changed (like after a resize), this makes a snapshot instead.
+	pub fn encode_delta(&mut self, automaton: &flat::Automaton) -> Vec<u8> {
+		let (bounds, live) = flat_cells(automaton);
+		self.delta(bounds, automaton.get_generation(), live)
+	}
+
+	/// Makes a delta with every cell of a deep automaton that changed since the last delta, just like `encode_delta`.
+	pub fn encode_delta_deep(&mut self, automaton: &deep::Automaton) -> Vec<u8> {
+		let (bounds, live) = deep_cells(automaton);
+		self.delta(bounds, automaton.get_generation(), live)
+	}
+
+	/// Makes a snapshot of every live and dying cell of a flat automaton, which brings any copy up to date no matter what it missed.
+	/// Snapshots get a sequence number just like deltas do, and the deltas made afterwards carry on from them.
+	pub fn encode_snapshot(&mut self, automaton: &flat::Automaton) -> Vec<u8> {
+		let (bounds, live) = flat_cells(automaton);
+		self.last.clear();
+		self.bounds = None;
+		self.delta(bounds, automaton.get_generation(), live)
+	}
+
+	/// Makes a snapshot of every live and dying cell of a deep automaton, just like `encode_snapshot`.
+	pub fn encode_snapshot_deep(&mut self, automaton: &deep::Automaton) -> Vec<u8> {
+		let (bounds, live) = deep_cells(automaton);
+		self.last.clear();
+		self.bounds = None;
+		self.delta(bounds, automaton.get_generation(), live)
+	}
+
+	/// Get the sequence number the next delta will have, which is how many deltas and snapshots have been made so far.
+	pub fn sequence(&self) -> u64 {
+		self.sequence
+	}
+
+	/// Makes the next delta from the live cells of the grid.
+	fn delta(&mut self, bounds: [usize; 3], generation: u64, live: HashMap<[usize; 3], u8>) -> Vec<u8> {
+		let kind = if self.bounds == Some(bounds) { DELTA } else { SNAPSHOT };
+		if kind == SNAPSHOT { self.last.clear(); }
+
+		// cells that died show up as changes to state 0
+		let died = self.last.keys().filter(|v| !live.contains_key(*v)).map(|v| (*v, 0));
+		let changed = live.iter().filter(|(v, s)| self.last.get(*v) != Some(s)).map(|(v, s)| (*v, *s));
+		let bytes = encode(kind, self.sequence, generation, bounds, died.chain(changed).collect());
+
+		self.bounds = Some(bounds);
+		self.last = live;
+		self.sequence += 1;
+		bytes
+	}
+}
+
+impl DeltaDecoder {
+	/// Creates a decoder expecting the very first delta an encoder makes.
+	pub fn new() -> DeltaDecoder {
+		DeltaDecoder::default()
+	}
+
+	/// Applies a delta or snapshot to a flat automaton, setting its cells and its generation to match the one it came from.
+	/// 
+	/// Deltas have to come in order. One that doesn't (because the one before it got lost, say) gives back a `MissedDelta` error, and a snapshot will be needed to catch up.
+	/// Bytes that aren't a delta, or one for a grid with different bounds or fewer cell states, give back an `InvalidDelta` error, and so does one with a sequence number too big to have one after it.
+	/// Either way, the automaton is left as it was.
+	pub fn apply_delta(&mut self, automaton: &mut flat::Automaton, bytes: &[u8]) -> Result<(), AutomataError> {
+		let bounds = *automaton.get_bounds();
+		let message = self.read(bytes, [bounds.x(), bounds.y(), 1], automaton.get_rules().states())?;
+
+		if message.snapshot {
+			for v in automaton.live_cells_in(&flat::Rect2::new(flat::Vec2::new(0, 0), bounds)).into_keys() { automaton.set_cell(v, 0); }
+		}
+
+		for ([x, y, _], s) in message.cells { automaton.set_cell(flat::Vec2::new(x, y), s); }
+		automaton.set_generation(message.generation);
+		self.sequence = message.next;
+		Ok(())
+	}
+
+	/// Applies a delta or snapshot to a deep automaton, just like `apply_delta`.
+	pub fn apply_delta_deep(&mut self, automaton: &mut deep::Automaton, bytes: &[u8]) -> Result<(), AutomataError> {
+		let bounds = *automaton.get_bounds();
+		let message = self.read(bytes, [bounds.x(), bounds.y(), bounds.z()], automaton.get_rules().states())?;
+
+		if message.snapshot {
+			for v in automaton.live_cells_in(&deep::Box3::new(deep::Vec3::new(0, 0, 0), bounds)).into_keys() { automaton.set_cell(v, 0); }
+		}
+
+		for ([x, y, z], s) in message.cells { automaton.set_cell(deep::Vec3::new(x, y, z), s); }
+		automaton.set_generation(message.generation);
+		self.sequence = message.next;
+		Ok(())
+	}
+
+	/// Get the sequence number of the delta that should come next.
+	pub fn sequence(&self) -> u64 {
+		self.sequence
+	}
+
+	/// Reads a message in, checking it fits a grid with the given bounds and amount of cell states, and that it's the next one if it's a delta.
+	fn read(&self, mut bytes: &[u8], bounds: [usize; 3], states: u8) -> Result<Message, AutomataError> {
+		let bytes = &mut bytes;
+		let [version, kind] = match bytes {
+			[version, kind, ..] => [*version, *kind],
+			_ => return Err(invalid("it's cut short"))
+		};
+
+		*bytes = &bytes[2..];
+		if version != VERSION { return Err(invalid(&format!("version {} isn't supported", version))); }
+		if kind != DELTA && kind != SNAPSHOT { return Err(invalid(&format!("unknown message kind {}", kind))); }
+
+		let (sequence, generation) = (varint(bytes)?, varint(bytes)?);
+		if kind == DELTA && sequence != self.sequence { return Err(AutomataError::MissedDelta(self.sequence, sequence)); }
+		let next = sequence.checked_add(1).ok_or_else(|| invalid("its sequence number is too big for anything to come after it"))?;
+
+		if kind == SNAPSHOT {
+			let theirs = [varint(bytes)?, varint(bytes)?, varint(bytes)?];
+			if theirs != bounds.map(|b| b as u64) { return Err(invalid(&format!("it's for a {}x{}x{} grid", theirs[0], theirs[1], theirs[2]))); }
+		}
+
+		let [width, height, depth] = bounds.map(|b| b as u64);
+		let mut cells = Vec::new();
+		let mut index: u64 = 0;
+
+		for _ in 0..varint(bytes)? {
+			index = index.checked_add(varint(bytes)?).ok_or_else(|| invalid("a cell is outside the grid"))?;
+			if index >= width * height * depth { return Err(invalid("a cell is outside the grid")); }
+
+			let state = match bytes.split_first() {
+				Some((state, rest)) => { *bytes = rest; *state },
+				None => return Err(invalid("it's cut short"))
+			};
+
+			if state >= states { return Err(invalid(&format!("state {} is too big for the rules", state))); }
+			cells.push(([(index % width) as usize, (index / width % height) as usize, (index / (width * height)) as usize], state));
+		}
+
+		Ok(Message { snapshot: kind == SNAPSHOT, next, generation, cells })
+	}
+}
+
+/// Get the bounds and live cells of a flat automaton, with every cell 1 deep.
+fn flat_cells(automaton: &flat::Automaton) -> ([usize; 3], HashMap<[usize; 3], u8>) {
+	let bounds = automaton.get_bounds();
+	let live = automaton.live_cells_in(&flat::Rect2::new(flat::Vec2::new(0, 0), *bounds)).into_iter().map(|(v, s)| ([v.x(), v.y(), 0], s)).collect();
+	([bounds.x(), bounds.y(), 1], live)
+}
+
+/// Get the bounds and live cells of a deep automaton.
+fn deep_cells(automaton: &deep::Automaton) -> ([usize; 3], HashMap<[usize; 3], u8>) {
+	let bounds = automaton.get_bounds();
+	let live = automaton.live_cells_in(&deep::Box3::new(deep::Vec3::new(0, 0, 0), *bounds)).into_iter().map(|(v, s)| ([v.x(), v.y(), v.z()], s)).collect();
+	([bounds.x(), bounds.y(), bounds.z()], live)
+}
+
+/// Writes a message out. Snapshots get the bounds written in too.
+fn encode(kind: u8, sequence: u64, generation: u64, bounds: [usize; 3], cells: Vec<([usize; 3], u8)>) -> Vec<u8> {
+	let index = |[x, y, z]: [usize; 3]| ((z as u64 * bounds[1] as u64) + y as u64) * bounds[0] as u64 + x as u64;
+	let mut cells = cells.into_iter().map(|(v, s)| (index(v), s)).collect::<Vec<(u64, u8)>>();
+	cells.sort_unstable();
+
+	// writing into a vector never fails
+	let mut bytes = vec![VERSION, kind];
+	let _ = write_varint(&mut bytes, sequence);
+	let _ = write_varint(&mut bytes, generation);
+	if kind == SNAPSHOT { for b in bounds { let _ = write_varint(&mut bytes, b as u64); } }
+	let _ = write_varint(&mut bytes, cells.len() as u64);
+
+	let mut last = 0;
+
+	for (i, s) in cells {
+		let _ = write_varint(&mut bytes, i - last);
+		bytes.push(s);
+		last = i;
+	}
+
+	bytes
+}
+
+/// Reads a varint off the front of the bytes.
+fn varint(bytes: &mut &[u8]) -> Result<u64, AutomataError> {
+	crate::checkpoint::read_varint(bytes).map_err(|_| invalid("it's cut short"))
+}
+
+/// Makes an error for a delta that doesn't make sense.
+fn invalid(reason: &str) -> AutomataError {
+	AutomataError::InvalidDelta(reason.to_string())
+}
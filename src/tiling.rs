@@ -0,0 +1,209 @@
+//! This module splits huge 3D grids up into tiles, so each tile can be ticked by a different process (or machine) and only the cells along their faces need sending back and forth.
+//! 
+//! Every tile is a box of the whole grid with a layer of ghost cells wrapped around it, as thick as the furthest a neighbor can be.
+//! Before each tick, the cells just inside every face (from `boundary_slice`) get sent to the tile on the other side of that face, which puts them into its ghost cells (with `set_ghost_slice`).
+//! Then every tile ticks on its own, and comes out exactly the same as the whole grid would have. Getting the slices from one tile to another is up to you, whether that's MPI, sockets, or just a loop.
+//! 
+//! ```
+//! # use cellular_snapp::{AutomataRules, Method, tiling::{Face, Tile}, deep::{Automaton, Vec3}};
+//! let rules = AutomataRules::new(4..=5, 5, 2, Method::Moore);
+//! let seed: Vec<Vec3> = (0..60).map(|i| Vec3::new(6 + i % 5, 2 + i / 5 % 4, 2 + i / 20)).filter(|v| (v.x() + v.y() + v.z()) % 3 != 0).collect();
+//! 
+//! // one 16x8x8 grid, and the same grid split down the middle into two tiles
+//! let mut whole = Automaton::new(rules.clone(), Vec3::new(16, 8, 8), seed.clone()).unwrap();
+//! let mut left = Tile::new(rules.clone(), Vec3::new(8, 8, 8), seed.iter().filter(|v| v.x() < 8).copied().collect()).unwrap();
+//! let mut right = Tile::new(rules, Vec3::new(8, 8, 8), seed.iter().filter(|v| v.x() >= 8).map(|v| Vec3::new(v.x() - 8, v.y(), v.z())).collect()).unwrap();
+//! 
+//! for _ in 0..10 {
+//!     let (to_right, to_left) = (left.boundary_slice(Face::MaxX), right.boundary_slice(Face::MinX));
+//!     right.set_ghost_slice(Face::MinX, &to_right).unwrap();
+//!     left.set_ghost_slice(Face::MaxX, &to_left).unwrap();
+//! 
+//!     whole.tick();
+//!     left.tick();
+//!     right.tick();
+//! }
+//! 
+//! for v in (0..16 * 8 * 8).map(|i| Vec3::new(i % 16, i / 16 % 8, i / 128)) {
+//!     let tile = if v.x() < 8 { left.get_cell(&v) } else { right.get_cell(&Vec3::new(v.x() - 8, v.y(), v.z())) };
+//!     assert_eq!(tile, whole.get_cell(&v));
+//! }
+//! ```
+//! 
+//! Slices hold a state for every cell in the layer, in the order they're laid out row by row, and then layer by layer.
+//! They cover the ghost cells on the other two axes too, so when tiles touch along more than one axis, swapping every X face, then every Y face, then every Z face
+//! (in that order) carries the cells along the edges and the corners over to the diagonal neighbors as well.
+//! 
+//! Ghost cells die every tick, so faces without a tile on the other side just act like the edge of the grid.
+//! Random rolls are tied to each cell's position inside its tile, so stochastic rules won't tick the same way they would on the whole grid.
+
+//--> Imports <--
+
+use crate::{AutomataError, AutomataRules, Backend};
+use crate::deep::{Automaton, Box3, Vec3};
+use std::collections::HashMap;
+
+//--> Structs <--
+
+/// A box of a bigger 3D grid, with a layer of ghost cells around it that hold copies of the cells just past its faces.
+#[derive(Clone)]
+pub struct Tile {
+	automaton: Automaton,
+	size: Vec3,
+	halo: usize
+}
+
+//--> Enums <--
+
+/// One of the six faces of a tile.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Face {
+	/// The face where x is 0.
+	MinX,
+	/// The face where x is as big as it gets.
+	MaxX,
+	/// The face where y is 0.
+	MinY,
+	/// The face where y is as big as it gets.
+	MaxY,
+	/// The face where z is 0.
+	MinZ,
+	/// The face where z is as big as it gets.
+	MaxZ
+}
+
+//--> Functions <--
+
+impl Tile {
+	/// Creates a new tile with the given rules, size (not counting ghost cells), and starting cells, which are positioned from the tile's own corner.
+	/// This fails for the same reasons `Automaton::new` does.
+	pub fn new(rules: AutomataRules, size: Vec3, start_cells: Vec<Vec3>) -> Result<Tile, AutomataError> {
+		Tile::with_backend(rules, size, start_cells, Backend::HashMap)
+	}
+
+	/// Creates a new tile just like `new`, except the cells are kept in the given backend.
+	pub fn with_backend(rules: AutomataRules, size: Vec3, start_cells: Vec<Vec3>, backend: Backend) -> Result<Tile, AutomataError> {
+		let halo = rules.method().reach() as usize;
+		let padded = Vec3::new(size.x() + 2 * halo, size.y() + 2 * halo, size.z() + 2 * halo);
+
+		let start_cells = start_cells.into_iter()
+			.filter(|v| v.x() < size.x() && v.y() < size.y() && v.z() < size.z())
+			.map(|v| Vec3::new(v.x() + halo, v.y() + halo, v.z() + halo))
+			.collect();
+
+		Ok(Tile { automaton: Automaton::with_backend(rules, padded, start_cells, backend)?, size, halo })
+	}
+
+	/// Sets how many threads each tick gets split up between, just like `Automaton::threads`.
+	pub fn threads(mut self, threads: usize) -> Tile {
+		self.automaton = self.automaton.threads(threads);
+		self
+	}
+
+	/// Advances the tile by one tick, using whatever is in the ghost cells for the neighbors past each face, and then kills every ghost cell.
+	pub fn tick(&mut self) {
+		self.automaton.tick();
+
+		for face in [Face::MinX, Face::MaxX, Face::MinY, Face::MaxY, Face::MinZ, Face::MaxZ] {
+			for v in self.automaton.live_cells_in(&self.layer(face, true)).into_keys() { self.automaton.set_cell(v, 0); }
+		}
+	}
+
+	/// Get the state of every cell in the layer just inside a face, ready to be sent to the tile on the other side of it.
+	/// The layer is as thick as the ghost cells, and covers the ghost cells along the other two axes.
+	pub fn boundary_slice(&self, face: Face) -> Vec<u8> {
+		cells_of(&self.layer(face, false)).map(|v| self.automaton.get_cell(&v).unwrap_or(0)).collect()
+	}
+
+	/// Fills in the ghost cells past a face with a slice from the tile on the other side of it, in the same layout `boundary_slice` gives.
+	/// This gives back a `WrongSliceLength` error with how long the slice should be if it doesn't fit, without changing anything.
+	pub fn set_ghost_slice(&mut self, face: Face, slice: &[u8]) -> Result<(), AutomataError> {
+		let layer = self.layer(face, true);
+		let cells: Vec<Vec3> = cells_of(&layer).collect();
+		if cells.len() != slice.len() { return Err(AutomataError::WrongSliceLength(cells.len())); }
+
+		for (v, state) in cells.into_iter().zip(slice) { self.automaton.set_cell(v, *state); }
+		Ok(())
+	}
+
+	/// Get the state of a cell inside the tile (not counting ghost cells), positioned from the tile's own corner, or `None` if it's outside.
+	pub fn get_cell(&self, v: &Vec3) -> Option<u8> {
+		self.contains(v).then(|| self.automaton.get_cell(&self.padded(v))).flatten()
+	}
+
+	/// Sets the state of a cell inside the tile, positioned from the tile's own corner. Cells outside the tile are ignored.
+	pub fn set_cell(&mut self, v: Vec3, state: u8) {
+		if self.contains(&v) { self.automaton.set_cell(self.padded(&v), state); }
+	}
+
+	/// Get every live or dying cell inside the tile, positioned from the tile's own corner.
+	pub fn live_cells(&self) -> HashMap<Vec3, u8> {
+		let h = self.halo;
+		let inside = Box3::new(Vec3::new(h, h, h), Vec3::new(self.size.x() + h, self.size.y() + h, self.size.z() + h));
+		self.automaton.live_cells_in(&inside).into_iter().map(|(v, s)| (Vec3::new(v.x() - h, v.y() - h, v.z() - h), s)).collect()
+	}
+
+	/// Get the size of the tile, not counting ghost cells.
+	pub fn size(&self) -> &Vec3 {
+		&self.size
+	}
+
+	/// Get how thick the layer of ghost cells is, which is the furthest a neighbor can be along any one axis.
+	pub fn halo(&self) -> usize {
+		self.halo
+	}
+
+	/// Get the automaton the tile ticks, which has the ghost cells around the outside, so the tile's own cells start `halo` cells in from every corner.
+	pub fn automaton(&self) -> &Automaton {
+		&self.automaton
+	}
+
+	/// Checks whether a cell is inside the tile.
+	fn contains(&self, v: &Vec3) -> bool {
+		v.x() < self.size.x() && v.y() < self.size.y() && v.z() < self.size.z()
+	}
+
+	/// Get where a cell of the tile is in the automaton, past the ghost cells.
+	fn padded(&self, v: &Vec3) -> Vec3 {
+		Vec3::new(v.x() + self.halo, v.y() + self.halo, v.z() + self.halo)
+	}
+
+	/// Get the layer of ghost cells past a face, or the layer of the tile's own cells just inside it.
+	fn layer(&self, face: Face, ghost: bool) -> Box3 {
+		let (h, size) = (self.halo, [self.size.x(), self.size.y(), self.size.z()]);
+		let mut min = [0; 3];
+		let mut max = size.map(|s| s + 2 * h);
+
+		let (axis, start) = match face {
+			Face::MinX => (0, if ghost { 0 } else { h }),
+			Face::MaxX => (0, if ghost { size[0] + h } else { size[0] }),
+			Face::MinY => (1, if ghost { 0 } else { h }),
+			Face::MaxY => (1, if ghost { size[1] + h } else { size[1] }),
+			Face::MinZ => (2, if ghost { 0 } else { h }),
+			Face::MaxZ => (2, if ghost { size[2] + h } else { size[2] })
+		};
+
+		(min[axis], max[axis]) = (start, start + h);
+		Box3::new(Vec3::new(min[0], min[1], min[2]), Vec3::new(max[0], max[1], max[2]))
+	}
+}
+
+impl Face {
+	/// Get the face on the other side of the tile, which is the one a neighboring tile puts this face's slice into.
+	pub fn opposite(&self) -> Face {
+		match self {
+			Face::MinX => Face::MaxX,
+			Face::MaxX => Face::MinX,
+			Face::MinY => Face::MaxY,
+			Face::MaxY => Face::MinY,
+			Face::MinZ => Face::MaxZ,
+			Face::MaxZ => Face::MinZ
+		}
+	}
+}
+
+/// Goes through every cell in a box, row by row, and then layer by layer.
+fn cells_of(region: &Box3) -> impl Iterator<Item = Vec3> + '_ {
+	let (min, max) = (region.min(), region.max());
+	(min.z()..max.z()).flat_map(move |z| (min.y()..max.y()).flat_map(move |y| (min.x()..max.x()).map(move |x| Vec3::new(x, y, z))))
+}
@@ -0,0 +1,154 @@
+//! A sparse tree for storing cells: a quadtree in 2D and an octree in 3D.
+//! 
+//! Only cells that aren't dead get stored, so memory scales with how much is going on rather than how big the grid is.
+//! Space is split in half along every axis until each piece holds only a handful of cells, which also makes it quick to find every cell in a region.
+
+//--> Structs <--
+
+/// A tree holding the states of cells in `D` dimensions.
+#[derive(Clone)]
+pub(crate) struct Tree<const D: usize> {
+	root: Node<D>,
+	size: usize,
+	len: usize
+}
+
+//--> Enums <--
+
+/// A piece of space in the tree, which either holds a few cells directly or is split into 2^D smaller pieces.
+#[derive(Clone)]
+enum Node<const D: usize> {
+	Leaf(Vec<([usize; D], u8)>),
+	Branch(Vec<Node<D>>)
+}
+
+//--> Functions <--
+
+/// How many cells a leaf can hold before it gets split up.
+const LEAF_SIZE: usize = 16;
+
+impl<const D: usize> Tree<D> {
+	/// Creates an empty tree big enough to hold everything within the given bounds.
+	pub(crate) fn new(bounds: [usize; D]) -> Tree<D> {
+		let size = bounds.iter().copied().max().unwrap_or(1).max(1).next_power_of_two();
+		Tree { root: Node::Leaf(Vec::new()), size, len: 0 }
+	}
+
+	/// Get the state of a cell, which is 0 if it isn't stored.
+	pub(crate) fn get(&self, p: &[usize; D]) -> u8 {
+		let (mut node, mut origin, mut size) = (&self.root, [0; D], self.size);
+
+		loop {
+			match node {
+				Node::Leaf(cells) => return cells.iter().find(|(c, _)| c == p).map_or(0, |(_, s)| *s),
+				Node::Branch(children) => {
+					size /= 2;
+					let i = child_index(p, &mut origin, size);
+					node = &children[i];
+				}
+			}
+		}
+	}
+
+	/// Sets the state of a cell. Setting a cell to 0 takes it out of the tree.
+	pub(crate) fn set(&mut self, p: [usize; D], state: u8) {
+		let (added, removed) = self.root.set(p, state, [0; D], self.size);
+		self.len = self.len + added as usize - removed as usize;
+	}
+
+	/// Get every stored cell.
+	pub(crate) fn cells(&self) -> Vec<([usize; D], u8)> {
+		let mut out = Vec::with_capacity(self.len);
+		self.root.query(&[0; D], &[usize::MAX; D], [0; D], self.size, &mut out);
+		out
+	}
+
+	/// Get every stored cell from `min` up to (but not including) `max`.
+	pub(crate) fn query(&self, min: &[usize; D], max: &[usize; D]) -> Vec<([usize; D], u8)> {
+		let mut out = Vec::new();
+		self.root.query(min, max, [0; D], self.size, &mut out);
+		out
+	}
+}
+
+impl<const D: usize> Node<D> {
+	/// Sets the state of a cell, returning whether a cell was added or removed.
+	fn set(&mut self, p: [usize; D], state: u8, mut origin: [usize; D], size: usize) -> (bool, bool) {
+		match self {
+			Node::Leaf(cells) => {
+				let result = match (cells.iter().position(|(c, _)| *c == p), state) {
+					(Some(i), 0) => { cells.swap_remove(i); (false, true) },
+					(Some(i), s) => { cells[i].1 = s; (false, false) },
+					(None, 0) => (false, false),
+					(None, s) => { cells.push((p, s)); (true, false) }
+				};
+
+				if cells.len() > LEAF_SIZE && size > 1 {
+					let mut children = vec![Node::Leaf(Vec::new()); 1 << D];
+
+					for (c, s) in cells.drain(..) {
+						let mut o = origin;
+						let i = child_index(&c, &mut o, size / 2);
+						children[i].set(c, s, o, size / 2);
+					}
+
+					*self = Node::Branch(children);
+				}
+
+				result
+			},
+			Node::Branch(children) => {
+				let i = child_index(&p, &mut origin, size / 2);
+				let result = children[i].set(p, state, origin, size / 2);
+
+				// if everything left fits in a single leaf, fold the children back up
+				if result.1 && children.iter().all(|c| matches!(c, Node::Leaf(_))) {
+					let total = children.iter().map(|c| match c { Node::Leaf(cells) => cells.len(), _ => 0 }).sum::<usize>();
+
+					if total <= LEAF_SIZE / 2 {
+						let cells = children.drain(..).flat_map(|c| match c { Node::Leaf(cells) => cells, _ => Vec::new() }).collect();
+						*self = Node::Leaf(cells);
+					}
+				}
+
+				result
+			}
+		}
+	}
+
+	/// Collects every cell from `min` up to (but not including) `max` into `out`.
+	fn query(&self, min: &[usize; D], max: &[usize; D], origin: [usize; D], size: usize, out: &mut Vec<([usize; D], u8)>) {
+		// skip this piece entirely if it doesn't overlap the region
+		if (0..D).any(|d| origin[d] >= max[d] || origin[d].saturating_add(size) <= min[d]) { return; }
+
+		match self {
+			Node::Leaf(cells) => out.extend(cells.iter().filter(|(c, _)| (0..D).all(|d| c[d] >= min[d] && c[d] < max[d])).cloned()),
+			Node::Branch(children) => {
+				let half = size / 2;
+
+				for (i, child) in children.iter().enumerate() {
+					let mut o = origin;
+					for (d, axis) in o.iter_mut().enumerate() {
+						if i & 1 << d != 0 { *axis += half; }
+					}
+
+					child.query(min, max, o, half, out);
+				}
+			}
+		}
+	}
+}
+
+/// Works out which child of a branch a point falls into, moving the origin over to that child's corner.
+fn child_index<const D: usize>(p: &[usize; D], origin: &mut [usize; D], half: usize) -> usize {
+	let mut i = 0;
+
+	for d in 0..D {
+		if p[d] >= origin[d] + half {
+			i |= 1 << d;
+			origin[d] += half;
+		}
+	}
+
+	i
+}
@@ -0,0 +1,83 @@
+//! This module exposes the 2D automaton to JavaScript through wasm-bindgen, so it can run in a browser.
+//! 
+//! Build it with the `wasm` feature (using wasm-pack or similar), and then over in JavaScript:
+//! 
+//! ```js
+//! const life = new FlatAutomaton("B3/S23", 50, 50, new Uint32Array([1, 0, 2, 1, 0, 2, 1, 2, 2, 2]));
+//! life.tick();
+//! const cells = life.cells(); // a Uint8Array, one byte per cell, row by row
+//! ```
+
+//--> Imports <--
+
+use crate::{AutomataError, flat};
+use wasm_bindgen::prelude::*;
+
+//--> Structs <--
+
+/// A flat (2D) automaton, as seen from JavaScript.
+#[wasm_bindgen]
+pub struct FlatAutomaton(flat::Automaton);
+
+//--> Functions <--
+
+#[wasm_bindgen]
+impl FlatAutomaton {
+	/// Creates a new 2D automaton from a rule string, a size, and a flat list of live cell coordinates (x0, y0, x1, y1, and so on).
+	#[wasm_bindgen(constructor)]
+	pub fn new(rule: &str, width: usize, height: usize, seed: &[u32]) -> Result<FlatAutomaton, JsError> {
+		let rules = rule.parse().map_err(|e: AutomataError| JsError::new(&e.to_string()))?;
+		let seed = seed.chunks_exact(2).map(|c| flat::Vec2::new(c[0] as usize, c[1] as usize)).collect();
+
+		flat::Automaton::new(rules, flat::Vec2::new(width, height), seed)
+			.map(FlatAutomaton)
+			.map_err(|e| JsError::new(&e.to_string()))
+	}
+
+	/// Advances the automaton by one tick.
+	pub fn tick(&mut self) {
+		self.0.tick();
+	}
+
+	/// Get the width of the grid.
+	pub fn width(&self) -> usize {
+		self.0.get_bounds().x()
+	}
+
+	/// Get the height of the grid.
+	pub fn height(&self) -> usize {
+		self.0.get_bounds().y()
+	}
+
+	/// Sets one cell's state. States too big for the rules are clamped to alive, and cells outside the grid are ignored.
+	pub fn set_cell(&mut self, x: usize, y: usize, state: u8) {
+		self.0.set_cell(flat::Vec2::new(x, y), state);
+	}
+
+	/// Paints a straight line of cells from (x0, y0) to (x1, y1), both ends included, all in one call instead of one `set_cell` per cell.
+	pub fn paint_line(&mut self, x0: usize, y0: usize, x1: usize, y1: usize, state: u8) {
+		self.0.paint_line(flat::Vec2::new(x0, y0), flat::Vec2::new(x1, y1), state);
+	}
+
+	/// Paints a filled circle of cells around (x, y).
+	pub fn paint_circle(&mut self, x: usize, y: usize, radius: usize, state: u8) {
+		self.0.paint_circle(flat::Vec2::new(x, y), radius, state);
+	}
+
+	/// Paints a filled box of cells with (x0, y0) and (x1, y1) as opposite corners, both included.
+	pub fn paint_box(&mut self, x0: usize, y0: usize, x1: usize, y1: usize, state: u8) {
+		self.0.paint_box(flat::Vec2::new(x0, y0), flat::Vec2::new(x1, y1), state);
+	}
+
+	/// Get every cell's state as one flat buffer, row by row (so cell (x, y) is at `y * width + x`), ready to be drawn to a canvas.
+	pub fn cells(&self) -> Vec<u8> {
+		let width = self.width();
+		let mut buffer = vec![0; width * self.height()];
+
+		for (v, s) in self.0.get_cells() {
+			buffer[v.y() * width + v.x()] = s;
+		}
+
+		buffer
+	}
+}